@@ -1,8 +1,14 @@
 use {
     clap::{Parser, Subcommand},
     color_eyre::Result,
-    glues_server::ServerArgs,
-    glues_tui::cli,
+    glues_core::{Glues, rpc},
+    glues_server::{
+        ServerArgs,
+        archive::{self, BackupArgs, RestoreArgs},
+        fsck::{self, FsckArgs},
+    },
+    glues_tui::cli::{self, TuiArgs},
+    tokio::io::{self, BufReader},
 };
 
 #[derive(Parser)]
@@ -16,20 +22,38 @@ use {
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+
+    #[command(flatten)]
+    tui: TuiArgs,
 }
 
 #[derive(Subcommand)]
 enum Command {
     /// Run the Glues proxy server
     Server(ServerArgs),
+    /// Write a notebook to a portable archive file
+    Backup(BackupArgs),
+    /// Rebuild an archive file into an empty notebook
+    Restore(RestoreArgs),
+    /// Check a notebook for broken links between directories, notes and attachments
+    Fsck(FsckArgs),
+    /// Drive the headless core with JSON lines on stdin and stdout
+    CoreRpc,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let Cli { command } = Cli::parse();
+    let Cli { command, tui } = Cli::parse();
 
     match command {
         Some(Command::Server(args)) => glues_server::run(args).await,
-        None => cli::run().await,
+        Some(Command::Backup(args)) => archive::backup(args).await,
+        Some(Command::Restore(args)) => archive::restore(args).await,
+        Some(Command::Fsck(args)) => fsck::fsck(args).await,
+        Some(Command::CoreRpc) => {
+            let input = BufReader::new(io::stdin());
+            Ok(rpc::serve(&mut Glues::new(), input, io::stdout()).await?)
+        }
+        None => cli::run(tui).await,
     }
 }
@@ -1,51 +1,59 @@
 use crate::{
     Error, Result,
-    data::{Directory, Note},
-    types::{DirectoryId, NoteId},
+    data::{Attachment, Directory, Note},
+    types::{AttachmentId, DirectoryId, NoteId},
 };
 use async_trait::async_trait;
 
-use {
-    gluesql::gluesql_git_storage::{GitStorage, StorageType},
-    reqwest::blocking::Client,
-    std::path::PathBuf,
-    std::time::Duration,
+use {reqwest::blocking::Client, std::time::Duration};
+
+pub use git_sync::{
+    GitSync, GitSyncOptions, Resolution, SyncChange, SyncConflict, SyncMode, SyncTrigger,
 };
 
+/// Whether the server is reachable, how many writes wait to be replayed and
+/// how many queued writes the server refused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionStatus {
+    pub online: bool,
+    pub pending: usize,
+    pub rejected: usize,
+}
+
 #[derive(Clone)]
 pub enum SyncJob {
-    Git {
-        path: PathBuf,
-        remote: String,
-        branch: String,
-    },
+    Git(GitSync),
     Proxy {
         url: String,
         auth_token: Option<String>,
+        tls: proxy::TlsOptions,
     },
 }
 
 impl SyncJob {
     pub fn run(self) -> Result<()> {
         match self {
-            SyncJob::Git {
-                path,
-                remote,
-                branch,
+            SyncJob::Git(sync) => sync.run()?,
+            SyncJob::Proxy {
+                url,
+                auth_token,
+                tls,
             } => {
-                let mut storage = GitStorage::open(path, StorageType::File)?;
-                storage.set_remote(remote);
-                storage.set_branch(branch);
-                storage.pull()?;
-                storage.push()?;
-            }
-            SyncJob::Proxy { url, auth_token } => {
                 use crate::backend::proxy::{
                     request::ProxyRequest,
                     response::{ProxyResponse, ResultPayload},
                 };
 
-                let client = Client::builder().timeout(Duration::from_secs(30)).build()?;
+                let mut builder = Client::builder()
+                    .use_rustls_tls()
+                    .timeout(Duration::from_secs(30));
+                for certificate in tls.root_certificates()? {
+                    builder = builder.add_root_certificate(certificate);
+                }
+                if let Some(identity) = tls.identity()? {
+                    builder = builder.identity(identity);
+                }
+                let client = builder.build()?;
                 let mut request = client.post(&url).json(&ProxyRequest::Sync);
                 if let Some(token) = auth_token.as_ref() {
                     request = request.bearer_auth(token);
@@ -93,6 +101,12 @@ pub trait CoreBackend: Send {
     async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()>;
 
     async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>>;
+    /// Notes and child directories of each directory, in the given order.
+    /// Remote backends fetch them all in one round trip.
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>>;
     async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String>;
     async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note>;
     async fn remove_note(&mut self, note_id: NoteId) -> Result<()>;
@@ -100,9 +114,46 @@ pub trait CoreBackend: Send {
     async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()>;
     async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()>;
 
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>>;
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>>;
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment>;
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()>;
+
+    /// Restores an archived entry with its original id and timestamps. Restoring
+    /// a root directory, whose `parent_id` is its own id, replaces the root of an
+    /// empty notebook.
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()>;
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()>;
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()>;
+
     async fn log(&mut self, category: String, message: String) -> Result<()>;
 
-    fn sync_job(&self) -> Option<SyncJob>;
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>>;
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>>;
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()>;
+
+    /// Starts a transaction ended by `commit` or `rollback`. Returns `false`
+    /// without starting one when the storage cannot roll back.
+    async fn begin(&mut self) -> Result<bool>;
+    async fn commit(&mut self) -> Result<()>;
+    async fn rollback(&mut self) -> Result<()>;
+
+    /// Takes the changes made since the last call along with the job syncing them.
+    fn sync_job(&mut self) -> Option<SyncJob>;
+    /// Called once a job taken from `sync_job` has finished, whether or not it
+    /// succeeded, since it may have changed the storage underneath.
+    fn sync_finished(&mut self);
+    fn sync_trigger(&self) -> SyncTrigger;
+    /// Reachability of a remote backend, `None` for local storages.
+    fn connection_status(&self) -> Option<ConnectionStatus>;
+    /// Queued writes the server refused since the last call, described for
+    /// the user. They are set aside rather than dropped.
+    fn take_rejected_writes(&mut self) -> Vec<String>;
 }
 
 #[async_trait]
@@ -143,6 +194,13 @@ impl CoreBackend for Box<dyn CoreBackend + Send> {
         (**self).fetch_notes(directory_id).await
     }
 
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        (**self).fetch_children(directory_ids).await
+    }
+
     async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
         (**self).fetch_note_content(note_id).await
     }
@@ -167,16 +225,92 @@ impl CoreBackend for Box<dyn CoreBackend + Send> {
         (**self).move_note(note_id, directory_id).await
     }
 
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        (**self).fetch_attachments(note_id).await
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        (**self).fetch_attachment_data(attachment_id).await
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        (**self).add_attachment(note_id, name, data).await
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        (**self).remove_attachment(attachment_id).await
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        (**self).restore_directory(directory).await
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        (**self).restore_note(note, content).await
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        (**self).restore_attachment(attachment, data).await
+    }
+
     async fn log(&mut self, category: String, message: String) -> Result<()> {
         (**self).log(category, message).await
     }
 
-    fn sync_job(&self) -> Option<SyncJob> {
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        (**self).fetch_meta(key).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        (**self).fetch_all_meta().await
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        (**self).update_meta(key, value).await
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        (**self).begin().await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        (**self).commit().await
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        (**self).rollback().await
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
         (**self).sync_job()
     }
+
+    fn sync_finished(&mut self) {
+        (**self).sync_finished()
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        (**self).sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        (**self).connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        (**self).take_rejected_writes()
+    }
 }
 
 pub type BackendBox = Box<dyn CoreBackend + Send>;
 
+pub mod encrypted;
+mod git_sync;
+pub mod layer;
 pub mod local;
 pub mod proxy;
@@ -0,0 +1,585 @@
+mod cipher;
+
+pub use cipher::Cipher;
+
+use {
+    super::{ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+    crate::{
+        Error, Result,
+        data::{Attachment, Directory, Note},
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    async_trait::async_trait,
+    base64::{Engine, engine::general_purpose::STANDARD as BASE64},
+    gluesql::core::chrono::Utc,
+    uuid::Uuid,
+};
+
+/// `Meta` key holding the key derivation parameters and the key check value.
+pub const ENCRYPTION_META_KEY: &str = "encryption";
+
+/// PBKDF2 rounds used for newly encrypted notebooks. The count is stored with
+/// the salt, so raising it later does not lock out existing notebooks.
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+
+const KDF: &str = "pbkdf2-sha256";
+const CIPHERTEXT_PREFIX: &str = "enc1:";
+const PENDING_PREFIX: &str = "pending$";
+
+/// Client side encryption over any backend. Note names, directory names, note
+/// content, attachments and log messages are encrypted before they reach the inner backend,
+/// so storages, git remotes and proxy servers only ever see ciphertext.
+pub struct Encrypted<B> {
+    inner: B,
+    cipher: Cipher,
+}
+
+impl<B: CoreBackend> Encrypted<B> {
+    /// Opens an encrypted notebook. Plain notebooks are refused with
+    /// [`Error::NotEncrypted`], they are only encrypted by [`Encrypted::encrypt`].
+    pub async fn unlock(mut inner: B, passphrase: &str) -> Result<Self> {
+        check_passphrase(passphrase)?;
+
+        let value = inner
+            .fetch_meta(ENCRYPTION_META_KEY.to_owned())
+            .await?
+            .ok_or(Error::NotEncrypted)?;
+        let params = KeyParams::parse(&value)?;
+        let cipher = Cipher::derive(passphrase, &params.salt, params.iterations);
+        if !cipher.verify(&params.check) {
+            return Err(Error::Encryption("wrong passphrase".to_owned()));
+        }
+
+        let mut encrypted = Self { inner, cipher };
+        if params.pending {
+            encrypted.finish_encryption(params).await?;
+        }
+
+        Ok(encrypted)
+    }
+
+    /// Encrypts a plain notebook with `passphrase` and opens it.
+    pub async fn encrypt(inner: B, passphrase: &str) -> Result<Self> {
+        Self::encrypt_with_iterations(inner, passphrase, DEFAULT_ITERATIONS).await
+    }
+
+    /// Same as [`Encrypted::encrypt`], choosing the PBKDF2 rounds.
+    ///
+    /// The notebook is rewritten in a single transaction when the storage can
+    /// roll back. Otherwise the key parameters are stored first, marked as
+    /// pending, so a conversion cut short is finished by the next
+    /// [`Encrypted::unlock`] rather than leaving the notebook half plain.
+    pub async fn encrypt_with_iterations(
+        mut inner: B,
+        passphrase: &str,
+        iterations: u32,
+    ) -> Result<Self> {
+        check_passphrase(passphrase)?;
+        if is_encrypted(&mut inner).await? {
+            return Err(Error::Encryption(
+                "this notebook is already encrypted".to_owned(),
+            ));
+        }
+
+        let salt = cipher::random_salt();
+        let cipher = Cipher::derive(passphrase, &salt, iterations);
+        let params = KeyParams {
+            pending: true,
+            iterations,
+            salt,
+            check: cipher.key_check().to_vec(),
+        };
+        let mut encrypted = Self { inner, cipher };
+
+        if !encrypted.inner.begin().await? {
+            encrypted
+                .inner
+                .update_meta(ENCRYPTION_META_KEY.to_owned(), params.to_string())
+                .await?;
+            encrypted.finish_encryption(params).await?;
+
+            return Ok(encrypted);
+        }
+
+        match encrypted.finish_encryption(params).await {
+            Ok(()) => encrypted.inner.commit().await?,
+            Err(err) => {
+                encrypted.inner.rollback().await?;
+                return Err(err);
+            }
+        }
+
+        Ok(encrypted)
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    /// The value is bound to `field`, the place it is stored in.
+    fn seal(&self, value: &str, field: Field) -> String {
+        let sealed = self
+            .cipher
+            .encrypt(value.as_bytes(), field.aad().as_bytes());
+        format!("{CIPHERTEXT_PREFIX}{}", BASE64.encode(&sealed))
+    }
+
+    fn open(&self, value: &str, field: Field) -> Result<String> {
+        let encoded = value
+            .strip_prefix(CIPHERTEXT_PREFIX)
+            .ok_or_else(|| Error::Encryption("found a value which is not encrypted".to_owned()))?;
+        let plaintext = self
+            .cipher
+            .decrypt(&decode_base64(encoded)?, field.aad().as_bytes())?;
+
+        String::from_utf8(plaintext)
+            .map_err(|_| Error::Encryption("decrypted value is not valid UTF-8".to_owned()))
+    }
+
+    fn seal_data(&self, data: &[u8], attachment_id: &str) -> Vec<u8> {
+        let aad = Field::AttachmentData(attachment_id).aad();
+        self.cipher.encrypt(data, aad.as_bytes())
+    }
+
+    fn open_directory(&self, mut directory: Directory) -> Result<Directory> {
+        directory.name = self.open(&directory.name, Field::DirectoryName(&directory.id))?;
+        Ok(directory)
+    }
+
+    fn open_note(&self, mut note: Note) -> Result<Note> {
+        note.name = self.open(&note.name, Field::NoteName(&note.id))?;
+        Ok(note)
+    }
+
+    fn open_attachment(&self, mut attachment: Attachment) -> Result<Attachment> {
+        attachment.name = self.open(&attachment.name, Field::AttachmentName(&attachment.id))?;
+        attachment.size = attachment.size.saturating_sub(Cipher::OVERHEAD as u64);
+        Ok(attachment)
+    }
+
+    /// Seals every value still in the clear, then stores the key parameters
+    /// as complete.
+    async fn finish_encryption(&mut self, mut params: KeyParams) -> Result<()> {
+        self.encrypt_existing().await?;
+
+        params.pending = false;
+        self.inner
+            .update_meta(ENCRYPTION_META_KEY.to_owned(), params.to_string())
+            .await
+    }
+
+    /// `value` sealed for `field`, or `None` when it already is.
+    fn reseal(&self, value: &str, field: Field) -> Option<String> {
+        self.open(value, field)
+            .is_err()
+            .then(|| self.seal(value, field))
+    }
+
+    /// Rewrites every name and note of a plain notebook as ciphertext. Values
+    /// which are already sealed are left alone, so a conversion cut short can
+    /// run again. Attachments keep their ids.
+    async fn encrypt_existing(&mut self) -> Result<()> {
+        let root_id = self.inner.root_id();
+        let root = self.inner.fetch_directory(root_id.clone()).await?;
+        if let Some(name) = self.reseal(&root.name, Field::DirectoryName(&root_id)) {
+            self.inner.rename_directory(root_id.clone(), name).await?;
+        }
+
+        let mut pending = vec![root_id];
+        while let Some(directory_id) = pending.pop() {
+            for directory in self.inner.fetch_directories(directory_id.clone()).await? {
+                if let Some(name) =
+                    self.reseal(&directory.name, Field::DirectoryName(&directory.id))
+                {
+                    self.inner
+                        .rename_directory(directory.id.clone(), name)
+                        .await?;
+                }
+                pending.push(directory.id);
+            }
+
+            for note in self.inner.fetch_notes(directory_id).await? {
+                let content = self.inner.fetch_note_content(note.id.clone()).await?;
+                if let Some(content) = self.reseal(&content, Field::NoteContent(&note.id)) {
+                    self.inner
+                        .update_note_content(note.id.clone(), content)
+                        .await?;
+                }
+                if let Some(name) = self.reseal(&note.name, Field::NoteName(&note.id)) {
+                    self.inner.rename_note(note.id.clone(), name).await?;
+                }
+
+                for mut attachment in self.inner.fetch_attachments(note.id.clone()).await? {
+                    let Some(name) =
+                        self.reseal(&attachment.name, Field::AttachmentName(&attachment.id))
+                    else {
+                        continue;
+                    };
+
+                    let data = self
+                        .inner
+                        .fetch_attachment_data(attachment.id.clone())
+                        .await?;
+                    let data = self.seal_data(&data, &attachment.id);
+                    attachment.name = name;
+                    self.inner.remove_attachment(attachment.id.clone()).await?;
+                    self.inner.restore_attachment(attachment, data).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn check_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.is_empty() {
+        return Err(Error::Encryption("passphrase cannot be empty".to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Whether `backend` holds an encrypted notebook, or one being encrypted,
+/// which must be opened with [`Encrypted::unlock`].
+pub async fn is_encrypted<B: CoreBackend + ?Sized>(backend: &mut B) -> Result<bool> {
+    Ok(backend
+        .fetch_meta(ENCRYPTION_META_KEY.to_owned())
+        .await?
+        .is_some())
+}
+
+/// Where a sealed value is stored. Its ciphertext is bound to it as associated
+/// data, so a value copied into another row or column fails to open.
+#[derive(Clone, Copy)]
+enum Field<'a> {
+    DirectoryName(&'a str),
+    NoteName(&'a str),
+    NoteContent(&'a str),
+    AttachmentName(&'a str),
+    AttachmentData(&'a str),
+    Log(&'a str),
+}
+
+impl Field<'_> {
+    fn aad(self) -> String {
+        match self {
+            Self::DirectoryName(id) => format!("directory:{id}:name"),
+            Self::NoteName(id) => format!("note:{id}:name"),
+            Self::NoteContent(id) => format!("note:{id}:content"),
+            Self::AttachmentName(id) => format!("attachment:{id}:name"),
+            Self::AttachmentData(id) => format!("attachment:{id}:data"),
+            Self::Log(category) => format!("log:{category}"),
+        }
+    }
+}
+
+/// Timestamp for rows whose ids are made here rather than by the inner
+/// backend, in the format the storages return.
+fn now() -> String {
+    Utc::now().naive_utc().to_string()
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>> {
+    BASE64
+        .decode(value)
+        .map_err(|_| Error::Encryption("invalid base64".to_owned()))
+}
+
+/// Stored as `pbkdf2-sha256$<iterations>$<salt>$<check>` with base64 fields,
+/// behind a `pending$` prefix while the notebook is being encrypted.
+struct KeyParams {
+    pending: bool,
+    iterations: u32,
+    salt: Vec<u8>,
+    check: Vec<u8>,
+}
+
+impl KeyParams {
+    fn parse(value: &str) -> Result<Self> {
+        let invalid = || Error::Encryption(format!("invalid encryption metadata '{value}'"));
+
+        let (pending, value) = match value.strip_prefix(PENDING_PREFIX) {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let [kdf, iterations, salt, check] = value.split('$').collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        if kdf != KDF {
+            return Err(Error::Encryption(format!(
+                "unsupported key derivation '{kdf}'"
+            )));
+        }
+
+        Ok(Self {
+            pending,
+            iterations: iterations.parse().map_err(|_| invalid())?,
+            salt: decode_base64(salt)?,
+            check: decode_base64(check)?,
+        })
+    }
+}
+
+impl std::fmt::Display for KeyParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.pending {
+            write!(f, "{PENDING_PREFIX}")?;
+        }
+
+        write!(
+            f,
+            "{KDF}${}${}${}",
+            self.iterations,
+            BASE64.encode(&self.salt),
+            BASE64.encode(&self.check)
+        )
+    }
+}
+
+#[async_trait]
+impl<B: CoreBackend> CoreBackend for Encrypted<B> {
+    fn root_id(&self) -> DirectoryId {
+        self.inner.root_id()
+    }
+
+    async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
+        let directory = self.inner.fetch_directory(directory_id).await?;
+        self.open_directory(directory)
+    }
+
+    async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
+        self.inner
+            .fetch_directories(parent_id)
+            .await?
+            .into_iter()
+            .map(|directory| self.open_directory(directory))
+            .collect()
+    }
+
+    /// New rows get their ids here, before their names are sealed, and are
+    /// stored as restores.
+    async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
+        let now = now();
+        let directory = Directory {
+            id: Uuid::now_v7().to_string(),
+            parent_id,
+            name,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.restore_directory(directory.clone()).await?;
+
+        Ok(directory)
+    }
+
+    async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
+        self.inner.remove_directory(directory_id).await
+    }
+
+    async fn move_directory(
+        &mut self,
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    ) -> Result<()> {
+        self.inner.move_directory(directory_id, parent_id).await
+    }
+
+    async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
+        let name = self.seal(&name, Field::DirectoryName(&directory_id));
+        self.inner.rename_directory(directory_id, name).await
+    }
+
+    async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
+        self.inner
+            .fetch_notes(directory_id)
+            .await?
+            .into_iter()
+            .map(|note| self.open_note(note))
+            .collect()
+    }
+
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        self.inner
+            .fetch_children(directory_ids)
+            .await?
+            .into_iter()
+            .map(|(notes, directories)| {
+                let notes = notes
+                    .into_iter()
+                    .map(|note| self.open_note(note))
+                    .collect::<Result<_>>()?;
+                let directories = directories
+                    .into_iter()
+                    .map(|directory| self.open_directory(directory))
+                    .collect::<Result<_>>()?;
+
+                Ok((notes, directories))
+            })
+            .collect()
+    }
+
+    async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
+        let content = self.inner.fetch_note_content(note_id.clone()).await?;
+        self.open(&content, Field::NoteContent(&note_id))
+    }
+
+    async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
+        let now = now();
+        let note = Note {
+            id: Uuid::now_v7().to_string(),
+            directory_id,
+            name,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        self.restore_note(note.clone(), String::new()).await?;
+
+        Ok(note)
+    }
+
+    async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
+        self.inner.remove_note(note_id).await
+    }
+
+    async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
+        let name = self.seal(&name, Field::NoteName(&note_id));
+        self.inner.rename_note(note_id, name).await
+    }
+
+    async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
+        let content = self.seal(&content, Field::NoteContent(&note_id));
+        self.inner.update_note_content(note_id, content).await
+    }
+
+    async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
+        self.inner.move_note(note_id, directory_id).await
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        self.inner
+            .fetch_attachments(note_id)
+            .await?
+            .into_iter()
+            .map(|attachment| self.open_attachment(attachment))
+            .collect()
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        let data = self
+            .inner
+            .fetch_attachment_data(attachment_id.clone())
+            .await?;
+        let aad = Field::AttachmentData(&attachment_id).aad();
+        self.cipher.decrypt(&data, aad.as_bytes())
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        let attachment = Attachment {
+            id: Uuid::now_v7().to_string(),
+            note_id,
+            name,
+            size: data.len() as u64,
+            created_at: now(),
+        };
+        self.restore_attachment(attachment.clone(), data).await?;
+
+        Ok(attachment)
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        self.inner.remove_attachment(attachment_id).await
+    }
+
+    async fn restore_directory(&mut self, mut directory: Directory) -> Result<()> {
+        directory.name = self.seal(&directory.name, Field::DirectoryName(&directory.id));
+        self.inner.restore_directory(directory).await
+    }
+
+    async fn restore_note(&mut self, mut note: Note, content: String) -> Result<()> {
+        note.name = self.seal(&note.name, Field::NoteName(&note.id));
+        let content = self.seal(&content, Field::NoteContent(&note.id));
+        self.inner.restore_note(note, content).await
+    }
+
+    async fn restore_attachment(
+        &mut self,
+        mut attachment: Attachment,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        attachment.name = self.seal(&attachment.name, Field::AttachmentName(&attachment.id));
+        let data = self.seal_data(&data, &attachment.id);
+        self.inner.restore_attachment(attachment, data).await
+    }
+
+    async fn log(&mut self, category: String, message: String) -> Result<()> {
+        let message = self.seal(&message, Field::Log(&category));
+        self.inner.log(category, message).await
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        self.inner.fetch_meta(key).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        self.inner.fetch_all_meta().await
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        if key == ENCRYPTION_META_KEY {
+            return Err(Error::Encryption(
+                "encryption metadata cannot be overwritten".to_owned(),
+            ));
+        }
+
+        self.inner.update_meta(key, value).await
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        self.inner.begin().await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.inner.commit().await
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        self.inner.rollback().await
+    }
+
+    /// The inner backend only sees sealed names, so changes are described by id.
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        let mut job = self.inner.sync_job();
+        if let Some(SyncJob::Git(sync)) = job.as_mut() {
+            for change in &mut sync.changes {
+                change.name = None;
+            }
+        }
+
+        job
+    }
+
+    fn sync_finished(&mut self) {
+        self.inner.sync_finished();
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.inner.sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.inner.take_rejected_writes()
+    }
+}
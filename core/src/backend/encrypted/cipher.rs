@@ -0,0 +1,97 @@
+//! Primitives behind notebook encryption: PBKDF2-HMAC-SHA256 for deriving keys from a
+//! passphrase and XChaCha20-Poly1305 for values, with random salts and nonces from the OS.
+
+use {
+    crate::{Error, Result},
+    chacha20poly1305::{
+        AeadCore, KeyInit, XChaCha20Poly1305, XNonce,
+        aead::{Aead, OsRng, Payload, rand_core::RngCore},
+    },
+    sha2::Sha256,
+    subtle::ConstantTimeEq,
+};
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 24;
+const TAG_LEN: usize = 16;
+
+const SALT_LEN: usize = 16;
+
+/// Key derived from a passphrase, along with the value telling whether the
+/// passphrase was right.
+#[derive(Clone)]
+pub struct Cipher {
+    aead: XChaCha20Poly1305,
+    check: [u8; KEY_LEN],
+}
+
+impl Cipher {
+    /// Bytes a sealed value adds to its plaintext: the nonce and the tag.
+    pub const OVERHEAD: usize = NONCE_LEN + TAG_LEN;
+
+    pub fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Self {
+        let mut keys = [0; 2 * KEY_LEN];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut keys);
+
+        let (key, check) = keys.split_at(KEY_LEN);
+        Self {
+            aead: XChaCha20Poly1305::new(key.into()),
+            check: check.try_into().expect("check is KEY_LEN bytes"),
+        }
+    }
+
+    /// Value stored next to the salt which tells whether a passphrase is right
+    /// without decrypting any note.
+    pub fn key_check(&self) -> [u8; KEY_LEN] {
+        self.check
+    }
+
+    pub fn verify(&self, check: &[u8]) -> bool {
+        self.check.ct_eq(check).into()
+    }
+
+    /// Seals `plaintext` under a random nonce. `aad` names where the value is
+    /// stored, so it cannot be moved to another row and still open.
+    pub fn encrypt(&self, plaintext: &[u8], aad: &[u8]) -> Vec<u8> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .expect("XChaCha20-Poly1305 seals any value that fits in memory");
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&ciphertext);
+        sealed
+    }
+
+    pub fn decrypt(&self, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < Self::OVERHEAD {
+            return Err(Error::Encryption("ciphertext is too short".to_owned()));
+        }
+
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.aead
+            .decrypt(
+                XNonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad,
+                },
+            )
+            .map_err(|_| {
+                Error::Encryption("ciphertext was modified, moved or uses another key".to_owned())
+            })
+    }
+}
+
+pub fn random_salt() -> Vec<u8> {
+    let mut salt = vec![0; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    salt
+}
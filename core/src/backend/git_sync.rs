@@ -0,0 +1,369 @@
+use {
+    crate::{
+        Error, Result,
+        types::{DirectoryId, NoteId},
+    },
+    serde::{Deserialize, Serialize},
+    std::{fmt, fs, io, path::PathBuf, process::Command, time::Duration},
+};
+
+/// Git config key choosing [`SyncMode`], e.g. `git config glues.syncMode pull`.
+pub const SYNC_MODE_CONFIG: &str = "glues.syncMode";
+
+/// Git config key choosing [`SyncTrigger`]: `change`, `manual` or a number of seconds.
+pub const SYNC_TRIGGER_CONFIG: &str = "glues.syncTrigger";
+
+const DEFAULT_MESSAGE: &str = "Update notes";
+
+/// Which way a sync moves commits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncMode {
+    #[default]
+    Both,
+    PullOnly,
+    PushOnly,
+}
+
+impl SyncMode {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "both" => Ok(Self::Both),
+            "pull" => Ok(Self::PullOnly),
+            "push" => Ok(Self::PushOnly),
+            _ => Err(Error::Sync(format!(
+                "invalid {SYNC_MODE_CONFIG} '{value}', expected both, pull or push"
+            ))),
+        }
+    }
+
+    fn pulls(self) -> bool {
+        self != Self::PushOnly
+    }
+
+    fn pushes(self) -> bool {
+        self != Self::PullOnly
+    }
+}
+
+/// When the frontend starts a sync.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SyncTrigger {
+    /// After every change to the notebook.
+    #[default]
+    OnChange,
+    Interval(Duration),
+    /// Only when the user asks for it.
+    Manual,
+}
+
+impl SyncTrigger {
+    fn parse(value: &str) -> Result<Self> {
+        match value {
+            "change" => Ok(Self::OnChange),
+            "manual" => Ok(Self::Manual),
+            _ => match value.parse::<u64>() {
+                Ok(secs) if secs > 0 => Ok(Self::Interval(Duration::from_secs(secs))),
+                _ => Err(Error::Sync(format!(
+                    "invalid {SYNC_TRIGGER_CONFIG} '{value}', \
+                     expected change, manual or a number of seconds"
+                ))),
+            },
+        }
+    }
+}
+
+/// Sync settings of a Git notebook, read from the repository config.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GitSyncOptions {
+    pub mode: SyncMode,
+    pub trigger: SyncTrigger,
+}
+
+impl GitSyncOptions {
+    pub fn load(path: &PathBuf) -> Result<Self> {
+        let mode = match git_config(path, SYNC_MODE_CONFIG)? {
+            Some(value) => SyncMode::parse(&value)?,
+            None => SyncMode::default(),
+        };
+        let trigger = match git_config(path, SYNC_TRIGGER_CONFIG)? {
+            Some(value) => SyncTrigger::parse(&value)?,
+            None => SyncTrigger::default(),
+        };
+
+        Ok(Self { mode, trigger })
+    }
+}
+
+/// One line of a sync commit message, e.g. `Rename note 'Todo'`. `name` is
+/// left out for encrypted notebooks, which are described by id instead.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncChange {
+    pub action: &'static str,
+    pub id: String,
+    pub name: Option<String>,
+}
+
+impl fmt::Display for SyncChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{} '{name}'", self.action),
+            None => write!(f, "{} {}", self.action, self.id),
+        }
+    }
+}
+
+/// Rows which both sides of a merge changed. The merge is left in progress
+/// until [`GitSync::resolve`] is called.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncConflict {
+    pub notes: Vec<NoteId>,
+    pub directories: Vec<DirectoryId>,
+    /// Conflicted files which are not note or directory rows.
+    pub files: Vec<String>,
+}
+
+impl fmt::Display for SyncConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "merge conflict in {} notes, {} directories and {} other files",
+            self.notes.len(),
+            self.directories.len(),
+            self.files.len()
+        )
+    }
+}
+
+/// How to finish a conflicted merge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Resolution {
+    KeepLocal,
+    TakeRemote,
+    /// Abort the merge and leave both sides as they were.
+    Abort,
+}
+
+#[derive(Clone, Debug)]
+pub struct GitSync {
+    pub path: PathBuf,
+    pub remote: String,
+    pub branch: String,
+    pub mode: SyncMode,
+    /// Changes since the last sync, described in the commit message.
+    pub changes: Vec<SyncChange>,
+}
+
+impl GitSync {
+    /// Squashes the commits made since the last sync into one commit which
+    /// lists the changes, then pulls and pushes as `mode` allows.
+    pub fn run(&self) -> Result<()> {
+        let upstream = self.fetch()?;
+
+        if let (true, Some(upstream)) = (self.mode.pushes(), &upstream) {
+            self.squash_local_commits(upstream)?;
+        }
+
+        if let (true, Some(upstream)) = (self.mode.pulls(), &upstream) {
+            self.merge(upstream)?;
+        }
+
+        if self.mode.pushes() {
+            self.git(&["push", &self.remote, &self.branch])?;
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the merge left by a [`SyncConflict`] and pushes the result.
+    pub fn resolve(&self, resolution: Resolution) -> Result<()> {
+        let (side, stage) = match resolution {
+            Resolution::KeepLocal => ("--ours", "2"),
+            Resolution::TakeRemote => ("--theirs", "3"),
+            Resolution::Abort => {
+                self.git(&["merge", "--abort"])?;
+                return Ok(());
+            }
+        };
+
+        // a file missing from the chosen stage was deleted on that side
+        let mut kept = Vec::new();
+        let mut deleted = Vec::new();
+        for file in self.conflicted_files()? {
+            if self.unmerged_stages(&file)?.iter().any(|s| s == stage) {
+                kept.push(file);
+            } else {
+                deleted.push(file);
+            }
+        }
+
+        if !kept.is_empty() {
+            let mut checkout = vec!["checkout", side, "--"];
+            checkout.extend(kept.iter().map(String::as_str));
+            self.git(&checkout)?;
+
+            let mut add = vec!["add", "--"];
+            add.extend(kept.iter().map(String::as_str));
+            self.git(&add)?;
+        }
+        if !deleted.is_empty() {
+            // `git rm` would take the table directory along with its last row
+            let mut rm = vec!["rm", "--quiet", "--cached", "--"];
+            rm.extend(deleted.iter().map(String::as_str));
+            self.git(&rm)?;
+            for file in &deleted {
+                match fs::remove_file(self.path.join(file)) {
+                    Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                        return Err(Error::Sync(format!("failed to remove {file}: {err}")));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.git(&["commit", "--no-edit"])?;
+
+        if self.mode.pushes() {
+            self.git(&["push", &self.remote, &self.branch])?;
+        }
+
+        Ok(())
+    }
+
+    /// Commit message listing every change once, in the order they were made.
+    pub fn message(&self) -> String {
+        let mut lines: Vec<String> = Vec::new();
+        for change in &self.changes {
+            let line = format!("- {change}");
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+
+        match lines.as_slice() {
+            [] => DEFAULT_MESSAGE.to_owned(),
+            [line] => line[2..].to_owned(),
+            _ => format!(
+                "{DEFAULT_MESSAGE} ({} changes)\n\n{}",
+                lines.len(),
+                lines.join("\n")
+            ),
+        }
+    }
+
+    /// Returns the remote tracking ref, or `None` before the branch is first
+    /// pushed. Failing to reach the remote is an error.
+    fn fetch(&self) -> Result<Option<String>> {
+        let head = format!("refs/heads/{}", self.branch);
+        if self
+            .git(&["ls-remote", "--heads", &self.remote, &head])?
+            .is_empty()
+        {
+            return Ok(None);
+        }
+
+        let upstream = format!("{}/{}", self.remote, self.branch);
+        let refspec = format!("+{head}:refs/remotes/{upstream}");
+        self.git(&["fetch", &self.remote, &refspec])?;
+
+        Ok(Some(upstream))
+    }
+
+    fn squash_local_commits(&self, upstream: &str) -> Result<()> {
+        let base = self.git(&["merge-base", "HEAD", upstream])?;
+        let head = self.git(&["rev-parse", "HEAD"])?;
+        if base == head {
+            return Ok(());
+        }
+
+        self.git(&["reset", "--soft", &base])?;
+        self.git(&["commit", "--allow-empty", "-m", &self.message()])?;
+
+        Ok(())
+    }
+
+    fn merge(&self, upstream: &str) -> Result<()> {
+        let Err(err) = self.git(&["merge", "--no-edit", upstream]) else {
+            return Ok(());
+        };
+
+        let files = self.conflicted_files()?;
+        if files.is_empty() {
+            return Err(err);
+        }
+
+        let mut conflict = SyncConflict {
+            notes: Vec::new(),
+            directories: Vec::new(),
+            files: Vec::new(),
+        };
+        for file in files {
+            match row_id(&file) {
+                Some(("Note", id)) => conflict.notes.push(id),
+                Some(("Directory", id)) => conflict.directories.push(id),
+                _ => conflict.files.push(file),
+            }
+        }
+
+        Err(Error::SyncConflict(conflict))
+    }
+
+    fn conflicted_files(&self) -> Result<Vec<String>> {
+        let output = self.git(&["diff", "--name-only", "--diff-filter=U"])?;
+
+        Ok(output.lines().map(str::to_owned).collect())
+    }
+
+    /// Index stages present for an unmerged `file`: 1 for the base, 2 for
+    /// ours and 3 for theirs.
+    fn unmerged_stages(&self, file: &str) -> Result<Vec<String>> {
+        let output = self.git(&["ls-files", "--unmerged", "--", file])?;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(2).map(str::to_owned))
+            .collect())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String> {
+        git(&self.path, args)
+    }
+}
+
+/// Maps a row file such as `Note/00<uuid hex>.ron` to its table and UUID.
+fn row_id(file: &str) -> Option<(&str, String)> {
+    let (table, name) = file.split_once('/')?;
+    let hex = name.strip_suffix(".ron")?.strip_prefix("00")?;
+    let id = u128::from_str_radix(hex, 16).ok()?;
+
+    Some((table, uuid::Uuid::from_u128(id).to_string()))
+}
+
+fn git_config(path: &PathBuf, key: &str) -> Result<Option<String>> {
+    // `git config --get` exits with 1 when the key is not set
+    Ok(git(path, &["config", "--get", key]).ok())
+}
+
+fn git(path: &PathBuf, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .map_err(|err| Error::Sync(format!("failed to run git: {err}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let message = if stderr.trim().is_empty() {
+            stdout
+        } else {
+            stderr
+        };
+
+        return Err(Error::Sync(format!(
+            "git {} failed: {}",
+            args[0],
+            message.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
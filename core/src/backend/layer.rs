@@ -0,0 +1,123 @@
+//! Wrappers adding cross-cutting behavior to any backend, stacked in the
+//! spirit of tower layers:
+//!
+//! ```no_run
+//! # async fn demo() -> Result<(), glues_core::Error> {
+//! use glues_core::backend::{BackendBox, layer::{CacheLayer, LayerStack, TimingLayer}, local::Db};
+//!
+//! let backend: BackendBox = Box::new(Db::memory().await?);
+//! let backend = LayerStack::new()
+//!     .layer(TimingLayer::default())
+//!     .layer(CacheLayer)
+//!     .apply(backend);
+//! # Ok(())
+//! # }
+//! ```
+
+mod cache;
+mod fault;
+mod read_only;
+mod timing;
+
+pub use {
+    cache::{CacheLayer, Cached},
+    fault::{FaultLayer, Faulty},
+    read_only::{ReadOnly, ReadOnlyLayer},
+    timing::{TIMING_LOG_CATEGORY, Timed, TimingLayer},
+};
+
+use {
+    super::BackendBox,
+    crate::{Error, Result},
+    std::{fmt, str::FromStr, sync::Arc, time::Duration},
+};
+
+/// Wraps a backend into one with extra behavior.
+pub trait Layer: Send + Sync {
+    fn layer(&self, inner: BackendBox) -> BackendBox;
+}
+
+/// Layers applied in the order they were added, the first one outermost.
+#[derive(Clone, Default)]
+pub struct LayerStack {
+    layers: Vec<Arc<dyn Layer>>,
+}
+
+impl LayerStack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn apply(&self, backend: BackendBox) -> BackendBox {
+        self.layers
+            .iter()
+            .rev()
+            .fold(backend, |backend, layer| layer.layer(backend))
+    }
+}
+
+impl fmt::Debug for LayerStack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LayerStack")
+            .field("layers", &self.layers.len())
+            .finish()
+    }
+}
+
+/// Reads a comma separated list of built-in layers, outermost first:
+/// `cache`, `read-only`, `timing`, `timing=<ms>` logging only calls at least
+/// that slow, and `fault=<n>` failing every n-th call.
+impl FromStr for LayerStack {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        let mut stack = Self::new();
+        for spec in value
+            .split(',')
+            .map(str::trim)
+            .filter(|spec| !spec.is_empty())
+        {
+            let (name, arg) = match spec.split_once('=') {
+                Some((name, arg)) => (name.trim(), Some(arg.trim())),
+                None => (spec, None),
+            };
+            let number = |what: &str| -> Result<u64> {
+                arg.ok_or_else(|| Error::Layer(format!("{name} needs {what}, e.g. {name}=10")))?
+                    .parse()
+                    .map_err(|_| Error::Layer(format!("invalid {what} in '{spec}'")))
+            };
+
+            stack = match (name, arg) {
+                ("cache", None) => stack.layer(CacheLayer),
+                ("read-only", None) => stack.layer(ReadOnlyLayer),
+                ("timing", None) => stack.layer(TimingLayer::default()),
+                ("timing", Some(_)) => stack.layer(TimingLayer::new(Duration::from_millis(
+                    number("a threshold in milliseconds")?,
+                ))),
+                ("fault", _) => {
+                    let every = number("a call interval")?;
+                    if every == 0 {
+                        return Err(Error::Layer("fault interval must be at least 1".to_owned()));
+                    }
+                    stack.layer(FaultLayer::every(every))
+                }
+                _ => return Err(Error::Layer(format!("unknown layer '{spec}'"))),
+            };
+        }
+
+        Ok(stack)
+    }
+}
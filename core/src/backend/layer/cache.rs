@@ -0,0 +1,334 @@
+use {
+    super::Layer,
+    crate::{
+        Result,
+        backend::{BackendBox, ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+        data::{Attachment, Directory, Note},
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    async_trait::async_trait,
+    std::collections::HashMap,
+};
+
+/// Wraps backends in [`Cached`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CacheLayer;
+
+impl Layer for CacheLayer {
+    fn layer(&self, inner: BackendBox) -> BackendBox {
+        Box::new(Cached::new(inner))
+    }
+}
+
+/// Answers repeated `fetch_*` calls from memory. Writes through this layer
+/// drop the cached reads, so it only fits backends nobody else writes to
+/// while it is open, apart from what a sync brings in.
+pub struct Cached<B> {
+    inner: B,
+    directories: HashMap<DirectoryId, Directory>,
+    children: HashMap<DirectoryId, Vec<Directory>>,
+    notes: HashMap<DirectoryId, Vec<Note>>,
+    contents: HashMap<NoteId, String>,
+    attachments: HashMap<NoteId, Vec<Attachment>>,
+    attachment_data: HashMap<AttachmentId, Vec<u8>>,
+    meta: HashMap<String, Option<String>>,
+    all_meta: Option<Vec<(String, String)>>,
+}
+
+impl<B: CoreBackend> Cached<B> {
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            directories: HashMap::new(),
+            children: HashMap::new(),
+            notes: HashMap::new(),
+            contents: HashMap::new(),
+            attachments: HashMap::new(),
+            attachment_data: HashMap::new(),
+            meta: HashMap::new(),
+            all_meta: None,
+        }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn clear(&mut self) {
+        self.directories.clear();
+        self.children.clear();
+        self.notes.clear();
+        self.contents.clear();
+        self.attachments.clear();
+        self.attachment_data.clear();
+        self.meta.clear();
+        self.all_meta = None;
+    }
+
+    /// Drops every cached read once `result` shows the write went through.
+    fn written<T>(&mut self, result: Result<T>) -> Result<T> {
+        if result.is_ok() {
+            self.clear();
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl<B: CoreBackend> CoreBackend for Cached<B> {
+    fn root_id(&self) -> DirectoryId {
+        self.inner.root_id()
+    }
+
+    async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
+        if let Some(directory) = self.directories.get(&directory_id) {
+            return Ok(directory.clone());
+        }
+
+        let directory = self.inner.fetch_directory(directory_id.clone()).await?;
+        self.directories.insert(directory_id, directory.clone());
+
+        Ok(directory)
+    }
+
+    async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
+        if let Some(directories) = self.children.get(&parent_id) {
+            return Ok(directories.clone());
+        }
+
+        let directories = self.inner.fetch_directories(parent_id.clone()).await?;
+        self.children.insert(parent_id, directories.clone());
+
+        Ok(directories)
+    }
+
+    async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
+        let result = self.inner.add_directory(parent_id, name).await;
+        self.written(result)
+    }
+
+    async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
+        let result = self.inner.remove_directory(directory_id).await;
+        self.written(result)
+    }
+
+    async fn move_directory(
+        &mut self,
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    ) -> Result<()> {
+        let result = self.inner.move_directory(directory_id, parent_id).await;
+        self.written(result)
+    }
+
+    async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
+        let result = self.inner.rename_directory(directory_id, name).await;
+        self.written(result)
+    }
+
+    async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
+        if let Some(notes) = self.notes.get(&directory_id) {
+            return Ok(notes.clone());
+        }
+
+        let notes = self.inner.fetch_notes(directory_id.clone()).await?;
+        self.notes.insert(directory_id, notes.clone());
+
+        Ok(notes)
+    }
+
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        let cached: Option<Vec<_>> = directory_ids
+            .iter()
+            .map(|id| Some((self.notes.get(id)?.clone(), self.children.get(id)?.clone())))
+            .collect();
+        if let Some(children) = cached {
+            return Ok(children);
+        }
+
+        let children = self.inner.fetch_children(directory_ids.clone()).await?;
+        for (id, (notes, directories)) in directory_ids.into_iter().zip(&children) {
+            self.notes.insert(id.clone(), notes.clone());
+            self.children.insert(id, directories.clone());
+        }
+
+        Ok(children)
+    }
+
+    async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
+        if let Some(content) = self.contents.get(&note_id) {
+            return Ok(content.clone());
+        }
+
+        let content = self.inner.fetch_note_content(note_id.clone()).await?;
+        self.contents.insert(note_id, content.clone());
+
+        Ok(content)
+    }
+
+    async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
+        let result = self.inner.add_note(directory_id, name).await;
+        self.written(result)
+    }
+
+    async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
+        let result = self.inner.remove_note(note_id).await;
+        self.written(result)
+    }
+
+    async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
+        let result = self.inner.rename_note(note_id, name).await;
+        self.written(result)
+    }
+
+    /// Edits are the most frequent write, so they keep everything but the
+    /// note listings, whose `updated_at` changes.
+    async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
+        self.inner
+            .update_note_content(note_id.clone(), content.clone())
+            .await?;
+        self.notes.clear();
+        self.contents.insert(note_id, content);
+
+        Ok(())
+    }
+
+    async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
+        let result = self.inner.move_note(note_id, directory_id).await;
+        self.written(result)
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        if let Some(attachments) = self.attachments.get(&note_id) {
+            return Ok(attachments.clone());
+        }
+
+        let attachments = self.inner.fetch_attachments(note_id.clone()).await?;
+        self.attachments.insert(note_id, attachments.clone());
+
+        Ok(attachments)
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        if let Some(data) = self.attachment_data.get(&attachment_id) {
+            return Ok(data.clone());
+        }
+
+        let data = self
+            .inner
+            .fetch_attachment_data(attachment_id.clone())
+            .await?;
+        self.attachment_data.insert(attachment_id, data.clone());
+
+        Ok(data)
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        let result = self.inner.add_attachment(note_id, name, data).await;
+        self.written(result)
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        let result = self.inner.remove_attachment(attachment_id).await;
+        self.written(result)
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        let result = self.inner.restore_directory(directory).await;
+        self.written(result)
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        let result = self.inner.restore_note(note, content).await;
+        self.written(result)
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        let result = self.inner.restore_attachment(attachment, data).await;
+        self.written(result)
+    }
+
+    async fn log(&mut self, category: String, message: String) -> Result<()> {
+        self.inner.log(category, message).await
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        if let Some(value) = self.meta.get(&key) {
+            return Ok(value.clone());
+        }
+
+        let value = self.inner.fetch_meta(key.clone()).await?;
+        self.meta.insert(key, value.clone());
+
+        Ok(value)
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        if let Some(entries) = &self.all_meta {
+            return Ok(entries.clone());
+        }
+
+        let entries = self.inner.fetch_all_meta().await?;
+        for (key, value) in &entries {
+            self.meta.insert(key.clone(), Some(value.clone()));
+        }
+        self.all_meta = Some(entries.clone());
+
+        Ok(entries)
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        self.inner.update_meta(key.clone(), value.clone()).await?;
+        self.meta.insert(key, Some(value));
+        self.all_meta = None;
+
+        Ok(())
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        self.inner.begin().await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.inner.commit().await
+    }
+
+    /// Reads cached inside the transaction may be gone after it.
+    async fn rollback(&mut self) -> Result<()> {
+        self.clear();
+        self.inner.rollback().await
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        self.inner.sync_job()
+    }
+
+    /// The sync may have pulled in changes made elsewhere. Clearing only now,
+    /// rather than when the job is handed out, keeps reads made while it ran
+    /// from caching what it replaced.
+    fn sync_finished(&mut self) {
+        self.clear();
+        self.inner.sync_finished();
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.inner.sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.inner.take_rejected_writes()
+    }
+}
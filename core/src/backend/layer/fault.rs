@@ -0,0 +1,257 @@
+use {
+    super::Layer,
+    crate::{
+        Error, Result,
+        backend::{BackendBox, ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+        data::{Attachment, Directory, Note},
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    async_trait::async_trait,
+};
+
+/// Wraps backends in [`Faulty`].
+#[derive(Clone, Debug)]
+pub struct FaultLayer {
+    every: u64,
+    operations: Vec<String>,
+}
+
+impl FaultLayer {
+    /// Fails every `every`-th call, counting from the first one.
+    pub fn every(every: u64) -> Self {
+        Self {
+            every: every.max(1),
+            operations: Vec::new(),
+        }
+    }
+
+    /// Counts and fails only calls to the named `CoreBackend` methods.
+    pub fn only<I, S>(mut self, operations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.operations = operations.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl Layer for FaultLayer {
+    fn layer(&self, inner: BackendBox) -> BackendBox {
+        Box::new(Faulty::new(inner, self.clone()))
+    }
+}
+
+/// Fails calls on a fixed schedule with [`Error::BackendError`] instead of
+/// passing them on, to test how callers cope with a flaky backend.
+pub struct Faulty<B> {
+    inner: B,
+    plan: FaultLayer,
+    calls: u64,
+}
+
+impl<B: CoreBackend> Faulty<B> {
+    pub fn new(inner: B, plan: FaultLayer) -> Self {
+        Self {
+            inner,
+            plan,
+            calls: 0,
+        }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    fn inject(&mut self, operation: &str) -> Result<()> {
+        let FaultLayer { every, operations } = &self.plan;
+        if !operations.is_empty() && !operations.iter().any(|name| name == operation) {
+            return Ok(());
+        }
+
+        self.calls += 1;
+        if self.calls.is_multiple_of(*every) {
+            return Err(Error::BackendError(format!(
+                "injected fault in {operation}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: CoreBackend> CoreBackend for Faulty<B> {
+    fn root_id(&self) -> DirectoryId {
+        self.inner.root_id()
+    }
+
+    async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
+        self.inject("fetch_directory")?;
+        self.inner.fetch_directory(directory_id).await
+    }
+
+    async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
+        self.inject("fetch_directories")?;
+        self.inner.fetch_directories(parent_id).await
+    }
+
+    async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
+        self.inject("add_directory")?;
+        self.inner.add_directory(parent_id, name).await
+    }
+
+    async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
+        self.inject("remove_directory")?;
+        self.inner.remove_directory(directory_id).await
+    }
+
+    async fn move_directory(
+        &mut self,
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    ) -> Result<()> {
+        self.inject("move_directory")?;
+        self.inner.move_directory(directory_id, parent_id).await
+    }
+
+    async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
+        self.inject("rename_directory")?;
+        self.inner.rename_directory(directory_id, name).await
+    }
+
+    async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
+        self.inject("fetch_notes")?;
+        self.inner.fetch_notes(directory_id).await
+    }
+
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        self.inject("fetch_children")?;
+        self.inner.fetch_children(directory_ids).await
+    }
+
+    async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
+        self.inject("fetch_note_content")?;
+        self.inner.fetch_note_content(note_id).await
+    }
+
+    async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
+        self.inject("add_note")?;
+        self.inner.add_note(directory_id, name).await
+    }
+
+    async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
+        self.inject("remove_note")?;
+        self.inner.remove_note(note_id).await
+    }
+
+    async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
+        self.inject("rename_note")?;
+        self.inner.rename_note(note_id, name).await
+    }
+
+    async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
+        self.inject("update_note_content")?;
+        self.inner.update_note_content(note_id, content).await
+    }
+
+    async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
+        self.inject("move_note")?;
+        self.inner.move_note(note_id, directory_id).await
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        self.inject("fetch_attachments")?;
+        self.inner.fetch_attachments(note_id).await
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        self.inject("fetch_attachment_data")?;
+        self.inner.fetch_attachment_data(attachment_id).await
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        self.inject("add_attachment")?;
+        self.inner.add_attachment(note_id, name, data).await
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        self.inject("remove_attachment")?;
+        self.inner.remove_attachment(attachment_id).await
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        self.inject("restore_directory")?;
+        self.inner.restore_directory(directory).await
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        self.inject("restore_note")?;
+        self.inner.restore_note(note, content).await
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        self.inject("restore_attachment")?;
+        self.inner.restore_attachment(attachment, data).await
+    }
+
+    async fn log(&mut self, category: String, message: String) -> Result<()> {
+        self.inject("log")?;
+        self.inner.log(category, message).await
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        self.inject("fetch_meta")?;
+        self.inner.fetch_meta(key).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        self.inject("fetch_all_meta")?;
+        self.inner.fetch_all_meta().await
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        self.inject("update_meta")?;
+        self.inner.update_meta(key, value).await
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        self.inner.begin().await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.inner.commit().await
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        self.inner.rollback().await
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        self.inner.sync_job()
+    }
+
+    fn sync_finished(&mut self) {
+        self.inner.sync_finished();
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.inner.sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.inner.take_rejected_writes()
+    }
+}
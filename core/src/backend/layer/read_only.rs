@@ -0,0 +1,184 @@
+use {
+    super::Layer,
+    crate::{
+        Error, Result,
+        backend::{BackendBox, ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+        data::{Attachment, Directory, Note},
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    async_trait::async_trait,
+};
+
+/// Wraps backends in [`ReadOnly`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReadOnlyLayer;
+
+impl Layer for ReadOnlyLayer {
+    fn layer(&self, inner: BackendBox) -> BackendBox {
+        Box::new(ReadOnly::new(inner))
+    }
+}
+
+/// Refuses every change to the notebook with [`Error::ReadOnly`]. Entries
+/// written through `log` are kept, as they describe the session rather than
+/// the notebook.
+pub struct ReadOnly<B> {
+    inner: B,
+}
+
+impl<B: CoreBackend> ReadOnly<B> {
+    pub fn new(inner: B) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+}
+
+fn refuse<T>(operation: &str) -> Result<T> {
+    Err(Error::ReadOnly(operation.to_owned()))
+}
+
+#[async_trait]
+impl<B: CoreBackend> CoreBackend for ReadOnly<B> {
+    fn root_id(&self) -> DirectoryId {
+        self.inner.root_id()
+    }
+
+    async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
+        self.inner.fetch_directory(directory_id).await
+    }
+
+    async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
+        self.inner.fetch_directories(parent_id).await
+    }
+
+    async fn add_directory(&mut self, _: DirectoryId, _: String) -> Result<Directory> {
+        refuse("add_directory")
+    }
+
+    async fn remove_directory(&mut self, _: DirectoryId) -> Result<()> {
+        refuse("remove_directory")
+    }
+
+    async fn move_directory(&mut self, _: DirectoryId, _: DirectoryId) -> Result<()> {
+        refuse("move_directory")
+    }
+
+    async fn rename_directory(&mut self, _: DirectoryId, _: String) -> Result<()> {
+        refuse("rename_directory")
+    }
+
+    async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
+        self.inner.fetch_notes(directory_id).await
+    }
+
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        self.inner.fetch_children(directory_ids).await
+    }
+
+    async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
+        self.inner.fetch_note_content(note_id).await
+    }
+
+    async fn add_note(&mut self, _: DirectoryId, _: String) -> Result<Note> {
+        refuse("add_note")
+    }
+
+    async fn remove_note(&mut self, _: NoteId) -> Result<()> {
+        refuse("remove_note")
+    }
+
+    async fn rename_note(&mut self, _: NoteId, _: String) -> Result<()> {
+        refuse("rename_note")
+    }
+
+    async fn update_note_content(&mut self, _: NoteId, _: String) -> Result<()> {
+        refuse("update_note_content")
+    }
+
+    async fn move_note(&mut self, _: NoteId, _: DirectoryId) -> Result<()> {
+        refuse("move_note")
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        self.inner.fetch_attachments(note_id).await
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        self.inner.fetch_attachment_data(attachment_id).await
+    }
+
+    async fn add_attachment(&mut self, _: NoteId, _: String, _: Vec<u8>) -> Result<Attachment> {
+        refuse("add_attachment")
+    }
+
+    async fn remove_attachment(&mut self, _: AttachmentId) -> Result<()> {
+        refuse("remove_attachment")
+    }
+
+    async fn restore_directory(&mut self, _: Directory) -> Result<()> {
+        refuse("restore_directory")
+    }
+
+    async fn restore_note(&mut self, _: Note, _: String) -> Result<()> {
+        refuse("restore_note")
+    }
+
+    async fn restore_attachment(&mut self, _: Attachment, _: Vec<u8>) -> Result<()> {
+        refuse("restore_attachment")
+    }
+
+    async fn log(&mut self, category: String, message: String) -> Result<()> {
+        self.inner.log(category, message).await
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        self.inner.fetch_meta(key).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        self.inner.fetch_all_meta().await
+    }
+
+    async fn update_meta(&mut self, _: String, _: String) -> Result<()> {
+        refuse("update_meta")
+    }
+
+    /// Nothing can be written, so there is nothing to roll back either.
+    async fn begin(&mut self) -> Result<bool> {
+        Ok(true)
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        self.inner.sync_job()
+    }
+
+    fn sync_finished(&mut self) {
+        self.inner.sync_finished();
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.inner.sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.inner.take_rejected_writes()
+    }
+}
@@ -0,0 +1,268 @@
+use {
+    super::Layer,
+    crate::{
+        Result,
+        backend::{BackendBox, ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+        data::{Attachment, Directory, Note},
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    async_trait::async_trait,
+    std::time::{Duration, Instant},
+};
+
+/// `Log` category of the entries written by [`Timed`].
+pub const TIMING_LOG_CATEGORY: &str = "timing";
+
+/// Wraps backends in [`Timed`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimingLayer {
+    threshold: Duration,
+}
+
+impl TimingLayer {
+    /// Logs only calls taking at least `threshold`.
+    pub fn new(threshold: Duration) -> Self {
+        Self { threshold }
+    }
+}
+
+impl Layer for TimingLayer {
+    fn layer(&self, inner: BackendBox) -> BackendBox {
+        Box::new(Timed::new(inner, self.threshold))
+    }
+}
+
+/// Measures each call and writes its latency through [`CoreBackend::log`],
+/// e.g. `fetch_notes took 3ms`. Failing to log never fails the call.
+pub struct Timed<B> {
+    inner: B,
+    threshold: Duration,
+}
+
+impl<B: CoreBackend> Timed<B> {
+    pub fn new(inner: B, threshold: Duration) -> Self {
+        Self { inner, threshold }
+    }
+
+    pub fn into_inner(self) -> B {
+        self.inner
+    }
+
+    async fn record<T>(
+        &mut self,
+        operation: &str,
+        started: Instant,
+        result: Result<T>,
+    ) -> Result<T> {
+        let elapsed = started.elapsed();
+        if elapsed >= self.threshold {
+            let outcome = if result.is_ok() { "" } else { " and failed" };
+            let message = format!("{operation} took {}ms{outcome}", elapsed.as_millis());
+            let _ = self
+                .inner
+                .log(TIMING_LOG_CATEGORY.to_owned(), message)
+                .await;
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl<B: CoreBackend> CoreBackend for Timed<B> {
+    fn root_id(&self) -> DirectoryId {
+        self.inner.root_id()
+    }
+
+    async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
+        let started = Instant::now();
+        let result = self.inner.fetch_directory(directory_id).await;
+        self.record("fetch_directory", started, result).await
+    }
+
+    async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_directories(parent_id).await;
+        self.record("fetch_directories", started, result).await
+    }
+
+    async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
+        let started = Instant::now();
+        let result = self.inner.add_directory(parent_id, name).await;
+        self.record("add_directory", started, result).await
+    }
+
+    async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.remove_directory(directory_id).await;
+        self.record("remove_directory", started, result).await
+    }
+
+    async fn move_directory(
+        &mut self,
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    ) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.move_directory(directory_id, parent_id).await;
+        self.record("move_directory", started, result).await
+    }
+
+    async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.rename_directory(directory_id, name).await;
+        self.record("rename_directory", started, result).await
+    }
+
+    async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_notes(directory_id).await;
+        self.record("fetch_notes", started, result).await
+    }
+
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_children(directory_ids).await;
+        self.record("fetch_children", started, result).await
+    }
+
+    async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
+        let started = Instant::now();
+        let result = self.inner.fetch_note_content(note_id).await;
+        self.record("fetch_note_content", started, result).await
+    }
+
+    async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
+        let started = Instant::now();
+        let result = self.inner.add_note(directory_id, name).await;
+        self.record("add_note", started, result).await
+    }
+
+    async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.remove_note(note_id).await;
+        self.record("remove_note", started, result).await
+    }
+
+    async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.rename_note(note_id, name).await;
+        self.record("rename_note", started, result).await
+    }
+
+    async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.update_note_content(note_id, content).await;
+        self.record("update_note_content", started, result).await
+    }
+
+    async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.move_note(note_id, directory_id).await;
+        self.record("move_note", started, result).await
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_attachments(note_id).await;
+        self.record("fetch_attachments", started, result).await
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_attachment_data(attachment_id).await;
+        self.record("fetch_attachment_data", started, result).await
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        let started = Instant::now();
+        let result = self.inner.add_attachment(note_id, name, data).await;
+        self.record("add_attachment", started, result).await
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.remove_attachment(attachment_id).await;
+        self.record("remove_attachment", started, result).await
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.restore_directory(directory).await;
+        self.record("restore_directory", started, result).await
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.restore_note(note, content).await;
+        self.record("restore_note", started, result).await
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.restore_attachment(attachment, data).await;
+        self.record("restore_attachment", started, result).await
+    }
+
+    async fn log(&mut self, category: String, message: String) -> Result<()> {
+        self.inner.log(category, message).await
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_meta(key).await;
+        self.record("fetch_meta", started, result).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        let started = Instant::now();
+        let result = self.inner.fetch_all_meta().await;
+        self.record("fetch_all_meta", started, result).await
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        let started = Instant::now();
+        let result = self.inner.update_meta(key, value).await;
+        self.record("update_meta", started, result).await
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        self.inner.begin().await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        self.inner.commit().await
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        self.inner.rollback().await
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        self.inner.sync_job()
+    }
+
+    fn sync_finished(&mut self) {
+        self.inner.sync_finished();
+    }
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.inner.sync_trigger()
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.inner.connection_status()
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.inner.take_rejected_writes()
+    }
+}
@@ -1,7 +1,12 @@
 mod native;
 pub use native::*;
 
+mod attachment;
 mod core_backend;
 mod directory;
+mod fsck;
+pub use fsck::{FsckIssue, FsckReport};
+mod integrity;
 mod log;
+mod meta;
 mod note;
@@ -0,0 +1,165 @@
+use {
+    super::{Db, Execute},
+    crate::{
+        Result,
+        data::Attachment,
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{bytea, col, num, table, text, uuid},
+            row_conversion::SelectExt,
+        },
+    },
+    uuid::Uuid,
+};
+
+#[derive(FromGlueRow)]
+struct AttachmentRow {
+    id: String,
+    note_id: String,
+    name: String,
+    size: i64,
+    created_at: String,
+}
+
+impl From<AttachmentRow> for Attachment {
+    fn from(row: AttachmentRow) -> Self {
+        Self {
+            id: row.id,
+            note_id: row.note_id,
+            name: row.name,
+            size: row.size as u64,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(FromGlueRow)]
+struct AttachmentDataRow {
+    data: Vec<u8>,
+}
+
+const ATTACHMENT_COLUMNS: [&str; 5] = ["id", "note_id", "name", "size", "created_at"];
+
+impl Db {
+    pub async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        let attachments = table("Attachment")
+            .select()
+            .filter(col("note_id").eq(uuid(note_id)))
+            .project(ATTACHMENT_COLUMNS.to_vec())
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<AttachmentRow>()?
+            .into_iter()
+            .map(Attachment::from)
+            .collect();
+
+        Ok(attachments)
+    }
+
+    pub async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        let row = table("Attachment")
+            .select()
+            .filter(col("id").eq(uuid(attachment_id)))
+            .project("data")
+            .execute(&mut self.storage)
+            .await?
+            .one_as::<AttachmentDataRow>()?;
+
+        Ok(row.data)
+    }
+
+    pub async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        self.ensure_note(&note_id).await?;
+
+        let id = Uuid::now_v7().to_string();
+        let size = data.len() as i64;
+
+        table("Attachment")
+            .insert()
+            .columns(vec!["id", "note_id", "name", "size", "data"])
+            .values(vec![vec![
+                uuid(id.clone()),
+                uuid(note_id),
+                text(name),
+                num(size),
+                bytea(data),
+            ]])
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(table("Attachment")
+            .select()
+            .filter(col("id").eq(uuid(id)))
+            .project(ATTACHMENT_COLUMNS.to_vec())
+            .execute(&mut self.storage)
+            .await?
+            .one_as::<AttachmentRow>()
+            .map(Attachment::from)?)
+    }
+
+    /// Inserts `attachment` with its original id and creation time. The size is
+    /// taken from `data`.
+    pub async fn restore_attachment(
+        &mut self,
+        attachment: Attachment,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        self.ensure_note(&attachment.note_id).await?;
+        let size = data.len() as i64;
+
+        table("Attachment")
+            .insert()
+            .columns(vec!["id", "note_id", "name", "size", "data", "created_at"])
+            .values(vec![vec![
+                uuid(attachment.id),
+                uuid(attachment.note_id),
+                text(attachment.name),
+                num(size),
+                bytea(data),
+                text(attachment.created_at),
+            ]])
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        table("Attachment")
+            .delete()
+            .filter(col("id").eq(uuid(attachment_id)))
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn remove_note_attachments(&mut self, note_id: NoteId) -> Result<()> {
+        table("Attachment")
+            .delete()
+            .filter(col("note_id").eq(uuid(note_id)))
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn remove_directory_attachments(
+        &mut self,
+        directory_id: DirectoryId,
+    ) -> Result<()> {
+        for note in self.fetch_notes(directory_id).await? {
+            self.remove_note_attachments(note.id).await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,35 @@
 use crate::{
     Result,
-    backend::{CoreBackend, SyncJob},
-    data::{Directory, Note},
-    types::{DirectoryId, NoteId},
+    backend::{ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+    data::{Attachment, Directory, Note},
+    types::{AttachmentId, DirectoryId, NoteId},
 };
 use async_trait::async_trait;
 
 use super::Db;
 
+impl Db {
+    /// Names are only looked up when the change will be synced.
+    async fn note_name(&mut self, note_id: &NoteId) -> Option<String> {
+        if !self.tracks_changes() {
+            return None;
+        }
+
+        self.fetch_note_name(note_id.clone()).await.ok()
+    }
+
+    async fn directory_name(&mut self, directory_id: &DirectoryId) -> Option<String> {
+        if !self.tracks_changes() {
+            return None;
+        }
+
+        self.fetch_directory(directory_id.clone())
+            .await
+            .ok()
+            .map(|directory| directory.name)
+    }
+}
+
 #[async_trait]
 impl CoreBackend for Db {
     fn root_id(&self) -> DirectoryId {
@@ -23,11 +45,22 @@ impl CoreBackend for Db {
     }
 
     async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
-        Db::add_directory(self, parent_id, name).await
+        let directory = Db::add_directory(self, parent_id, name).await?;
+        self.track(
+            "Add directory",
+            directory.id.clone(),
+            Some(directory.name.clone()),
+        );
+
+        Ok(directory)
     }
 
     async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
-        Db::remove_directory(self, directory_id).await
+        let name = self.directory_name(&directory_id).await;
+        Db::remove_directory(self, directory_id.clone()).await?;
+        self.track("Remove directory", directory_id, name);
+
+        Ok(())
     }
 
     async fn move_directory(
@@ -35,46 +68,171 @@ impl CoreBackend for Db {
         directory_id: DirectoryId,
         parent_id: DirectoryId,
     ) -> Result<()> {
-        Db::move_directory(self, directory_id, parent_id).await
+        Db::move_directory(self, directory_id.clone(), parent_id).await?;
+        let name = self.directory_name(&directory_id).await;
+        self.track("Move directory", directory_id, name);
+
+        Ok(())
     }
 
     async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
-        Db::rename_directory(self, directory_id, name).await
+        Db::rename_directory(self, directory_id.clone(), name.clone()).await?;
+        self.track("Rename directory", directory_id, Some(name));
+
+        Ok(())
     }
 
     async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
         Db::fetch_notes(self, directory_id).await
     }
 
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        let mut children = Vec::with_capacity(directory_ids.len());
+        for directory_id in directory_ids {
+            let notes = Db::fetch_notes(self, directory_id.clone()).await?;
+            let directories = Db::fetch_directories(self, directory_id).await?;
+            children.push((notes, directories));
+        }
+
+        Ok(children)
+    }
+
     async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
         Db::fetch_note_content(self, note_id).await
     }
 
     async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
-        Db::add_note(self, directory_id, name).await
+        let note = Db::add_note(self, directory_id, name).await?;
+        self.track("Add note", note.id.clone(), Some(note.name.clone()));
+
+        Ok(note)
     }
 
     async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
-        Db::remove_note(self, note_id).await
+        let name = self.note_name(&note_id).await;
+        Db::remove_note(self, note_id.clone()).await?;
+        self.track("Remove note", note_id, name);
+
+        Ok(())
     }
 
     async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
-        Db::rename_note(self, note_id, name).await
+        Db::rename_note(self, note_id.clone(), name.clone()).await?;
+        self.track("Rename note", note_id, Some(name));
+
+        Ok(())
     }
 
     async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
-        Db::update_note_content(self, note_id, content).await
+        Db::update_note_content(self, note_id.clone(), content).await?;
+        let name = self.note_name(&note_id).await;
+        self.track("Edit note", note_id, name);
+
+        Ok(())
     }
 
     async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
-        Db::move_note(self, note_id, directory_id).await
+        Db::move_note(self, note_id.clone(), directory_id).await?;
+        let name = self.note_name(&note_id).await;
+        self.track("Move note", note_id, name);
+
+        Ok(())
+    }
+
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        Db::fetch_attachments(self, note_id).await
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        Db::fetch_attachment_data(self, attachment_id).await
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        let attachment = Db::add_attachment(self, note_id.clone(), name, data).await?;
+        let name = self.note_name(&note_id).await;
+        self.track("Add attachment to note", note_id, name);
+
+        Ok(attachment)
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        Db::remove_attachment(self, attachment_id.clone()).await?;
+        self.track("Remove attachment", attachment_id, None);
+
+        Ok(())
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        let (id, name) = (directory.id.clone(), directory.name.clone());
+        Db::restore_directory(self, directory).await?;
+        self.track("Restore directory", id, Some(name));
+
+        Ok(())
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        let (id, name) = (note.id.clone(), note.name.clone());
+        Db::restore_note(self, note, content).await?;
+        self.track("Restore note", id, Some(name));
+
+        Ok(())
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        Db::restore_attachment(self, attachment, data).await
     }
 
     async fn log(&mut self, category: String, message: String) -> Result<()> {
         Db::log(self, category, message).await
     }
 
-    fn sync_job(&self) -> Option<SyncJob> {
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        Db::fetch_meta(self, key).await
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        Db::fetch_all_meta(self).await
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        Db::update_meta(self, key, value).await
+    }
+
+    async fn begin(&mut self) -> Result<bool> {
+        Db::begin(self).await
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        Db::commit(self).await
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        Db::rollback(self).await
+    }
+
+    fn sync_job(&mut self) -> Option<SyncJob> {
         Db::sync_job(self)
     }
+
+    fn sync_finished(&mut self) {}
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        self.sync_options.trigger
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        None
+    }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        Vec::new()
+    }
 }
@@ -1,11 +1,11 @@
 use {
     super::{Db, Execute},
-    crate::{Result, data::Directory, types::DirectoryId},
+    crate::{Error, IntegrityError, Result, data::Directory, types::DirectoryId},
     async_recursion::async_recursion,
     gluesql::{
         FromGlueRow,
         core::{
-            ast_builder::{col, function::now, table, text, uuid},
+            ast_builder::{col, function::now, null, table, text, uuid},
             row_conversion::SelectExt,
         },
     },
@@ -76,6 +76,8 @@ impl Db {
         parent_id: DirectoryId,
         name: String,
     ) -> Result<Directory> {
+        self.ensure_directory(&parent_id).await?;
+
         let id = Uuid::now_v7().to_string();
         table("Directory")
             .insert()
@@ -87,8 +89,67 @@ impl Db {
         self.fetch_directory(id).await
     }
 
+    /// Inserts `directory` with its original id and timestamps. A root directory,
+    /// whose `parent_id` is its own id, takes the place of the empty root.
+    pub async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        let Directory {
+            id,
+            parent_id,
+            name,
+            created_at,
+            updated_at,
+        } = directory;
+        let is_root = id == parent_id;
+
+        if is_root {
+            let root_id = self.root_id.clone();
+            let is_empty = self.fetch_directories(root_id.clone()).await?.is_empty()
+                && self.fetch_notes(root_id.clone()).await?.is_empty();
+            if !is_empty {
+                return Err(Error::InvalidState(
+                    "the root directory can only be restored into an empty notebook".to_owned(),
+                ));
+            }
+
+            table("Directory")
+                .delete()
+                .filter(col("id").eq(uuid(root_id)))
+                .execute(&mut self.storage)
+                .await?;
+        } else {
+            self.ensure_directory(&parent_id).await?;
+        }
+
+        let parent_id = if is_root { null() } else { uuid(parent_id) };
+        table("Directory")
+            .insert()
+            .columns(vec!["id", "parent_id", "name", "created_at", "updated_at"])
+            .values(vec![vec![
+                uuid(id.clone()),
+                parent_id,
+                text(name),
+                text(created_at),
+                text(updated_at),
+            ]])
+            .execute(&mut self.storage)
+            .await?;
+
+        if is_root {
+            self.root_id = id;
+        }
+
+        Ok(())
+    }
+
     #[async_recursion]
     pub async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
+        if directory_id == self.root_id {
+            return Err(IntegrityError::RootDirectory.into());
+        }
+
+        self.remove_directory_attachments(directory_id.clone())
+            .await?;
+
         table("Note")
             .delete()
             .filter(col("directory_id").eq(uuid(directory_id.clone())))
@@ -114,6 +175,8 @@ impl Db {
         directory_id: DirectoryId,
         parent_id: DirectoryId,
     ) -> Result<()> {
+        self.ensure_movable(&directory_id, &parent_id).await?;
+
         table("Directory")
             .update()
             .filter(col("id").eq(uuid(directory_id)))
@@ -0,0 +1,257 @@
+use {
+    super::{Db, Execute},
+    crate::{
+        Result,
+        types::{AttachmentId, DirectoryId, NoteId},
+    },
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{col, function::now, table, uuid},
+            row_conversion::SelectExt,
+        },
+    },
+    std::{
+        collections::{BTreeMap, HashSet},
+        fmt,
+    },
+};
+
+/// Broken link between notebook rows found by [`Db::fsck`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FsckIssue {
+    /// Directory without a parent other than the notebook root.
+    DuplicateRoot { directory_id: DirectoryId },
+    /// Directory whose parent does not exist.
+    OrphanDirectory {
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    },
+    /// Directories whose parents lead back to themselves instead of the root.
+    Cycle { directory_ids: Vec<DirectoryId> },
+    /// Note in a directory which does not exist.
+    OrphanNote {
+        note_id: NoteId,
+        directory_id: DirectoryId,
+    },
+    /// Attachment of a note which does not exist.
+    OrphanAttachment {
+        attachment_id: AttachmentId,
+        note_id: NoteId,
+    },
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateRoot { directory_id } => {
+                write!(f, "directory {directory_id} is a second root")
+            }
+            Self::OrphanDirectory {
+                directory_id,
+                parent_id,
+            } => write!(
+                f,
+                "directory {directory_id} is in missing directory {parent_id}"
+            ),
+            Self::Cycle { directory_ids } => {
+                write!(f, "directories {} form a cycle", directory_ids.join(" -> "))
+            }
+            Self::OrphanNote {
+                note_id,
+                directory_id,
+            } => write!(f, "note {note_id} is in missing directory {directory_id}"),
+            Self::OrphanAttachment {
+                attachment_id,
+                note_id,
+            } => write!(
+                f,
+                "attachment {attachment_id} belongs to missing note {note_id}"
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FsckReport {
+    pub issues: Vec<FsckIssue>,
+    /// Whether the issues were repaired.
+    pub repaired: bool,
+}
+
+#[derive(FromGlueRow)]
+struct DirectoryLinkRow {
+    id: String,
+    parent_id: Option<String>,
+}
+
+#[derive(FromGlueRow)]
+struct NoteLinkRow {
+    id: String,
+    directory_id: String,
+}
+
+#[derive(FromGlueRow)]
+struct AttachmentLinkRow {
+    id: String,
+    note_id: String,
+}
+
+impl Db {
+    /// Looks for rows which are not reachable from the root. With `repair`,
+    /// stray directories and notes are moved into the root, one directory of
+    /// each cycle is, and orphaned attachments are removed.
+    pub async fn fsck(&mut self, repair: bool) -> Result<FsckReport> {
+        let parents: BTreeMap<DirectoryId, Option<DirectoryId>> = table("Directory")
+            .select()
+            .project(vec!["id", "parent_id"])
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<DirectoryLinkRow>()?
+            .into_iter()
+            .map(|row| (row.id, row.parent_id))
+            .collect();
+        let notes = table("Note")
+            .select()
+            .project(vec!["id", "directory_id"])
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<NoteLinkRow>()?;
+        let attachments = table("Attachment")
+            .select()
+            .project(vec!["id", "note_id"])
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<AttachmentLinkRow>()?;
+
+        let mut issues = directory_issues(&self.root_id, &parents);
+        issues.extend(
+            notes
+                .iter()
+                .filter(|note| !parents.contains_key(&note.directory_id))
+                .map(|note| FsckIssue::OrphanNote {
+                    note_id: note.id.clone(),
+                    directory_id: note.directory_id.clone(),
+                }),
+        );
+        let note_ids: HashSet<&NoteId> = notes.iter().map(|note| &note.id).collect();
+        issues.extend(
+            attachments
+                .iter()
+                .filter(|attachment| !note_ids.contains(&attachment.note_id))
+                .map(|attachment| FsckIssue::OrphanAttachment {
+                    attachment_id: attachment.id.clone(),
+                    note_id: attachment.note_id.clone(),
+                }),
+        );
+
+        if repair {
+            for issue in &issues {
+                self.repair(issue).await?;
+            }
+        }
+
+        Ok(FsckReport {
+            repaired: repair && !issues.is_empty(),
+            issues,
+        })
+    }
+
+    async fn repair(&mut self, issue: &FsckIssue) -> Result<()> {
+        let root_id = self.root_id.clone();
+        match issue {
+            FsckIssue::DuplicateRoot { directory_id }
+            | FsckIssue::OrphanDirectory { directory_id, .. } => {
+                self.reparent(directory_id, &root_id).await?;
+            }
+            FsckIssue::Cycle { directory_ids } => {
+                if let Some(directory_id) = directory_ids.first() {
+                    self.reparent(directory_id, &root_id).await?;
+                }
+            }
+            FsckIssue::OrphanNote { note_id, .. } => {
+                table("Note")
+                    .update()
+                    .filter(col("id").eq(uuid(note_id.clone())))
+                    .set("directory_id", uuid(root_id))
+                    .set("updated_at", now())
+                    .execute(&mut self.storage)
+                    .await?;
+                self.track("Move note", note_id.clone(), None);
+            }
+            FsckIssue::OrphanAttachment { attachment_id, .. } => {
+                self.remove_attachment(attachment_id.clone()).await?;
+                self.track("Remove attachment", attachment_id.clone(), None);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves without the checks of `move_directory`, which refuse to touch
+    /// broken rows.
+    async fn reparent(
+        &mut self,
+        directory_id: &DirectoryId,
+        parent_id: &DirectoryId,
+    ) -> Result<()> {
+        table("Directory")
+            .update()
+            .filter(col("id").eq(uuid(directory_id.clone())))
+            .set("parent_id", uuid(parent_id.clone()))
+            .set("updated_at", now())
+            .execute(&mut self.storage)
+            .await?;
+        self.track("Move directory", directory_id.clone(), None);
+
+        Ok(())
+    }
+}
+
+/// Classifies every directory the root cannot reach. Directories below a
+/// broken one are fine once it is repaired, so only the broken one is listed.
+fn directory_issues(
+    root_id: &DirectoryId,
+    parents: &BTreeMap<DirectoryId, Option<DirectoryId>>,
+) -> Vec<FsckIssue> {
+    let mut issues = Vec::new();
+    let mut settled: HashSet<&DirectoryId> = HashSet::from([root_id]);
+
+    for start in parents.keys() {
+        let mut path: Vec<&DirectoryId> = Vec::new();
+        let mut current = start;
+        loop {
+            if settled.contains(current) {
+                break;
+            }
+            if let Some(position) = path.iter().position(|id| *id == current) {
+                issues.push(FsckIssue::Cycle {
+                    directory_ids: path[position..].iter().map(|id| (*id).clone()).collect(),
+                });
+                break;
+            }
+            path.push(current);
+
+            match parents.get(current) {
+                Some(Some(parent_id)) if parents.contains_key(parent_id) => current = parent_id,
+                Some(Some(parent_id)) => {
+                    issues.push(FsckIssue::OrphanDirectory {
+                        directory_id: current.clone(),
+                        parent_id: parent_id.clone(),
+                    });
+                    break;
+                }
+                _ => {
+                    issues.push(FsckIssue::DuplicateRoot {
+                        directory_id: current.clone(),
+                    });
+                    break;
+                }
+            }
+        }
+
+        settled.extend(path);
+    }
+
+    issues
+}
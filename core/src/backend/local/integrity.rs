@@ -0,0 +1,94 @@
+use {
+    super::{Db, Execute},
+    crate::{
+        IntegrityError, Result,
+        types::{DirectoryId, NoteId},
+    },
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{col, table, uuid},
+            row_conversion::SelectExt,
+        },
+    },
+    std::collections::HashSet,
+};
+
+#[derive(FromGlueRow)]
+struct ParentRow {
+    parent_id: Option<String>,
+}
+
+impl Db {
+    pub(super) async fn ensure_directory(&mut self, directory_id: &DirectoryId) -> Result<()> {
+        self.fetch_parent_id(directory_id).await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn ensure_note(&mut self, note_id: &NoteId) -> Result<()> {
+        let exists = table("Note")
+            .select()
+            .filter(col("id").eq(uuid(note_id.clone())))
+            .project(col("id"))
+            .execute(&mut self.storage)
+            .await?
+            .select()
+            .expect("Note query must yield a result set")
+            .count()
+            > 0;
+
+        if !exists {
+            return Err(IntegrityError::MissingNote(note_id.clone()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks that `directory_id` can be moved under `parent_id`: both exist,
+    /// the root stays in place and no directory ends up inside itself.
+    pub(super) async fn ensure_movable(
+        &mut self,
+        directory_id: &DirectoryId,
+        parent_id: &DirectoryId,
+    ) -> Result<()> {
+        if *directory_id == self.root_id {
+            return Err(IntegrityError::RootDirectory.into());
+        }
+        self.ensure_directory(directory_id).await?;
+
+        let mut visited = HashSet::new();
+        let mut current = Some(parent_id.clone());
+        while let Some(id) = current {
+            if id == *directory_id {
+                return Err(IntegrityError::Cycle {
+                    directory_id: directory_id.clone(),
+                    parent_id: parent_id.clone(),
+                }
+                .into());
+            }
+            if id == self.root_id || !visited.insert(id.clone()) {
+                break;
+            }
+
+            current = self.fetch_parent_id(&id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Parent of an existing directory, `None` for roots.
+    async fn fetch_parent_id(&mut self, directory_id: &DirectoryId) -> Result<Option<DirectoryId>> {
+        table("Directory")
+            .select()
+            .filter(col("id").eq(uuid(directory_id.clone())))
+            .project(col("parent_id"))
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<ParentRow>()?
+            .into_iter()
+            .next()
+            .map(|row| row.parent_id)
+            .ok_or_else(|| IntegrityError::MissingDirectory(directory_id.clone()).into())
+    }
+}
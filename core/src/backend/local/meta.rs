@@ -0,0 +1,70 @@
+use {
+    super::{Db, Execute},
+    crate::Result,
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{col, table, text},
+            row_conversion::SelectExt,
+        },
+    },
+};
+
+#[derive(FromGlueRow)]
+struct MetaValueRow {
+    value: String,
+}
+
+#[derive(FromGlueRow)]
+struct MetaRow {
+    key: String,
+    value: String,
+}
+
+impl Db {
+    pub async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        let value = table("Meta")
+            .select()
+            .filter(col("key").eq(text(key)))
+            .project("value")
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<MetaValueRow>()?
+            .into_iter()
+            .next()
+            .map(|row| row.value);
+
+        Ok(value)
+    }
+
+    pub async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        let entries = table("Meta")
+            .select()
+            .project(vec!["key", "value"])
+            .execute(&mut self.storage)
+            .await?
+            .rows_as::<MetaRow>()?
+            .into_iter()
+            .map(|row| (row.key, row.value))
+            .collect();
+
+        Ok(entries)
+    }
+
+    pub async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        table("Meta")
+            .delete()
+            .filter(col("key").eq(text(key.clone())))
+            .execute(&mut self.storage)
+            .await?;
+
+        table("Meta")
+            .insert()
+            .columns(vec!["key", "value"])
+            .values(vec![vec![text(key), text(value)]])
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(())
+    }
+}
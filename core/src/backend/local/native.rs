@@ -1,5 +1,10 @@
 use {
-    crate::{Error, Result, backend::SyncJob, schema::setup, types::DirectoryId},
+    crate::{
+        Error, Result,
+        backend::{GitSync, GitSyncOptions, SyncChange, SyncJob},
+        schema::{root_id, setup},
+        types::DirectoryId,
+    },
     async_trait::async_trait,
     gluesql::{
         core::ast_builder::Build,
@@ -13,6 +18,8 @@ use {
 pub struct Db {
     pub storage: Storage,
     pub root_id: DirectoryId,
+    pub sync_options: GitSyncOptions,
+    changes: Vec<SyncChange>,
 }
 
 pub enum Storage {
@@ -23,25 +30,19 @@ pub enum Storage {
     Mongo(Glue<MongoStorage>),
 }
 
-impl Db {
-    pub async fn memory() -> Result<Self> {
-        let glue = Glue::new(MemoryStorage::default());
-        let mut storage = Storage::Memory(glue);
-
-        let root_id = setup(&mut storage).await?;
-
-        Ok(Self { storage, root_id })
+impl Storage {
+    pub fn memory() -> Self {
+        Storage::Memory(Glue::new(MemoryStorage::default()))
     }
 
-    pub async fn file(path: &str) -> Result<Self> {
-        let mut storage = FileStorage::new(path).map(Glue::new).map(Storage::File)?;
-
-        let root_id = setup(&mut storage).await?;
-
-        Ok(Self { storage, root_id })
+    pub fn file(path: &str) -> Result<Self> {
+        FileStorage::new(path)
+            .map(Glue::new)
+            .map(Storage::File)
+            .map_err(Into::into)
     }
 
-    pub async fn redb(path: &str) -> Result<Self> {
+    pub fn redb(path: &str) -> Result<Self> {
         if let Some(parent) = std::path::Path::new(path).parent()
             && !parent.as_os_str().is_empty()
         {
@@ -49,46 +50,120 @@ impl Db {
                 .map_err(|e| Error::BackendError(format!("failed to create directory: {e}")))?;
         }
 
-        let mut storage = RedbStorage::new(path).map(Glue::new).map(Storage::Redb)?;
-
-        let root_id = setup(&mut storage).await?;
-
-        Ok(Self { storage, root_id })
+        RedbStorage::new(path)
+            .map(Glue::new)
+            .map(Storage::Redb)
+            .map_err(Into::into)
     }
 
-    pub async fn git(path: &str, remote: String, branch: String) -> Result<Self> {
+    pub fn git(path: &str, remote: String, branch: String) -> Result<Self> {
         let mut storage = GitStorage::open(path, StorageType::File)?;
         storage.set_remote(remote);
         storage.set_branch(branch);
 
-        let mut storage = Storage::Git(Glue::new(storage));
-        let root_id = setup(&mut storage).await?;
-
-        Ok(Self { storage, root_id })
+        Ok(Storage::Git(Glue::new(storage)))
     }
 
     pub async fn mongo(conn_str: &str, db_name: &str) -> Result<Self> {
-        let mut storage = MongoStorage::new(conn_str, db_name)
+        MongoStorage::new(conn_str, db_name)
             .await
             .map(Glue::new)
-            .map(Storage::Mongo)?;
+            .map(Storage::Mongo)
+            .map_err(Into::into)
+    }
+}
 
+impl Db {
+    /// Migrates the storage to the current schema and opens it as a notebook.
+    pub async fn open(mut storage: Storage) -> Result<Self> {
         let root_id = setup(&mut storage).await?;
+        let sync_options = match &storage {
+            Storage::Git(glue) => GitSyncOptions::load(&glue.storage.path)?,
+            _ => GitSyncOptions::default(),
+        };
+
+        Ok(Self {
+            storage,
+            root_id,
+            sync_options,
+            changes: Vec::new(),
+        })
+    }
+
+    pub async fn memory() -> Result<Self> {
+        Self::open(Storage::memory()).await
+    }
 
-        Ok(Self { storage, root_id })
+    pub async fn file(path: &str) -> Result<Self> {
+        Self::open(Storage::file(path)?).await
+    }
+
+    pub async fn redb(path: &str) -> Result<Self> {
+        Self::open(Storage::redb(path)?).await
+    }
+
+    pub async fn git(path: &str, remote: String, branch: String) -> Result<Self> {
+        Self::open(Storage::git(path, remote, branch)?).await
     }
 
-    pub fn sync_job(&self) -> Option<SyncJob> {
+    pub async fn mongo(conn_str: &str, db_name: &str) -> Result<Self> {
+        Self::open(Storage::mongo(conn_str, db_name).await?).await
+    }
+
+    pub fn sync_job(&mut self) -> Option<SyncJob> {
         if let Storage::Git(glue) = &self.storage {
-            Some(SyncJob::Git {
+            Some(SyncJob::Git(GitSync {
                 path: glue.storage.path.clone(),
                 remote: glue.storage.remote.clone(),
                 branch: glue.storage.branch.clone(),
-            })
+                mode: self.sync_options.mode,
+                changes: std::mem::take(&mut self.changes),
+            }))
         } else {
             None
         }
     }
+
+    /// Only redb storage can roll back, the others write every statement
+    /// right away.
+    pub async fn begin(&mut self) -> Result<bool> {
+        match &mut self.storage {
+            Storage::Redb(glue) => {
+                glue.execute("BEGIN").await?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub async fn commit(&mut self) -> Result<()> {
+        if let Storage::Redb(glue) = &mut self.storage {
+            glue.execute("COMMIT").await?;
+        }
+
+        Ok(())
+    }
+
+    /// A restored root may be rolled back as well, so the root is read again.
+    pub async fn rollback(&mut self) -> Result<()> {
+        if let Storage::Redb(glue) = &mut self.storage {
+            glue.execute("ROLLBACK").await?;
+            self.root_id = root_id(&mut self.storage).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Remembers a change for the next sync commit message. Only Git storage syncs.
+    pub(crate) fn track(&mut self, action: &'static str, id: String, name: Option<String>) {
+        if self.tracks_changes() {
+            self.changes.push(SyncChange { action, id, name });
+        }
+    }
+
+    pub(crate) fn tracks_changes(&self) -> bool {
+        matches!(self.storage, Storage::Git(_))
+    }
 }
 
 #[async_trait]
@@ -41,6 +41,11 @@ struct NoteContentRow {
     content: String,
 }
 
+#[derive(FromGlueRow)]
+struct NoteNameRow {
+    name: String,
+}
+
 impl Db {
     pub async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
         let row = table("Note")
@@ -54,6 +59,18 @@ impl Db {
         Ok(row.content)
     }
 
+    pub(crate) async fn fetch_note_name(&mut self, note_id: NoteId) -> Result<String> {
+        let row = table("Note")
+            .select()
+            .filter(col("id").eq(uuid(note_id)))
+            .project(col("name"))
+            .execute(&mut self.storage)
+            .await?
+            .one_as::<NoteNameRow>()?;
+
+        Ok(row.name)
+    }
+
     pub async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
         let notes = table("Note")
             .select()
@@ -76,6 +93,8 @@ impl Db {
     }
 
     pub async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
+        self.ensure_directory(&directory_id).await?;
+
         let id = Uuid::now_v7().to_string();
         table("Note")
             .insert()
@@ -100,7 +119,37 @@ impl Db {
             .map(Note::from)?)
     }
 
+    /// Inserts `note` with its original id and timestamps.
+    pub async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        self.ensure_directory(&note.directory_id).await?;
+
+        table("Note")
+            .insert()
+            .columns(vec![
+                "id",
+                "directory_id",
+                "name",
+                "created_at",
+                "updated_at",
+                "content",
+            ])
+            .values(vec![vec![
+                uuid(note.id),
+                uuid(note.directory_id),
+                text(note.name),
+                text(note.created_at),
+                text(note.updated_at),
+                text(content),
+            ]])
+            .execute(&mut self.storage)
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
+        self.remove_note_attachments(note_id.clone()).await?;
+
         table("Note")
             .delete()
             .filter(col("id").eq(uuid(note_id)))
@@ -135,6 +184,9 @@ impl Db {
     }
 
     pub async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
+        self.ensure_note(&note_id).await?;
+        self.ensure_directory(&directory_id).await?;
+
         table("Note")
             .update()
             .filter(col("id").eq(uuid(note_id)))
@@ -1,7 +1,15 @@
+mod cache;
 pub mod client;
+mod queue;
 pub mod request;
 pub mod response;
 pub mod server;
+mod tls;
 
 pub use client::ProxyClient;
-pub use server::ProxyServer;
+pub use server::{ProxyServer, SyncRun, TransferLimits};
+pub use tls::TlsOptions;
+
+/// Largest attachment slice sent in one request, in bytes before base64.
+/// Keeps every request well below the body limits of HTTP servers.
+pub const ATTACHMENT_CHUNK_SIZE: usize = 256 * 1024;
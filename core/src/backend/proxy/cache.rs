@@ -0,0 +1,412 @@
+use {
+    super::{request::ProxyRequest, response::ResultPayload},
+    crate::{
+        Error, Result,
+        data::{Attachment, Directory, Note},
+        types::{DirectoryId, NoteId},
+    },
+    serde::{Serialize, de::DeserializeOwned},
+    std::collections::{BTreeSet, HashMap, VecDeque},
+};
+
+/// Note contents beyond this many bytes push out the least recently stored
+/// ones. Listings are small next to the contents and are kept whole.
+const MAX_CONTENT_BYTES: usize = 32 * 1024 * 1024;
+
+/// Last known answers of the server, kept so the tree stays browsable while
+/// offline. Queued writes are applied here as well, so reads made before they
+/// are replayed already see them.
+///
+/// The cache is stored as one entry per answer, keyed like `notes:<id>`, and
+/// only the entries changed since the last [`ReadCache::take_changes`] are
+/// written back.
+#[derive(Clone, Debug, Default)]
+pub struct ReadCache {
+    pub root_id: DirectoryId,
+    directories: HashMap<DirectoryId, Directory>,
+    children: HashMap<DirectoryId, Vec<Directory>>,
+    notes: HashMap<DirectoryId, Vec<Note>>,
+    contents: HashMap<NoteId, String>,
+    /// Ids of `contents`, least recently stored first.
+    content_order: VecDeque<NoteId>,
+    attachments: HashMap<NoteId, Vec<Attachment>>,
+    meta: HashMap<String, String>,
+    /// Set once `FetchAllMeta` answered, after which `meta` holds every key.
+    all_meta: bool,
+    changed: BTreeSet<String>,
+}
+
+impl ReadCache {
+    pub fn new(root_id: DirectoryId) -> Self {
+        let mut cache = Self {
+            root_id,
+            ..Self::default()
+        };
+        cache.mark("root", "");
+
+        cache
+    }
+
+    /// Rebuilds the cache from the entries written by `take_changes`.
+    pub fn from_entries(entries: Vec<(String, String)>) -> Result<Self> {
+        let mut cache = Self::default();
+        for (key, value) in entries {
+            let (kind, id) = key.split_once(':').unwrap_or((key.as_str(), ""));
+            let id = id.to_owned();
+            match kind {
+                "root" => cache.root_id = parse(&value)?,
+                "all_meta" => cache.all_meta = parse(&value)?,
+                "directory" => {
+                    cache.directories.insert(id, parse(&value)?);
+                }
+                "children" => {
+                    cache.children.insert(id, parse(&value)?);
+                }
+                "notes" => {
+                    cache.notes.insert(id, parse(&value)?);
+                }
+                "content" => {
+                    cache.content_order.push_back(id.clone());
+                    cache.contents.insert(id, parse(&value)?);
+                }
+                "attachments" => {
+                    cache.attachments.insert(id, parse(&value)?);
+                }
+                "meta" => {
+                    cache.meta.insert(id, parse(&value)?);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// Entries changed since the last call, with `None` for removed ones.
+    pub fn take_changes(&mut self) -> Result<Vec<(String, Option<String>)>> {
+        std::mem::take(&mut self.changed)
+            .into_iter()
+            .map(|key| {
+                let value = self.entry(&key)?;
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn entry(&self, key: &str) -> Result<Option<String>> {
+        let (kind, id) = key.split_once(':').unwrap_or((key, ""));
+        match kind {
+            "root" => to_json(&self.root_id).map(Some),
+            "all_meta" => to_json(self.all_meta).map(Some),
+            "directory" => self.directories.get(id).map(to_json).transpose(),
+            "children" => self.children.get(id).map(to_json).transpose(),
+            "notes" => self.notes.get(id).map(to_json).transpose(),
+            "content" => self.contents.get(id).map(to_json).transpose(),
+            "attachments" => self.attachments.get(id).map(to_json).transpose(),
+            "meta" => self.meta.get(id).map(to_json).transpose(),
+            _ => Ok(None),
+        }
+    }
+
+    fn mark(&mut self, kind: &str, id: &str) {
+        self.changed.insert(if id.is_empty() {
+            kind.to_owned()
+        } else {
+            format!("{kind}:{id}")
+        });
+    }
+
+    /// Answers a read from the cache, `None` when it was never fetched.
+    pub fn get(&self, request: &ProxyRequest) -> Option<ResultPayload> {
+        use ProxyRequest::*;
+
+        let payload = match request {
+            RootId => ResultPayload::Id(self.root_id.clone()),
+            FetchDirectory { directory_id } => {
+                ResultPayload::Directory(self.directories.get(directory_id)?.clone())
+            }
+            FetchDirectories { parent_id } => {
+                ResultPayload::Directories(self.children.get(parent_id)?.clone())
+            }
+            FetchNotes { directory_id } => {
+                ResultPayload::Notes(self.notes.get(directory_id)?.clone())
+            }
+            FetchNoteContent { note_id } => {
+                ResultPayload::Text(self.contents.get(note_id)?.clone())
+            }
+            FetchAttachments { note_id } => {
+                ResultPayload::Attachments(self.attachments.get(note_id)?.clone())
+            }
+            FetchMeta { key } => match self.meta.get(key) {
+                Some(value) => ResultPayload::OptionalText(Some(value.clone())),
+                None if self.all_meta => ResultPayload::OptionalText(None),
+                None => return None,
+            },
+            FetchAllMeta if self.all_meta => {
+                let mut entries: Vec<_> = self
+                    .meta
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                entries.sort();
+                ResultPayload::Entries(entries)
+            }
+            _ => return None,
+        };
+
+        Some(payload)
+    }
+
+    /// Remembers the answer to a read.
+    pub fn store(&mut self, request: &ProxyRequest, payload: &ResultPayload) {
+        use ProxyRequest::*;
+
+        match (request, payload) {
+            (FetchDirectory { .. }, ResultPayload::Directory(directory)) => {
+                self.put_directory(directory.clone());
+            }
+            (FetchDirectories { parent_id }, ResultPayload::Directories(directories)) => {
+                for directory in directories {
+                    self.put_directory(directory.clone());
+                }
+                self.children.insert(parent_id.clone(), directories.clone());
+                self.mark("children", parent_id);
+            }
+            (FetchNotes { directory_id }, ResultPayload::Notes(notes)) => {
+                self.notes.insert(directory_id.clone(), notes.clone());
+                self.mark("notes", directory_id);
+            }
+            (FetchNoteContent { note_id }, ResultPayload::Text(content)) => {
+                self.insert_content(note_id.clone(), content.clone());
+            }
+            (FetchAttachments { note_id }, ResultPayload::Attachments(attachments)) => {
+                self.attachments
+                    .insert(note_id.clone(), attachments.clone());
+                self.mark("attachments", note_id);
+            }
+            (FetchMeta { key }, ResultPayload::OptionalText(value)) => {
+                match value {
+                    Some(value) => {
+                        self.meta.insert(key.clone(), value.clone());
+                    }
+                    None => {
+                        self.meta.remove(key);
+                    }
+                }
+                self.mark("meta", key);
+            }
+            (FetchAllMeta, ResultPayload::Entries(entries)) => {
+                let keys: Vec<String> = self.meta.keys().cloned().collect();
+                for key in keys.iter().chain(entries.iter().map(|(key, _)| key)) {
+                    self.mark("meta", key);
+                }
+                self.meta = entries.iter().cloned().collect();
+                self.all_meta = true;
+                self.mark("all_meta", "");
+            }
+            _ => {}
+        }
+    }
+
+    /// Applies a write, with `payload` holding what the server created for it.
+    pub fn apply(&mut self, request: &ProxyRequest, payload: &ResultPayload) {
+        use ProxyRequest::*;
+
+        match (request, payload) {
+            (AddDirectory { .. }, ResultPayload::Directory(directory))
+            | (RestoreDirectory { directory }, _) => {
+                // a new directory starts empty
+                self.children.insert(directory.id.clone(), Vec::new());
+                self.notes.insert(directory.id.clone(), Vec::new());
+                self.mark("children", &directory.id);
+                self.mark("notes", &directory.id);
+                self.insert_directory(directory.clone());
+            }
+            (AddNote { .. }, ResultPayload::Note(note)) => {
+                self.insert_note(note.clone(), String::new());
+            }
+            (RestoreNote { note, content }, _) => self.insert_note(note.clone(), content.clone()),
+            (RemoveDirectory { directory_id }, _) => {
+                if let Some(directory) = self.remove_directory(directory_id) {
+                    self.forget_directory(&directory.id);
+                }
+            }
+            (
+                MoveDirectory {
+                    directory_id,
+                    parent_id,
+                },
+                _,
+            ) => {
+                if let Some(mut directory) = self.remove_directory(directory_id) {
+                    directory.parent_id = parent_id.clone();
+                    self.insert_directory(directory);
+                }
+            }
+            (RenameDirectory { directory_id, name }, _) => {
+                if let Some(mut directory) = self.remove_directory(directory_id) {
+                    directory.name = name.clone();
+                    self.insert_directory(directory);
+                }
+            }
+            (RemoveNote { note_id }, _) => {
+                self.remove_note(note_id);
+                self.remove_content(note_id);
+                self.attachments.remove(note_id);
+                self.mark("attachments", note_id);
+            }
+            (RenameNote { note_id, name }, _) => {
+                if let Some(mut note) = self.remove_note(note_id) {
+                    note.name = name.clone();
+                    self.push_note(note);
+                }
+            }
+            (
+                MoveNote {
+                    note_id,
+                    directory_id,
+                },
+                _,
+            ) => {
+                if let Some(mut note) = self.remove_note(note_id) {
+                    note.directory_id = directory_id.clone();
+                    self.push_note(note);
+                }
+            }
+            (UpdateNoteContent { note_id, content }, _) => {
+                self.insert_content(note_id.clone(), content.clone());
+            }
+            (StartAttachmentUpload { .. }, ResultPayload::Attachment(attachment))
+            | (StartAttachmentRestore { attachment }, _) => {
+                if let Some(attachments) = self.attachments.get_mut(&attachment.note_id) {
+                    attachments.push(attachment.clone());
+                    self.mark("attachments", &attachment.note_id);
+                }
+            }
+            (RemoveAttachment { attachment_id }, _) => {
+                let note_id = self
+                    .attachments
+                    .iter_mut()
+                    .find_map(|(note_id, attachments)| {
+                        let index = attachments
+                            .iter()
+                            .position(|attachment| &attachment.id == attachment_id)?;
+                        attachments.remove(index);
+                        Some(note_id.clone())
+                    });
+                if let Some(note_id) = note_id {
+                    self.mark("attachments", &note_id);
+                }
+            }
+            (UpdateMeta { key, value }, _) => {
+                self.meta.insert(key.clone(), value.clone());
+                self.mark("meta", key);
+            }
+            _ => {}
+        }
+    }
+
+    fn put_directory(&mut self, directory: Directory) {
+        self.mark("directory", &directory.id);
+        self.directories.insert(directory.id.clone(), directory);
+    }
+
+    /// Listings which were never fetched stay unknown rather than partial.
+    fn insert_directory(&mut self, directory: Directory) {
+        if directory.id == directory.parent_id {
+            self.root_id = directory.id.clone();
+            self.mark("root", "");
+        } else if let Some(children) = self.children.get_mut(&directory.parent_id) {
+            children.push(directory.clone());
+            self.mark("children", &directory.parent_id);
+        }
+        self.put_directory(directory);
+    }
+
+    fn remove_directory(&mut self, directory_id: &DirectoryId) -> Option<Directory> {
+        let directory = self.directories.remove(directory_id)?;
+        self.mark("directory", directory_id);
+        if let Some(children) = self.children.get_mut(&directory.parent_id) {
+            children.retain(|child| &child.id != directory_id);
+            self.mark("children", &directory.parent_id);
+        }
+
+        Some(directory)
+    }
+
+    /// Drops everything cached below a removed directory.
+    fn forget_directory(&mut self, directory_id: &DirectoryId) {
+        self.mark("notes", directory_id);
+        for note in self.notes.remove(directory_id).unwrap_or_default() {
+            self.remove_content(&note.id);
+            self.attachments.remove(&note.id);
+            self.mark("attachments", &note.id);
+        }
+
+        self.mark("children", directory_id);
+        for child in self.children.remove(directory_id).unwrap_or_default() {
+            self.directories.remove(&child.id);
+            self.mark("directory", &child.id);
+            self.forget_directory(&child.id);
+        }
+    }
+
+    fn insert_content(&mut self, note_id: NoteId, content: String) {
+        self.content_order.retain(|id| id != &note_id);
+        self.content_order.push_back(note_id.clone());
+        self.mark("content", &note_id);
+        self.contents.insert(note_id, content);
+
+        let mut size: usize = self.contents.values().map(String::len).sum();
+        while size > MAX_CONTENT_BYTES && self.content_order.len() > 1 {
+            let Some(oldest) = self.content_order.pop_front() else {
+                break;
+            };
+            self.mark("content", &oldest);
+            if let Some(content) = self.contents.remove(&oldest) {
+                size -= content.len();
+            }
+        }
+    }
+
+    fn remove_content(&mut self, note_id: &NoteId) {
+        self.content_order.retain(|id| id != note_id);
+        self.contents.remove(note_id);
+        self.mark("content", note_id);
+    }
+
+    fn insert_note(&mut self, note: Note, content: String) {
+        self.insert_content(note.id.clone(), content);
+        self.attachments.insert(note.id.clone(), Vec::new());
+        self.mark("attachments", &note.id);
+        self.push_note(note);
+    }
+
+    fn push_note(&mut self, note: Note) {
+        let directory_id = note.directory_id.clone();
+        if let Some(notes) = self.notes.get_mut(&directory_id) {
+            notes.push(note);
+            self.mark("notes", &directory_id);
+        }
+    }
+
+    fn remove_note(&mut self, note_id: &NoteId) -> Option<Note> {
+        let note = self.notes.values_mut().find_map(|notes| {
+            let index = notes.iter().position(|note| &note.id == note_id)?;
+            Some(notes.remove(index))
+        })?;
+        self.mark("notes", &note.directory_id);
+
+        Some(note)
+    }
+}
+
+fn to_json<T: Serialize>(value: T) -> Result<String> {
+    serde_json::to_string(&value)
+        .map_err(|err| Error::Proxy(format!("failed to save offline cache: {err}")))
+}
+
+fn parse<T: DeserializeOwned>(value: &str) -> Result<T> {
+    serde_json::from_str(value)
+        .map_err(|err| Error::Proxy(format!("offline cache is corrupted: {err}")))
+}
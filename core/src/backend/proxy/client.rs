@@ -1,65 +1,481 @@
+use super::ATTACHMENT_CHUNK_SIZE;
+use super::cache::ReadCache;
+use super::queue::WriteQueue;
 use super::request::ProxyRequest;
 use super::response::{ProxyResponse, ResultPayload};
+use super::tls::TlsOptions;
 use crate::{
     Error, Result,
-    backend::{CoreBackend, SyncJob},
-    data::{Directory, Note},
-    types::{DirectoryId, NoteId},
+    backend::{ConnectionStatus, CoreBackend, SyncJob, SyncTrigger},
+    data::{Attachment, Directory, Note},
+    types::{AttachmentId, DirectoryId, NoteId},
 };
 use async_trait::async_trait;
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use gluesql::core::chrono::Utc;
 use reqwest::{Client, StatusCode};
+use std::time::{Duration, Instant};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a server which timed out is left alone before the next attempt.
+/// Refused connections fail fast, so they are retried on every request.
+const RETRY_INTERVAL: Duration = Duration::from_secs(10);
 
 pub struct ProxyClient {
     url: String,
     client: Client,
     root_id: DirectoryId,
     auth_token: Option<String>,
+    tls: TlsOptions,
+    offline: Option<Offline>,
+}
+
+/// Offline support, enabled by [`ProxyClient::connect_with_queue`].
+struct Offline {
+    queue: WriteQueue,
+    cache: ReadCache,
+    online: bool,
+    retry_at: Instant,
+    /// Refused writes not yet handed out by `take_rejected_writes`.
+    rejections: Vec<String>,
+}
+
+impl Offline {
+    fn went_offline(&mut self, err: &Error) {
+        let timed_out = matches!(err, Error::Reqwest(err) if err.is_timeout());
+
+        self.online = false;
+        self.retry_at = Instant::now()
+            + if timed_out {
+                RETRY_INTERVAL
+            } else {
+                Duration::ZERO
+            };
+    }
 }
 
 impl ProxyClient {
     pub async fn connect<U: Into<String>>(url: U, auth_token: Option<String>) -> Result<Self> {
+        Self::connect_with_tls(url, auth_token, TlsOptions::default()).await
+    }
+
+    /// Connects to an HTTPS server with a custom CA bundle or client identity.
+    pub async fn connect_with_tls<U: Into<String>>(
+        url: U,
+        auth_token: Option<String>,
+        tls: TlsOptions,
+    ) -> Result<Self> {
         let url = url.into();
-        let client = Client::new();
-        let mut request = client.post(&url).json(&ProxyRequest::RootId);
-        if let Some(token) = auth_token.as_ref() {
-            request = request.bearer_auth(token);
-        }
-        let resp = request.send().await?;
-        if resp.status() == StatusCode::UNAUTHORIZED {
-            return Err(Error::Proxy(
-                "proxy server rejected the authentication token".to_owned(),
-            ));
-        }
-        let resp: ProxyResponse = resp.json().await?;
-        let root_id = match resp {
-            ProxyResponse::Ok(ResultPayload::Id(id)) => id,
-            ProxyResponse::Err(e) => return Err(Error::Proxy(e)),
-            ProxyResponse::Ok(_) => {
-                return Err(Error::InvalidResponse("invalid response".to_owned()));
-            }
-        };
+        let client = http_client(&tls)?;
+        let root_id = fetch_root_id(&client, &url, auth_token.as_deref()).await?;
 
         Ok(Self {
             url,
             client,
             root_id,
             auth_token,
+            tls,
+            offline: None,
         })
     }
 
+    /// Connects with a write queue stored at `queue_path`. Writes made while
+    /// the server is unreachable are queued and replayed in order once it is
+    /// back, and reads are answered from the cache of earlier answers. The
+    /// notebook opens offline when it was opened online before.
+    pub async fn connect_with_queue<U: Into<String>>(
+        url: U,
+        auth_token: Option<String>,
+        tls: TlsOptions,
+        queue_path: &str,
+    ) -> Result<Self> {
+        let url = url.into();
+        let client = http_client(&tls)?;
+        let mut queue = WriteQueue::open(queue_path, &url).await?;
+        let cached = queue.load_cache().await?;
+
+        let (root_id, online, cache) =
+            match fetch_root_id(&client, &url, auth_token.as_deref()).await {
+                Ok(root_id) => {
+                    let cache = match cached {
+                        Some(cache) if cache.root_id == root_id => cache,
+                        cached => {
+                            if cached.is_some() {
+                                queue.clear_cache().await?;
+                            }
+
+                            ReadCache::new(root_id.clone())
+                        }
+                    };
+
+                    (root_id, true, cache)
+                }
+                Err(err) if is_unreachable(&err) => match cached {
+                    Some(cache) => (cache.root_id.clone(), false, cache),
+                    None => return Err(err),
+                },
+                Err(err) => return Err(err),
+            };
+
+        Ok(Self {
+            url,
+            client,
+            root_id,
+            auth_token,
+            tls,
+            offline: Some(Offline {
+                queue,
+                cache,
+                online,
+                retry_at: Instant::now(),
+                rejections: Vec::new(),
+            }),
+        })
+    }
+
+    /// Queued writes the server refused, oldest first, with its answer.
+    pub async fn rejected_writes(&mut self) -> Result<Vec<(ProxyRequest, String)>> {
+        match self.offline.as_mut() {
+            Some(offline) => offline.queue.rejected().await,
+            None => Ok(Vec::new()),
+        }
+    }
+
     async fn rpc(&self, req: ProxyRequest) -> Result<ProxyResponse> {
-        let mut request = self.client.post(&self.url).json(&req);
-        if let Some(token) = self.auth_token.as_ref() {
-            request = request.bearer_auth(token);
+        send(&self.client, &self.url, self.auth_token.as_deref(), &req).await
+    }
+
+    /// Sends a read, answered from the cache while the server is unreachable.
+    async fn read(&mut self, request: ProxyRequest) -> Result<ProxyResponse> {
+        if self.offline.is_none() {
+            return self.rpc(request).await;
+        }
+
+        if self.replay().await? {
+            match self.rpc(request.clone()).await {
+                Ok(response) => {
+                    if let (ProxyResponse::Ok(payload), Some(offline)) =
+                        (&response, self.offline.as_mut())
+                    {
+                        offline.cache.store(&request, payload);
+                        offline.queue.save_cache(&mut offline.cache).await?;
+                    }
+
+                    return Ok(response);
+                }
+                Err(err) if is_unreachable(&err) => {
+                    if let Some(offline) = self.offline.as_mut() {
+                        offline.went_offline(&err);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.offline
+            .as_ref()
+            .and_then(|offline| offline.cache.get(&request))
+            .map(ProxyResponse::Ok)
+            .ok_or_else(|| {
+                Error::Proxy(format!(
+                    "server is unreachable and {request} was not fetched before"
+                ))
+            })
+    }
+
+    /// Sends a write, queued while the server is unreachable. A write which
+    /// timed out fails instead, as it may have reached the server.
+    async fn write(&mut self, request: ProxyRequest) -> Result<ProxyResponse> {
+        if self.offline.is_none() {
+            return self.rpc(request).await;
+        }
+
+        if self.replay().await? {
+            match self.rpc(request.clone()).await {
+                Ok(response) => {
+                    if let (ProxyResponse::Ok(payload), Some(offline)) =
+                        (&response, self.offline.as_mut())
+                    {
+                        offline.cache.apply(&request, payload);
+                        offline.queue.save_cache(&mut offline.cache).await?;
+                    }
+
+                    return Ok(response);
+                }
+                Err(err) if is_unreachable(&err) => {
+                    if let Some(offline) = self.offline.as_mut() {
+                        offline.went_offline(&err);
+                    }
+                    // the server may have applied a write which timed out,
+                    // and replaying it could apply it twice
+                    if !is_unsent(&err) {
+                        return Err(err);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let (request, payload) = queued(request);
+        let Some(offline) = self.offline.as_mut() else {
+            unreachable!("offline support is enabled");
+        };
+        offline.queue.push(&request).await?;
+        offline.cache.apply(&request, &payload);
+        offline.queue.save_cache(&mut offline.cache).await?;
+
+        Ok(ProxyResponse::Ok(payload))
+    }
+
+    /// Sends the queued writes in order. Returns whether the server is
+    /// reachable and nothing is left in the queue. Writes the server refuses
+    /// are set aside and reported through `take_rejected_writes`, so one bad
+    /// write neither blocks the queue nor gets lost.
+    async fn replay(&mut self) -> Result<bool> {
+        let Self {
+            client,
+            url,
+            auth_token,
+            offline,
+            ..
+        } = self;
+        let Some(offline) = offline.as_mut() else {
+            return Ok(true);
+        };
+
+        if !offline.online && Instant::now() < offline.retry_at {
+            return Ok(false);
         }
-        let resp = request.send().await?;
-        if resp.status() == StatusCode::UNAUTHORIZED {
+
+        while let Some((seq, request)) = offline.queue.front().await? {
+            match send(client, url, auth_token.as_deref(), &request).await {
+                Ok(ProxyResponse::Ok(_)) => offline.queue.remove(seq).await?,
+                Ok(ProxyResponse::Err(e)) => {
+                    offline.queue.reject(seq, &request, &e).await?;
+                    offline
+                        .rejections
+                        .push(format!("queued {request} was rejected by the server: {e}"));
+                }
+                Err(err) if is_unreachable(&err) => {
+                    offline.went_offline(&err);
+                    return Ok(false);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        offline.online = true;
+        Ok(true)
+    }
+
+    /// Sends `requests` as one `ProxyRequest::Batch` and returns a response
+    /// for each. Batches are never queued: while the server is unreachable
+    /// only batches of reads are answered, from the cache.
+    pub async fn batch(
+        &mut self,
+        requests: Vec<ProxyRequest>,
+        atomic: bool,
+    ) -> Result<Vec<ProxyResponse>> {
+        if self.replay().await? {
+            let batch = ProxyRequest::Batch {
+                requests: requests.clone(),
+                atomic,
+            };
+            match self.rpc(batch).await {
+                Ok(ProxyResponse::Ok(ResultPayload::Batch(responses)))
+                    if responses.len() == requests.len() =>
+                {
+                    if let Some(offline) = self.offline.as_mut() {
+                        for (request, response) in requests.iter().zip(&responses) {
+                            if let ProxyResponse::Ok(payload) = response {
+                                if request.is_read() {
+                                    offline.cache.store(request, payload);
+                                } else {
+                                    offline.cache.apply(request, payload);
+                                }
+                            }
+                        }
+                        offline.queue.save_cache(&mut offline.cache).await?;
+                    }
+
+                    return Ok(responses);
+                }
+                Ok(ProxyResponse::Err(e)) => return Err(Error::Proxy(e)),
+                Ok(ProxyResponse::Ok(_)) => {
+                    return Err(Error::InvalidResponse("invalid response".to_owned()));
+                }
+                Err(err) if self.offline.is_some() && is_unreachable(&err) => {
+                    if let Some(offline) = self.offline.as_mut() {
+                        offline.went_offline(&err);
+                    }
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let Some(offline) = self.offline.as_ref() else {
+            unreachable!("replay only fails with offline support enabled");
+        };
+        requests
+            .into_iter()
+            .map(|request| {
+                if !request.is_read() {
+                    return Err(Error::Proxy(format!(
+                        "server is unreachable and batched {request} cannot be queued"
+                    )));
+                }
+
+                offline
+                    .cache
+                    .get(&request)
+                    .map(ProxyResponse::Ok)
+                    .ok_or_else(|| {
+                        Error::Proxy(format!(
+                            "server is unreachable and {request} was not fetched before"
+                        ))
+                    })
+            })
+            .collect()
+    }
+
+    /// Opens an upload with `start`, sends `data` in chunks and returns the
+    /// response of `FinishAttachmentUpload`. Uploads are never queued.
+    async fn upload(&mut self, start: ProxyRequest, data: &[u8]) -> Result<ProxyResponse> {
+        if !self.replay().await? {
             return Err(Error::Proxy(
-                "proxy server rejected the authentication token".to_owned(),
+                "attachments can only be uploaded while the server is reachable".to_owned(),
             ));
         }
-        let resp: ProxyResponse = resp.json().await?;
-        Ok(resp)
+
+        let upload_id = match self.rpc(start.clone()).await? {
+            ProxyResponse::Ok(ResultPayload::Id(upload_id)) => upload_id,
+            ProxyResponse::Err(e) => return Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => {
+                return Err(Error::InvalidResponse("invalid response".to_owned()));
+            }
+        };
+
+        for chunk in data.chunks(ATTACHMENT_CHUNK_SIZE) {
+            let request = ProxyRequest::UploadAttachmentChunk {
+                upload_id: upload_id.clone(),
+                data: BASE64.encode(chunk),
+            };
+            match self.rpc(request).await? {
+                ProxyResponse::Ok(ResultPayload::Unit) => {}
+                ProxyResponse::Err(e) => return Err(Error::Proxy(e)),
+                ProxyResponse::Ok(_) => {
+                    return Err(Error::InvalidResponse("invalid response".to_owned()));
+                }
+            }
+        }
+
+        let response = self
+            .rpc(ProxyRequest::FinishAttachmentUpload { upload_id })
+            .await?;
+        if let (ProxyResponse::Ok(payload), Some(offline)) = (&response, self.offline.as_mut()) {
+            offline.cache.apply(&start, payload);
+            offline.queue.save_cache(&mut offline.cache).await?;
+        }
+
+        Ok(response)
+    }
+}
+
+fn http_client(tls: &TlsOptions) -> Result<Client> {
+    let mut builder = Client::builder()
+        .use_rustls_tls()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT);
+    for certificate in tls.root_certificates()? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    if let Some(identity) = tls.identity()? {
+        builder = builder.identity(identity);
+    }
+
+    Ok(builder.build()?)
+}
+
+async fn fetch_root_id(
+    client: &Client,
+    url: &str,
+    auth_token: Option<&str>,
+) -> Result<DirectoryId> {
+    match send(client, url, auth_token, &ProxyRequest::RootId).await? {
+        ProxyResponse::Ok(ResultPayload::Id(id)) => Ok(id),
+        ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+        ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+    }
+}
+
+async fn send(
+    client: &Client,
+    url: &str,
+    auth_token: Option<&str>,
+    req: &ProxyRequest,
+) -> Result<ProxyResponse> {
+    let mut request = client.post(url).json(req);
+    if let Some(token) = auth_token {
+        request = request.bearer_auth(token);
+    }
+    let resp = request.send().await?;
+    if resp.status() == StatusCode::UNAUTHORIZED {
+        return Err(Error::Proxy(
+            "proxy server rejected the authentication token".to_owned(),
+        ));
+    }
+    let resp: ProxyResponse = resp.json().await?;
+    Ok(resp)
+}
+
+fn is_unreachable(err: &Error) -> bool {
+    matches!(err, Error::Reqwest(err) if err.is_connect() || err.is_timeout())
+}
+
+/// Whether the request failed before it reached the server, so sending it
+/// again cannot apply it twice.
+fn is_unsent(err: &Error) -> bool {
+    matches!(err, Error::Reqwest(err) if err.is_connect())
+}
+
+/// Turns a write into the request to queue and the answer the server would
+/// give. New entries get their ids here and are queued as restores, so
+/// later queued writes can refer to them.
+fn queued(request: ProxyRequest) -> (ProxyRequest, ResultPayload) {
+    let now = Utc::now().naive_utc().to_string();
+
+    match request {
+        ProxyRequest::AddDirectory { parent_id, name } => {
+            let directory = Directory {
+                id: uuid::Uuid::now_v7().to_string(),
+                parent_id,
+                name,
+                created_at: now.clone(),
+                updated_at: now,
+            };
+            let payload = ResultPayload::Directory(directory.clone());
+
+            (ProxyRequest::RestoreDirectory { directory }, payload)
+        }
+        ProxyRequest::AddNote { directory_id, name } => {
+            let note = Note {
+                id: uuid::Uuid::now_v7().to_string(),
+                directory_id,
+                name,
+                created_at: now.clone(),
+                updated_at: now,
+            };
+            let payload = ResultPayload::Note(note.clone());
+            let request = ProxyRequest::RestoreNote {
+                note,
+                content: String::new(),
+            };
+
+            (request, payload)
+        }
+        request => (request, ResultPayload::Unit),
     }
 }
 
@@ -71,7 +487,7 @@ impl CoreBackend for ProxyClient {
 
     async fn fetch_directory(&mut self, directory_id: DirectoryId) -> Result<Directory> {
         match self
-            .rpc(ProxyRequest::FetchDirectory { directory_id })
+            .read(ProxyRequest::FetchDirectory { directory_id })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Directory(dir)) => Ok(dir),
@@ -82,7 +498,7 @@ impl CoreBackend for ProxyClient {
 
     async fn fetch_directories(&mut self, parent_id: DirectoryId) -> Result<Vec<Directory>> {
         match self
-            .rpc(ProxyRequest::FetchDirectories { parent_id })
+            .read(ProxyRequest::FetchDirectories { parent_id })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Directories(dirs)) => Ok(dirs),
@@ -93,7 +509,7 @@ impl CoreBackend for ProxyClient {
 
     async fn add_directory(&mut self, parent_id: DirectoryId, name: String) -> Result<Directory> {
         match self
-            .rpc(ProxyRequest::AddDirectory { parent_id, name })
+            .write(ProxyRequest::AddDirectory { parent_id, name })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Directory(dir)) => Ok(dir),
@@ -104,7 +520,7 @@ impl CoreBackend for ProxyClient {
 
     async fn remove_directory(&mut self, directory_id: DirectoryId) -> Result<()> {
         match self
-            .rpc(ProxyRequest::RemoveDirectory { directory_id })
+            .write(ProxyRequest::RemoveDirectory { directory_id })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
@@ -119,7 +535,7 @@ impl CoreBackend for ProxyClient {
         parent_id: DirectoryId,
     ) -> Result<()> {
         match self
-            .rpc(ProxyRequest::MoveDirectory {
+            .write(ProxyRequest::MoveDirectory {
                 directory_id,
                 parent_id,
             })
@@ -133,7 +549,7 @@ impl CoreBackend for ProxyClient {
 
     async fn rename_directory(&mut self, directory_id: DirectoryId, name: String) -> Result<()> {
         match self
-            .rpc(ProxyRequest::RenameDirectory { directory_id, name })
+            .write(ProxyRequest::RenameDirectory { directory_id, name })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
@@ -143,15 +559,58 @@ impl CoreBackend for ProxyClient {
     }
 
     async fn fetch_notes(&mut self, directory_id: DirectoryId) -> Result<Vec<Note>> {
-        match self.rpc(ProxyRequest::FetchNotes { directory_id }).await? {
+        match self.read(ProxyRequest::FetchNotes { directory_id }).await? {
             ProxyResponse::Ok(ResultPayload::Notes(notes)) => Ok(notes),
             ProxyResponse::Err(e) => Err(Error::Proxy(e)),
             ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
         }
     }
 
+    async fn fetch_children(
+        &mut self,
+        directory_ids: Vec<DirectoryId>,
+    ) -> Result<Vec<(Vec<Note>, Vec<Directory>)>> {
+        if directory_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let requests = directory_ids
+            .into_iter()
+            .flat_map(|directory_id| {
+                [
+                    ProxyRequest::FetchNotes {
+                        directory_id: directory_id.clone(),
+                    },
+                    ProxyRequest::FetchDirectories {
+                        parent_id: directory_id,
+                    },
+                ]
+            })
+            .collect();
+
+        let mut responses = self.batch(requests, false).await?.into_iter();
+        let mut children = Vec::new();
+        while let (Some(notes), Some(directories)) = (responses.next(), responses.next()) {
+            match (notes, directories) {
+                (
+                    ProxyResponse::Ok(ResultPayload::Notes(notes)),
+                    ProxyResponse::Ok(ResultPayload::Directories(directories)),
+                ) => children.push((notes, directories)),
+                (ProxyResponse::Err(e), _) | (_, ProxyResponse::Err(e)) => {
+                    return Err(Error::Proxy(e));
+                }
+                _ => return Err(Error::InvalidResponse("invalid response".to_owned())),
+            }
+        }
+
+        Ok(children)
+    }
+
     async fn fetch_note_content(&mut self, note_id: NoteId) -> Result<String> {
-        match self.rpc(ProxyRequest::FetchNoteContent { note_id }).await? {
+        match self
+            .read(ProxyRequest::FetchNoteContent { note_id })
+            .await?
+        {
             ProxyResponse::Ok(ResultPayload::Text(text)) => Ok(text),
             ProxyResponse::Err(e) => Err(Error::Proxy(e)),
             ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
@@ -160,7 +619,7 @@ impl CoreBackend for ProxyClient {
 
     async fn add_note(&mut self, directory_id: DirectoryId, name: String) -> Result<Note> {
         match self
-            .rpc(ProxyRequest::AddNote { directory_id, name })
+            .write(ProxyRequest::AddNote { directory_id, name })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Note(note)) => Ok(note),
@@ -170,7 +629,7 @@ impl CoreBackend for ProxyClient {
     }
 
     async fn remove_note(&mut self, note_id: NoteId) -> Result<()> {
-        match self.rpc(ProxyRequest::RemoveNote { note_id }).await? {
+        match self.write(ProxyRequest::RemoveNote { note_id }).await? {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
             ProxyResponse::Err(e) => Err(Error::Proxy(e)),
             ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
@@ -178,7 +637,10 @@ impl CoreBackend for ProxyClient {
     }
 
     async fn rename_note(&mut self, note_id: NoteId, name: String) -> Result<()> {
-        match self.rpc(ProxyRequest::RenameNote { note_id, name }).await? {
+        match self
+            .write(ProxyRequest::RenameNote { note_id, name })
+            .await?
+        {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
             ProxyResponse::Err(e) => Err(Error::Proxy(e)),
             ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
@@ -187,7 +649,7 @@ impl CoreBackend for ProxyClient {
 
     async fn update_note_content(&mut self, note_id: NoteId, content: String) -> Result<()> {
         match self
-            .rpc(ProxyRequest::UpdateNoteContent { note_id, content })
+            .write(ProxyRequest::UpdateNoteContent { note_id, content })
             .await?
         {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
@@ -198,7 +660,7 @@ impl CoreBackend for ProxyClient {
 
     async fn move_note(&mut self, note_id: NoteId, directory_id: DirectoryId) -> Result<()> {
         match self
-            .rpc(ProxyRequest::MoveNote {
+            .write(ProxyRequest::MoveNote {
                 note_id,
                 directory_id,
             })
@@ -210,18 +672,197 @@ impl CoreBackend for ProxyClient {
         }
     }
 
+    async fn fetch_attachments(&mut self, note_id: NoteId) -> Result<Vec<Attachment>> {
+        match self
+            .read(ProxyRequest::FetchAttachments { note_id })
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Attachments(attachments)) => Ok(attachments),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn fetch_attachment_data(&mut self, attachment_id: AttachmentId) -> Result<Vec<u8>> {
+        let mut data = Vec::new();
+
+        loop {
+            let request = ProxyRequest::FetchAttachmentChunk {
+                attachment_id: attachment_id.clone(),
+                offset: data.len() as u64,
+            };
+            let (chunk, size) = match self.rpc(request).await? {
+                ProxyResponse::Ok(ResultPayload::Chunk { data, size }) => (data, size),
+                ProxyResponse::Err(e) => return Err(Error::Proxy(e)),
+                ProxyResponse::Ok(_) => {
+                    return Err(Error::InvalidResponse("invalid response".to_owned()));
+                }
+            };
+
+            let chunk = BASE64.decode(&chunk).map_err(|_| {
+                Error::InvalidResponse("attachment chunk is not valid base64".to_owned())
+            })?;
+            if chunk.is_empty() && (data.len() as u64) < size {
+                return Err(Error::InvalidResponse(
+                    "attachment ended before its size".to_owned(),
+                ));
+            }
+            data.extend_from_slice(&chunk);
+
+            if data.len() as u64 >= size {
+                return Ok(data);
+            }
+        }
+    }
+
+    async fn add_attachment(
+        &mut self,
+        note_id: NoteId,
+        name: String,
+        data: Vec<u8>,
+    ) -> Result<Attachment> {
+        match self
+            .upload(ProxyRequest::StartAttachmentUpload { note_id, name }, &data)
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Attachment(attachment)) => Ok(attachment),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn remove_attachment(&mut self, attachment_id: AttachmentId) -> Result<()> {
+        match self
+            .write(ProxyRequest::RemoveAttachment { attachment_id })
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn restore_directory(&mut self, directory: Directory) -> Result<()> {
+        let root_id = (directory.id == directory.parent_id).then(|| directory.id.clone());
+
+        match self
+            .write(ProxyRequest::RestoreDirectory { directory })
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Unit) => {
+                if let Some(root_id) = root_id {
+                    self.root_id = root_id;
+                }
+
+                Ok(())
+            }
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn restore_note(&mut self, note: Note, content: String) -> Result<()> {
+        match self
+            .write(ProxyRequest::RestoreNote { note, content })
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn restore_attachment(&mut self, attachment: Attachment, data: Vec<u8>) -> Result<()> {
+        match self
+            .upload(ProxyRequest::StartAttachmentRestore { attachment }, &data)
+            .await?
+        {
+            ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
     async fn log(&mut self, category: String, message: String) -> Result<()> {
-        match self.rpc(ProxyRequest::Log { category, message }).await? {
+        match self.write(ProxyRequest::Log { category, message }).await? {
+            ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn fetch_meta(&mut self, key: String) -> Result<Option<String>> {
+        match self.read(ProxyRequest::FetchMeta { key }).await? {
+            ProxyResponse::Ok(ResultPayload::OptionalText(value)) => Ok(value),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn fetch_all_meta(&mut self) -> Result<Vec<(String, String)>> {
+        match self.read(ProxyRequest::FetchAllMeta).await? {
+            ProxyResponse::Ok(ResultPayload::Entries(entries)) => Ok(entries),
+            ProxyResponse::Err(e) => Err(Error::Proxy(e)),
+            ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
+        }
+    }
+
+    async fn update_meta(&mut self, key: String, value: String) -> Result<()> {
+        match self.write(ProxyRequest::UpdateMeta { key, value }).await? {
             ProxyResponse::Ok(ResultPayload::Unit) => Ok(()),
             ProxyResponse::Err(e) => Err(Error::Proxy(e)),
             ProxyResponse::Ok(_) => Err(Error::InvalidResponse("invalid response".to_owned())),
         }
     }
 
-    fn sync_job(&self) -> Option<SyncJob> {
+    /// Transactions live on the server, see `ProxyRequest::Batch`.
+    async fn begin(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn commit(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn rollback(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// The server syncs only after the queued writes reached it.
+    fn sync_job(&mut self) -> Option<SyncJob> {
+        if self
+            .offline
+            .as_ref()
+            .is_some_and(|offline| !offline.online || !offline.queue.is_empty())
+        {
+            return None;
+        }
+
         Some(SyncJob::Proxy {
             url: self.url.clone(),
             auth_token: self.auth_token.clone(),
+            tls: self.tls.clone(),
+        })
+    }
+
+    fn sync_finished(&mut self) {}
+
+    fn sync_trigger(&self) -> SyncTrigger {
+        SyncTrigger::OnChange
+    }
+
+    fn connection_status(&self) -> Option<ConnectionStatus> {
+        self.offline.as_ref().map(|offline| ConnectionStatus {
+            online: offline.online,
+            pending: offline.queue.len(),
+            rejected: offline.queue.rejected_len(),
         })
     }
+
+    fn take_rejected_writes(&mut self) -> Vec<String> {
+        self.offline
+            .as_mut()
+            .map(|offline| std::mem::take(&mut offline.rejections))
+            .unwrap_or_default()
+    }
 }
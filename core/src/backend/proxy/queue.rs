@@ -0,0 +1,270 @@
+use {
+    super::{cache::ReadCache, request::ProxyRequest},
+    crate::{Error, Result},
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{Execute, col, num, table, text},
+            row_conversion::SelectExt,
+        },
+        gluesql_redb_storage::RedbStorage,
+        prelude::Glue,
+    },
+};
+
+#[derive(FromGlueRow)]
+struct QueueRow {
+    seq: i64,
+    request: String,
+}
+
+#[derive(FromGlueRow)]
+struct RejectedRow {
+    request: String,
+    error: String,
+}
+
+#[derive(FromGlueRow)]
+struct CacheRow {
+    key: String,
+    value: String,
+}
+
+/// Writes made while the proxy server was unreachable, stored in a redb file
+/// in the order they were made, along with the read cache of the server.
+/// One file can hold the queues of several servers, told apart by url.
+/// The cache is kept one row per entry, so saving it only writes the
+/// entries which changed. Writes the server refused are moved aside to
+/// `ProxyRejected` with its answer, so they are never lost.
+pub struct WriteQueue {
+    glue: Glue<RedbStorage>,
+    url: String,
+    next_seq: i64,
+    len: usize,
+    rejected: usize,
+}
+
+impl WriteQueue {
+    pub async fn open(path: &str, url: &str) -> Result<Self> {
+        let storage = RedbStorage::new(path)?;
+        let mut glue = Glue::new(storage);
+
+        table("ProxyQueue")
+            .create_table_if_not_exists()
+            .add_column("seq INTEGER NOT NULL")
+            .add_column("url TEXT NOT NULL")
+            .add_column("request TEXT NOT NULL")
+            .execute(&mut glue)
+            .await?;
+
+        table("ProxyRejected")
+            .create_table_if_not_exists()
+            .add_column("seq INTEGER NOT NULL")
+            .add_column("url TEXT NOT NULL")
+            .add_column("request TEXT NOT NULL")
+            .add_column("error TEXT NOT NULL")
+            .execute(&mut glue)
+            .await?;
+
+        // the whole cache used to be stored in one row
+        table("ProxyCache")
+            .drop_table_if_exists()
+            .execute(&mut glue)
+            .await?;
+        table("ProxyCacheEntry")
+            .create_table_if_not_exists()
+            .add_column("id TEXT PRIMARY KEY")
+            .add_column("url TEXT NOT NULL")
+            .add_column("key TEXT NOT NULL")
+            .add_column("value TEXT NOT NULL")
+            .execute(&mut glue)
+            .await?;
+
+        let mut queue = Self {
+            glue,
+            url: url.to_owned(),
+            next_seq: 0,
+            len: 0,
+            rejected: 0,
+        };
+        let rows = queue.rows().await?;
+        queue.len = rows.len();
+        queue.next_seq = rows.last().map_or(0, |row| row.seq + 1);
+        queue.rejected = queue.rejected_rows().await?.len();
+
+        Ok(queue)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of writes the server refused.
+    pub fn rejected_len(&self) -> usize {
+        self.rejected
+    }
+
+    pub async fn push(&mut self, request: &ProxyRequest) -> Result<()> {
+        let request = serde_json::to_string(request)
+            .map_err(|err| Error::Proxy(format!("failed to queue request: {err}")))?;
+
+        table("ProxyQueue")
+            .insert()
+            .columns(vec!["seq", "url", "request"])
+            .values(vec![vec![
+                num(self.next_seq),
+                text(self.url.clone()),
+                text(request),
+            ]])
+            .execute(&mut self.glue)
+            .await?;
+
+        self.next_seq += 1;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Oldest queued request with the sequence number which removes it.
+    pub async fn front(&mut self) -> Result<Option<(i64, ProxyRequest)>> {
+        let Some(row) = self.rows().await?.into_iter().next() else {
+            return Ok(None);
+        };
+        let request = serde_json::from_str(&row.request)
+            .map_err(|err| Error::Proxy(format!("queued request is corrupted: {err}")))?;
+
+        Ok(Some((row.seq, request)))
+    }
+
+    pub async fn remove(&mut self, seq: i64) -> Result<()> {
+        table("ProxyQueue")
+            .delete()
+            .filter(
+                col("url")
+                    .eq(text(self.url.clone()))
+                    .and(col("seq").eq(num(seq))),
+            )
+            .execute(&mut self.glue)
+            .await?;
+
+        self.len = self.len.saturating_sub(1);
+
+        Ok(())
+    }
+
+    /// Moves a queued request the server refused to `ProxyRejected`.
+    pub async fn reject(&mut self, seq: i64, request: &ProxyRequest, error: &str) -> Result<()> {
+        let request = serde_json::to_string(request)
+            .map_err(|err| Error::Proxy(format!("failed to set aside request: {err}")))?;
+
+        table("ProxyRejected")
+            .insert()
+            .columns(vec!["seq", "url", "request", "error"])
+            .values(vec![vec![
+                num(seq),
+                text(self.url.clone()),
+                text(request),
+                text(error),
+            ]])
+            .execute(&mut self.glue)
+            .await?;
+        self.rejected += 1;
+
+        self.remove(seq).await
+    }
+
+    /// Writes the server refused, oldest first, with its answer.
+    pub async fn rejected(&mut self) -> Result<Vec<(ProxyRequest, String)>> {
+        self.rejected_rows()
+            .await?
+            .into_iter()
+            .map(|row| {
+                let request = serde_json::from_str(&row.request)
+                    .map_err(|err| Error::Proxy(format!("rejected request is corrupted: {err}")))?;
+
+                Ok((request, row.error))
+            })
+            .collect()
+    }
+
+    async fn rejected_rows(&mut self) -> Result<Vec<RejectedRow>> {
+        Ok(table("ProxyRejected")
+            .select()
+            .filter(col("url").eq(text(self.url.clone())))
+            .project(vec!["request", "error"])
+            .order_by("seq ASC")
+            .execute(&mut self.glue)
+            .await?
+            .rows_as::<RejectedRow>()?)
+    }
+
+    pub async fn load_cache(&mut self) -> Result<Option<ReadCache>> {
+        let rows = table("ProxyCacheEntry")
+            .select()
+            .filter(col("url").eq(text(self.url.clone())))
+            .project(vec!["key", "value"])
+            .execute(&mut self.glue)
+            .await?
+            .rows_as::<CacheRow>()?;
+        if rows.is_empty() {
+            return Ok(None);
+        }
+
+        let entries = rows.into_iter().map(|row| (row.key, row.value)).collect();
+        ReadCache::from_entries(entries).map(Some)
+    }
+
+    /// Writes the entries of `cache` changed since it was last saved.
+    pub async fn save_cache(&mut self, cache: &mut ReadCache) -> Result<()> {
+        for (key, value) in cache.take_changes()? {
+            let id = format!("{}\n{key}", self.url);
+            table("ProxyCacheEntry")
+                .delete()
+                .filter(col("id").eq(text(id.clone())))
+                .execute(&mut self.glue)
+                .await?;
+
+            if let Some(value) = value {
+                table("ProxyCacheEntry")
+                    .insert()
+                    .columns(vec!["id", "url", "key", "value"])
+                    .values(vec![vec![
+                        text(id),
+                        text(self.url.clone()),
+                        text(key),
+                        text(value),
+                    ]])
+                    .execute(&mut self.glue)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Drops the stored cache, for a server which now serves another notebook.
+    pub async fn clear_cache(&mut self) -> Result<()> {
+        table("ProxyCacheEntry")
+            .delete()
+            .filter(col("url").eq(text(self.url.clone())))
+            .execute(&mut self.glue)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn rows(&mut self) -> Result<Vec<QueueRow>> {
+        Ok(table("ProxyQueue")
+            .select()
+            .filter(col("url").eq(text(self.url.clone())))
+            .project(vec!["seq", "request"])
+            .order_by("seq ASC")
+            .execute(&mut self.glue)
+            .await?
+            .rows_as::<QueueRow>()?)
+    }
+}
@@ -1,7 +1,9 @@
-use crate::types::{DirectoryId, NoteId};
+use crate::data::{Attachment, Directory, Note};
+use crate::types::{AttachmentId, DirectoryId, NoteId};
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Display)]
 #[serde(tag = "method", content = "data")]
 pub enum ProxyRequest {
     RootId,
@@ -51,9 +53,127 @@ pub enum ProxyRequest {
         note_id: NoteId,
         directory_id: DirectoryId,
     },
+    FetchAttachments {
+        note_id: NoteId,
+    },
+    /// Returns up to `ATTACHMENT_CHUNK_SIZE` bytes starting at `offset`.
+    FetchAttachmentChunk {
+        attachment_id: AttachmentId,
+        offset: u64,
+    },
+    /// Uploads are assembled on the server from base64 chunks and stored by
+    /// `FinishAttachmentUpload`.
+    StartAttachmentUpload {
+        note_id: NoteId,
+        name: String,
+    },
+    UploadAttachmentChunk {
+        upload_id: String,
+        data: String,
+    },
+    FinishAttachmentUpload {
+        upload_id: String,
+    },
+    RemoveAttachment {
+        attachment_id: AttachmentId,
+    },
+    RestoreDirectory {
+        directory: Directory,
+    },
+    RestoreNote {
+        note: Note,
+        content: String,
+    },
+    /// Opens an upload like `StartAttachmentUpload`, which is finished by
+    /// restoring `attachment` with its original id.
+    StartAttachmentRestore {
+        attachment: Attachment,
+    },
     Log {
         category: String,
         message: String,
     },
+    FetchMeta {
+        key: String,
+    },
+    FetchAllMeta,
+    UpdateMeta {
+        key: String,
+        value: String,
+    },
     Sync,
+    /// Runs `requests` in order in one round trip, answered by
+    /// `ResultPayload::Batch` with a response for each. An `atomic` batch
+    /// stops at the first failing request and rolls back the writes made
+    /// before it.
+    Batch {
+        requests: Vec<ProxyRequest>,
+        atomic: bool,
+    },
+}
+
+impl ProxyRequest {
+    /// Whether the request leaves the notebook untouched.
+    pub fn is_read(&self) -> bool {
+        use ProxyRequest::*;
+
+        match self {
+            RootId
+            | FetchDirectory { .. }
+            | FetchDirectories { .. }
+            | FetchNotes { .. }
+            | FetchNoteContent { .. }
+            | FetchAttachments { .. }
+            | FetchAttachmentChunk { .. }
+            | FetchMeta { .. }
+            | FetchAllMeta => true,
+            Batch { requests, .. } => requests.iter().all(ProxyRequest::is_read),
+            _ => false,
+        }
+    }
+
+    /// Ids of the directories, notes and attachments the request touches.
+    pub fn targets(&self) -> Vec<String> {
+        use ProxyRequest::*;
+
+        match self {
+            FetchDirectory { directory_id }
+            | RemoveDirectory { directory_id }
+            | RenameDirectory { directory_id, .. }
+            | FetchNotes { directory_id }
+            | AddNote { directory_id, .. } => vec![directory_id.clone()],
+            FetchDirectories { parent_id } | AddDirectory { parent_id, .. } => {
+                vec![parent_id.clone()]
+            }
+            MoveDirectory {
+                directory_id,
+                parent_id,
+            } => vec![directory_id.clone(), parent_id.clone()],
+            FetchNoteContent { note_id }
+            | RemoveNote { note_id }
+            | RenameNote { note_id, .. }
+            | UpdateNoteContent { note_id, .. }
+            | FetchAttachments { note_id }
+            | StartAttachmentUpload { note_id, .. } => vec![note_id.clone()],
+            MoveNote {
+                note_id,
+                directory_id,
+            } => vec![note_id.clone(), directory_id.clone()],
+            FetchAttachmentChunk { attachment_id, .. } | RemoveAttachment { attachment_id } => {
+                vec![attachment_id.clone()]
+            }
+            RestoreDirectory { directory } => vec![directory.id.clone()],
+            RestoreNote { note, .. } => vec![note.id.clone()],
+            StartAttachmentRestore { attachment } => vec![attachment.id.clone()],
+            Batch { requests, .. } => requests.iter().flat_map(ProxyRequest::targets).collect(),
+            RootId
+            | UploadAttachmentChunk { .. }
+            | FinishAttachmentUpload { .. }
+            | Log { .. }
+            | FetchMeta { .. }
+            | FetchAllMeta
+            | UpdateMeta { .. }
+            | Sync => Vec::new(),
+        }
+    }
 }
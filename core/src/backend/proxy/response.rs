@@ -1,15 +1,15 @@
-use crate::data::{Directory, Note};
+use crate::data::{Attachment, Directory, Note};
 use crate::types::DirectoryId;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "status", content = "data")]
 pub enum ProxyResponse {
     Ok(ResultPayload),
     Err(String),
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(tag = "type", content = "value")]
 pub enum ResultPayload {
     Id(DirectoryId),
@@ -17,6 +17,17 @@ pub enum ResultPayload {
     Directories(Vec<Directory>),
     Note(Note),
     Notes(Vec<Note>),
+    Attachment(Attachment),
+    Attachments(Vec<Attachment>),
+    /// Base64 slice of an attachment and the full attachment size.
+    Chunk {
+        data: String,
+        size: u64,
+    },
     Text(String),
+    OptionalText(Option<String>),
+    Entries(Vec<(String, String)>),
+    /// Responses to the requests of a `ProxyRequest::Batch`, in order.
+    Batch(Vec<ProxyResponse>),
     Unit,
 }
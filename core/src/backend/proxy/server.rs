@@ -1,18 +1,170 @@
+use super::ATTACHMENT_CHUNK_SIZE;
 use super::request::ProxyRequest;
 use super::response::{ProxyResponse, ResultPayload};
 use crate::{
     Error,
     backend::{BackendBox, CoreBackend, SyncJob},
+    data::Attachment,
+    types::{AttachmentId, NoteId},
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
 };
 use tokio::task;
+use uuid::Uuid;
 
 pub struct ProxyServer {
     pub db: BackendBox,
+    uploads: HashMap<String, Upload>,
+    downloads: HashMap<AttachmentId, Download>,
+    limits: TransferLimits,
+    sync_runs: Vec<SyncRun>,
+}
+
+/// Bounds on the attachment data held in memory between chunk requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferLimits {
+    /// Largest attachment one upload may grow to, in bytes.
+    pub max_upload_size: usize,
+    /// Largest total of the uploads and cached downloads in progress, in
+    /// bytes. Chunks past it are rejected and downloads are not cached.
+    pub max_pending_size: usize,
+    /// Transfers without a request for this long are dropped.
+    pub idle_timeout: Duration,
+}
+
+impl Default for TransferLimits {
+    fn default() -> Self {
+        Self {
+            max_upload_size: 64 * 1024 * 1024,
+            max_pending_size: 256 * 1024 * 1024,
+            idle_timeout: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+/// Sync job run for a `Sync` request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyncRun {
+    pub duration: Duration,
+    pub error: Option<String>,
+}
+
+/// Attachment being received chunk by chunk. `restore` is set for uploads
+/// opened by `StartAttachmentRestore`.
+struct Upload {
+    note_id: NoteId,
+    name: String,
+    data: Vec<u8>,
+    restore: Option<Attachment>,
+    touched: Instant,
+}
+
+/// Attachment being sent chunk by chunk, fetched once when the transfer
+/// starts at offset 0.
+struct Download {
+    data: Vec<u8>,
+    touched: Instant,
 }
 
 impl ProxyServer {
     pub fn new(db: BackendBox) -> Self {
-        Self { db }
+        Self {
+            db,
+            uploads: HashMap::new(),
+            downloads: HashMap::new(),
+            limits: TransferLimits::default(),
+            sync_runs: Vec::new(),
+        }
+    }
+
+    pub fn with_transfer_limits(mut self, limits: TransferLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Takes the sync jobs run since the last call.
+    pub fn take_sync_runs(&mut self) -> Vec<SyncRun> {
+        std::mem::take(&mut self.sync_runs)
+    }
+
+    fn expire_transfers(&mut self) {
+        let timeout = self.limits.idle_timeout;
+        self.uploads
+            .retain(|_, upload| upload.touched.elapsed() < timeout);
+        self.downloads
+            .retain(|_, download| download.touched.elapsed() < timeout);
+    }
+
+    fn pending_size(&self) -> usize {
+        let uploads = self.uploads.values().map(|upload| upload.data.len());
+        let downloads = self.downloads.values().map(|download| download.data.len());
+        uploads.chain(downloads).sum()
+    }
+
+    /// Serves chunks from the blob cached by the first one, so a transfer
+    /// reads the attachment from storage once. The cache entry is dropped
+    /// with the last chunk.
+    async fn fetch_attachment_chunk(
+        &mut self,
+        attachment_id: AttachmentId,
+        offset: u64,
+    ) -> Result<ResultPayload, Error> {
+        self.expire_transfers();
+        if offset == 0 {
+            self.downloads.remove(&attachment_id);
+        }
+
+        let data = match self.downloads.remove(&attachment_id) {
+            Some(download) => download.data,
+            None => self.db.fetch_attachment_data(attachment_id.clone()).await?,
+        };
+        let start = (offset as usize).min(data.len());
+        let end = (start + ATTACHMENT_CHUNK_SIZE).min(data.len());
+        let payload = ResultPayload::Chunk {
+            data: BASE64.encode(&data[start..end]),
+            size: data.len() as u64,
+        };
+
+        if end < data.len() && self.pending_size() + data.len() <= self.limits.max_pending_size {
+            let download = Download {
+                data,
+                touched: Instant::now(),
+            };
+            self.downloads.insert(attachment_id, download);
+        }
+
+        Ok(payload)
+    }
+
+    fn upload_attachment_chunk(&mut self, upload_id: String, data: String) -> Result<(), String> {
+        self.expire_transfers();
+        let pending_size = self.pending_size();
+        let Some(upload) = self.uploads.get_mut(&upload_id) else {
+            return Err(format!("unknown or expired upload: {upload_id}"));
+        };
+
+        let Ok(data) = BASE64.decode(&data) else {
+            self.uploads.remove(&upload_id);
+            return Err("attachment chunk is not valid base64".to_owned());
+        };
+        if upload.data.len() + data.len() > self.limits.max_upload_size {
+            self.uploads.remove(&upload_id);
+            return Err(format!(
+                "attachment exceeds the upload limit of {} bytes",
+                self.limits.max_upload_size
+            ));
+        }
+        if pending_size + data.len() > self.limits.max_pending_size {
+            self.uploads.remove(&upload_id);
+            return Err("too many attachments in transfer, try again later".to_owned());
+        }
+
+        upload.data.extend_from_slice(&data);
+        upload.touched = Instant::now();
+        Ok(())
     }
 
     async fn run_sync_job(job: SyncJob) -> Result<(), Error> {
@@ -22,6 +174,59 @@ impl ProxyServer {
     }
 
     pub async fn handle(&mut self, req: ProxyRequest) -> ProxyResponse {
+        match req {
+            ProxyRequest::Batch { requests, atomic } => self.handle_batch(requests, atomic).await,
+            req => self.handle_one(req).await,
+        }
+    }
+
+    /// Writes of an atomic batch run in a storage transaction, so batches
+    /// with writes are only atomic on storages which can roll back.
+    async fn handle_batch(&mut self, requests: Vec<ProxyRequest>, atomic: bool) -> ProxyResponse {
+        let mut responses = Vec::with_capacity(requests.len());
+        if !atomic {
+            for request in requests {
+                responses.push(self.handle_one(request).await);
+            }
+
+            return ProxyResponse::Ok(ResultPayload::Batch(responses));
+        }
+
+        let transaction = !requests.iter().all(ProxyRequest::is_read);
+        if transaction {
+            match self.db.begin().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return ProxyResponse::Err(
+                        "storage cannot roll back, atomic batches may only read".to_owned(),
+                    );
+                }
+                Err(e) => return ProxyResponse::Err(e.to_string()),
+            }
+        }
+
+        for (idx, request) in requests.into_iter().enumerate() {
+            match self.handle_one(request).await {
+                ProxyResponse::Err(e) => {
+                    let message = format!("batch request {idx} failed: {e}");
+                    if transaction && let Err(err) = self.db.rollback().await {
+                        return ProxyResponse::Err(format!("{message}, rollback failed: {err}"));
+                    }
+
+                    return ProxyResponse::Err(message);
+                }
+                response => responses.push(response),
+            }
+        }
+
+        if transaction && let Err(e) = self.db.commit().await {
+            return ProxyResponse::Err(e.to_string());
+        }
+
+        ProxyResponse::Ok(ResultPayload::Batch(responses))
+    }
+
+    async fn handle_one(&mut self, req: ProxyRequest) -> ProxyResponse {
         use ProxyRequest::*;
         match req {
             RootId => ProxyResponse::Ok(ResultPayload::Id(self.db.root_id())),
@@ -40,6 +245,7 @@ impl ProxyServer {
                 }
             }
             RemoveDirectory { directory_id } => {
+                self.downloads.clear();
                 match self.db.remove_directory(directory_id).await {
                     Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
                     Err(e) => ProxyResponse::Err(e.to_string()),
@@ -70,10 +276,13 @@ impl ProxyServer {
                 Ok(note) => ProxyResponse::Ok(ResultPayload::Note(note)),
                 Err(e) => ProxyResponse::Err(e.to_string()),
             },
-            RemoveNote { note_id } => match self.db.remove_note(note_id).await {
-                Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
-                Err(e) => ProxyResponse::Err(e.to_string()),
-            },
+            RemoveNote { note_id } => {
+                self.downloads.clear();
+                match self.db.remove_note(note_id).await {
+                    Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                    Err(e) => ProxyResponse::Err(e.to_string()),
+                }
+            }
             RenameNote { note_id, name } => match self.db.rename_note(note_id, name).await {
                 Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
                 Err(e) => ProxyResponse::Err(e.to_string()),
@@ -91,17 +300,123 @@ impl ProxyServer {
                 Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
                 Err(e) => ProxyResponse::Err(e.to_string()),
             },
+            FetchAttachments { note_id } => match self.db.fetch_attachments(note_id).await {
+                Ok(attachments) => ProxyResponse::Ok(ResultPayload::Attachments(attachments)),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
+            FetchAttachmentChunk {
+                attachment_id,
+                offset,
+            } => match self.fetch_attachment_chunk(attachment_id, offset).await {
+                Ok(payload) => ProxyResponse::Ok(payload),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
+            StartAttachmentUpload { note_id, name } => {
+                self.expire_transfers();
+                let upload_id = Uuid::now_v7().to_string();
+                let upload = Upload {
+                    note_id,
+                    name,
+                    data: Vec::new(),
+                    restore: None,
+                    touched: Instant::now(),
+                };
+                self.uploads.insert(upload_id.clone(), upload);
+
+                ProxyResponse::Ok(ResultPayload::Id(upload_id))
+            }
+            StartAttachmentRestore { attachment } => {
+                self.expire_transfers();
+                let upload_id = Uuid::now_v7().to_string();
+                let upload = Upload {
+                    note_id: attachment.note_id.clone(),
+                    name: attachment.name.clone(),
+                    data: Vec::new(),
+                    restore: Some(attachment),
+                    touched: Instant::now(),
+                };
+                self.uploads.insert(upload_id.clone(), upload);
+
+                ProxyResponse::Ok(ResultPayload::Id(upload_id))
+            }
+            UploadAttachmentChunk { upload_id, data } => {
+                match self.upload_attachment_chunk(upload_id, data) {
+                    Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                    Err(message) => ProxyResponse::Err(message),
+                }
+            }
+            FinishAttachmentUpload { upload_id } => {
+                self.expire_transfers();
+                let Some(upload) = self.uploads.remove(&upload_id) else {
+                    return ProxyResponse::Err(format!("unknown or expired upload: {upload_id}"));
+                };
+
+                if let Some(attachment) = upload.restore {
+                    return match self.db.restore_attachment(attachment, upload.data).await {
+                        Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                        Err(e) => ProxyResponse::Err(e.to_string()),
+                    };
+                }
+
+                match self
+                    .db
+                    .add_attachment(upload.note_id, upload.name, upload.data)
+                    .await
+                {
+                    Ok(attachment) => ProxyResponse::Ok(ResultPayload::Attachment(attachment)),
+                    Err(e) => ProxyResponse::Err(e.to_string()),
+                }
+            }
+            RemoveAttachment { attachment_id } => {
+                self.downloads.remove(&attachment_id);
+                match self.db.remove_attachment(attachment_id).await {
+                    Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                    Err(e) => ProxyResponse::Err(e.to_string()),
+                }
+            }
+            RestoreDirectory { directory } => match self.db.restore_directory(directory).await {
+                Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
+            RestoreNote { note, content } => match self.db.restore_note(note, content).await {
+                Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
             Log { category, message } => match self.db.log(category, message).await {
                 Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
                 Err(e) => ProxyResponse::Err(e.to_string()),
             },
+            FetchMeta { key } => match self.db.fetch_meta(key).await {
+                Ok(value) => ProxyResponse::Ok(ResultPayload::OptionalText(value)),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
+            FetchAllMeta => match self.db.fetch_all_meta().await {
+                Ok(entries) => ProxyResponse::Ok(ResultPayload::Entries(entries)),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
+            UpdateMeta { key, value } => match self.db.update_meta(key, value).await {
+                Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                Err(e) => ProxyResponse::Err(e.to_string()),
+            },
             Sync => match self.db.sync_job() {
-                Some(job) => match Self::run_sync_job(job).await {
-                    Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
-                    Err(e) => ProxyResponse::Err(e.to_string()),
-                },
+                Some(job) => {
+                    let started = Instant::now();
+                    let result = Self::run_sync_job(job).await;
+                    self.db.sync_finished();
+                    self.downloads.clear();
+                    self.sync_runs.push(SyncRun {
+                        duration: started.elapsed(),
+                        error: result.as_ref().err().map(ToString::to_string),
+                    });
+
+                    match result {
+                        Ok(()) => ProxyResponse::Ok(ResultPayload::Unit),
+                        Err(e) => ProxyResponse::Err(e.to_string()),
+                    }
+                }
                 None => ProxyResponse::Ok(ResultPayload::Unit),
             },
+            Batch { .. } => ProxyResponse::Err("batches cannot be nested".to_owned()),
         }
     }
 }
@@ -0,0 +1,41 @@
+use {
+    crate::{Error, Result},
+    reqwest::{Certificate, Identity},
+    serde::{Deserialize, Serialize},
+};
+
+/// Certificates for proxy servers served over HTTPS, as paths to PEM files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsOptions {
+    /// CA bundle trusted next to the built-in roots, e.g. for self-signed
+    /// servers.
+    pub ca_cert: Option<String>,
+    /// Client certificate chain followed by its private key, presented to
+    /// servers which require client certificates.
+    pub client_identity: Option<String>,
+}
+
+impl TlsOptions {
+    pub(crate) fn root_certificates(&self) -> Result<Vec<Certificate>> {
+        let Some(path) = self.ca_cert.as_deref() else {
+            return Ok(Vec::new());
+        };
+
+        Certificate::from_pem_bundle(&read(path, "CA bundle")?)
+            .map_err(|err| Error::Proxy(format!("invalid CA bundle {path}: {err}")))
+    }
+
+    pub(crate) fn identity(&self) -> Result<Option<Identity>> {
+        let Some(path) = self.client_identity.as_deref() else {
+            return Ok(None);
+        };
+
+        Identity::from_pem(&read(path, "client identity")?)
+            .map(Some)
+            .map_err(|err| Error::Proxy(format!("invalid client identity {path}: {err}")))
+    }
+}
+
+fn read(path: &str, what: &str) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|err| Error::Proxy(format!("failed to read {what} {path}: {err}")))
+}
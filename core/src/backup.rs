@@ -0,0 +1,218 @@
+use {
+    crate::{
+        Error, Result,
+        backend::{CoreBackend, encrypted::ENCRYPTION_META_KEY},
+        data::{Attachment, Directory, Note},
+        schema::SCHEMA_VERSION_KEY,
+    },
+    serde::{Deserialize, Serialize},
+    std::collections::BTreeMap,
+};
+
+/// Value of [`Archive::format`], which tells archives apart from other JSON files.
+pub const ARCHIVE_FORMAT: &str = "glues-archive";
+
+/// Archive layout written by this build.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// `Meta` entries which describe the storage rather than the notebook. The
+/// target keeps its own schema version, and an archive is always plaintext.
+const STORAGE_META_KEYS: [&str; 2] = [SCHEMA_VERSION_KEY, ENCRYPTION_META_KEY];
+
+/// Whole notebook in one backend agnostic value, written as JSON with
+/// attachment bytes in base64. Directories are listed parents first, so they
+/// can be restored in order.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Archive {
+    pub format: String,
+    pub version: u32,
+    pub root: Directory,
+    pub directories: Vec<Directory>,
+    pub notes: Vec<ArchivedNote>,
+    pub meta: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedNote {
+    #[serde(flatten)]
+    pub note: Note,
+    pub content: String,
+    pub attachments: Vec<ArchivedAttachment>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArchivedAttachment {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+impl Archive {
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|err| Error::Archive(err.to_string()))
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let archive: Self =
+            serde_json::from_str(json).map_err(|err| Error::Archive(err.to_string()))?;
+
+        if archive.format != ARCHIVE_FORMAT {
+            return Err(Error::Archive(format!(
+                "unknown archive format '{}'",
+                archive.format
+            )));
+        }
+        if archive.version > ARCHIVE_VERSION {
+            return Err(Error::Archive(format!(
+                "archive version {} is newer than {ARCHIVE_VERSION}, \
+                 the latest version this Glues supports",
+                archive.version
+            )));
+        }
+
+        Ok(archive)
+    }
+}
+
+/// Reads the whole notebook behind `db`. An encrypted notebook is dumped in
+/// plaintext when `db` is unlocked.
+pub async fn dump<B: CoreBackend + ?Sized>(db: &mut B) -> Result<Archive> {
+    let root = db.fetch_directory(db.root_id()).await?;
+    let mut directories = Vec::new();
+    let mut notes = Vec::new();
+
+    let mut pending = vec![root.id.clone()];
+    while let Some(directory_id) = pending.pop() {
+        for note in db.fetch_notes(directory_id.clone()).await? {
+            let content = db.fetch_note_content(note.id.clone()).await?;
+            let mut attachments = Vec::new();
+            for attachment in db.fetch_attachments(note.id.clone()).await? {
+                let data = db.fetch_attachment_data(attachment.id.clone()).await?;
+                attachments.push(ArchivedAttachment { attachment, data });
+            }
+
+            notes.push(ArchivedNote {
+                note,
+                content,
+                attachments,
+            });
+        }
+
+        for directory in db.fetch_directories(directory_id).await? {
+            pending.push(directory.id.clone());
+            directories.push(directory);
+        }
+    }
+
+    let meta = db
+        .fetch_all_meta()
+        .await?
+        .into_iter()
+        .filter(|(key, _)| !STORAGE_META_KEYS.contains(&key.as_str()))
+        .collect();
+
+    Ok(Archive {
+        format: ARCHIVE_FORMAT.to_owned(),
+        version: ARCHIVE_VERSION,
+        root,
+        directories,
+        notes,
+        meta,
+    })
+}
+
+/// Rebuilds `archive` in `db`, which must hold an empty notebook. The archived
+/// root replaces the root of `db`, so every id stays as it was. A failed
+/// restore is rolled back, or on storages which cannot roll back, the restored
+/// directories and notes are removed again.
+pub async fn restore<B: CoreBackend + ?Sized>(db: &mut B, archive: Archive) -> Result<()> {
+    let root_id = db.root_id();
+    let is_empty = db.fetch_directories(root_id.clone()).await?.is_empty()
+        && db.fetch_notes(root_id).await?.is_empty();
+    if !is_empty {
+        return Err(Error::Archive(
+            "an archive can only be restored into an empty notebook".to_owned(),
+        ));
+    }
+
+    if db.begin().await? {
+        return match restore_entries(db, archive).await {
+            Ok(()) => db.commit().await,
+            Err(err) => match db.rollback().await {
+                Ok(()) => Err(err),
+                Err(rollback) => Err(Error::Archive(format!(
+                    "{err}, rollback failed: {rollback}"
+                ))),
+            },
+        };
+    }
+
+    match restore_entries(db, archive).await {
+        Ok(()) => Ok(()),
+        Err(err) => match clear(db).await {
+            Ok(()) => Err(err),
+            Err(cleanup) => Err(Error::Archive(format!(
+                "{err}, cleaning up failed: {cleanup}"
+            ))),
+        },
+    }
+}
+
+async fn restore_entries<B: CoreBackend + ?Sized>(db: &mut B, archive: Archive) -> Result<()> {
+    db.restore_directory(archive.root).await?;
+    for directory in archive.directories {
+        db.restore_directory(directory).await?;
+    }
+
+    for ArchivedNote {
+        note,
+        content,
+        attachments,
+    } in archive.notes
+    {
+        db.restore_note(note, content).await?;
+        for ArchivedAttachment { attachment, data } in attachments {
+            db.restore_attachment(attachment, data).await?;
+        }
+    }
+
+    for (key, value) in archive.meta {
+        if !STORAGE_META_KEYS.contains(&key.as_str()) {
+            db.update_meta(key, value).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes everything under the root, leaving an empty notebook behind.
+async fn clear<B: CoreBackend + ?Sized>(db: &mut B) -> Result<()> {
+    let root_id = db.root_id();
+    for directory in db.fetch_directories(root_id.clone()).await? {
+        db.remove_directory(directory.id).await?;
+    }
+    for note in db.fetch_notes(root_id).await? {
+        db.remove_note(note.id).await?;
+    }
+
+    Ok(())
+}
+
+mod base64_bytes {
+    use {
+        base64::{Engine, engine::general_purpose::STANDARD as BASE64},
+        serde::{Deserialize, Deserializer, Serializer, de::Error as _},
+    };
+
+    pub fn serialize<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(data))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        BASE64
+            .decode(&encoded)
+            .map_err(|_| D::Error::custom("invalid base64"))
+    }
+}
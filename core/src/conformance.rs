@@ -0,0 +1,645 @@
+//! Behavioral contract every [`CoreBackend`] is expected to follow.
+//!
+//! [`run`] drives each check against a fresh notebook from a factory and
+//! panics with the name of the first check which fails, so backends outside
+//! this crate can prove compatibility from a plain `#[tokio::test]`:
+//!
+//! ```no_run
+//! # async fn demo() {
+//! use glues_core::{backend::local::Db, conformance};
+//!
+//! conformance::run(|| async { Db::memory().await.unwrap() }).await;
+//! # }
+//! ```
+
+use {
+    crate::{
+        backend::CoreBackend,
+        data::{Directory, Note},
+    },
+    std::future::Future,
+};
+
+/// Well-formed id which no notebook row uses.
+pub const MISSING_ID: &str = "00000000-0000-7000-8000-000000000000";
+
+/// Runs every check, each on a new backend returned by `factory`. Backends
+/// must start as an empty notebook.
+pub async fn run<B, F, Fut>(mut factory: F)
+where
+    B: CoreBackend,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = B>,
+{
+    root_directory(&mut factory().await).await;
+    directory_crud(&mut factory().await).await;
+    note_crud(&mut factory().await).await;
+    moves(&mut factory().await).await;
+    recursive_removal(&mut factory().await).await;
+    attachments(&mut factory().await).await;
+    fetch_children(&mut factory().await).await;
+    timestamps(&mut factory().await).await;
+    unicode_names(&mut factory().await).await;
+    meta(&mut factory().await).await;
+    error_cases(&mut factory().await).await;
+}
+
+/// The root is a directory named `Notes` which is its own parent.
+pub async fn root_directory<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let root = db
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("root_directory: root should be fetched");
+
+    assert_eq!(root.id, root_id, "root_directory: id");
+    assert_eq!(root.parent_id, root_id, "root_directory: parent_id");
+    assert_eq!(root.name, "Notes", "root_directory: name");
+    assert!(
+        db.fetch_directories(root_id.clone())
+            .await
+            .expect("root_directory: directories should be listed")
+            .is_empty(),
+        "root_directory: new notebook has no directories"
+    );
+    assert!(
+        db.fetch_notes(root_id)
+            .await
+            .expect("root_directory: notes should be listed")
+            .is_empty(),
+        "root_directory: new notebook has no notes"
+    );
+}
+
+pub async fn directory_crud<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let work = db
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .expect("directory_crud: directory should be added");
+    assert_eq!(work.name, "Work", "directory_crud: added name");
+    assert_eq!(work.parent_id, root_id, "directory_crud: added parent");
+
+    let fetched = db
+        .fetch_directory(work.id.clone())
+        .await
+        .expect("directory_crud: directory should be fetched");
+    assert_eq!(fetched.name, "Work", "directory_crud: fetched name");
+
+    db.add_directory(root_id.clone(), "Home".to_owned())
+        .await
+        .expect("directory_crud: second directory should be added");
+    assert_eq!(
+        sorted_names(
+            db.fetch_directories(root_id.clone())
+                .await
+                .expect("directory_crud: directories should be listed")
+        ),
+        ["Home", "Work"],
+        "directory_crud: listed directories"
+    );
+
+    db.rename_directory(work.id.clone(), "Office".to_owned())
+        .await
+        .expect("directory_crud: directory should be renamed");
+    let renamed = db
+        .fetch_directory(work.id.clone())
+        .await
+        .expect("directory_crud: renamed directory should be fetched");
+    assert_eq!(renamed.name, "Office", "directory_crud: renamed name");
+
+    db.remove_directory(work.id.clone())
+        .await
+        .expect("directory_crud: directory should be removed");
+    assert_eq!(
+        sorted_names(
+            db.fetch_directories(root_id)
+                .await
+                .expect("directory_crud: directories should be listed after removal")
+        ),
+        ["Home"],
+        "directory_crud: directories after removal"
+    );
+    assert!(
+        db.fetch_directory(work.id).await.is_err(),
+        "directory_crud: removed directory cannot be fetched"
+    );
+}
+
+pub async fn note_crud<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let note = db
+        .add_note(root_id.clone(), "Todo".to_owned())
+        .await
+        .expect("note_crud: note should be added");
+    assert_eq!(note.name, "Todo", "note_crud: added name");
+    assert_eq!(note.directory_id, root_id, "note_crud: added directory");
+    assert_eq!(
+        db.fetch_note_content(note.id.clone())
+            .await
+            .expect("note_crud: new content should be fetched"),
+        "",
+        "note_crud: new notes are empty"
+    );
+
+    db.update_note_content(note.id.clone(), "- milk\n- eggs\n".to_owned())
+        .await
+        .expect("note_crud: content should be updated");
+    assert_eq!(
+        db.fetch_note_content(note.id.clone())
+            .await
+            .expect("note_crud: content should be fetched"),
+        "- milk\n- eggs\n",
+        "note_crud: updated content"
+    );
+
+    db.rename_note(note.id.clone(), "Groceries".to_owned())
+        .await
+        .expect("note_crud: note should be renamed");
+    assert_eq!(
+        sorted_names(
+            db.fetch_notes(root_id.clone())
+                .await
+                .expect("note_crud: notes should be listed")
+        ),
+        ["Groceries"],
+        "note_crud: renamed note"
+    );
+
+    db.remove_note(note.id.clone())
+        .await
+        .expect("note_crud: note should be removed");
+    assert!(
+        db.fetch_notes(root_id)
+            .await
+            .expect("note_crud: notes should be listed after removal")
+            .is_empty(),
+        "note_crud: notes after removal"
+    );
+    assert!(
+        db.fetch_note_content(note.id).await.is_err(),
+        "note_crud: removed note content cannot be fetched"
+    );
+}
+
+pub async fn moves<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let work = add_directory(db, &root_id, "Work").await;
+    let archive = add_directory(db, &root_id, "Archive").await;
+    let project = add_directory(db, &work.id, "Project").await;
+    let note = add_note(db, &work.id, "Plan").await;
+
+    db.move_note(note.id.clone(), archive.id.clone())
+        .await
+        .expect("moves: note should be moved");
+    assert!(
+        db.fetch_notes(work.id.clone())
+            .await
+            .expect("moves: source notes should be listed")
+            .is_empty(),
+        "moves: note left its directory"
+    );
+    assert_eq!(
+        sorted_names(
+            db.fetch_notes(archive.id.clone())
+                .await
+                .expect("moves: target notes should be listed")
+        ),
+        ["Plan"],
+        "moves: note arrived"
+    );
+
+    db.move_directory(project.id.clone(), archive.id.clone())
+        .await
+        .expect("moves: directory should be moved");
+    let moved = db
+        .fetch_directory(project.id.clone())
+        .await
+        .expect("moves: moved directory should be fetched");
+    assert_eq!(moved.parent_id, archive.id, "moves: new parent");
+    assert!(
+        db.fetch_directories(work.id)
+            .await
+            .expect("moves: source directories should be listed")
+            .is_empty(),
+        "moves: directory left its parent"
+    );
+
+    db.move_directory(archive.id.clone(), root_id.clone())
+        .await
+        .expect("moves: moving into the current parent should succeed");
+    assert_eq!(
+        db.fetch_directory(archive.id)
+            .await
+            .expect("moves: directory should be fetched")
+            .parent_id,
+        root_id,
+        "moves: parent kept"
+    );
+}
+
+/// Removing a directory takes its subdirectories, notes and attachments along.
+pub async fn recursive_removal<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let work = add_directory(db, &root_id, "Work").await;
+    let project = add_directory(db, &work.id, "Project").await;
+    let deep = add_directory(db, &project.id, "Deep").await;
+    let note = add_note(db, &work.id, "Plan").await;
+    let deep_note = add_note(db, &deep.id, "Detail").await;
+    let attachment = db
+        .add_attachment(deep_note.id.clone(), "data.bin".to_owned(), vec![1, 2, 3])
+        .await
+        .expect("recursive_removal: attachment should be added");
+    let kept = add_note(db, &root_id, "Kept").await;
+
+    db.remove_directory(work.id.clone())
+        .await
+        .expect("recursive_removal: directory should be removed");
+
+    for id in [work.id, project.id, deep.id] {
+        assert!(
+            db.fetch_directory(id.clone()).await.is_err(),
+            "recursive_removal: directory {id} should be gone"
+        );
+    }
+    for id in [note.id, deep_note.id] {
+        assert!(
+            db.fetch_note_content(id.clone()).await.is_err(),
+            "recursive_removal: note {id} should be gone"
+        );
+    }
+    assert!(
+        db.fetch_attachment_data(attachment.id).await.is_err(),
+        "recursive_removal: attachment should be gone"
+    );
+    assert!(
+        db.fetch_directories(root_id.clone())
+            .await
+            .expect("recursive_removal: directories should be listed")
+            .is_empty(),
+        "recursive_removal: root has no directories left"
+    );
+    assert_eq!(
+        db.fetch_notes(root_id)
+            .await
+            .expect("recursive_removal: notes should be listed")
+            .into_iter()
+            .map(|note| note.id)
+            .collect::<Vec<_>>(),
+        [kept.id],
+        "recursive_removal: unrelated notes stay"
+    );
+}
+
+pub async fn attachments<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let note = add_note(db, &root_id, "Photos").await;
+    let data: Vec<u8> = (0..=255).cycle().take(100_000).collect();
+
+    let attachment = db
+        .add_attachment(note.id.clone(), "photo.png".to_owned(), data.clone())
+        .await
+        .expect("attachments: attachment should be added");
+    assert_eq!(attachment.name, "photo.png", "attachments: name");
+    assert_eq!(attachment.note_id, note.id, "attachments: note");
+    assert_eq!(attachment.size, data.len() as u64, "attachments: size");
+
+    let listed = db
+        .fetch_attachments(note.id.clone())
+        .await
+        .expect("attachments: attachments should be listed");
+    assert_eq!(listed.len(), 1, "attachments: listed count");
+    assert_eq!(listed[0].id, attachment.id, "attachments: listed id");
+    assert_eq!(
+        db.fetch_attachment_data(attachment.id.clone())
+            .await
+            .expect("attachments: data should be fetched"),
+        data,
+        "attachments: data round trip"
+    );
+
+    db.remove_attachment(attachment.id.clone())
+        .await
+        .expect("attachments: attachment should be removed");
+    assert!(
+        db.fetch_attachments(note.id)
+            .await
+            .expect("attachments: attachments should be listed after removal")
+            .is_empty(),
+        "attachments: removed attachment is not listed"
+    );
+}
+
+/// Children come back in the order of the requested directories.
+pub async fn fetch_children<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let work = add_directory(db, &root_id, "Work").await;
+    let home = add_directory(db, &root_id, "Home").await;
+    add_directory(db, &work.id, "Project").await;
+    add_note(db, &work.id, "Plan").await;
+    add_note(db, &home.id, "Chores").await;
+
+    let children = db
+        .fetch_children(vec![home.id.clone(), work.id.clone(), root_id])
+        .await
+        .expect("fetch_children: children should be fetched");
+    assert_eq!(children.len(), 3, "fetch_children: one entry per directory");
+
+    let [
+        (home_notes, home_dirs),
+        (work_notes, work_dirs),
+        (root_notes, root_dirs),
+    ] = <[_; 3]>::try_from(children).expect("fetch_children: three entries");
+    assert_eq!(
+        sorted_names(home_notes),
+        ["Chores"],
+        "fetch_children: home notes"
+    );
+    assert!(home_dirs.is_empty(), "fetch_children: home directories");
+    assert_eq!(
+        sorted_names(work_notes),
+        ["Plan"],
+        "fetch_children: work notes"
+    );
+    assert_eq!(
+        sorted_names(work_dirs),
+        ["Project"],
+        "fetch_children: work directories"
+    );
+    assert!(root_notes.is_empty(), "fetch_children: root notes");
+    assert_eq!(
+        sorted_names(root_dirs),
+        ["Home", "Work"],
+        "fetch_children: root directories"
+    );
+}
+
+/// Timestamps survive a round trip and writes never move `updated_at` back.
+pub async fn timestamps<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let directory = add_directory(db, &root_id, "Work").await;
+    let note = add_note(db, &directory.id, "Plan").await;
+
+    for (what, created_at, updated_at) in [
+        ("directory", &directory.created_at, &directory.updated_at),
+        ("note", &note.created_at, &note.updated_at),
+    ] {
+        assert!(
+            !created_at.is_empty(),
+            "timestamps: {what} has a creation time"
+        );
+        assert!(
+            updated_at >= created_at,
+            "timestamps: {what} updated_at {updated_at} precedes created_at {created_at}"
+        );
+    }
+
+    let fetched = db
+        .fetch_directory(directory.id.clone())
+        .await
+        .expect("timestamps: directory should be fetched");
+    assert_eq!(
+        fetched.created_at, directory.created_at,
+        "timestamps: directory created_at round trip"
+    );
+
+    db.rename_directory(directory.id.clone(), "Office".to_owned())
+        .await
+        .expect("timestamps: directory should be renamed");
+    let renamed = db
+        .fetch_directory(directory.id.clone())
+        .await
+        .expect("timestamps: renamed directory should be fetched");
+    assert_eq!(
+        renamed.created_at, directory.created_at,
+        "timestamps: rename keeps created_at"
+    );
+    assert!(
+        renamed.updated_at >= directory.updated_at,
+        "timestamps: rename does not move updated_at back"
+    );
+
+    db.update_note_content(note.id.clone(), "content".to_owned())
+        .await
+        .expect("timestamps: content should be updated");
+    let updated = db
+        .fetch_notes(directory.id)
+        .await
+        .expect("timestamps: notes should be listed")
+        .into_iter()
+        .find(|listed| listed.id == note.id)
+        .expect("timestamps: note should be listed");
+    assert_eq!(
+        updated.created_at, note.created_at,
+        "timestamps: edit keeps created_at"
+    );
+    assert!(
+        updated.updated_at >= note.updated_at,
+        "timestamps: edit does not move updated_at back"
+    );
+}
+
+pub async fn unicode_names<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let names = [
+        "메모장",
+        "Ñandú 🦤",
+        "日本語のノート",
+        "emoji 📝✨",
+        "quote's \"name\"",
+    ];
+
+    for name in names {
+        let directory = add_directory(db, &root_id, name).await;
+        assert_eq!(directory.name, name, "unicode_names: directory name");
+
+        let note = add_note(db, &directory.id, name).await;
+        let content = format!("# {name}\n\n{name}");
+        db.update_note_content(note.id.clone(), content.clone())
+            .await
+            .expect("unicode_names: content should be updated");
+        assert_eq!(
+            db.fetch_note_content(note.id)
+                .await
+                .expect("unicode_names: content should be fetched"),
+            content,
+            "unicode_names: content round trip"
+        );
+        assert_eq!(
+            sorted_names(
+                db.fetch_notes(directory.id)
+                    .await
+                    .expect("unicode_names: notes should be listed")
+            ),
+            [name],
+            "unicode_names: note name"
+        );
+    }
+
+    let mut expected = names.map(str::to_owned).to_vec();
+    expected.sort();
+    assert_eq!(
+        sorted_names(
+            db.fetch_directories(root_id)
+                .await
+                .expect("unicode_names: directories should be listed")
+        ),
+        expected,
+        "unicode_names: directory names"
+    );
+}
+
+pub async fn meta<B: CoreBackend>(db: &mut B) {
+    let key = "conformance.key".to_owned();
+    assert_eq!(
+        db.fetch_meta(key.clone())
+            .await
+            .expect("meta: missing key should be fetched"),
+        None,
+        "meta: missing key"
+    );
+
+    db.update_meta(key.clone(), "one".to_owned())
+        .await
+        .expect("meta: value should be written");
+    db.update_meta(key.clone(), "two".to_owned())
+        .await
+        .expect("meta: value should be overwritten");
+    assert_eq!(
+        db.fetch_meta(key.clone())
+            .await
+            .expect("meta: value should be fetched"),
+        Some("two".to_owned()),
+        "meta: latest value"
+    );
+    assert!(
+        db.fetch_all_meta()
+            .await
+            .expect("meta: entries should be listed")
+            .contains(&(key, "two".to_owned())),
+        "meta: listed entry"
+    );
+}
+
+/// Reads of missing rows fail, and so do writes which would break the tree.
+pub async fn error_cases<B: CoreBackend>(db: &mut B) {
+    let root_id = db.root_id();
+    let missing = MISSING_ID.to_owned();
+    let work = add_directory(db, &root_id, "Work").await;
+    let project = add_directory(db, &work.id, "Project").await;
+    let note = add_note(db, &work.id, "Plan").await;
+
+    assert!(
+        db.fetch_directory(missing.clone()).await.is_err(),
+        "error_cases: fetching a missing directory"
+    );
+    assert!(
+        db.fetch_note_content(missing.clone()).await.is_err(),
+        "error_cases: fetching missing note content"
+    );
+    assert!(
+        db.fetch_attachment_data(missing.clone()).await.is_err(),
+        "error_cases: fetching missing attachment data"
+    );
+    assert!(
+        db.add_directory(missing.clone(), "Lost".to_owned())
+            .await
+            .is_err(),
+        "error_cases: adding a directory to a missing parent"
+    );
+    assert!(
+        db.add_note(missing.clone(), "Lost".to_owned())
+            .await
+            .is_err(),
+        "error_cases: adding a note to a missing directory"
+    );
+    assert!(
+        db.add_attachment(missing.clone(), "lost.txt".to_owned(), vec![0])
+            .await
+            .is_err(),
+        "error_cases: attaching to a missing note"
+    );
+    assert!(
+        db.move_note(note.id.clone(), missing.clone())
+            .await
+            .is_err(),
+        "error_cases: moving a note into a missing directory"
+    );
+    assert!(
+        db.move_directory(work.id.clone(), missing).await.is_err(),
+        "error_cases: moving a directory into a missing parent"
+    );
+    assert!(
+        db.move_directory(work.id.clone(), work.id.clone())
+            .await
+            .is_err(),
+        "error_cases: moving a directory into itself"
+    );
+    assert!(
+        db.move_directory(work.id.clone(), project.id.clone())
+            .await
+            .is_err(),
+        "error_cases: moving a directory into its descendant"
+    );
+    assert!(
+        db.move_directory(root_id.clone(), work.id.clone())
+            .await
+            .is_err(),
+        "error_cases: moving the root"
+    );
+    assert!(
+        db.remove_directory(root_id.clone()).await.is_err(),
+        "error_cases: removing the root"
+    );
+
+    assert_eq!(
+        db.fetch_directory(work.id)
+            .await
+            .expect("error_cases: directory should still be fetched")
+            .parent_id,
+        root_id,
+        "error_cases: refused moves leave the tree as it was"
+    );
+    assert_eq!(
+        db.fetch_directory(root_id.clone())
+            .await
+            .expect("error_cases: root should still be fetched")
+            .parent_id,
+        root_id,
+        "error_cases: root stays in place"
+    );
+}
+
+async fn add_directory<B: CoreBackend>(db: &mut B, parent_id: &str, name: &str) -> Directory {
+    db.add_directory(parent_id.to_owned(), name.to_owned())
+        .await
+        .unwrap_or_else(|err| panic!("directory {name} should be added: {err}"))
+}
+
+async fn add_note<B: CoreBackend>(db: &mut B, directory_id: &str, name: &str) -> Note {
+    db.add_note(directory_id.to_owned(), name.to_owned())
+        .await
+        .unwrap_or_else(|err| panic!("note {name} should be added: {err}"))
+}
+
+trait Named {
+    fn name(&self) -> &str;
+}
+
+impl Named for Directory {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Named for Note {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+fn sorted_names<T: Named>(items: Vec<T>) -> Vec<String> {
+    let mut names: Vec<String> = items.iter().map(|item| item.name().to_owned()).collect();
+    names.sort();
+    names
+}
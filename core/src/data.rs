@@ -1,5 +1,5 @@
 use {
-    crate::types::{DirectoryId, NoteId},
+    crate::types::{AttachmentId, DirectoryId, NoteId},
     serde::{Deserialize, Serialize},
 };
 
@@ -20,3 +20,14 @@ pub struct Directory {
     pub created_at: String,
     pub updated_at: String,
 }
+
+/// File attached to a note. The bytes are fetched separately, so listing the
+/// attachments of a note stays cheap.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attachment {
+    pub id: AttachmentId,
+    pub note_id: NoteId,
+    pub name: String,
+    pub size: u64,
+    pub created_at: String,
+}
@@ -1,4 +1,11 @@
-use {gluesql::core::row_conversion::RowConversionError, thiserror::Error as ThisError};
+use {
+    crate::{
+        backend::SyncConflict,
+        types::{DirectoryId, NoteId},
+    },
+    gluesql::core::row_conversion::RowConversionError,
+    thiserror::Error as ThisError,
+};
 
 #[derive(ThisError, Debug)]
 pub enum Error {
@@ -26,6 +33,52 @@ pub enum Error {
     #[error("backend: {0}")]
     BackendError(String),
 
+    #[error("schema: {0}")]
+    Schema(String),
+
+    #[error("encryption: {0}")]
+    Encryption(String),
+
+    #[error("encryption: this notebook is not encrypted")]
+    NotEncrypted,
+
+    #[error("archive: {0}")]
+    Archive(String),
+
+    #[error("sync: {0}")]
+    Sync(String),
+
+    #[error("sync: {0}")]
+    SyncConflict(SyncConflict),
+
     #[error("row conversion: {0}")]
     RowConversion(#[from] RowConversionError),
+
+    #[error("read-only: {0} is not allowed")]
+    ReadOnly(String),
+
+    #[error("layer: {0}")]
+    Layer(String),
+
+    #[error("integrity: {0}")]
+    Integrity(#[from] IntegrityError),
+}
+
+/// Writes refused because they would break the notebook tree.
+#[derive(ThisError, Clone, Debug, PartialEq, Eq)]
+pub enum IntegrityError {
+    #[error("directory {0} does not exist")]
+    MissingDirectory(DirectoryId),
+
+    #[error("note {0} does not exist")]
+    MissingNote(NoteId),
+
+    #[error("directory {directory_id} cannot move into itself or its descendant {parent_id}")]
+    Cycle {
+        directory_id: DirectoryId,
+        parent_id: DirectoryId,
+    },
+
+    #[error("the root directory cannot be moved or removed")]
+    RootDirectory,
 }
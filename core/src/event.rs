@@ -1,12 +1,15 @@
 use {
     crate::{
+        backend::proxy::TlsOptions,
         data::{Directory, Note},
-        types::{DirectoryId, NoteId},
+        state::notebook::NotebookSession,
+        types::{AttachmentId, Cursor, DirectoryId, NoteId},
     },
+    serde::{Deserialize, Serialize},
     strum_macros::Display,
 };
 
-#[derive(Clone, Debug, Display)]
+#[derive(Clone, Debug, Display, Serialize, Deserialize)]
 pub enum Event {
     #[strum(to_string = "Key::{0}")]
     Key(KeyEvent),
@@ -20,7 +23,7 @@ pub enum Event {
     Cancel,
 }
 
-#[derive(Clone, Debug, Display)]
+#[derive(Clone, Debug, Display, Serialize, Deserialize)]
 pub enum EntryEvent {
     OpenMemory,
     OpenRedb(String),
@@ -34,13 +37,26 @@ pub enum EntryEvent {
         conn_str: String,
         db_name: String,
     },
+    /// Writes made offline are queued in the redb file at `queue_path`.
     OpenProxy {
         url: String,
         auth_token: Option<String>,
+        tls: TlsOptions,
+        queue_path: Option<String>,
+    },
+    /// Opens the encrypted notebook of `open` with `passphrase`.
+    OpenEncrypted {
+        open: Box<EntryEvent>,
+        passphrase: String,
+    },
+    /// Encrypts the plain notebook of `open` with `passphrase`, then opens it.
+    EncryptNotebook {
+        open: Box<EntryEvent>,
+        passphrase: String,
     },
 }
 
-#[derive(Clone, Debug, Display)]
+#[derive(Clone, Debug, Display, Serialize, Deserialize)]
 pub enum NotebookEvent {
     OpenDirectory(DirectoryId),
     CloseDirectory(DirectoryId),
@@ -64,24 +80,54 @@ pub enum NotebookEvent {
     AddDirectory(String),
 
     MoveNote(DirectoryId),
+
+    /// Attaches the bytes of a file to the selected note.
+    AddAttachment {
+        name: String,
+        data: Vec<u8>,
+    },
+    RemoveAttachment(AttachmentId),
     MoveDirectory(DirectoryId),
 
     OpenNote,
     EditNote,
     ViewNote,
+    CompleteNoteLink,
 
-    UpdateNoteContent { note_id: NoteId, content: String },
+    UpdateNoteContent {
+        note_id: NoteId,
+        content: String,
+    },
+
+    SetMark {
+        mark: char,
+        cursor: Cursor,
+    },
+    JumpToMark {
+        mark: char,
+        linewise: bool,
+        cursor: Cursor,
+    },
+    JumpOlder(Cursor),
+    JumpNewer,
+
+    /// Switches to the tab at the index, e.g. when its title is clicked.
+    SelectTab(usize),
 
     CloseEntryDialog,
+
+    /// Reopens saved directories and tabs, skipping those which no longer exist.
+    RestoreSession(NotebookSession),
 }
 
-#[derive(Clone, Copy, Debug, Display)]
+#[derive(Clone, Copy, Debug, Display, Serialize, Deserialize)]
 pub enum KeyEvent {
     A,
     B,
     C,
     D,
     E,
+    F,
     G,
     H,
     I,
@@ -101,6 +147,7 @@ pub enum KeyEvent {
     Y,
     Z,
     CapA,
+    CapF,
     CapG,
     CapH,
     CapI,
@@ -109,15 +156,32 @@ pub enum KeyEvent {
     CapL,
     CapO,
     CapS,
+    CapT,
     CapU,
+    CapV,
     CapX,
     CtrlH,
+    CtrlI,
+    CtrlO,
     CtrlR,
+    CtrlV,
     DollarSign,
     Caret,
     QuestionMark,
     AngleBracketOpen,
     AngleBracketClose,
+    ParenOpen,
+    ParenClose,
+    SquareBracketOpen,
+    SquareBracketClose,
+    CurlyBracketOpen,
+    CurlyBracketClose,
+    SingleQuote,
+    DoubleQuote,
+    Backtick,
+    Semicolon,
+    Comma,
+    Percent,
     Num(NumKey),
     Left,
     Right,
@@ -130,9 +194,12 @@ pub enum KeyEvent {
     Dot,
     Dash,
     Esc,
+
+    /// Printable character without a dedicated variant, e.g. the target of `f{char}`.
+    Char(char),
 }
 
-#[derive(Clone, Copy, Debug, Display)]
+#[derive(Clone, Copy, Debug, Display, Serialize, Deserialize)]
 pub enum NumKey {
     One,
     Two,
@@ -146,6 +213,89 @@ pub enum NumKey {
     Zero,
 }
 
+impl KeyEvent {
+    /// Returns the character typed for this key, if it is a printable one.
+    pub fn to_char(self) -> Option<char> {
+        let ch = match self {
+            KeyEvent::A => 'a',
+            KeyEvent::B => 'b',
+            KeyEvent::C => 'c',
+            KeyEvent::D => 'd',
+            KeyEvent::E => 'e',
+            KeyEvent::F => 'f',
+            KeyEvent::G => 'g',
+            KeyEvent::H => 'h',
+            KeyEvent::I => 'i',
+            KeyEvent::J => 'j',
+            KeyEvent::K => 'k',
+            KeyEvent::L => 'l',
+            KeyEvent::M => 'm',
+            KeyEvent::N => 'n',
+            KeyEvent::O => 'o',
+            KeyEvent::P => 'p',
+            KeyEvent::S => 's',
+            KeyEvent::T => 't',
+            KeyEvent::U => 'u',
+            KeyEvent::V => 'v',
+            KeyEvent::W => 'w',
+            KeyEvent::X => 'x',
+            KeyEvent::Y => 'y',
+            KeyEvent::Z => 'z',
+            KeyEvent::CapA => 'A',
+            KeyEvent::CapF => 'F',
+            KeyEvent::CapG => 'G',
+            KeyEvent::CapH => 'H',
+            KeyEvent::CapI => 'I',
+            KeyEvent::CapJ => 'J',
+            KeyEvent::CapK => 'K',
+            KeyEvent::CapL => 'L',
+            KeyEvent::CapO => 'O',
+            KeyEvent::CapS => 'S',
+            KeyEvent::CapT => 'T',
+            KeyEvent::CapU => 'U',
+            KeyEvent::CapV => 'V',
+            KeyEvent::CapX => 'X',
+            KeyEvent::DollarSign => '$',
+            KeyEvent::Caret => '^',
+            KeyEvent::QuestionMark => '?',
+            KeyEvent::AngleBracketOpen => '<',
+            KeyEvent::AngleBracketClose => '>',
+            KeyEvent::ParenOpen => '(',
+            KeyEvent::ParenClose => ')',
+            KeyEvent::SquareBracketOpen => '[',
+            KeyEvent::SquareBracketClose => ']',
+            KeyEvent::CurlyBracketOpen => '{',
+            KeyEvent::CurlyBracketClose => '}',
+            KeyEvent::SingleQuote => '\'',
+            KeyEvent::DoubleQuote => '"',
+            KeyEvent::Backtick => '`',
+            KeyEvent::Semicolon => ';',
+            KeyEvent::Comma => ',',
+            KeyEvent::Percent => '%',
+            KeyEvent::Num(n) => char::from_digit(usize::from(n) as u32, 10)?,
+            KeyEvent::Space => ' ',
+            KeyEvent::Tilde => '~',
+            KeyEvent::Dot => '.',
+            KeyEvent::Dash => '-',
+            KeyEvent::Char(ch) => ch,
+            KeyEvent::CtrlH
+            | KeyEvent::CtrlI
+            | KeyEvent::CtrlO
+            | KeyEvent::CtrlR
+            | KeyEvent::CtrlV
+            | KeyEvent::Left
+            | KeyEvent::Right
+            | KeyEvent::Up
+            | KeyEvent::Down
+            | KeyEvent::Enter
+            | KeyEvent::Tab
+            | KeyEvent::Esc => return None,
+        };
+
+        Some(ch)
+    }
+}
+
 impl From<EntryEvent> for Event {
     fn from(event: EntryEvent) -> Self {
         Self::Entry(event)
@@ -1,12 +1,14 @@
 use crate::{
     Event, Result, Transition,
-    backend::BackendBox,
+    backend::{BackendBox, layer::LayerStack},
     state::{EntryState, State},
 };
 
 pub struct Glues {
     pub db: Option<BackendBox>,
     pub state: State,
+    /// Wrapped around every backend the entry screen opens.
+    pub layers: LayerStack,
 }
 
 impl Default for Glues {
@@ -20,6 +22,7 @@ impl Glues {
         Self {
             db: None,
             state: EntryState.into(),
+            layers: LayerStack::new(),
         }
     }
 
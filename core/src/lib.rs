@@ -1,16 +1,19 @@
 pub mod backend;
+pub mod backup;
+pub mod conformance;
 mod error;
 mod event;
 mod glues;
-mod schema;
 
 pub mod data;
+pub mod rpc;
+pub mod schema;
 pub mod state;
 pub mod transition;
 pub mod types;
 
 pub use backend::CoreBackend;
-pub use error::Error;
+pub use error::{Error, IntegrityError};
 pub use event::{EntryEvent, Event, KeyEvent, NotebookEvent, NumKey};
 pub use glues::Glues;
 pub use transition::{EntryTransition, NotebookTransition, Transition};
@@ -0,0 +1,125 @@
+//! Drives a [`Glues`] instance over JSON lines, so frontends outside the Rust
+//! process can send events and render the transitions they produce:
+//!
+//! ```text
+//! > {"id":1,"method":"Dispatch","data":{"Entry":"OpenMemory"}}
+//! < {"id":1,"status":"Ok","data":{"transition":{"Entry":"OpenNotebook"},"state":{...}}}
+//! ```
+
+use {
+    crate::{Event, Glues, Transition, types::KeymapGroup},
+    serde::{Deserialize, Serialize},
+    tokio::io::{self, AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt},
+};
+
+/// One line sent by the frontend. `id` is echoed back in the response.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RpcRequest {
+    #[serde(default)]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub call: RpcCall,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "method", content = "data")]
+pub enum RpcCall {
+    Dispatch(Event),
+    /// Describes the current state without changing it.
+    State,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub reply: RpcReply,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status", content = "data")]
+pub enum RpcReply {
+    Ok(Box<RpcOutput>),
+    Err(String),
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RpcOutput {
+    /// Set for `Dispatch` calls.
+    pub transition: Option<Transition>,
+    pub state: StateView,
+}
+
+/// What a frontend needs to render besides the transitions.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct StateView {
+    pub description: String,
+    pub keymap_visible: bool,
+    pub keymap: Vec<KeymapGroup>,
+}
+
+impl StateView {
+    pub fn of(glues: &Glues) -> crate::Result<Self> {
+        Ok(Self {
+            description: glues.state.describe()?,
+            keymap_visible: glues.state.keymap,
+            keymap: glues.state.keymap(),
+        })
+    }
+}
+
+/// Answers one request.
+pub async fn handle(glues: &mut Glues, request: RpcRequest) -> RpcResponse {
+    let RpcRequest { id, call } = request;
+    let transition = match call {
+        RpcCall::Dispatch(event) => match glues.dispatch(event).await {
+            Ok(transition) => Some(transition),
+            Err(err) => return RpcResponse::err(id, err.to_string()),
+        },
+        RpcCall::State => None,
+    };
+
+    match StateView::of(glues) {
+        Ok(state) => RpcResponse {
+            id,
+            reply: RpcReply::Ok(Box::new(RpcOutput { transition, state })),
+        },
+        Err(err) => RpcResponse::err(id, err.to_string()),
+    }
+}
+
+/// Reads requests from `input` line by line and writes a response line for
+/// each of them until `input` ends. Lines which are not valid requests are
+/// answered with an error without an `id`.
+pub async fn serve<R, W>(glues: &mut Glues, input: R, mut output: W) -> io::Result<()>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut lines = input.lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<RpcRequest>(&line) {
+            Ok(request) => handle(glues, request).await,
+            Err(err) => RpcResponse::err(None, format!("invalid request: {err}")),
+        };
+        let mut json = serde_json::to_vec(&response).map_err(io::Error::other)?;
+        json.push(b'\n');
+        output.write_all(&json).await?;
+        output.flush().await?;
+    }
+
+    Ok(())
+}
+
+impl RpcResponse {
+    fn err(id: Option<u64>, message: String) -> Self {
+        Self {
+            id,
+            reply: RpcReply::Err(message),
+        }
+    }
+}
@@ -1,82 +1,87 @@
 use {
     crate::{
-        Result,
+        Error, Result,
         backend::local::{Execute, Storage},
         types::DirectoryId,
     },
     gluesql::{
         FromGlueRow,
         core::{
-            ast_builder::{col, table, text},
+            ast_builder::{col, glue_tables, table, text, timestamp},
+            chrono::{TimeDelta, Utc},
             row_conversion::SelectExt,
         },
     },
+    std::{future::Future, pin::Pin},
 };
 
-#[derive(FromGlueRow)]
-struct RootRow {
-    id: String,
-}
+/// Schema version written by this build, the version of the last migration.
+pub const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
 
-pub async fn setup(storage: &mut Storage) -> Result<DirectoryId> {
-    table("Log")
-        .create_table_if_not_exists()
-        .add_column("category TEXT NULL")
-        .add_column("message TEXT NOT NULL")
-        .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .execute(storage)
-        .await?;
+/// `Meta` key holding the schema version of a notebook.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
 
-    table("Log").delete().execute(storage).await?;
+/// `Meta` key holding how many days of `Log` entries a notebook keeps.
+pub const LOG_RETENTION_KEY: &str = "log_retention_days";
 
-    table("Meta")
-        .create_table_if_not_exists()
-        .add_column("key TEXT PRIMARY KEY")
-        .add_column("value TEXT NOT NULL")
-        .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .execute(storage)
-        .await?;
+/// Days of `Log` entries kept by notebooks which set no retention.
+pub const DEFAULT_LOG_RETENTION_DAYS: u32 = 30;
 
-    table("Directory")
-        .create_table_if_not_exists()
-        .add_column("id UUID PRIMARY KEY DEFAULT GENERATE_UUID()")
-        .add_column("parent_id UUID NULL")
-        .add_column("name TEXT NOT NULL")
-        .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .execute(storage)
-        .await?;
+type MigrationFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
 
-    table("Note")
-        .create_table_if_not_exists()
-        .add_column("id UUID PRIMARY KEY")
-        .add_column("name TEXT NOT NULL")
-        .add_column("directory_id UUID NOT NULL")
-        .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
-        .add_column("content TEXT NOT NULL DEFAULT ''")
-        .execute(storage)
-        .await?;
+/// One step of the schema history. Steps run in order, each one upgrading a
+/// notebook from `version - 1` to `version`.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    apply: for<'a> fn(&'a mut Storage) -> MigrationFuture<'a>,
+}
 
-    let schema_version_not_exists = table("Meta")
-        .select()
-        .filter(col("key").eq(text("schema_version")))
-        .project("key")
-        .execute(storage)
-        .await?
-        .select()
-        .expect("Meta query must yield a result set")
-        .count()
-        == 0;
+/// Every schema change, oldest first. New steps are appended with the next version;
+/// released steps must never be edited, since notebooks already ran them.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create Log, Meta, Directory and Note tables",
+        apply: create_tables,
+    },
+    Migration {
+        version: 2,
+        description: "create Attachment table",
+        apply: create_attachment_table,
+    },
+];
 
-    if schema_version_not_exists {
-        table("Meta")
-            .insert()
-            .columns(vec!["key", "value"])
-            .values(vec![vec![text("schema_version"), text("1")]])
-            .execute(storage)
-            .await?;
+/// Where a notebook stands relative to [`SCHEMA_VERSION`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Version the notebook was at when it was opened, 0 for an empty storage.
+    pub current: u32,
+    pub target: u32,
+    /// Steps between `current` and `target` as `(version, description)`.
+    pub pending: Vec<(u32, &'static str)>,
+}
+
+impl MigrationReport {
+    pub fn is_up_to_date(&self) -> bool {
+        self.pending.is_empty()
     }
+}
+
+#[derive(FromGlueRow)]
+struct RootRow {
+    id: String,
+}
+
+#[derive(FromGlueRow)]
+struct MetaValueRow {
+    value: String,
+}
+
+pub async fn setup(storage: &mut Storage) -> Result<DirectoryId> {
+    migrate(storage).await?;
+
+    prune_log(storage).await?;
 
     let root_not_exists = table("Directory")
         .select()
@@ -98,13 +103,206 @@ pub async fn setup(storage: &mut Storage) -> Result<DirectoryId> {
             .await?;
     }
 
+    root_id(storage).await
+}
+
+/// Id of the root directory. A broken notebook can hold several roots, the
+/// oldest one stays the root until `Db::fsck` repairs the others.
+pub(crate) async fn root_id(storage: &mut Storage) -> Result<DirectoryId> {
     let row = table("Directory")
         .select()
         .filter(col("parent_id").is_null())
         .project("id")
+        .order_by("created_at")
+        .limit(1)
         .execute(storage)
         .await?
         .one_as::<RootRow>()?;
 
     Ok(row.id)
 }
+
+/// Drops the `Log` entries older than the retention set under
+/// [`LOG_RETENTION_KEY`], so the table does not grow without bound.
+async fn prune_log(storage: &mut Storage) -> Result<()> {
+    let value = table("Meta")
+        .select()
+        .filter(col("key").eq(text(LOG_RETENTION_KEY)))
+        .project("value")
+        .execute(storage)
+        .await?
+        .rows_as::<MetaValueRow>()?
+        .into_iter()
+        .next()
+        .map(|row| row.value);
+    let days = match value {
+        None => DEFAULT_LOG_RETENTION_DAYS,
+        Some(value) => value
+            .parse()
+            .map_err(|_| Error::Schema(format!("invalid log retention '{value}'")))?,
+    };
+
+    let cutoff = Utc::now().naive_utc() - TimeDelta::days(days.into());
+    table("Log")
+        .delete()
+        .filter(col("created_at").lt(timestamp(cutoff.to_string())))
+        .execute(storage)
+        .await?;
+
+    Ok(())
+}
+
+/// Lists the migrations `migrate` would run without touching the storage.
+pub async fn report(storage: &mut Storage) -> Result<MigrationReport> {
+    let current = schema_version(storage).await?;
+    if current > SCHEMA_VERSION {
+        return Err(Error::Schema(format!(
+            "notebook schema version {current} is newer than {SCHEMA_VERSION}, \
+             the latest version this Glues supports"
+        )));
+    }
+
+    let pending = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current)
+        .map(|migration| (migration.version, migration.description))
+        .collect();
+
+    Ok(MigrationReport {
+        current,
+        target: SCHEMA_VERSION,
+        pending,
+    })
+}
+
+/// Upgrades the storage to [`SCHEMA_VERSION`] one step at a time. The version is
+/// recorded after every step, so an interrupted upgrade resumes where it stopped.
+/// Returns the report of the steps that were applied.
+pub async fn migrate(storage: &mut Storage) -> Result<MigrationReport> {
+    let report = report(storage).await?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > report.current) {
+        (migration.apply)(storage).await?;
+        write_schema_version(storage, migration.version).await?;
+    }
+
+    Ok(report)
+}
+
+async fn schema_version(storage: &mut Storage) -> Result<u32> {
+    let meta_exists = glue_tables()
+        .select()
+        .filter(col("TABLE_NAME").eq(text("Meta")))
+        .execute(storage)
+        .await?
+        .select()
+        .expect("GLUE_TABLES query must yield a result set")
+        .count()
+        > 0;
+
+    if !meta_exists {
+        return Ok(0);
+    }
+
+    let value = table("Meta")
+        .select()
+        .filter(col("key").eq(text(SCHEMA_VERSION_KEY)))
+        .project("value")
+        .execute(storage)
+        .await?
+        .rows_as::<MetaValueRow>()?
+        .into_iter()
+        .next()
+        .map(|row| row.value);
+
+    match value {
+        None => Ok(0),
+        Some(value) => value
+            .parse()
+            .map_err(|_| Error::Schema(format!("invalid schema version '{value}'"))),
+    }
+}
+
+async fn write_schema_version(storage: &mut Storage, version: u32) -> Result<()> {
+    table("Meta")
+        .delete()
+        .filter(col("key").eq(text(SCHEMA_VERSION_KEY)))
+        .execute(storage)
+        .await?;
+
+    table("Meta")
+        .insert()
+        .columns(vec!["key", "value"])
+        .values(vec![vec![
+            text(SCHEMA_VERSION_KEY),
+            text(version.to_string()),
+        ]])
+        .execute(storage)
+        .await?;
+
+    Ok(())
+}
+
+/// Version 1. Notebooks created before versioning already have these tables,
+/// which is why every statement tolerates existing ones.
+fn create_tables(storage: &mut Storage) -> MigrationFuture<'_> {
+    Box::pin(async move {
+        table("Log")
+            .create_table_if_not_exists()
+            .add_column("category TEXT NULL")
+            .add_column("message TEXT NOT NULL")
+            .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .execute(storage)
+            .await?;
+
+        table("Meta")
+            .create_table_if_not_exists()
+            .add_column("key TEXT PRIMARY KEY")
+            .add_column("value TEXT NOT NULL")
+            .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .execute(storage)
+            .await?;
+
+        table("Directory")
+            .create_table_if_not_exists()
+            .add_column("id UUID PRIMARY KEY DEFAULT GENERATE_UUID()")
+            .add_column("parent_id UUID NULL")
+            .add_column("name TEXT NOT NULL")
+            .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .execute(storage)
+            .await?;
+
+        table("Note")
+            .create_table_if_not_exists()
+            .add_column("id UUID PRIMARY KEY")
+            .add_column("name TEXT NOT NULL")
+            .add_column("directory_id UUID NOT NULL")
+            .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .add_column("updated_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .add_column("content TEXT NOT NULL DEFAULT ''")
+            .execute(storage)
+            .await?;
+
+        Ok(())
+    })
+}
+
+/// Version 2. Attachment bytes live in their own table, away from `Note`, so
+/// fetching the note tree never loads them.
+fn create_attachment_table(storage: &mut Storage) -> MigrationFuture<'_> {
+    Box::pin(async move {
+        table("Attachment")
+            .create_table_if_not_exists()
+            .add_column("id UUID PRIMARY KEY")
+            .add_column("note_id UUID NOT NULL")
+            .add_column("name TEXT NOT NULL")
+            .add_column("size INTEGER NOT NULL")
+            .add_column("data BYTEA NOT NULL")
+            .add_column("created_at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .execute(storage)
+            .await?;
+
+        Ok(())
+    })
+}
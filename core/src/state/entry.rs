@@ -1,6 +1,11 @@
 use crate::{
     EntryEvent, EntryTransition, Error, Event, Glues, Result,
-    backend::{local::Db, proxy::ProxyClient},
+    backend::{
+        BackendBox,
+        encrypted::{Encrypted, is_encrypted},
+        local::Db,
+        proxy::ProxyClient,
+    },
     state::notebook::NotebookState,
     types::{KeymapGroup, KeymapItem},
 };
@@ -13,53 +18,32 @@ impl EntryState {
         use Event::*;
 
         match event {
-            Entry(OpenMemory) => {
-                let mut db = Db::memory().await?;
-                let root_id = db.root_id.clone();
-                let note_id = db.add_note(root_id, "Sample Note".to_owned()).await?.id;
-                db.update_note_content(note_id, "Hi :D".to_owned()).await?;
+            Entry(OpenEncrypted { open, passphrase }) => {
+                let backend = open_backend(*open).await?;
+                let backend = Encrypted::unlock(backend, &passphrase).await?;
 
-                glues.db = Some(Box::new(db));
+                glues.db = Some(glues.layers.apply(Box::new(backend)));
                 glues.state = NotebookState::new(glues).await?.into();
                 Ok(EntryTransition::OpenNotebook)
             }
-            Entry(OpenFile(path)) => {
-                let db = Db::file(&path).await?;
-                glues.db = Some(Box::new(db));
-                glues.state = NotebookState::new(glues).await?.into();
-
-                Ok(EntryTransition::OpenNotebook)
-            }
-            Entry(OpenRedb(path)) => {
-                let db = Db::redb(&path).await?;
-                glues.db = Some(Box::new(db));
-                glues.state = NotebookState::new(glues).await?.into();
+            Entry(EncryptNotebook { open, passphrase }) => {
+                let backend = open_backend(*open).await?;
+                let backend = Encrypted::encrypt(backend, &passphrase).await?;
 
-                Ok(EntryTransition::OpenNotebook)
-            }
-            Entry(OpenGit {
-                path,
-                remote,
-                branch,
-            }) => {
-                let db = Db::git(&path, remote, branch).await?;
-                glues.db = Some(Box::new(db));
+                glues.db = Some(glues.layers.apply(Box::new(backend)));
                 glues.state = NotebookState::new(glues).await?.into();
-
                 Ok(EntryTransition::OpenNotebook)
             }
-            Entry(OpenMongo { conn_str, db_name }) => {
-                let db = Db::mongo(&conn_str, &db_name).await?;
-                glues.db = Some(Box::new(db));
-                glues.state = NotebookState::new(glues).await?.into();
+            Entry(open) => {
+                let mut backend = open_backend(open).await?;
+                if is_encrypted(&mut backend).await? {
+                    return Err(Error::Encryption(
+                        "this notebook is encrypted, a passphrase is required".to_owned(),
+                    ));
+                }
 
-                Ok(EntryTransition::OpenNotebook)
-            }
-            Entry(OpenProxy { url, auth_token }) => {
-                let client = ProxyClient::connect(url, auth_token).await?;
-                glues.db = Some(Box::new(client));
+                glues.db = Some(glues.layers.apply(backend));
                 glues.state = NotebookState::new(glues).await?.into();
-
                 Ok(EntryTransition::OpenNotebook)
             }
             Key(_) => Ok(EntryTransition::Inedible(event)),
@@ -91,6 +75,54 @@ impl EntryState {
                     KeymapItem::new("q", "Quit"),
                 ],
             ),
+            KeymapGroup::new(
+                "Recent notebooks",
+                vec![
+                    KeymapItem::new("1-9", "Open recent notebook"),
+                    KeymapItem::new("P", "Pin or unpin selected notebook"),
+                    KeymapItem::new("x", "Forget selected notebook"),
+                ],
+            ),
         ]
     }
 }
+
+async fn open_backend(event: EntryEvent) -> Result<BackendBox> {
+    use EntryEvent::*;
+
+    let backend: BackendBox = match event {
+        OpenMemory => {
+            let mut db = Db::memory().await?;
+            let root_id = db.root_id.clone();
+            let note_id = db.add_note(root_id, "Sample Note".to_owned()).await?.id;
+            db.update_note_content(note_id, "Hi :D".to_owned()).await?;
+
+            Box::new(db)
+        }
+        OpenFile(path) => Box::new(Db::file(&path).await?),
+        OpenRedb(path) => Box::new(Db::redb(&path).await?),
+        OpenGit {
+            path,
+            remote,
+            branch,
+        } => Box::new(Db::git(&path, remote, branch).await?),
+        OpenMongo { conn_str, db_name } => Box::new(Db::mongo(&conn_str, &db_name).await?),
+        OpenProxy {
+            url,
+            auth_token,
+            tls,
+            queue_path: None,
+        } => Box::new(ProxyClient::connect_with_tls(url, auth_token, tls).await?),
+        OpenProxy {
+            url,
+            auth_token,
+            tls,
+            queue_path: Some(queue_path),
+        } => Box::new(ProxyClient::connect_with_queue(url, auth_token, tls, &queue_path).await?),
+        OpenEncrypted { open, .. } | EncryptNotebook { open, .. } => {
+            return Box::pin(open_backend(*open)).await;
+        }
+    };
+
+    Ok(backend)
+}
@@ -1,15 +1,18 @@
 mod consume;
 mod directory_item;
 mod inner_state;
+mod marks;
 
 use {
     crate::{
         Error, Event, Glues, NotebookTransition, Result,
         data::{Directory, Note},
         state::GetInner,
-        types::{DirectoryId, Id, KeymapGroup},
+        transition::{CharSearch, CharSearchKind, TextObjectScope, VimOperator, VisualKind},
+        types::{DirectoryId, Id, KeymapGroup, NoteId},
     },
-    consume::{directory, note, tabs},
+    consume::{attachment, directory, mark, note, session, tabs},
+    serde::{Deserialize, Serialize},
 };
 
 pub use inner_state::{
@@ -19,6 +22,7 @@ pub use inner_state::{
 };
 
 pub use directory_item::{DirectoryItem, DirectoryItemChildren, TreeItem};
+pub use marks::{GlobalMark, Jump, Marks};
 
 pub struct NotebookState {
     pub root: DirectoryItem,
@@ -26,6 +30,15 @@ pub struct NotebookState {
     pub tabs: Vec<Tab>,
     pub tab_index: Option<usize>,
 
+    /// Last `f`/`F`/`t`/`T` search, repeated by `;` and `,`.
+    pub last_char_search: Option<CharSearch>,
+
+    /// Shape of the current, or most recently left, visual mode selection.
+    pub visual_kind: VisualKind,
+
+    /// `m{a-z}` / `m{A-Z}` marks and the `Ctrl-o` / `Ctrl-i` jump list.
+    pub marks: Marks,
+
     pub inner_state: InnerState,
 }
 
@@ -35,6 +48,24 @@ pub struct Tab {
     pub breadcrumb: Vec<String>,
 }
 
+/// Opened directories and tabs of a notebook, saved by a frontend when it
+/// leaves and reopened with [`NotebookEvent::RestoreSession`].
+///
+/// [`NotebookEvent::RestoreSession`]: crate::NotebookEvent::RestoreSession
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotebookSession {
+    /// Parents come before their children.
+    pub directories: Vec<DirectoryId>,
+    pub tabs: Vec<SessionTab>,
+    pub tab_index: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionTab {
+    pub note_id: NoteId,
+    pub directory_id: DirectoryId,
+}
+
 pub enum SelectedItem {
     Note(Note),
     Directory(Directory),
@@ -65,12 +96,20 @@ impl NotebookState {
         };
         let selected = SelectedItem::Directory(root.directory.clone());
 
+        let mut marks = Marks::default();
+        if let Some(value) = db.fetch_meta(mark::GLOBAL_MARKS_KEY.to_owned()).await? {
+            marks.load_global_from_meta(&value);
+        }
+
         Ok(Self {
             inner_state: NoteTree(NoteTreeState::DirectorySelected),
             root,
             selected,
             tabs: Vec::new(),
             tab_index: None,
+            last_char_search: None,
+            visual_kind: VisualKind::Char,
+            marks,
         })
     }
 
@@ -186,16 +225,6 @@ impl NotebookState {
 
                 format!("Note '{name}' normal mode - delete '{n1}d{n2}'")
             }
-            Editor(EditorState::Normal(VimNormalState::DeleteInside(n))) => {
-                let name = &self.get_selected_note()?.name;
-                let n = if *n >= 2 {
-                    format!("{n}")
-                } else {
-                    "".to_owned()
-                };
-
-                format!("Note '{name}' normal mode - delete inside {n}di")
-            }
             Editor(EditorState::Normal(VimNormalState::Change(n))) => {
                 let name = &self.get_selected_note()?.name;
 
@@ -221,15 +250,39 @@ impl NotebookState {
 
                 format!("Note '{name}' normal mode - change '{n1}c{n2}'")
             }
-            Editor(EditorState::Normal(VimNormalState::ChangeInside(n))) => {
+            Editor(EditorState::Normal(VimNormalState::TextObject(operator, scope, n))) => {
                 let name = &self.get_selected_note()?.name;
                 let n = if *n >= 2 {
                     format!("{n}")
                 } else {
                     "".to_owned()
                 };
+                let keys = format!("{n}{}{}", operator_key(operator), scope_key(scope));
+
+                format!("Note '{name}' normal mode - text object '{keys}'")
+            }
+            Editor(EditorState::Normal(VimNormalState::CharSearch(operator, kind, n))) => {
+                let name = &self.get_selected_note()?.name;
+                let n = if *n >= 2 {
+                    format!("{n}")
+                } else {
+                    "".to_owned()
+                };
+                let operator = operator.as_ref().map(operator_key).unwrap_or_default();
+                let keys = format!("{n}{operator}{}", char_search_key(kind));
+
+                format!("Note '{name}' normal mode - find character '{keys}'")
+            }
+            Editor(EditorState::Normal(VimNormalState::Mark)) => {
+                let name = &self.get_selected_note()?.name;
+
+                format!("Note '{name}' normal mode - set mark 'm'")
+            }
+            Editor(EditorState::Normal(VimNormalState::MarkJump { linewise })) => {
+                let name = &self.get_selected_note()?.name;
+                let key = if *linewise { "'" } else { "`" };
 
-                format!("Note '{name}' normal mode - change inside {n}ci")
+                format!("Note '{name}' normal mode - jump to mark '{key}'")
             }
             Editor(EditorState::Normal(VimNormalState::Scroll)) => {
                 let name = &self.get_selected_note()?.name;
@@ -239,17 +292,43 @@ impl NotebookState {
             Editor(EditorState::Visual(VimVisualState::Idle)) => {
                 let name = &self.get_selected_note()?.name;
 
-                format!("Note '{name}' visual mode")
+                let mode = visual_mode_name(self.visual_kind);
+
+                format!("Note '{name}' {mode} mode")
             }
             Editor(EditorState::Visual(VimVisualState::Numbering(n))) => {
                 let name = &self.get_selected_note()?.name;
 
-                format!("Note '{name}' visual mode, input: '{n}'")
+                let mode = visual_mode_name(self.visual_kind);
+
+                format!("Note '{name}' {mode} mode, input: '{n}'")
             }
             Editor(EditorState::Visual(VimVisualState::Gateway)) => {
                 let name = &self.get_selected_note()?.name;
 
-                format!("Note '{name}' visual mode - gateway")
+                let mode = visual_mode_name(self.visual_kind);
+
+                format!("Note '{name}' {mode} mode - gateway")
+            }
+            Editor(EditorState::Visual(VimVisualState::TextObject(scope))) => {
+                let name = &self.get_selected_note()?.name;
+
+                let mode = visual_mode_name(self.visual_kind);
+
+                format!(
+                    "Note '{name}' {mode} mode - text object '{}'",
+                    scope_key(scope)
+                )
+            }
+            Editor(EditorState::Visual(VimVisualState::CharSearch(kind, _))) => {
+                let name = &self.get_selected_note()?.name;
+
+                let mode = visual_mode_name(self.visual_kind);
+
+                format!(
+                    "Note '{name}' {mode} mode - find character '{}'",
+                    char_search_key(kind)
+                )
             }
             Editor(EditorState::Insert) => {
                 let name = &self.get_selected_note()?.name;
@@ -263,6 +342,36 @@ impl NotebookState {
         inner_state::keymap(self)
     }
 
+    pub fn session(&self) -> NotebookSession {
+        fn opened(item: &DirectoryItem, directories: &mut Vec<DirectoryId>) {
+            let Some(children) = &item.children else {
+                return;
+            };
+
+            directories.push(item.directory.id.clone());
+            for child in &children.directories {
+                opened(child, directories);
+            }
+        }
+
+        let mut directories = Vec::new();
+        opened(&self.root, &mut directories);
+        directories.retain(|id| id != &self.root.directory.id);
+
+        NotebookSession {
+            directories,
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| SessionTab {
+                    note_id: tab.note.id.clone(),
+                    directory_id: tab.note.directory_id.clone(),
+                })
+                .collect(),
+            tab_index: self.tab_index,
+        }
+    }
+
     pub fn get_selected_note(&self) -> Result<&Note> {
         match &self.selected {
             SelectedItem::Note(note) => Ok(note),
@@ -298,6 +407,38 @@ impl NotebookState {
     }
 }
 
+fn operator_key(operator: &VimOperator) -> &'static str {
+    match operator {
+        VimOperator::Delete => "d",
+        VimOperator::Change => "c",
+        VimOperator::Yank => "y",
+    }
+}
+
+fn scope_key(scope: &TextObjectScope) -> &'static str {
+    match scope {
+        TextObjectScope::Inner => "i",
+        TextObjectScope::Around => "a",
+    }
+}
+
+fn visual_mode_name(kind: VisualKind) -> &'static str {
+    match kind {
+        VisualKind::Char => "visual",
+        VisualKind::Line => "visual line",
+        VisualKind::Block => "visual block",
+    }
+}
+
+fn char_search_key(kind: &CharSearchKind) -> &'static str {
+    match kind {
+        CharSearchKind::Find => "f",
+        CharSearchKind::FindBack => "F",
+        CharSearchKind::Till => "t",
+        CharSearchKind::TillBack => "T",
+    }
+}
+
 pub async fn consume(glues: &mut Glues, event: Event) -> Result<NotebookTransition> {
     let db = glues
         .db
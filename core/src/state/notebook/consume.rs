@@ -1,4 +1,7 @@
+pub mod attachment;
 mod breadcrumb;
 pub mod directory;
+pub mod mark;
 pub mod note;
+pub mod session;
 pub mod tabs;
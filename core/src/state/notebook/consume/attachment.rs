@@ -0,0 +1,48 @@
+use crate::{
+    NotebookTransition, Result, backend::CoreBackend, state::notebook::NotebookState,
+    transition::NoteTreeTransition, types::AttachmentId,
+};
+
+/// Attaches `data` to the selected note under `name`. Reading the file is
+/// left to the frontend.
+pub async fn add<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    name: String,
+    data: Vec<u8>,
+) -> Result<NotebookTransition> {
+    let note = state.get_selected_note()?.clone();
+
+    db.add_attachment(note.id.clone(), name.clone(), data)
+        .await?;
+    db.log(
+        "attachment::add".to_owned(),
+        format!("note: {}\nname: {name}", note.id),
+    )
+    .await?;
+
+    let attachments = db.fetch_attachments(note.id).await?;
+    Ok(NotebookTransition::NoteTree(
+        NoteTreeTransition::UpdateAttachments(attachments),
+    ))
+}
+
+pub async fn remove<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    attachment_id: AttachmentId,
+) -> Result<NotebookTransition> {
+    let note = state.get_selected_note()?.clone();
+
+    db.remove_attachment(attachment_id.clone()).await?;
+    db.log(
+        "attachment::remove".to_owned(),
+        format!("note: {}\n  id: {attachment_id}", note.id),
+    )
+    .await?;
+
+    let attachments = db.fetch_attachments(note.id).await?;
+    Ok(NotebookTransition::NoteTree(
+        NoteTreeTransition::UpdateAttachments(attachments),
+    ))
+}
@@ -3,7 +3,7 @@ use {
     crate::{
         Error, NotebookTransition, Result,
         backend::CoreBackend,
-        data::Directory,
+        data::{Directory, Note},
         state::notebook::{
             DirectoryItem, DirectoryItemChildren, InnerState, NoteTreeState, NotebookState,
             SelectedItem,
@@ -18,38 +18,21 @@ pub async fn open<B: CoreBackend + ?Sized>(
     state: &mut NotebookState,
     directory_id: DirectoryId,
 ) -> Result<NotebookTransition> {
-    let item = state
-        .root
-        .find_mut(&directory_id)
-        .ok_or(Error::NotFound(format!(
-            "[directory::open] directory not found: {directory_id}"
-        )))?;
-
-    let notes = db.fetch_notes(directory_id.clone()).await?;
-    let directories = db
-        .fetch_directories(directory_id.clone())
+    let (notes, directories) = db
+        .fetch_children(vec![directory_id.clone()])
         .await?
-        .into_iter()
-        .map(|directory| DirectoryItem {
-            directory,
-            children: None,
-        })
-        .collect::<Vec<_>>();
-
-    item.children = Some(DirectoryItemChildren {
-        notes: notes.clone(),
-        directories: directories.clone(),
-    });
-
-    Ok(NotebookTransition::NoteTree(
-        NoteTreeTransition::OpenDirectory {
-            id: directory_id,
-            notes,
-            directories,
-        },
-    ))
+        .pop()
+        .ok_or_else(|| {
+            Error::InvalidResponse(format!(
+                "[directory::open] children not fetched: {directory_id}"
+            ))
+        })?;
+
+    fill(state, directory_id, notes, directories)
 }
 
+/// Opens every directory between `directory_id` and the closest opened one,
+/// fetching their children in a single `fetch_children` call.
 pub async fn open_all<B: CoreBackend + ?Sized>(
     db: &mut B,
     state: &mut NotebookState,
@@ -81,16 +64,56 @@ pub async fn open_all<B: CoreBackend + ?Sized>(
 
     path.reverse();
 
+    let children = db.fetch_children(path.clone()).await?;
+    if children.len() != path.len() {
+        return Err(Error::InvalidResponse(
+            "[directory::open_all] children do not match the path".to_owned(),
+        ));
+    }
+
     let mut transition = NotebookTransition::None;
-    for (idx, id) in path.iter().enumerate() {
-        if idx == 0 || !state.check_opened(id) {
-            transition = open(db, state, id.clone()).await?;
-        }
+    for (id, (notes, directories)) in path.into_iter().zip(children) {
+        transition = fill(state, id, notes, directories)?;
     }
 
     Ok(transition)
 }
 
+fn fill(
+    state: &mut NotebookState,
+    directory_id: DirectoryId,
+    notes: Vec<Note>,
+    directories: Vec<Directory>,
+) -> Result<NotebookTransition> {
+    let item = state
+        .root
+        .find_mut(&directory_id)
+        .ok_or(Error::NotFound(format!(
+            "[directory::open] directory not found: {directory_id}"
+        )))?;
+
+    let directories = directories
+        .into_iter()
+        .map(|directory| DirectoryItem {
+            directory,
+            children: None,
+        })
+        .collect::<Vec<_>>();
+
+    item.children = Some(DirectoryItemChildren {
+        notes: notes.clone(),
+        directories: directories.clone(),
+    });
+
+    Ok(NotebookTransition::NoteTree(
+        NoteTreeTransition::OpenDirectory {
+            id: directory_id,
+            notes,
+            directories,
+        },
+    ))
+}
+
 pub fn close(state: &mut NotebookState, directory: Directory) -> Result<NotebookTransition> {
     state
         .root
@@ -0,0 +1,181 @@
+use {
+    super::{breadcrumb, directory},
+    crate::{
+        NotebookTransition, Result,
+        backend::CoreBackend,
+        data::Note,
+        state::notebook::{
+            EditorState, GlobalMark, InnerState, Jump, NotebookState, SelectedItem, Tab,
+            VimNormalState,
+        },
+        transition::NormalModeTransition,
+        types::Cursor,
+    },
+};
+
+/// `Meta` table key under which global marks of the notebook are stored.
+pub const GLOBAL_MARKS_KEY: &str = "global_marks";
+
+pub async fn set<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    mark: char,
+    cursor: Cursor,
+) -> Result<NotebookTransition> {
+    let note = state.get_editing()?.clone();
+
+    if mark.is_ascii_lowercase() {
+        state.marks.set_local(note.id, mark, cursor);
+    } else {
+        let global_mark = GlobalMark {
+            note_id: note.id,
+            directory_id: note.directory_id,
+            cursor,
+        };
+
+        state.marks.set_global(mark, global_mark);
+        save_global(db, state).await?;
+    }
+
+    Ok(NotebookTransition::None)
+}
+
+pub async fn jump_to<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    mark: char,
+    linewise: bool,
+    cursor: Cursor,
+) -> Result<NotebookTransition> {
+    let current = state.get_editing()?.clone();
+
+    let (note, target) = if mark.is_ascii_lowercase() {
+        match state.marks.get_local(&current.id, mark) {
+            Some(target) => (current.clone(), target),
+            None => return Ok(mark_not_set(mark)),
+        }
+    } else {
+        let Some(global_mark) = state.marks.get_global(mark).cloned() else {
+            return Ok(mark_not_set(mark));
+        };
+
+        match find_note(db, state, &global_mark).await? {
+            Some(note) => (note, global_mark.cursor),
+            None => {
+                state.marks.remove_global(mark);
+                save_global(db, state).await?;
+
+                return Ok(NotebookTransition::Info(format!(
+                    "Mark '{mark}' pointed to a note which no longer exists"
+                )));
+            }
+        }
+    };
+
+    state.marks.push_jump(Jump {
+        note: current,
+        cursor: Some(cursor),
+    });
+
+    jump(db, state, note, Some(target), linewise).await
+}
+
+pub async fn jump_older<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    cursor: Cursor,
+) -> Result<NotebookTransition> {
+    let current = Jump {
+        note: state.get_editing()?.clone(),
+        cursor: Some(cursor),
+    };
+
+    match state.marks.jump_older(current) {
+        Some(Jump { note, cursor }) => jump(db, state, note, cursor, false).await,
+        None => Ok(NotebookTransition::None),
+    }
+}
+
+pub async fn jump_newer<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+) -> Result<NotebookTransition> {
+    match state.marks.jump_newer() {
+        Some(Jump { note, cursor }) => jump(db, state, note, cursor, false).await,
+        None => Ok(NotebookTransition::None),
+    }
+}
+
+pub async fn save_global<B: CoreBackend + ?Sized>(db: &mut B, state: &NotebookState) -> Result<()> {
+    db.update_meta(GLOBAL_MARKS_KEY.to_owned(), state.marks.global_to_meta())
+        .await
+}
+
+async fn jump<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    note: Note,
+    cursor: Option<Cursor>,
+    linewise: bool,
+) -> Result<NotebookTransition> {
+    state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+    let content = match state.tabs.iter().position(|tab| tab.note.id == note.id) {
+        Some(i) => {
+            state.tab_index = Some(i);
+            None
+        }
+        None => {
+            let content = db.fetch_note_content(note.id.clone()).await?;
+
+            state.tabs.push(Tab {
+                note: note.clone(),
+                breadcrumb: vec![],
+            });
+            state.tab_index = Some(state.tabs.len() - 1);
+            Some(content)
+        }
+    };
+
+    state.selected = SelectedItem::Note(note.clone());
+    directory::open_all(db, state, note.directory_id.clone()).await?;
+
+    if content.is_some() {
+        breadcrumb::update_breadcrumbs(db, state).await?;
+    }
+
+    Ok(NotebookTransition::EditingNormalMode(
+        NormalModeTransition::Jump {
+            note_id: note.id,
+            content,
+            cursor,
+            linewise,
+        },
+    ))
+}
+
+async fn find_note<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &NotebookState,
+    global_mark: &GlobalMark,
+) -> Result<Option<Note>> {
+    if let Some(tab) = state
+        .tabs
+        .iter()
+        .find(|tab| tab.note.id == global_mark.note_id)
+    {
+        return Ok(Some(tab.note.clone()));
+    }
+
+    let note = db
+        .fetch_notes(global_mark.directory_id.clone())
+        .await?
+        .into_iter()
+        .find(|note| note.id == global_mark.note_id);
+
+    Ok(note)
+}
+
+fn mark_not_set(mark: char) -> NotebookTransition {
+    NotebookTransition::Info(format!("Mark '{mark}' is not set"))
+}
@@ -1,5 +1,5 @@
 use {
-    super::{breadcrumb, directory},
+    super::{breadcrumb, directory, mark},
     crate::{
         Error, NotebookTransition, Result,
         backend::CoreBackend,
@@ -14,10 +14,17 @@ use {
     std::cmp::min,
 };
 
-pub fn show_actions_dialog(state: &mut NotebookState, note: Note) -> NotebookTransition {
+pub async fn show_actions_dialog<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    note: Note,
+) -> Result<NotebookTransition> {
+    let attachments = db.fetch_attachments(note.id.clone()).await?;
     state.inner_state = InnerState::NoteTree(NoteTreeState::NoteMoreActions);
 
-    NotebookTransition::NoteTree(NoteTreeTransition::ShowNoteActionsDialog(note))
+    Ok(NotebookTransition::NoteTree(
+        NoteTreeTransition::ShowNoteActionsDialog { note, attachments },
+    ))
 }
 
 pub fn select(state: &mut NotebookState, note: Note) -> NotebookTransition {
@@ -77,6 +84,7 @@ pub async fn remove<B: CoreBackend + ?Sized>(
     note: Note,
 ) -> Result<NotebookTransition> {
     db.remove_note(note.id.clone()).await?;
+    state.marks.forget_note(&note.id);
 
     let directory = state
         .root
@@ -224,6 +232,9 @@ pub async fn move_note<B: CoreBackend + ?Sized>(
     });
 
     db.move_note(note.id.clone(), directory_id.clone()).await?;
+    if state.marks.move_note(&note.id, &directory_id) {
+        mark::save_global(db, state).await?;
+    }
     directory::close(state, state.root.directory.clone())?;
     directory::open_all(db, state, directory_id).await?;
 
@@ -236,3 +247,22 @@ pub async fn move_note<B: CoreBackend + ?Sized>(
         MoveModeTransition::Commit,
     )))
 }
+
+/// Names of every note in the notebook, offered when completing a `[[` link.
+pub async fn link_titles<B: CoreBackend + ?Sized>(db: &mut B) -> Result<NotebookTransition> {
+    let mut titles = Vec::new();
+    let mut directory_ids = vec![db.root_id()];
+
+    while let Some(directory_id) = directory_ids.pop() {
+        let notes = db.fetch_notes(directory_id.clone()).await?;
+        titles.extend(notes.into_iter().map(|note| note.name));
+
+        let directories = db.fetch_directories(directory_id).await?;
+        directory_ids.extend(directories.into_iter().map(|directory| directory.id));
+    }
+
+    titles.sort();
+    titles.dedup();
+
+    Ok(NotebookTransition::CompleteNoteLink(titles))
+}
@@ -0,0 +1,72 @@
+use {
+    super::{breadcrumb, directory},
+    crate::{
+        NotebookTransition, Result,
+        backend::CoreBackend,
+        state::notebook::{
+            EditorState, InnerState, NotebookSession, NotebookState, SelectedItem, SessionTab, Tab,
+            VimNormalState,
+        },
+    },
+};
+
+pub async fn restore<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    session: NotebookSession,
+) -> Result<NotebookTransition> {
+    let NotebookSession {
+        directories,
+        tabs,
+        tab_index,
+    } = session;
+
+    for directory_id in directories {
+        // removed or moved away since the session was saved
+        let _ = directory::open_all(db, state, directory_id).await;
+    }
+
+    let active = tab_index
+        .and_then(|i| tabs.get(i))
+        .map(|tab| tab.note_id.clone());
+    let mut contents = Vec::new();
+    for SessionTab {
+        note_id,
+        directory_id,
+    } in tabs
+    {
+        if state.tabs.iter().any(|tab| tab.note.id == note_id) {
+            continue;
+        }
+
+        let Ok(notes) = db.fetch_notes(directory_id).await else {
+            continue;
+        };
+        let Some(note) = notes.into_iter().find(|note| note.id == note_id) else {
+            continue;
+        };
+
+        let content = db.fetch_note_content(note_id.clone()).await?;
+        state.tabs.push(Tab {
+            note,
+            breadcrumb: vec![],
+        });
+        contents.push((note_id, content));
+    }
+
+    if state.tabs.is_empty() {
+        return Ok(NotebookTransition::RestoreSession(contents));
+    }
+
+    let i = active
+        .and_then(|id| state.tabs.iter().position(|tab| tab.note.id == id))
+        .unwrap_or_default();
+    let note = state.tabs[i].note.clone();
+    state.tab_index = Some(i);
+    state.selected = SelectedItem::Note(note);
+    state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+    breadcrumb::update_breadcrumbs(db, state).await?;
+
+    Ok(NotebookTransition::RestoreSession(contents))
+}
@@ -3,8 +3,8 @@ use {
         Error, NotebookTransition, Result,
         backend::CoreBackend,
         state::notebook::{
-            EditorState, InnerState, NoteTreeState, NotebookState, SelectedItem, VimNormalState,
-            directory,
+            EditorState, InnerState, Jump, NoteTreeState, NotebookState, SelectedItem,
+            VimNormalState, directory,
         },
         transition::NormalModeTransition,
     },
@@ -20,6 +20,11 @@ pub async fn select_prev<B: CoreBackend + ?Sized>(
     let i = state
         .tab_index
         .ok_or(Error::InvalidState("opened note must exist".to_owned()))?;
+    state.marks.push_jump(Jump {
+        note: state.tabs[i].note.clone(),
+        cursor: None,
+    });
+
     let i = if i == 0 { state.tabs.len() - 1 } else { i - 1 };
     state.tab_index = Some(i);
 
@@ -44,6 +49,11 @@ pub async fn select_next<B: CoreBackend + ?Sized>(
     let i = state
         .tab_index
         .ok_or(Error::InvalidState("opened note must exist".to_owned()))?;
+    state.marks.push_jump(Jump {
+        note: state.tabs[i].note.clone(),
+        cursor: None,
+    });
+
     let i = if i + 1 >= state.tabs.len() { 0 } else { i + 1 };
     state.tab_index = Some(i);
 
@@ -59,6 +69,37 @@ pub async fn select_next<B: CoreBackend + ?Sized>(
     ))
 }
 
+pub async fn select<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    i: usize,
+) -> Result<NotebookTransition> {
+    if i >= state.tabs.len() {
+        return Err(Error::InvalidState(format!("tab {i} does not exist")));
+    }
+
+    if let Some(prev) = state.tab_index {
+        state.marks.push_jump(Jump {
+            note: state.tabs[prev].note.clone(),
+            cursor: None,
+        });
+    }
+
+    state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+    state.tab_index = Some(i);
+
+    let note = &state.tabs[i].note;
+    state.selected = SelectedItem::Note(note.clone());
+
+    let note_id = note.id.clone();
+    let directory_id = note.directory_id.clone();
+
+    directory::open_all(db, state, directory_id).await?;
+    Ok(NotebookTransition::EditingNormalMode(
+        NormalModeTransition::SelectTab(note_id),
+    ))
+}
+
 pub fn move_prev(state: &mut NotebookState) -> Result<NotebookTransition> {
     let i = state
         .tab_index
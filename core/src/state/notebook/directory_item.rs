@@ -1,15 +1,18 @@
-use crate::{
-    data::{Directory, Note},
-    types::{DirectoryId, Id},
+use {
+    crate::{
+        data::{Directory, Note},
+        types::{DirectoryId, Id},
+    },
+    serde::{Deserialize, Serialize},
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DirectoryItem {
     pub directory: Directory,
     pub children: Option<DirectoryItemChildren>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DirectoryItemChildren {
     pub directories: Vec<DirectoryItem>,
     pub notes: Vec<Note>,
@@ -28,6 +31,14 @@ impl DirectoryItem {
             .find_map(|item| item.find(id))
     }
 
+    /// Name of a loaded note or directory.
+    pub fn find_name(&self, id: &Id) -> Option<&str> {
+        self.tree_items(0)
+            .into_iter()
+            .find(|item| item.id == id)
+            .map(|item| item.name)
+    }
+
     pub fn find_mut(&mut self, id: &DirectoryId) -> Option<&mut DirectoryItem> {
         if &self.directory.id == id {
             return Some(self);
@@ -4,7 +4,7 @@ mod note_tree;
 use crate::{
     Event, NotebookEvent, NotebookTransition, Result,
     backend::CoreBackend,
-    state::notebook::{NotebookState, note},
+    state::notebook::{NotebookState, mark, note, tabs},
     types::KeymapGroup,
 };
 pub use editor::{EditorState, VimNormalState, VimVisualState};
@@ -21,8 +21,30 @@ pub async fn consume<B: CoreBackend + ?Sized>(
     state: &mut NotebookState,
     event: Event,
 ) -> Result<NotebookTransition> {
-    if let Event::Notebook(NotebookEvent::UpdateNoteContent { note_id, content }) = event {
-        return note::update_content(db, note_id, content).await;
+    match event {
+        Event::Notebook(NotebookEvent::UpdateNoteContent { note_id, content }) => {
+            return note::update_content(db, note_id, content).await;
+        }
+        Event::Notebook(NotebookEvent::SetMark { mark, cursor }) => {
+            return mark::set(db, state, mark, cursor).await;
+        }
+        Event::Notebook(NotebookEvent::JumpToMark {
+            mark,
+            linewise,
+            cursor,
+        }) => {
+            return mark::jump_to(db, state, mark, linewise, cursor).await;
+        }
+        Event::Notebook(NotebookEvent::JumpOlder(cursor)) => {
+            return mark::jump_older(db, state, cursor).await;
+        }
+        Event::Notebook(NotebookEvent::JumpNewer) => {
+            return mark::jump_newer(db, state).await;
+        }
+        Event::Notebook(NotebookEvent::SelectTab(i)) => {
+            return tabs::select(db, state, i).await;
+        }
+        _ => {}
     }
 
     match state.inner_state {
@@ -1,6 +1,6 @@
 use crate::{
-    Event, NotebookTransition, Result, backend::CoreBackend, state::notebook::NotebookState,
-    types::KeymapGroup,
+    Event, KeyEvent, NotebookTransition, Result, backend::CoreBackend,
+    state::notebook::NotebookState, transition::TextObject, types::KeymapGroup,
 };
 
 pub(super) mod insert_mode;
@@ -26,7 +26,7 @@ pub async fn consume<B: CoreBackend + ?Sized>(
     match editor_state {
         EditorState::Normal(vim_state) => normal_mode::consume(db, state, vim_state, event).await,
         EditorState::Visual(vim_state) => visual_mode::consume(db, state, vim_state, event),
-        EditorState::Insert => insert_mode::consume(db, state, event),
+        EditorState::Insert => insert_mode::consume(db, state, event).await,
     }
 }
 
@@ -37,3 +37,23 @@ pub fn keymap(editor_state: EditorState) -> Vec<KeymapGroup> {
         EditorState::Insert => insert_mode::keymap(),
     }
 }
+
+/// Maps the key typed after `i` or `a` to the text object it selects.
+fn text_object(key: KeyEvent) -> Option<TextObject> {
+    let object = match key {
+        KeyEvent::W => TextObject::Word,
+        KeyEvent::P => TextObject::Paragraph,
+        KeyEvent::DoubleQuote => TextObject::Quote('"'),
+        KeyEvent::SingleQuote => TextObject::Quote('\''),
+        KeyEvent::Backtick => TextObject::Quote('`'),
+        KeyEvent::ParenOpen | KeyEvent::ParenClose | KeyEvent::B => TextObject::Pair('(', ')'),
+        KeyEvent::SquareBracketOpen | KeyEvent::SquareBracketClose => TextObject::Pair('[', ']'),
+        KeyEvent::CurlyBracketOpen | KeyEvent::CurlyBracketClose | KeyEvent::Char('B') => {
+            TextObject::Pair('{', '}')
+        }
+        KeyEvent::AngleBracketOpen | KeyEvent::AngleBracketClose => TextObject::Pair('<', '>'),
+        _ => return None,
+    };
+
+    Some(object)
+}
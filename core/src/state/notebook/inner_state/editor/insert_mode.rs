@@ -5,8 +5,8 @@ use crate::{
     types::{KeymapGroup, KeymapItem},
 };
 
-pub fn consume<B: CoreBackend + ?Sized>(
-    _db: &mut B,
+pub async fn consume<B: CoreBackend + ?Sized>(
+    db: &mut B,
     state: &mut NotebookState,
     event: Event,
 ) -> Result<NotebookTransition> {
@@ -15,6 +15,7 @@ pub fn consume<B: CoreBackend + ?Sized>(
 
     match event {
         Key(KeyEvent::Esc) | Notebook(ViewNote) => note::view(state),
+        Notebook(CompleteNoteLink) => note::link_titles(db).await,
         event @ Key(_) => Ok(NotebookTransition::Inedible(event)),
         _ => Err(Error::Todo(
             "Notebook::EditingInsertMode::consume".to_owned(),
@@ -27,6 +28,7 @@ pub fn keymap() -> Vec<KeymapGroup> {
         "General",
         vec![
             KeymapItem::new("Esc", "Save note and enter normal mode"),
+            KeymapItem::new("Ctrl+n Ctrl+p", "Complete words, #tags and [[note links"),
             KeymapItem::new("Ctrl+h", "Show editor keymap"),
         ],
     )]
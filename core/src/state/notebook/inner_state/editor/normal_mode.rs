@@ -1,21 +1,26 @@
 use crate::{
     Event, Result,
     backend::CoreBackend,
-    state::notebook::NotebookState,
-    transition::{NormalModeTransition, NotebookTransition},
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimMotion,
+        VimOperator,
+    },
     types::KeymapGroup,
 };
 
 mod change;
 mod change2;
-mod change_inside;
+mod char_search;
 mod delete;
 mod delete2;
-mod delete_inside;
 mod gateway;
 mod idle;
+mod mark;
+mod mark_jump;
 mod numbering;
 mod scroll;
+mod text_object;
 mod toggle;
 mod toggle_tab_close;
 mod yank;
@@ -32,10 +37,12 @@ pub enum VimNormalState {
     Yank2(usize, usize),
     Delete(usize),
     Delete2(usize, usize),
-    DeleteInside(usize),
     Change(usize),
     Change2(usize, usize),
-    ChangeInside(usize),
+    TextObject(VimOperator, TextObjectScope, usize),
+    CharSearch(Option<VimOperator>, CharSearchKind, usize),
+    Mark,
+    MarkJump { linewise: bool },
     Scroll,
 }
 
@@ -55,10 +62,16 @@ pub async fn consume<B: CoreBackend + ?Sized>(
         VimNormalState::Yank2(n1, n2) => yank2::consume(state, n1, n2, event),
         VimNormalState::Delete(n) => delete::consume(state, n, event),
         VimNormalState::Delete2(n1, n2) => delete2::consume(state, n1, n2, event),
-        VimNormalState::DeleteInside(n) => delete_inside::consume(state, n, event),
         VimNormalState::Change(n) => change::consume(state, n, event),
         VimNormalState::Change2(n1, n2) => change2::consume(state, n1, n2, event),
-        VimNormalState::ChangeInside(n) => change_inside::consume(state, n, event),
+        VimNormalState::TextObject(operator, scope, n) => {
+            text_object::consume(state, operator, scope, n, event)
+        }
+        VimNormalState::CharSearch(operator, kind, n) => {
+            char_search::consume(state, operator, kind, n, event)
+        }
+        VimNormalState::Mark => mark::consume(state, event),
+        VimNormalState::MarkJump { linewise } => mark_jump::consume(state, linewise, event),
         VimNormalState::Scroll => scroll::consume(state, event),
     }
 }
@@ -80,10 +93,91 @@ pub fn keymap(vim_state: VimNormalState) -> Vec<KeymapGroup> {
         VimNormalState::Yank2(n1, n2) => yank2::keymap(n1, n2),
         VimNormalState::Delete(n) => delete::keymap(n),
         VimNormalState::Delete2(n1, n2) => delete2::keymap(n1, n2),
-        VimNormalState::DeleteInside(n) => delete_inside::keymap(n),
         VimNormalState::Change(n) => change::keymap(n),
         VimNormalState::Change2(n1, n2) => change2::keymap(n1, n2),
-        VimNormalState::ChangeInside(n) => change_inside::keymap(n),
+        VimNormalState::TextObject(operator, scope, n) => text_object::keymap(operator, scope, n),
+        VimNormalState::CharSearch(operator, kind, n) => char_search::keymap(operator, kind, n),
+        VimNormalState::Mark => mark::keymap(),
+        VimNormalState::MarkJump { linewise } => mark_jump::keymap(linewise),
         VimNormalState::Scroll => scroll::keymap(),
     }
 }
+
+fn enter_text_object(
+    state: &mut NotebookState,
+    operator: VimOperator,
+    scope: TextObjectScope,
+    n: usize,
+) -> Result<NotebookTransition> {
+    state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::TextObject(
+        operator, scope, n,
+    )));
+
+    NormalModeTransition::TextObjectMode.into()
+}
+
+fn enter_char_search(
+    state: &mut NotebookState,
+    operator: Option<VimOperator>,
+    kind: CharSearchKind,
+    n: usize,
+) -> Result<NotebookTransition> {
+    state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::CharSearch(
+        operator, kind, n,
+    )));
+
+    NormalModeTransition::CharSearchMode.into()
+}
+
+/// Finishes an operator-pending command. `c` continues in insert mode while
+/// `d` and `y` go back to idle.
+fn operate(
+    state: &mut NotebookState,
+    operator: VimOperator,
+    motion: VimMotion,
+    n: usize,
+) -> Result<NotebookTransition> {
+    finish_operator(state, operator);
+
+    NormalModeTransition::Operate {
+        operator,
+        motion,
+        n,
+    }
+    .into()
+}
+
+fn finish_operator(state: &mut NotebookState, operator: VimOperator) {
+    state.inner_state = match operator {
+        VimOperator::Change => InnerState::Editor(EditorState::Insert),
+        VimOperator::Delete | VimOperator::Yank => {
+            InnerState::Editor(EditorState::Normal(VimNormalState::Idle))
+        }
+    };
+}
+
+/// Handles `;` and `,`, either as a plain cursor motion or as the target of a
+/// pending operator.
+fn repeat_char_search(
+    state: &mut NotebookState,
+    operator: Option<VimOperator>,
+    reverse: bool,
+    n: usize,
+) -> Result<NotebookTransition> {
+    let Some(search) = state.last_char_search else {
+        state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+        return Ok(NotebookTransition::None);
+    };
+    let search = if reverse { search.reversed() } else { search };
+    let motion = VimMotion::RepeatCharSearch(search);
+
+    match operator {
+        Some(operator) => operate(state, operator, motion, n),
+        None => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            NormalModeTransition::MoveCursorMotion(motion, n).into()
+        }
+    }
+}
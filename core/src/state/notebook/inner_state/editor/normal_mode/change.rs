@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, NumKey, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimKeymapKind,
+        VimMotion, VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -23,10 +26,43 @@ pub fn consume(state: &mut NotebookState, n: usize, event: Event) -> Result<Note
             Ok(NotebookTransition::None)
         }
         Key(KeyEvent::I) => {
-            state.inner_state =
-                InnerState::Editor(EditorState::Normal(super::VimNormalState::ChangeInside(n)));
-
-            ChangeInsideMode.into()
+            super::enter_text_object(state, VimOperator::Change, TextObjectScope::Inner, n)
+        }
+        Key(KeyEvent::A) => {
+            super::enter_text_object(state, VimOperator::Change, TextObjectScope::Around, n)
+        }
+        Key(KeyEvent::F) => {
+            super::enter_char_search(state, Some(VimOperator::Change), CharSearchKind::Find, n)
+        }
+        Key(KeyEvent::CapF) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::FindBack,
+            n,
+        ),
+        Key(KeyEvent::T) => {
+            super::enter_char_search(state, Some(VimOperator::Change), CharSearchKind::Till, n)
+        }
+        Key(KeyEvent::CapT) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::TillBack,
+            n,
+        ),
+        Key(KeyEvent::Semicolon) => {
+            super::repeat_char_search(state, Some(VimOperator::Change), false, n)
+        }
+        Key(KeyEvent::Comma) => {
+            super::repeat_char_search(state, Some(VimOperator::Change), true, n)
+        }
+        Key(KeyEvent::Percent) => {
+            super::operate(state, VimOperator::Change, VimMotion::MatchPair, n)
+        }
+        Key(KeyEvent::CurlyBracketClose) => {
+            super::operate(state, VimOperator::Change, VimMotion::ParagraphForward, n)
+        }
+        Key(KeyEvent::CurlyBracketOpen) => {
+            super::operate(state, VimOperator::Change, VimMotion::ParagraphBackward, n)
         }
         Key(KeyEvent::C) => {
             state.inner_state = InnerState::Editor(EditorState::Insert);
@@ -63,8 +99,14 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
     vec![KeymapGroup::new(
         "General",
         vec![
-            KeymapItem::new("i", "Enter change inside mode"),
             KeymapItem::new("c", format!("Delete {n} lines and enter insert mode")),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Change to a typed character"),
+            KeymapItem::new("t T", "Change until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Change to the matching bracket"),
+            KeymapItem::new("{ }", "Change to the paragraph boundary"),
             KeymapItem::new("Ctrl+h", "Show Vim keymap"),
             KeymapItem::new("Esc", "Cancel"),
         ],
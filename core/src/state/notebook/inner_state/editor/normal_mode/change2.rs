@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimKeymapKind,
+        VimMotion, VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -22,13 +25,72 @@ pub fn consume(
 
             Ok(NotebookTransition::None)
         }
-        Key(KeyEvent::I) => {
-            let n = n1.saturating_mul(n2);
-            state.inner_state =
-                InnerState::Editor(EditorState::Normal(super::VimNormalState::ChangeInside(n)));
-
-            ChangeInsideMode.into()
-        }
+        Key(KeyEvent::I) => super::enter_text_object(
+            state,
+            VimOperator::Change,
+            TextObjectScope::Inner,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::A) => super::enter_text_object(
+            state,
+            VimOperator::Change,
+            TextObjectScope::Around,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::F) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::Find,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::CapF) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::FindBack,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::T) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::Till,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::CapT) => super::enter_char_search(
+            state,
+            Some(VimOperator::Change),
+            CharSearchKind::TillBack,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::Semicolon) => super::repeat_char_search(
+            state,
+            Some(VimOperator::Change),
+            false,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::Comma) => super::repeat_char_search(
+            state,
+            Some(VimOperator::Change),
+            true,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::Percent) => super::operate(
+            state,
+            VimOperator::Change,
+            VimMotion::MatchPair,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::CurlyBracketClose) => super::operate(
+            state,
+            VimOperator::Change,
+            VimMotion::ParagraphForward,
+            n1.saturating_mul(n2),
+        ),
+        Key(KeyEvent::CurlyBracketOpen) => super::operate(
+            state,
+            VimOperator::Change,
+            VimMotion::ParagraphBackward,
+            n1.saturating_mul(n2),
+        ),
         Key(KeyEvent::C) => {
             let n = n1.saturating_mul(n2);
             state.inner_state = InnerState::Editor(EditorState::Insert);
@@ -79,8 +141,14 @@ pub fn keymap(n1: usize, n2: usize) -> Vec<KeymapGroup> {
             } else {
                 KeymapItem::new("c", format!("Delete {n1}*{n2} lines and enter insert mode"))
             },
-            KeymapItem::new("i", "Enter change inside mode"),
             KeymapItem::new("0-9", "Append steps"),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Change to a typed character"),
+            KeymapItem::new("t T", "Change until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Change to the matching bracket"),
+            KeymapItem::new("{ }", "Change to the paragraph boundary"),
             KeymapItem::new("Ctrl+h", "Show Vim keymap"),
             KeymapItem::new("Esc", "Cancel"),
         ],
@@ -0,0 +1,83 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{
+        CharSearch, CharSearchKind, NormalModeTransition, NotebookTransition, VimMotion,
+        VimOperator,
+    },
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume(
+    state: &mut NotebookState,
+    operator: Option<VimOperator>,
+    kind: CharSearchKind,
+    n: usize,
+    event: Event,
+) -> Result<NotebookTransition> {
+    use Event::*;
+    use NormalModeTransition::*;
+
+    match event {
+        Key(KeyEvent::Esc) => {
+            state.inner_state =
+                InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+            IdleMode.into()
+        }
+        Key(key) => match key.to_char() {
+            Some(target) => {
+                let search = CharSearch { kind, target };
+                let motion = VimMotion::CharSearch(search);
+                state.last_char_search = Some(search);
+
+                match operator {
+                    Some(operator) => super::operate(state, operator, motion, n),
+                    None => {
+                        state.inner_state =
+                            InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+                        MoveCursorMotion(motion, n).into()
+                    }
+                }
+            }
+            None => {
+                state.inner_state =
+                    InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+                super::idle::consume(state, event)
+            }
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingNormalMode::CharSearch::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap(operator: Option<VimOperator>, kind: CharSearchKind, n: usize) -> Vec<KeymapGroup> {
+    let target = match kind {
+        CharSearchKind::Find => "to the next",
+        CharSearchKind::FindBack => "back to the previous",
+        CharSearchKind::Till => "until the next",
+        CharSearchKind::TillBack => "back until the previous",
+    };
+    let target = if n == 1 {
+        format!("{target} typed character")
+    } else {
+        format!("{target} {n}th typed character")
+    };
+    let desc = match operator {
+        None => format!("Move cursor {target}"),
+        Some(VimOperator::Delete) => format!("Delete {target}"),
+        Some(VimOperator::Change) => format!("Change {target}"),
+        Some(VimOperator::Yank) => format!("Yank {target}"),
+    };
+
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("char", desc),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
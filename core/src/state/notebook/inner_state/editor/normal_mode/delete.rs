@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, NumKey, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimKeymapKind,
+        VimMotion, VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -69,10 +72,43 @@ pub fn consume(state: &mut NotebookState, n: usize, event: Event) -> Result<Note
             DeleteLineEnd(n).into()
         }
         Key(KeyEvent::I) => {
-            state.inner_state =
-                InnerState::Editor(EditorState::Normal(super::VimNormalState::DeleteInside(n)));
-
-            DeleteInsideMode.into()
+            super::enter_text_object(state, VimOperator::Delete, TextObjectScope::Inner, n)
+        }
+        Key(KeyEvent::A) => {
+            super::enter_text_object(state, VimOperator::Delete, TextObjectScope::Around, n)
+        }
+        Key(KeyEvent::F) => {
+            super::enter_char_search(state, Some(VimOperator::Delete), CharSearchKind::Find, n)
+        }
+        Key(KeyEvent::CapF) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::FindBack,
+            n,
+        ),
+        Key(KeyEvent::T) => {
+            super::enter_char_search(state, Some(VimOperator::Delete), CharSearchKind::Till, n)
+        }
+        Key(KeyEvent::CapT) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::TillBack,
+            n,
+        ),
+        Key(KeyEvent::Semicolon) => {
+            super::repeat_char_search(state, Some(VimOperator::Delete), false, n)
+        }
+        Key(KeyEvent::Comma) => {
+            super::repeat_char_search(state, Some(VimOperator::Delete), true, n)
+        }
+        Key(KeyEvent::Percent) => {
+            super::operate(state, VimOperator::Delete, VimMotion::MatchPair, n)
+        }
+        Key(KeyEvent::CurlyBracketClose) => {
+            super::operate(state, VimOperator::Delete, VimMotion::ParagraphForward, n)
+        }
+        Key(KeyEvent::CurlyBracketOpen) => {
+            super::operate(state, VimOperator::Delete, VimMotion::ParagraphBackward, n)
         }
         Key(KeyEvent::Esc) => {
             state.inner_state =
@@ -99,7 +135,6 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
     vec![KeymapGroup::new(
         "General",
         vec![
-            KeymapItem::new("i", "Enter delete inside mode"),
             KeymapItem::new("d", format!("Delete {n} lines")),
             KeymapItem::new("0", "Delete from start of line"),
             KeymapItem::new("b", "Delete previous word"),
@@ -108,6 +143,13 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
             KeymapItem::new("l", "Delete next character"),
             KeymapItem::new("$", "Delete to line end"),
             KeymapItem::new("1-9", "Append steps"),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Delete to a typed character"),
+            KeymapItem::new("t T", "Delete until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Delete to the matching bracket"),
+            KeymapItem::new("{ }", "Delete to the paragraph boundary"),
             KeymapItem::new("Ctrl+h", "Show Vim keymap"),
             KeymapItem::new("Esc", "Cancel"),
         ],
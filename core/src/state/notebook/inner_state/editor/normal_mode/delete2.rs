@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimKeymapKind,
+        VimMotion, VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -41,12 +44,56 @@ pub fn consume(
             DeleteLinesUp(n1 * n2 + 1).into()
         }
         Key(KeyEvent::I) => {
-            state.inner_state = InnerState::Editor(EditorState::Normal(
-                super::VimNormalState::DeleteInside(n1 * n2),
-            ));
-
-            DeleteInsideMode.into()
+            super::enter_text_object(state, VimOperator::Delete, TextObjectScope::Inner, n1 * n2)
+        }
+        Key(KeyEvent::A) => {
+            super::enter_text_object(state, VimOperator::Delete, TextObjectScope::Around, n1 * n2)
+        }
+        Key(KeyEvent::F) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::Find,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CapF) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::FindBack,
+            n1 * n2,
+        ),
+        Key(KeyEvent::T) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::Till,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CapT) => super::enter_char_search(
+            state,
+            Some(VimOperator::Delete),
+            CharSearchKind::TillBack,
+            n1 * n2,
+        ),
+        Key(KeyEvent::Semicolon) => {
+            super::repeat_char_search(state, Some(VimOperator::Delete), false, n1 * n2)
+        }
+        Key(KeyEvent::Comma) => {
+            super::repeat_char_search(state, Some(VimOperator::Delete), true, n1 * n2)
+        }
+        Key(KeyEvent::Percent) => {
+            super::operate(state, VimOperator::Delete, VimMotion::MatchPair, n1 * n2)
         }
+        Key(KeyEvent::CurlyBracketClose) => super::operate(
+            state,
+            VimOperator::Delete,
+            VimMotion::ParagraphForward,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CurlyBracketOpen) => super::operate(
+            state,
+            VimOperator::Delete,
+            VimMotion::ParagraphBackward,
+            n1 * n2,
+        ),
         Key(KeyEvent::B) => {
             state.inner_state =
                 InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
@@ -103,13 +150,19 @@ pub fn keymap(n1: usize, n2: usize) -> Vec<KeymapGroup> {
             } else {
                 KeymapItem::new("d", format!("Delete {n1}*{n2} lines"))
             },
-            KeymapItem::new("i", "Enter delete inside mode"),
             KeymapItem::new("b", "Delete previous word"),
             KeymapItem::new("e", "Delete to word end"),
             KeymapItem::new("h", "Delete previous character"),
             KeymapItem::new("l", "Delete next character"),
             KeymapItem::new("$", "Delete to line end"),
             KeymapItem::new("0-9", "Append steps"),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Delete to a typed character"),
+            KeymapItem::new("t T", "Delete until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Delete to the matching bracket"),
+            KeymapItem::new("{ }", "Delete to the paragraph boundary"),
             KeymapItem::new("Ctrl+h", "Show Vim keymap"),
             KeymapItem::new("Esc", "Cancel"),
         ],
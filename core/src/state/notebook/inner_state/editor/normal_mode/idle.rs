@@ -3,7 +3,10 @@ use crate::state::notebook::inner_state::VimVisualState;
 use crate::{
     Error, Event, KeyEvent, NotebookEvent, NumKey, Result,
     state::notebook::{EditorState, InnerState, NoteTreeState, NotebookState, directory, note},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind, VisualModeTransition},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, VimKeymapKind, VimMotion,
+        VisualKind, VisualModeTransition,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -20,7 +23,7 @@ pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransi
 
             Ok(NotebookTransition::BrowseNoteTree)
         }
-        Key(KeyEvent::T) => {
+        Key(KeyEvent::Space) => {
             state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Toggle));
 
             ToggleMode.into()
@@ -30,6 +33,27 @@ pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransi
 
             ScrollMode.into()
         }
+        Key(KeyEvent::M) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Mark));
+
+            MarkMode.into()
+        }
+        Key(KeyEvent::SingleQuote) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::MarkJump {
+                linewise: true,
+            }));
+
+            MarkJumpMode.into()
+        }
+        Key(KeyEvent::Backtick) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::MarkJump {
+                linewise: false,
+            }));
+
+            MarkJumpMode.into()
+        }
+        Key(KeyEvent::CtrlO) => JumpOlder.into(),
+        Key(KeyEvent::CtrlI) => JumpNewer.into(),
         Key(KeyEvent::P) => Paste.into(),
         Key(KeyEvent::U) => Undo.into(),
         Key(KeyEvent::CtrlR) => Redo.into(),
@@ -45,6 +69,15 @@ pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransi
         Key(KeyEvent::Tilde) => SwitchCase.into(),
         Key(KeyEvent::Caret) => MoveCursorLineNonEmptyStart.into(),
         Key(KeyEvent::CapG) => MoveCursorBottom.into(),
+        Key(KeyEvent::Percent) => MoveCursorMotion(VimMotion::MatchPair, 1).into(),
+        Key(KeyEvent::CurlyBracketClose) => MoveCursorMotion(VimMotion::ParagraphForward, 1).into(),
+        Key(KeyEvent::CurlyBracketOpen) => MoveCursorMotion(VimMotion::ParagraphBackward, 1).into(),
+        Key(KeyEvent::F) => super::enter_char_search(state, None, CharSearchKind::Find, 1),
+        Key(KeyEvent::CapF) => super::enter_char_search(state, None, CharSearchKind::FindBack, 1),
+        Key(KeyEvent::T) => super::enter_char_search(state, None, CharSearchKind::Till, 1),
+        Key(KeyEvent::CapT) => super::enter_char_search(state, None, CharSearchKind::TillBack, 1),
+        Key(KeyEvent::Semicolon) => super::repeat_char_search(state, None, false, 1),
+        Key(KeyEvent::Comma) => super::repeat_char_search(state, None, true, 1),
         Key(KeyEvent::I) => {
             state.inner_state = InnerState::Editor(EditorState::Insert);
 
@@ -52,11 +85,14 @@ pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransi
         }
         Key(KeyEvent::V) => {
             state.inner_state = InnerState::Editor(EditorState::Visual(VimVisualState::Idle));
+            state.visual_kind = VisualKind::Char;
 
             Ok(NotebookTransition::EditingVisualMode(
                 VisualModeTransition::IdleMode,
             ))
         }
+        Key(KeyEvent::CapV) => enter_visual(state, VisualKind::Line),
+        Key(KeyEvent::CtrlV) => enter_visual(state, VisualKind::Block),
         Key(KeyEvent::CapI) => {
             state.inner_state = InnerState::Editor(EditorState::Insert);
 
@@ -127,13 +163,27 @@ pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransi
     }
 }
 
+fn enter_visual(state: &mut NotebookState, kind: VisualKind) -> Result<NotebookTransition> {
+    state.inner_state = InnerState::Editor(EditorState::Visual(VimVisualState::Idle));
+    state.visual_kind = kind;
+
+    Ok(NotebookTransition::EditingVisualMode(
+        VisualModeTransition::SwitchKind(kind),
+    ))
+}
+
 pub fn keymap() -> Vec<KeymapGroup> {
     let items = vec![
         KeymapItem::new("Tab", "Browse notes"),
-        KeymapItem::new("t", "Enter toggle-tabs mode"),
+        KeymapItem::new("Space", "Enter toggle mode"),
         KeymapItem::new("i", "Enter insert mode"),
         KeymapItem::new("v", "Enter visual mode"),
+        KeymapItem::new("V", "Enter visual line mode"),
+        KeymapItem::new("Ctrl+v", "Enter visual block mode"),
         KeymapItem::new("z", "Enter scroll mode"),
+        KeymapItem::new("m", "Set a mark"),
+        KeymapItem::new("' `", "Jump to a mark"),
+        KeymapItem::new("Ctrl+o Ctrl+i", "Jump to older or newer position"),
         KeymapItem::new("Ctrl+h", "Show Vim keymap"),
         KeymapItem::new("Esc", "Quit"),
     ];
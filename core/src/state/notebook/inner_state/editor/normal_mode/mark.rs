@@ -0,0 +1,35 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{NormalModeTransition, NotebookTransition},
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume(state: &mut NotebookState, event: Event) -> Result<NotebookTransition> {
+    use Event::*;
+    use NormalModeTransition::*;
+
+    state.inner_state = InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+    match event {
+        Key(KeyEvent::Esc) => IdleMode.into(),
+        Key(key) => match key.to_char() {
+            Some(mark) if mark.is_ascii_alphabetic() => SetMark(mark).into(),
+            _ => super::idle::consume(state, event),
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingNormalMode::Mark::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap() -> Vec<KeymapGroup> {
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("a-z", "Set a mark local to this note"),
+            KeymapItem::new("A-Z", "Set a global mark, usable from any note"),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
@@ -0,0 +1,44 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{NormalModeTransition, NotebookTransition},
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume(
+    state: &mut NotebookState,
+    linewise: bool,
+    event: Event,
+) -> Result<NotebookTransition> {
+    use Event::*;
+    use NormalModeTransition::*;
+
+    state.inner_state = InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+    match event {
+        Key(KeyEvent::Esc) => IdleMode.into(),
+        Key(key) => match key.to_char() {
+            Some(mark) if mark.is_ascii_alphabetic() => JumpToMark { mark, linewise }.into(),
+            _ => super::idle::consume(state, event),
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingNormalMode::MarkJump::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap(linewise: bool) -> Vec<KeymapGroup> {
+    let target = if linewise {
+        "Jump to the first non-blank character of the marked line"
+    } else {
+        "Jump to the exact marked position"
+    };
+
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("a-z|A-Z", target),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
@@ -2,7 +2,9 @@ use super::VimNormalState;
 use crate::{
     Error, Event, KeyEvent, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, VimKeymapKind, VimMotion,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -53,6 +55,22 @@ pub fn consume(state: &mut NotebookState, n: usize, event: Event) -> Result<Note
 
             MoveCursorWordBack(n).into()
         }
+        Key(KeyEvent::F) => super::enter_char_search(state, None, CharSearchKind::Find, n),
+        Key(KeyEvent::CapF) => super::enter_char_search(state, None, CharSearchKind::FindBack, n),
+        Key(KeyEvent::T) => super::enter_char_search(state, None, CharSearchKind::Till, n),
+        Key(KeyEvent::CapT) => super::enter_char_search(state, None, CharSearchKind::TillBack, n),
+        Key(KeyEvent::Semicolon) => super::repeat_char_search(state, None, false, n),
+        Key(KeyEvent::Comma) => super::repeat_char_search(state, None, true, n),
+        Key(KeyEvent::CurlyBracketClose) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            MoveCursorMotion(VimMotion::ParagraphForward, n).into()
+        }
+        Key(KeyEvent::CurlyBracketOpen) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            MoveCursorMotion(VimMotion::ParagraphBackward, n).into()
+        }
         Key(KeyEvent::CapG) => {
             state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
 
@@ -113,6 +131,12 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
         KeymapItem::new("k", format!("Move cursor {n} steps up")),
         KeymapItem::new("h", format!("Move cursor {n} steps left")),
         KeymapItem::new("l", format!("Move cursor {n} steps right")),
+        KeymapItem::new("f F", format!("Move cursor to the {n}th typed character")),
+        KeymapItem::new(
+            "t T",
+            format!("Move cursor until the {n}th typed character"),
+        ),
+        KeymapItem::new("{ }", format!("Move cursor {n} paragraphs")),
         KeymapItem::new("0-9", "Append steps"),
         KeymapItem::new("Ctrl+h", "Show Vim keymap"),
         KeymapItem::new("Esc", "Cancel"),
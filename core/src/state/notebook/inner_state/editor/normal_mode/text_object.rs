@@ -0,0 +1,79 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{NormalModeTransition, NotebookTransition, TextObjectScope, VimOperator},
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume(
+    state: &mut NotebookState,
+    operator: VimOperator,
+    scope: TextObjectScope,
+    n: usize,
+    event: Event,
+) -> Result<NotebookTransition> {
+    use Event::*;
+    use NormalModeTransition::*;
+
+    match event {
+        Key(KeyEvent::Esc) => {
+            state.inner_state =
+                InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+            IdleMode.into()
+        }
+        Key(key) => match super::super::text_object(key) {
+            Some(object) => {
+                super::finish_operator(state, operator);
+
+                OperateTextObject {
+                    operator,
+                    scope,
+                    object,
+                    n,
+                }
+                .into()
+            }
+            None => {
+                state.inner_state =
+                    InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+
+                super::idle::consume(state, event)
+            }
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingNormalMode::TextObject::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap(operator: VimOperator, scope: TextObjectScope, n: usize) -> Vec<KeymapGroup> {
+    let verb = match operator {
+        VimOperator::Delete => "Delete",
+        VimOperator::Change => "Change",
+        VimOperator::Yank => "Yank",
+    };
+    let scope = match scope {
+        TextObjectScope::Inner => "inside",
+        TextObjectScope::Around => "around",
+    };
+    let word = if n == 1 {
+        format!("{verb} {scope} the current word")
+    } else {
+        format!("{verb} {scope} {n} words from cursor")
+    };
+
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("w", word),
+            KeymapItem::new("p", format!("{verb} {scope} the paragraph")),
+            KeymapItem::new("\" ' `", format!("{verb} {scope} quotes")),
+            KeymapItem::new("( ) b", format!("{verb} {scope} parentheses")),
+            KeymapItem::new("[ ]", format!("{verb} {scope} brackets")),
+            KeymapItem::new("{ } B", format!("{verb} {scope} braces")),
+            KeymapItem::new("< >", format!("{verb} {scope} angle brackets")),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
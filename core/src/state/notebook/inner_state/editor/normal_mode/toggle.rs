@@ -44,6 +44,13 @@ pub async fn consume<B: CoreBackend + ?Sized>(
 
             ToggleSyntaxHighlight.into()
         }
+        Key(KeyEvent::E) => {
+            state.inner_state =
+                InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
+            let note_id = state.get_editing()?.id.clone();
+
+            EditExternally(note_id).into()
+        }
         event @ Key(_) => {
             state.inner_state =
                 InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
@@ -75,6 +82,7 @@ pub fn keymap() -> Vec<KeymapGroup> {
                 KeymapItem::new("b", "Toggle browser"),
                 KeymapItem::new("n", "Toggle editor line number"),
                 KeymapItem::new("s", "Toggle syntax highlighting"),
+                KeymapItem::new("e", "Edit in $VISUAL or $EDITOR"),
                 KeymapItem::new("Esc", "Cancel"),
             ],
         ),
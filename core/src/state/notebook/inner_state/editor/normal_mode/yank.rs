@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, NumKey, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimMotion,
+        VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -23,6 +26,35 @@ pub fn consume(state: &mut NotebookState, n: usize, event: Event) -> Result<Note
 
             YankLines(n).into()
         }
+        Key(KeyEvent::I) => {
+            super::enter_text_object(state, VimOperator::Yank, TextObjectScope::Inner, n)
+        }
+        Key(KeyEvent::A) => {
+            super::enter_text_object(state, VimOperator::Yank, TextObjectScope::Around, n)
+        }
+        Key(KeyEvent::F) => {
+            super::enter_char_search(state, Some(VimOperator::Yank), CharSearchKind::Find, n)
+        }
+        Key(KeyEvent::CapF) => {
+            super::enter_char_search(state, Some(VimOperator::Yank), CharSearchKind::FindBack, n)
+        }
+        Key(KeyEvent::T) => {
+            super::enter_char_search(state, Some(VimOperator::Yank), CharSearchKind::Till, n)
+        }
+        Key(KeyEvent::CapT) => {
+            super::enter_char_search(state, Some(VimOperator::Yank), CharSearchKind::TillBack, n)
+        }
+        Key(KeyEvent::Semicolon) => {
+            super::repeat_char_search(state, Some(VimOperator::Yank), false, n)
+        }
+        Key(KeyEvent::Comma) => super::repeat_char_search(state, Some(VimOperator::Yank), true, n),
+        Key(KeyEvent::Percent) => super::operate(state, VimOperator::Yank, VimMotion::MatchPair, n),
+        Key(KeyEvent::CurlyBracketClose) => {
+            super::operate(state, VimOperator::Yank, VimMotion::ParagraphForward, n)
+        }
+        Key(KeyEvent::CurlyBracketOpen) => {
+            super::operate(state, VimOperator::Yank, VimMotion::ParagraphBackward, n)
+        }
         Key(KeyEvent::Esc) => {
             state.inner_state =
                 InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
@@ -47,6 +79,13 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
         vec![
             KeymapItem::new("y", format!("Yank {n} lines")),
             KeymapItem::new("1-9", "Append steps"),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Yank to a typed character"),
+            KeymapItem::new("t T", "Yank until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Yank to the matching bracket"),
+            KeymapItem::new("{ }", "Yank to the paragraph boundary"),
             KeymapItem::new("Esc", "Cancel"),
         ],
     )]
@@ -1,7 +1,10 @@
 use crate::{
     Error, Event, KeyEvent, Result,
     state::notebook::{EditorState, InnerState, NotebookState},
-    transition::{NormalModeTransition, NotebookTransition},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimMotion,
+        VimOperator,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -28,6 +31,57 @@ pub fn consume(
 
             YankLines(n1 * n2).into()
         }
+        Key(KeyEvent::I) => {
+            super::enter_text_object(state, VimOperator::Yank, TextObjectScope::Inner, n1 * n2)
+        }
+        Key(KeyEvent::A) => {
+            super::enter_text_object(state, VimOperator::Yank, TextObjectScope::Around, n1 * n2)
+        }
+        Key(KeyEvent::F) => super::enter_char_search(
+            state,
+            Some(VimOperator::Yank),
+            CharSearchKind::Find,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CapF) => super::enter_char_search(
+            state,
+            Some(VimOperator::Yank),
+            CharSearchKind::FindBack,
+            n1 * n2,
+        ),
+        Key(KeyEvent::T) => super::enter_char_search(
+            state,
+            Some(VimOperator::Yank),
+            CharSearchKind::Till,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CapT) => super::enter_char_search(
+            state,
+            Some(VimOperator::Yank),
+            CharSearchKind::TillBack,
+            n1 * n2,
+        ),
+        Key(KeyEvent::Semicolon) => {
+            super::repeat_char_search(state, Some(VimOperator::Yank), false, n1 * n2)
+        }
+        Key(KeyEvent::Comma) => {
+            super::repeat_char_search(state, Some(VimOperator::Yank), true, n1 * n2)
+        }
+        Key(KeyEvent::Percent) => {
+            super::operate(state, VimOperator::Yank, VimMotion::MatchPair, n1 * n2)
+        }
+        Key(KeyEvent::CurlyBracketClose) => super::operate(
+            state,
+            VimOperator::Yank,
+            VimMotion::ParagraphForward,
+            n1 * n2,
+        ),
+        Key(KeyEvent::CurlyBracketOpen) => super::operate(
+            state,
+            VimOperator::Yank,
+            VimMotion::ParagraphBackward,
+            n1 * n2,
+        ),
         Key(KeyEvent::Esc) => {
             state.inner_state =
                 InnerState::Editor(EditorState::Normal(super::VimNormalState::Idle));
@@ -56,6 +110,13 @@ pub fn keymap(n1: usize, n2: usize) -> Vec<KeymapGroup> {
                 KeymapItem::new("y", format!("Yank {n1}*{n2} lines"))
             },
             KeymapItem::new("0-9", "Append steps"),
+            KeymapItem::new("i", "Select inside a text object"),
+            KeymapItem::new("a", "Select around a text object"),
+            KeymapItem::new("f F", "Yank to a typed character"),
+            KeymapItem::new("t T", "Yank until a typed character"),
+            KeymapItem::new("; ,", "Repeat the last character search"),
+            KeymapItem::new("%", "Yank to the matching bracket"),
+            KeymapItem::new("{ }", "Yank to the paragraph boundary"),
             KeymapItem::new("Esc", "Cancel"),
         ],
     )]
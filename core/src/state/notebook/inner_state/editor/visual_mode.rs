@@ -1,20 +1,27 @@
 use crate::{
     Event, Result,
     backend::CoreBackend,
-    state::notebook::NotebookState,
-    transition::{NotebookTransition, VisualModeTransition},
+    state::notebook::{EditorState, InnerState, NotebookState, VimNormalState},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimMotion,
+        VisualKind, VisualModeTransition,
+    },
     types::KeymapGroup,
 };
 
+mod char_search;
 mod gateway;
 mod idle;
 mod numbering;
+mod text_object;
 
 #[derive(Clone, Copy)]
 pub enum VimVisualState {
     Idle,
     Gateway,
     Numbering(usize),
+    TextObject(TextObjectScope),
+    CharSearch(CharSearchKind, usize),
 }
 
 pub fn consume<B: CoreBackend + ?Sized>(
@@ -27,6 +34,8 @@ pub fn consume<B: CoreBackend + ?Sized>(
         VimVisualState::Idle => idle::consume(db, state, event),
         VimVisualState::Gateway => gateway::consume(db, state, event),
         VimVisualState::Numbering(n) => numbering::consume(db, state, n, event),
+        VimVisualState::TextObject(scope) => text_object::consume(db, state, scope, event),
+        VimVisualState::CharSearch(kind, n) => char_search::consume(db, state, kind, n, event),
     }
 }
 
@@ -41,5 +50,50 @@ pub fn keymap(vim_state: VimVisualState) -> Vec<KeymapGroup> {
         VimVisualState::Idle => idle::keymap(),
         VimVisualState::Gateway => gateway::keymap(),
         VimVisualState::Numbering(n) => numbering::keymap(n),
+        VimVisualState::TextObject(scope) => text_object::keymap(scope),
+        VimVisualState::CharSearch(kind, n) => char_search::keymap(kind, n),
     }
 }
+
+fn enter_char_search(
+    state: &mut NotebookState,
+    kind: CharSearchKind,
+    n: usize,
+) -> Result<NotebookTransition> {
+    state.inner_state =
+        InnerState::Editor(EditorState::Visual(VimVisualState::CharSearch(kind, n)));
+
+    VisualModeTransition::CharSearchMode.into()
+}
+
+fn repeat_char_search(
+    state: &mut NotebookState,
+    reverse: bool,
+    n: usize,
+) -> Result<NotebookTransition> {
+    state.inner_state = InnerState::Editor(EditorState::Visual(VimVisualState::Idle));
+
+    let Some(search) = state.last_char_search else {
+        return Ok(NotebookTransition::None);
+    };
+    let search = if reverse { search.reversed() } else { search };
+
+    VisualModeTransition::MoveCursorMotion(VimMotion::RepeatCharSearch(search), n).into()
+}
+
+/// `v`, `V` and `Ctrl-v` inside visual mode: switch to the other selection shape,
+/// or leave visual mode when the key of the current shape is pressed again.
+fn switch_kind(state: &mut NotebookState, kind: VisualKind) -> Result<NotebookTransition> {
+    if state.visual_kind == kind {
+        state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+        return Ok(NotebookTransition::EditingNormalMode(
+            NormalModeTransition::IdleMode,
+        ));
+    }
+
+    state.inner_state = InnerState::Editor(EditorState::Visual(VimVisualState::Idle));
+    state.visual_kind = kind;
+
+    VisualModeTransition::SwitchKind(kind).into()
+}
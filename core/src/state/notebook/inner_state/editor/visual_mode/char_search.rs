@@ -0,0 +1,58 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    backend::CoreBackend,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{CharSearch, CharSearchKind, NotebookTransition, VimMotion, VisualModeTransition},
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    kind: CharSearchKind,
+    n: usize,
+    event: Event,
+) -> Result<NotebookTransition> {
+    use Event::*;
+    use VisualModeTransition::*;
+
+    state.inner_state = InnerState::Editor(EditorState::Visual(super::VimVisualState::Idle));
+
+    match event {
+        Key(KeyEvent::Esc) => IdleMode.into(),
+        Key(key) => match key.to_char() {
+            Some(target) => {
+                let search = CharSearch { kind, target };
+                state.last_char_search = Some(search);
+
+                MoveCursorMotion(VimMotion::CharSearch(search), n).into()
+            }
+            None => super::idle::consume(db, state, event),
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingVisualMode::CharSearch::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap(kind: CharSearchKind, n: usize) -> Vec<KeymapGroup> {
+    let target = match kind {
+        CharSearchKind::Find => "to the next",
+        CharSearchKind::FindBack => "back to the previous",
+        CharSearchKind::Till => "until the next",
+        CharSearchKind::TillBack => "back until the previous",
+    };
+    let desc = if n == 1 {
+        format!("Extend selection {target} typed character")
+    } else {
+        format!("Extend selection {target} {n}th typed character")
+    };
+
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("char", desc),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
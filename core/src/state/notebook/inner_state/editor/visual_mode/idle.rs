@@ -2,7 +2,10 @@ use crate::{
     Error, Event, KeyEvent, NumKey, Result,
     backend::CoreBackend,
     state::notebook::{EditorState, InnerState, NotebookState, VimNormalState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind, VisualModeTransition},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, TextObjectScope, VimKeymapKind,
+        VimMotion, VisualKind, VisualModeTransition,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -26,6 +29,42 @@ pub fn consume<B: CoreBackend + ?Sized>(
         Key(KeyEvent::DollarSign) => MoveCursorLineEnd.into(),
         Key(KeyEvent::Caret) => MoveCursorLineNonEmptyStart.into(),
         Key(KeyEvent::CapG) => MoveCursorBottom.into(),
+        Key(KeyEvent::Percent) => MoveCursorMotion(VimMotion::MatchPair, 1).into(),
+        Key(KeyEvent::CurlyBracketClose) => MoveCursorMotion(VimMotion::ParagraphForward, 1).into(),
+        Key(KeyEvent::CurlyBracketOpen) => MoveCursorMotion(VimMotion::ParagraphBackward, 1).into(),
+        Key(KeyEvent::F) => super::enter_char_search(state, CharSearchKind::Find, 1),
+        Key(KeyEvent::CapF) => super::enter_char_search(state, CharSearchKind::FindBack, 1),
+        Key(KeyEvent::T) => super::enter_char_search(state, CharSearchKind::Till, 1),
+        Key(KeyEvent::CapT) => super::enter_char_search(state, CharSearchKind::TillBack, 1),
+        Key(KeyEvent::Semicolon) => super::repeat_char_search(state, false, 1),
+        Key(KeyEvent::Comma) => super::repeat_char_search(state, true, 1),
+        Key(KeyEvent::V) => super::switch_kind(state, VisualKind::Char),
+        Key(KeyEvent::CapV) => super::switch_kind(state, VisualKind::Line),
+        Key(KeyEvent::CtrlV) => super::switch_kind(state, VisualKind::Block),
+        Key(KeyEvent::CapI) if state.visual_kind == VisualKind::Block => {
+            state.inner_state = InnerState::Editor(EditorState::Insert);
+
+            BlockInsert.into()
+        }
+        Key(KeyEvent::CapA) if state.visual_kind == VisualKind::Block => {
+            state.inner_state = InnerState::Editor(EditorState::Insert);
+
+            BlockAppend.into()
+        }
+        Key(KeyEvent::I) => {
+            state.inner_state = InnerState::Editor(EditorState::Visual(
+                super::VimVisualState::TextObject(TextObjectScope::Inner),
+            ));
+
+            TextObjectMode.into()
+        }
+        Key(KeyEvent::A) => {
+            state.inner_state = InnerState::Editor(EditorState::Visual(
+                super::VimVisualState::TextObject(TextObjectScope::Around),
+            ));
+
+            TextObjectMode.into()
+        }
         Key(KeyEvent::Tilde) => {
             state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
 
@@ -47,6 +86,21 @@ pub fn consume<B: CoreBackend + ?Sized>(
             DeleteSelection.into()
         }
 
+        Key(KeyEvent::AngleBracketClose) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            IndentSelection(1).into()
+        }
+        Key(KeyEvent::AngleBracketOpen) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            OutdentSelection(1).into()
+        }
+        Key(KeyEvent::CapJ) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            JoinSelection.into()
+        }
         Key(KeyEvent::S | KeyEvent::CapS) => {
             state.inner_state = InnerState::Editor(EditorState::Insert);
 
@@ -91,6 +145,12 @@ pub fn keymap() -> Vec<KeymapGroup> {
         KeymapItem::new("k", "Move cursor up"),
         KeymapItem::new("h", "Move cursor left"),
         KeymapItem::new("l", "Move cursor right"),
+        KeymapItem::new("i a", "Select a text object"),
+        KeymapItem::new("f F t T", "Extend selection to a typed character"),
+        KeymapItem::new("v V Ctrl+v", "Switch to character, line or block selection"),
+        KeymapItem::new("> <", "Indent or outdent selected lines"),
+        KeymapItem::new("J", "Join selected lines"),
+        KeymapItem::new("I A", "Insert before or append after the block"),
         KeymapItem::new("1-9", "Append steps"),
         KeymapItem::new("Ctrl+h", "Show Vim keymap"),
         KeymapItem::new("Esc", "Cancel"),
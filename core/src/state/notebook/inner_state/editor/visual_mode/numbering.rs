@@ -2,7 +2,10 @@ use crate::{
     Error, Event, KeyEvent, Result,
     backend::CoreBackend,
     state::notebook::{EditorState, InnerState, NotebookState, VimNormalState},
-    transition::{NormalModeTransition, NotebookTransition, VimKeymapKind, VisualModeTransition},
+    transition::{
+        CharSearchKind, NormalModeTransition, NotebookTransition, VimKeymapKind, VimMotion,
+        VisualModeTransition,
+    },
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -65,12 +68,40 @@ pub fn consume<B: CoreBackend + ?Sized>(
 
             MoveCursorWordBack(n).into()
         }
+        Key(KeyEvent::F) => super::enter_char_search(state, CharSearchKind::Find, n),
+        Key(KeyEvent::CapF) => super::enter_char_search(state, CharSearchKind::FindBack, n),
+        Key(KeyEvent::T) => super::enter_char_search(state, CharSearchKind::Till, n),
+        Key(KeyEvent::CapT) => super::enter_char_search(state, CharSearchKind::TillBack, n),
+        Key(KeyEvent::Semicolon) => super::repeat_char_search(state, false, n),
+        Key(KeyEvent::Comma) => super::repeat_char_search(state, true, n),
+        Key(KeyEvent::CurlyBracketClose) => {
+            state.inner_state =
+                InnerState::Editor(EditorState::Visual(super::VimVisualState::Idle));
+
+            MoveCursorMotion(VimMotion::ParagraphForward, n).into()
+        }
+        Key(KeyEvent::CurlyBracketOpen) => {
+            state.inner_state =
+                InnerState::Editor(EditorState::Visual(super::VimVisualState::Idle));
+
+            MoveCursorMotion(VimMotion::ParagraphBackward, n).into()
+        }
         Key(KeyEvent::CapG) => {
             state.inner_state =
                 InnerState::Editor(EditorState::Visual(super::VimVisualState::Idle));
 
             MoveCursorToLine(n).into()
         }
+        Key(KeyEvent::AngleBracketClose) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            IndentSelection(n).into()
+        }
+        Key(KeyEvent::AngleBracketOpen) => {
+            state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
+
+            OutdentSelection(n).into()
+        }
         Key(KeyEvent::Esc) => {
             state.inner_state = InnerState::Editor(EditorState::Normal(VimNormalState::Idle));
 
@@ -98,6 +129,16 @@ pub fn keymap(n: usize) -> Vec<KeymapGroup> {
         KeymapItem::new("k", format!("Move cursor {n} steps up")),
         KeymapItem::new("h", format!("Move cursor {n} steps left")),
         KeymapItem::new("l", format!("Move cursor {n} steps right")),
+        KeymapItem::new(
+            "f F",
+            format!("Extend selection to the {n}th typed character"),
+        ),
+        KeymapItem::new(
+            "t T",
+            format!("Extend selection until the {n}th typed character"),
+        ),
+        KeymapItem::new("{ }", format!("Extend selection by {n} paragraphs")),
+        KeymapItem::new("> <", format!("Indent or outdent selected lines {n} times")),
         KeymapItem::new("0-9", "Append steps"),
         KeymapItem::new("Ctrl+h", "Show Vim keymap"),
         KeymapItem::new("Esc", "Cancel"),
@@ -0,0 +1,56 @@
+use crate::{
+    Error, Event, KeyEvent, Result,
+    backend::CoreBackend,
+    state::notebook::{EditorState, InnerState, NotebookState},
+    transition::{NotebookTransition, TextObjectScope, VisualModeTransition},
+    types::{KeymapGroup, KeymapItem},
+};
+
+pub fn consume<B: CoreBackend + ?Sized>(
+    db: &mut B,
+    state: &mut NotebookState,
+    scope: TextObjectScope,
+    event: Event,
+) -> Result<NotebookTransition> {
+    use Event::*;
+    use VisualModeTransition::*;
+
+    state.inner_state = InnerState::Editor(EditorState::Visual(super::VimVisualState::Idle));
+
+    match event {
+        Key(KeyEvent::Esc) => IdleMode.into(),
+        Key(key) => match super::super::text_object(key) {
+            Some(object) => SelectTextObject {
+                scope,
+                object,
+                n: 1,
+            }
+            .into(),
+            None => super::idle::consume(db, state, event),
+        },
+        _ => Err(Error::Todo(
+            "Notebook::EditingVisualMode::TextObject::consume".to_owned(),
+        )),
+    }
+}
+
+pub fn keymap(scope: TextObjectScope) -> Vec<KeymapGroup> {
+    let scope = match scope {
+        TextObjectScope::Inner => "inside",
+        TextObjectScope::Around => "around",
+    };
+
+    vec![KeymapGroup::new(
+        "General",
+        vec![
+            KeymapItem::new("w", format!("Select {scope} the current word")),
+            KeymapItem::new("p", format!("Select {scope} the paragraph")),
+            KeymapItem::new("\" ' `", format!("Select {scope} quotes")),
+            KeymapItem::new("( ) b", format!("Select {scope} parentheses")),
+            KeymapItem::new("[ ]", format!("Select {scope} brackets")),
+            KeymapItem::new("{ } B", format!("Select {scope} braces")),
+            KeymapItem::new("< >", format!("Select {scope} angle brackets")),
+            KeymapItem::new("Esc", "Cancel"),
+        ],
+    )]
+}
@@ -3,7 +3,7 @@ use {
     crate::{
         Error, Event, KeyEvent, NotebookEvent, NotebookTransition, Result,
         backend::CoreBackend,
-        state::notebook::{InnerState, NotebookState, directory, note, tabs},
+        state::notebook::{InnerState, NotebookState, directory, note, session, tabs},
         transition::{MoveModeTransition, NoteTreeTransition},
         types::{KeymapGroup, KeymapItem},
     },
@@ -19,6 +19,7 @@ pub async fn consume<B: CoreBackend + ?Sized>(
 
     match event {
         Notebook(OpenDirectory(directory_id)) => directory::open(db, state, directory_id).await,
+        Notebook(RestoreSession(saved)) => session::restore(db, state, saved).await,
         Key(KeyEvent::L | KeyEvent::Right | KeyEvent::Enter) => {
             let directory = state.get_selected_directory()?.clone();
             let directory_item = state.root.find(&directory.id).ok_or(Error::NotFound(
@@ -132,6 +133,8 @@ pub fn keymap(state: &NotebookState) -> Vec<KeymapGroup> {
         items.push(KeymapItem::new("Tab", "Focus editor"));
     }
 
+    items.push(KeymapItem::new("S", "Sync now"));
+    items.push(KeymapItem::new("N", "Show notifications"));
     items.push(KeymapItem::new("Esc", "Quit"));
 
     vec![KeymapGroup::new("General", items)]
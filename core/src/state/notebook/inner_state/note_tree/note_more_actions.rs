@@ -1,7 +1,7 @@
 use crate::{
     Error, Event, NotebookEvent, NotebookTransition, Result,
     backend::CoreBackend,
-    state::notebook::{NotebookState, note},
+    state::notebook::{NotebookState, attachment, note},
     types::{KeymapGroup, KeymapItem},
 };
 
@@ -29,6 +29,10 @@ pub async fn consume<B: CoreBackend + ?Sized>(
 
             note::remove(db, state, note).await
         }
+        Notebook(AddAttachment { name, data }) => attachment::add(db, state, name, data).await,
+        Notebook(RemoveAttachment(attachment_id)) => {
+            attachment::remove(db, state, attachment_id).await
+        }
         Cancel => {
             let note = state.get_selected_note()?.clone();
 
@@ -48,6 +52,7 @@ pub fn keymap() -> Vec<KeymapGroup> {
             KeymapItem::new("j", "Select next"),
             KeymapItem::new("k", "Select Previous"),
             KeymapItem::new("Enter", "Run selected item"),
+            KeymapItem::new("x", "Remove selected attachment"),
             KeymapItem::new("Esc", "Close"),
         ],
     )]
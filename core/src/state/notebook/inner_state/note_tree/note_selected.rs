@@ -3,7 +3,7 @@ use {
     crate::{
         Error, Event, KeyEvent, NotebookEvent, NotebookTransition, Result,
         backend::CoreBackend,
-        state::notebook::{InnerState, NotebookState, directory, note, tabs},
+        state::notebook::{InnerState, NotebookState, directory, note, session, tabs},
         transition::{MoveModeTransition, NoteTreeTransition},
         types::{KeymapGroup, KeymapItem},
     },
@@ -19,6 +19,7 @@ pub async fn consume<B: CoreBackend + ?Sized>(
 
     match event {
         Notebook(OpenDirectory(directory_id)) => directory::open(db, state, directory_id).await,
+        Notebook(RestoreSession(saved)) => session::restore(db, state, saved).await,
         Notebook(CloseDirectory(directory_id)) => {
             let directory = state
                 .root
@@ -55,7 +56,7 @@ pub async fn consume<B: CoreBackend + ?Sized>(
         Key(KeyEvent::M) => {
             let note = state.get_selected_note()?.clone();
 
-            Ok(note::show_actions_dialog(state, note))
+            note::show_actions_dialog(db, state, note).await
         }
         Key(KeyEvent::Space) => {
             state.inner_state = InnerState::NoteTree(NoteTreeState::MoveMode);
@@ -122,6 +123,8 @@ pub fn keymap(state: &NotebookState) -> Vec<KeymapGroup> {
         actions.push(KeymapItem::new("Tab", "Focus editor"));
     }
 
+    actions.push(KeymapItem::new("S", "Sync now"));
+    actions.push(KeymapItem::new("N", "Show notifications"));
     actions.push(KeymapItem::new("Esc", "Quit"));
 
     vec![
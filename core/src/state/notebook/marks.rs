@@ -0,0 +1,176 @@
+use {
+    crate::{
+        data::Note,
+        types::{Cursor, DirectoryId, NoteId},
+    },
+    std::collections::{BTreeMap, HashMap},
+};
+
+const JUMP_LIST_LIMIT: usize = 100;
+
+/// `m{A-Z}` mark which can point into any note of the notebook.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlobalMark {
+    pub note_id: NoteId,
+    pub directory_id: DirectoryId,
+    pub cursor: Cursor,
+}
+
+/// Entry of the `Ctrl-o` / `Ctrl-i` jump list.
+///
+/// Tab switches do not know the cursor of the note being left, so they are
+/// recorded without one and restore the note where its editor was left.
+#[derive(Clone, Debug)]
+pub struct Jump {
+    pub note: Note,
+    pub cursor: Option<Cursor>,
+}
+
+#[derive(Default)]
+pub struct Marks {
+    local: HashMap<NoteId, HashMap<char, Cursor>>,
+    global: BTreeMap<char, GlobalMark>,
+    jumps: Vec<Jump>,
+    jump_index: usize,
+}
+
+impl Marks {
+    pub fn get_local(&self, note_id: &NoteId, mark: char) -> Option<Cursor> {
+        self.local.get(note_id)?.get(&mark).copied()
+    }
+
+    pub fn set_local(&mut self, note_id: NoteId, mark: char, cursor: Cursor) {
+        self.local.entry(note_id).or_default().insert(mark, cursor);
+    }
+
+    pub fn get_global(&self, mark: char) -> Option<&GlobalMark> {
+        self.global.get(&mark)
+    }
+
+    pub fn set_global(&mut self, mark: char, global_mark: GlobalMark) {
+        self.global.insert(mark, global_mark);
+    }
+
+    pub fn remove_global(&mut self, mark: char) {
+        self.global.remove(&mark);
+    }
+
+    /// Records the position being left, dropping any jumps that were undone with `Ctrl-o`.
+    pub fn push_jump(&mut self, jump: Jump) {
+        self.jumps.truncate(self.jump_index);
+
+        if self
+            .jumps
+            .last()
+            .is_some_and(|last| last.note.id == jump.note.id && last.cursor == jump.cursor)
+        {
+            self.jumps.pop();
+        }
+
+        self.jumps.push(jump);
+        if self.jumps.len() > JUMP_LIST_LIMIT {
+            self.jumps.remove(0);
+        }
+
+        self.jump_index = self.jumps.len();
+    }
+
+    /// Steps back in the jump list. `current` is remembered when leaving the newest
+    /// position, so that `Ctrl-i` can return to it.
+    pub fn jump_older(&mut self, current: Jump) -> Option<Jump> {
+        if self.jump_index == 0 {
+            return None;
+        }
+
+        if self.jump_index == self.jumps.len() {
+            self.jumps.push(current);
+        }
+
+        self.jump_index -= 1;
+        self.jumps.get(self.jump_index).cloned()
+    }
+
+    pub fn jump_newer(&mut self) -> Option<Jump> {
+        if self.jump_index + 1 >= self.jumps.len() {
+            return None;
+        }
+
+        self.jump_index += 1;
+        self.jumps.get(self.jump_index).cloned()
+    }
+
+    /// Drops every mark and jump which points into the removed note.
+    pub fn forget_note(&mut self, note_id: &NoteId) {
+        self.local.remove(note_id);
+
+        let removed_before_index = self.jumps[..self.jump_index]
+            .iter()
+            .filter(|jump| &jump.note.id == note_id)
+            .count();
+        self.jumps.retain(|jump| &jump.note.id != note_id);
+        self.jump_index -= removed_before_index;
+    }
+
+    /// Follows a note into its new directory. Returns `true` if a global mark changed.
+    pub fn move_note(&mut self, note_id: &NoteId, directory_id: &DirectoryId) -> bool {
+        for jump in self
+            .jumps
+            .iter_mut()
+            .filter(|jump| &jump.note.id == note_id)
+        {
+            jump.note.directory_id.clone_from(directory_id);
+        }
+
+        let mut changed = false;
+        for global_mark in self
+            .global
+            .values_mut()
+            .filter(|global_mark| &global_mark.note_id == note_id)
+        {
+            global_mark.directory_id.clone_from(directory_id);
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Serializes global marks for the `Meta` table, one tab separated mark per line.
+    pub fn global_to_meta(&self) -> String {
+        self.global
+            .iter()
+            .map(|(mark, global_mark)| {
+                format!(
+                    "{mark}\t{}\t{}\t{}\t{}",
+                    global_mark.note_id,
+                    global_mark.directory_id,
+                    global_mark.cursor.row,
+                    global_mark.cursor.col
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn load_global_from_meta(&mut self, value: &str) {
+        self.global = value
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t');
+                let mark = fields.next()?.chars().next()?;
+                let note_id = fields.next()?.to_owned();
+                let directory_id = fields.next()?.to_owned();
+                let row = fields.next()?.parse().ok()?;
+                let col = fields.next()?.parse().ok()?;
+
+                Some((
+                    mark,
+                    GlobalMark {
+                        note_id,
+                        directory_id,
+                        cursor: Cursor { row, col },
+                    },
+                ))
+            })
+            .collect();
+    }
+}
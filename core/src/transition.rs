@@ -1,14 +1,16 @@
 use {
     crate::{
         Event,
-        data::{Directory, Note},
+        backend::SyncConflict,
+        data::{Attachment, Directory, Note},
         state::notebook::DirectoryItem,
-        types::{DirectoryId, NoteId},
+        types::{Cursor, DirectoryId, NoteId},
     },
+    serde::{Deserialize, Serialize},
     strum_macros::Display,
 };
 
-#[derive(Display)]
+#[derive(Display, Serialize, Deserialize)]
 pub enum Transition {
     #[strum(to_string = "Keymap::{0}")]
     Keymap(KeymapTransition),
@@ -21,15 +23,20 @@ pub enum Transition {
 
     Log(String),
     Error(String),
+
+    /// A background sync stopped at a merge conflict which needs a [`Resolution`].
+    ///
+    /// [`Resolution`]: crate::backend::Resolution
+    SyncConflict(SyncConflict),
 }
 
-#[derive(Display)]
+#[derive(Display, Serialize, Deserialize)]
 pub enum KeymapTransition {
     Show,
     Hide,
 }
 
-#[derive(Display)]
+#[derive(Display, Serialize, Deserialize)]
 pub enum EntryTransition {
     OpenNotebook,
 
@@ -39,15 +46,20 @@ pub enum EntryTransition {
     None,
 }
 
-#[derive(Display)]
+#[derive(Display, Serialize, Deserialize)]
 pub enum NotebookTransition {
     ViewMode(Note),
     BrowseNoteTree,
     FocusEditor,
 
     UpdateNoteContent(NoteId),
+    /// Note names to complete a `[[` link with.
+    CompleteNoteLink(Vec<String>),
 
+    /// Refused or failed action, shown to the user as an error.
     Alert(String),
+    /// Notice shown like an alert which is not a failure, e.g. an unset mark.
+    Info(String),
 
     #[strum(to_string = "Inedible::{0}")]
     Inedible(Event),
@@ -57,8 +69,12 @@ pub enum NotebookTransition {
     EditingNormalMode(NormalModeTransition),
     EditingVisualMode(VisualModeTransition),
     ShowVimKeymap(VimKeymapKind),
+
+    /// Contents of the tabs reopened by a restored session, in tab order.
+    RestoreSession(Vec<(NoteId, String)>),
 }
 
+#[derive(Serialize, Deserialize)]
 pub enum NoteTreeTransition {
     OpenDirectory {
         id: DirectoryId,
@@ -82,7 +98,12 @@ pub enum NoteTreeTransition {
     AddNote(Note),
     AddDirectory(Directory),
 
-    ShowNoteActionsDialog(Note),
+    ShowNoteActionsDialog {
+        note: Note,
+        attachments: Vec<Attachment>,
+    },
+    /// Attachments of the selected note after one was added or removed.
+    UpdateAttachments(Vec<Attachment>),
     ShowDirectoryActionsDialog(Directory),
 
     MoveMode(MoveModeTransition),
@@ -105,6 +126,7 @@ pub enum NoteTreeTransition {
     GatewayMode,
 }
 
+#[derive(Serialize, Deserialize)]
 pub enum MoveModeTransition {
     Enter,
     SelectNext,
@@ -115,7 +137,7 @@ pub enum MoveModeTransition {
     Cancel,
 }
 
-#[derive(Clone, Copy, Display)]
+#[derive(Clone, Copy, Display, Serialize, Deserialize)]
 pub enum VimKeymapKind {
     NormalIdle,
     NormalNumbering,
@@ -127,7 +149,80 @@ pub enum VimKeymapKind {
     VisualNumbering,
 }
 
-#[derive(Display)]
+/// Operator waiting for a motion or a text object, e.g. the `d` in `dap`.
+#[derive(Clone, Copy, Display, Serialize, Deserialize)]
+pub enum VimOperator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Clone, Copy, Display, Serialize, Deserialize)]
+pub enum VimMotion {
+    CharSearch(CharSearch),
+    /// `;` and `,`: like `CharSearch`, but a till search skips the character
+    /// right next to the cursor so that repeating it keeps moving.
+    RepeatCharSearch(CharSearch),
+    MatchPair,
+    ParagraphForward,
+    ParagraphBackward,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct CharSearch {
+    pub kind: CharSearchKind,
+    pub target: char,
+}
+
+#[derive(Clone, Copy, Display, PartialEq, Serialize, Deserialize)]
+pub enum CharSearchKind {
+    /// `f`
+    Find,
+    /// `F`
+    FindBack,
+    /// `t`
+    Till,
+    /// `T`
+    TillBack,
+}
+
+/// Shape of a visual mode selection: `v`, `V` or `Ctrl-v`.
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisualKind {
+    #[default]
+    Char,
+    Line,
+    Block,
+}
+
+#[derive(Clone, Copy, Display, PartialEq, Serialize, Deserialize)]
+pub enum TextObjectScope {
+    Inner,
+    Around,
+}
+
+#[derive(Clone, Copy, Display, Serialize, Deserialize)]
+pub enum TextObject {
+    Word,
+    Paragraph,
+    Quote(char),
+    Pair(char, char),
+}
+
+impl CharSearch {
+    pub fn reversed(self) -> Self {
+        let kind = match self.kind {
+            CharSearchKind::Find => CharSearchKind::FindBack,
+            CharSearchKind::FindBack => CharSearchKind::Find,
+            CharSearchKind::Till => CharSearchKind::TillBack,
+            CharSearchKind::TillBack => CharSearchKind::Till,
+        };
+
+        Self { kind, ..self }
+    }
+}
+
+#[derive(Display, Serialize, Deserialize)]
 pub enum NormalModeTransition {
     IdleMode,
     ToggleMode,
@@ -136,20 +231,26 @@ pub enum NormalModeTransition {
     GatewayMode,
     YankMode,
     DeleteMode,
-    DeleteInsideMode,
     ChangeMode,
-    ChangeInsideMode,
+    TextObjectMode,
+    CharSearchMode,
+    MarkMode,
+    MarkJumpMode,
     ScrollMode,
 
     // toggle mode
     NextTab(NoteId),
     PrevTab(NoteId),
+    SelectTab(NoteId),
     CloseTab(NoteId),
     MoveTabNext(usize),
     MoveTabPrev(usize),
     ToggleLineNumbers,
     ToggleBrowser,
     ToggleSyntaxHighlight,
+    /// Hands the note to an external editor, whose result comes back as
+    /// `UpdateNoteContent`.
+    EditExternally(NoteId),
 
     // toggle tab close mode
     CloseRightTabs(usize),
@@ -168,6 +269,7 @@ pub enum NormalModeTransition {
     MoveCursorTop,
     MoveCursorBottom,
     MoveCursorToLine(usize),
+    MoveCursorMotion(VimMotion, usize),
     ScrollCenter,
     ScrollTop,
     ScrollBottom,
@@ -190,15 +292,45 @@ pub enum NormalModeTransition {
     Undo,
     Redo,
     YankLines(usize),
-    DeleteInsideWord(usize),
+    Operate {
+        operator: VimOperator,
+        motion: VimMotion,
+        n: usize,
+    },
+    OperateTextObject {
+        operator: VimOperator,
+        scope: TextObjectScope,
+        object: TextObject,
+        n: usize,
+    },
     SwitchCase,
+
+    // marks and jump list, answered by the frontend with the current cursor
+    SetMark(char),
+    JumpToMark {
+        mark: char,
+        linewise: bool,
+    },
+    JumpOlder,
+    JumpNewer,
+
+    /// Moves to a mark or jump list entry, opening its note first when needed.
+    /// `content` is set when the note had to be opened in a new tab.
+    Jump {
+        note_id: NoteId,
+        content: Option<String>,
+        cursor: Option<Cursor>,
+        linewise: bool,
+    },
 }
 
-#[derive(Display)]
+#[derive(Display, Serialize, Deserialize)]
 pub enum VisualModeTransition {
     IdleMode,
     NumberingMode,
     GatewayMode,
+    TextObjectMode,
+    CharSearchMode,
     MoveCursorDown(usize),
     MoveCursorUp(usize),
     MoveCursorBack(usize),
@@ -212,12 +344,24 @@ pub enum VisualModeTransition {
     MoveCursorTop,
     MoveCursorBottom,
     MoveCursorToLine(usize),
+    MoveCursorMotion(VimMotion, usize),
+    SelectTextObject {
+        scope: TextObjectScope,
+        object: TextObject,
+        n: usize,
+    },
     YankSelection,
     DeleteSelection,
     DeleteSelectionAndInsertMode,
     SwitchCase,
     ToUppercase,
     ToLowercase,
+    SwitchKind(VisualKind),
+    IndentSelection(usize),
+    OutdentSelection(usize),
+    JoinSelection,
+    BlockInsert,
+    BlockAppend,
 }
 
 impl From<KeymapTransition> for Transition {
@@ -1,8 +1,18 @@
+use serde::{Deserialize, Serialize};
+
 pub type NoteId = String; // UUID
 pub type DirectoryId = String; // UUID
+pub type AttachmentId = String; // UUID
 pub type Id = String; // UUID
 
-#[derive(Clone, Debug)]
+/// Cursor position inside a note, reported by the frontend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeymapItem {
     pub key: String,
     pub desc: String,
@@ -17,7 +27,7 @@ impl KeymapItem {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct KeymapGroup {
     pub title: String,
     pub items: Vec<KeymapItem>,
@@ -0,0 +1,122 @@
+use glues_core::backend::{CoreBackend, encrypted::Encrypted, local::Db};
+
+#[tokio::test]
+async fn attachments_follow_their_note() {
+    let mut db = Db::memory()
+        .await
+        .expect("in-memory backend should initialize");
+
+    let root_id = db.root_id();
+    let dir = db
+        .add_directory(root_id.clone(), "Trips".to_owned())
+        .await
+        .expect("backend should add directory");
+    let note = db
+        .add_note(dir.id.clone(), "Paris".to_owned())
+        .await
+        .expect("backend should add note");
+    let other = db
+        .add_note(root_id.clone(), "Other".to_owned())
+        .await
+        .expect("backend should add note");
+
+    let data = (0..=255).collect::<Vec<u8>>();
+    let photo = db
+        .add_attachment(note.id.clone(), "photo.png".to_owned(), data.clone())
+        .await
+        .expect("backend should add attachment");
+    assert_eq!(photo.name, "photo.png");
+    assert_eq!(photo.size, 256);
+    assert_eq!(photo.note_id, note.id);
+
+    let ticket = db
+        .add_attachment(note.id.clone(), "ticket.pdf".to_owned(), vec![])
+        .await
+        .expect("backend should add empty attachment");
+    db.add_attachment(other.id.clone(), "keep.txt".to_owned(), b"keep".to_vec())
+        .await
+        .expect("backend should add attachment");
+
+    let names = |attachments: Vec<glues_core::data::Attachment>| {
+        attachments
+            .into_iter()
+            .map(|attachment| attachment.name)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(db.fetch_attachments(note.id.clone()).await.unwrap()),
+        ["photo.png", "ticket.pdf"]
+    );
+    assert_eq!(
+        db.fetch_attachment_data(photo.id.clone()).await.unwrap(),
+        data
+    );
+    assert!(
+        db.fetch_attachment_data(ticket.id.clone())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    db.remove_attachment(ticket.id)
+        .await
+        .expect("backend should remove attachment");
+    assert_eq!(
+        names(db.fetch_attachments(note.id.clone()).await.unwrap()),
+        ["photo.png"]
+    );
+
+    db.remove_directory(dir.id)
+        .await
+        .expect("backend should remove directory");
+    assert!(db.fetch_attachments(note.id).await.unwrap().is_empty());
+    assert!(db.fetch_attachment_data(photo.id).await.is_err());
+    assert_eq!(
+        names(db.fetch_attachments(other.id.clone()).await.unwrap()),
+        ["keep.txt"]
+    );
+
+    db.remove_note(other.id.clone())
+        .await
+        .expect("backend should remove note");
+    assert!(db.fetch_attachments(other.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn encrypted_attachments() {
+    let db = Db::memory().await.unwrap();
+    let mut db = Encrypted::encrypt_with_iterations(db, "secret", 10)
+        .await
+        .unwrap();
+
+    let note = db
+        .add_note(db.root_id(), "Secrets".to_owned())
+        .await
+        .unwrap();
+    let attachment = db
+        .add_attachment(
+            note.id.clone(),
+            "plan.txt".to_owned(),
+            b"top secret".to_vec(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(attachment.name, "plan.txt");
+    assert_eq!(attachment.size, 10);
+
+    let listed = db.fetch_attachments(note.id.clone()).await.unwrap();
+    assert_eq!(listed[0].name, "plan.txt");
+    assert_eq!(listed[0].size, 10);
+    assert_eq!(
+        db.fetch_attachment_data(attachment.id.clone())
+            .await
+            .unwrap(),
+        b"top secret"
+    );
+
+    let mut inner = db.into_inner();
+    let stored = &inner.fetch_attachments(note.id).await.unwrap()[0];
+    assert!(stored.name.starts_with("enc1:"));
+    let data = inner.fetch_attachment_data(attachment.id).await.unwrap();
+    assert!(!data.windows(6).any(|window| window == b"secret"));
+}
@@ -97,4 +97,23 @@ async fn memory_backend_operations() {
     db.log("test".to_owned(), "message".to_owned())
         .await
         .expect("backend should append log entry");
+
+    // meta
+    let value = db
+        .fetch_meta("global_marks".to_owned())
+        .await
+        .expect("backend should fetch missing meta value");
+    assert_eq!(value, None);
+
+    db.update_meta("global_marks".to_owned(), "first".to_owned())
+        .await
+        .expect("backend should insert meta value");
+    db.update_meta("global_marks".to_owned(), "second".to_owned())
+        .await
+        .expect("backend should overwrite meta value");
+    let value = db
+        .fetch_meta("global_marks".to_owned())
+        .await
+        .expect("backend should fetch meta value");
+    assert_eq!(value.as_deref(), Some("second"));
 }
@@ -0,0 +1,230 @@
+use glues_core::{
+    Error,
+    backend::{
+        CoreBackend,
+        encrypted::Encrypted,
+        layer::{FaultLayer, Faulty},
+        local::Db,
+    },
+    backup::{self, Archive},
+};
+
+mod common;
+
+use common::TempDir;
+
+async fn sample_notebook() -> Db {
+    let mut db = Db::memory()
+        .await
+        .expect("in-memory backend should initialize");
+    let root_id = db.root_id();
+
+    let work = db
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .unwrap();
+    let projects = db
+        .add_directory(work.id.clone(), "Projects".to_owned())
+        .await
+        .unwrap();
+    let todo = db
+        .add_note(root_id.clone(), "Todo".to_owned())
+        .await
+        .unwrap();
+    db.update_note_content(todo.id.clone(), "- [ ] backup".to_owned())
+        .await
+        .unwrap();
+    let plan = db
+        .add_note(projects.id.clone(), "Plan".to_owned())
+        .await
+        .unwrap();
+    db.update_note_content(plan.id.clone(), "ship it".to_owned())
+        .await
+        .unwrap();
+    db.add_attachment(plan.id.clone(), "logo.png".to_owned(), vec![0, 1, 2, 255])
+        .await
+        .unwrap();
+    db.update_meta("global_marks".to_owned(), format!("A {}", plan.id))
+        .await
+        .unwrap();
+
+    db
+}
+
+/// Directories, notes and attachments as comparable tuples, walked from the root.
+async fn snapshot<B: CoreBackend>(db: &mut B) -> Vec<String> {
+    let archive = backup::dump(db).await.unwrap();
+    let mut lines = vec![format!(
+        "root {} {} {}",
+        archive.root.id, archive.root.name, archive.root.created_at
+    )];
+
+    for directory in archive.directories {
+        lines.push(format!(
+            "dir {} {} {} {} {}",
+            directory.id,
+            directory.parent_id,
+            directory.name,
+            directory.created_at,
+            directory.updated_at
+        ));
+    }
+    for note in archive.notes {
+        lines.push(format!(
+            "note {} {} {} {} {} {:?}",
+            note.note.id,
+            note.note.directory_id,
+            note.note.name,
+            note.note.created_at,
+            note.note.updated_at,
+            note.content
+        ));
+        for attachment in note.attachments {
+            lines.push(format!(
+                "attachment {} {} {} {:?}",
+                attachment.attachment.id,
+                attachment.attachment.name,
+                attachment.attachment.created_at,
+                attachment.data
+            ));
+        }
+    }
+    for (key, value) in archive.meta {
+        lines.push(format!("meta {key} {value}"));
+    }
+
+    lines.sort();
+    lines
+}
+
+#[tokio::test]
+async fn restore_keeps_ids_and_timestamps() {
+    let mut source = sample_notebook().await;
+    let expected = snapshot(&mut source).await;
+
+    let json = backup::dump(&mut source).await.unwrap().to_json().unwrap();
+    let archive = Archive::from_json(&json).expect("archive should parse");
+    assert_eq!(archive.directories.len(), 2);
+    assert_eq!(archive.notes.len(), 2);
+    assert!(!archive.meta.contains_key("schema_version"));
+
+    let mut target = Db::memory().await.unwrap();
+    backup::restore(&mut target, archive).await.unwrap();
+    assert_eq!(target.root_id(), source.root_id());
+    assert_eq!(snapshot(&mut target).await, expected);
+
+    // the restored notebook keeps working
+    let note = target
+        .add_note(target.root_id(), "New".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(note.directory_id, source.root_id());
+}
+
+#[tokio::test]
+async fn restore_needs_an_empty_notebook() {
+    let mut source = sample_notebook().await;
+    let archive = backup::dump(&mut source).await.unwrap();
+
+    let mut target = sample_notebook().await;
+    assert!(matches!(
+        backup::restore(&mut target, archive).await,
+        Err(Error::Archive(_))
+    ));
+}
+
+#[tokio::test]
+async fn archives_are_checked_when_read() {
+    let mut source = sample_notebook().await;
+    let mut archive = backup::dump(&mut source).await.unwrap();
+
+    archive.version = backup::ARCHIVE_VERSION + 1;
+    let json = archive.to_json().unwrap();
+    assert!(matches!(Archive::from_json(&json), Err(Error::Archive(_))));
+
+    archive.version = backup::ARCHIVE_VERSION;
+    archive.format = "something-else".to_owned();
+    let json = archive.to_json().unwrap();
+    assert!(matches!(Archive::from_json(&json), Err(Error::Archive(_))));
+
+    assert!(matches!(
+        Archive::from_json("{\"format\": \"glues-archive\"}"),
+        Err(Error::Archive(_))
+    ));
+}
+
+#[tokio::test]
+async fn encrypted_notebooks_round_trip_in_plaintext() {
+    let source = sample_notebook().await;
+    let mut source = Encrypted::encrypt_with_iterations(source, "secret", 10)
+        .await
+        .unwrap();
+    let expected = snapshot(&mut source).await;
+
+    let archive = backup::dump(&mut source).await.unwrap();
+    assert!(!archive.meta.contains_key("encryption"));
+    assert!(archive.to_json().unwrap().contains("ship it"));
+
+    let target = Db::memory().await.unwrap();
+    let mut target = Encrypted::encrypt_with_iterations(target, "other", 10)
+        .await
+        .unwrap();
+    backup::restore(&mut target, archive).await.unwrap();
+    assert_eq!(snapshot(&mut target).await, expected);
+
+    let mut inner = target.into_inner();
+    let root_id = inner.root_id();
+    let stored = inner.fetch_directories(root_id).await.unwrap();
+    assert!(stored[0].name.starts_with("enc1:"));
+}
+
+/// Restores `archive` into `target` with its attachments failing, and checks
+/// nothing was left behind.
+async fn fail_restore(target: Db, archive: Archive) -> Db {
+    let mut target = Faulty::new(target, FaultLayer::every(1).only(["restore_attachment"]));
+    assert!(matches!(
+        backup::restore(&mut target, archive).await,
+        Err(Error::BackendError(_))
+    ));
+
+    let mut target = target.into_inner();
+    let root_id = target.root_id();
+    assert!(
+        target
+            .fetch_directories(root_id.clone())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(target.fetch_notes(root_id).await.unwrap().is_empty());
+    target
+}
+
+#[tokio::test]
+async fn failed_restore_is_cleaned_up() {
+    let mut source = sample_notebook().await;
+    let expected = snapshot(&mut source).await;
+    let archive = backup::dump(&mut source).await.unwrap();
+
+    let target = Db::memory().await.unwrap();
+    let mut target = fail_restore(target, archive.clone()).await;
+
+    backup::restore(&mut target, archive).await.unwrap();
+    assert_eq!(snapshot(&mut target).await, expected);
+}
+
+#[tokio::test]
+async fn failed_restore_is_rolled_back() {
+    let mut source = sample_notebook().await;
+    let expected = snapshot(&mut source).await;
+    let archive = backup::dump(&mut source).await.unwrap();
+
+    let dir = TempDir::new("backup");
+    let target = Db::redb(&dir.join("notes.redb")).await.unwrap();
+    let root_id = target.root_id();
+    let mut target = fail_restore(target, archive.clone()).await;
+    assert_eq!(target.root_id(), root_id);
+
+    backup::restore(&mut target, archive).await.unwrap();
+    assert_eq!(snapshot(&mut target).await, expected);
+}
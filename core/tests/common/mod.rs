@@ -0,0 +1,125 @@
+use {
+    glues_core::backend::proxy::{ProxyServer, request::ProxyRequest},
+    std::{
+        io::ErrorKind, net::TcpListener, path::PathBuf, process::Command, sync::Arc,
+        thread::JoinHandle,
+    },
+    tiny_http::{Header, Response, Server},
+    tokio::sync::Mutex,
+};
+
+/// Directory removed with the test.
+#[allow(dead_code)]
+pub struct TempDir(PathBuf);
+
+#[allow(dead_code)]
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("glues-{name}-{}", uuid::Uuid::now_v7()));
+        std::fs::create_dir_all(&path).expect("temp dir should be created");
+        Self(path)
+    }
+
+    pub fn join(&self, name: &str) -> String {
+        self.0.join(name).to_string_lossy().into_owned()
+    }
+
+    /// Path not used by any earlier call.
+    pub fn fresh(&self) -> String {
+        self.join(&uuid::Uuid::now_v7().to_string())
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Creates a git repository at `path` with a committer configured.
+#[allow(dead_code)]
+pub fn git_init(path: &str) {
+    std::fs::create_dir_all(path).expect("git dir should be created");
+    for args in [
+        vec!["init", "--quiet"],
+        vec!["config", "user.name", "glues"],
+        vec!["config", "user.email", "glues@example.com"],
+    ] {
+        let status = Command::new("git")
+            .current_dir(path)
+            .args(args)
+            .status()
+            .expect("git should run");
+        assert!(status.success());
+    }
+}
+
+/// Binds an ephemeral local port, or returns `None` where the sandbox forbids
+/// it so that `test` is skipped.
+#[allow(dead_code)]
+pub fn bind(test: &str) -> Option<TcpListener> {
+    match TcpListener::bind("127.0.0.1:0") {
+        Ok(listener) => Some(listener),
+        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+            eprintln!("skipping {test}: {err}");
+            None
+        }
+        Err(err) => panic!("proxy server should bind to ephemeral port: {err}"),
+    }
+}
+
+/// Serves `server` on `listener` until the returned HTTP server is unblocked.
+#[allow(dead_code)]
+pub fn serve(
+    listener: TcpListener,
+    server: Arc<Mutex<ProxyServer>>,
+) -> (Arc<Server>, JoinHandle<()>) {
+    serve_with_token(listener, server, None)
+}
+
+/// Like [`serve`], answering 401 to requests without the bearer `token`.
+#[allow(dead_code)]
+pub fn serve_with_token(
+    listener: TcpListener,
+    server: Arc<Mutex<ProxyServer>>,
+    token: Option<&str>,
+) -> (Arc<Server>, JoinHandle<()>) {
+    let http = Arc::new(
+        Server::from_listener(listener, None).expect("proxy server should accept HTTP connections"),
+    );
+    let handle = tokio::runtime::Handle::current();
+    let expected_header = token.map(|token| format!("Bearer {token}"));
+    let http_clone = http.clone();
+    let thread = std::thread::spawn(move || {
+        for mut req in http_clone.incoming_requests() {
+            if let Some(expected_header) = &expected_header {
+                let header_ok = req
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Authorization"))
+                    .is_some_and(|h| h.value.as_str().trim() == expected_header);
+                if !header_ok {
+                    let _ = req.respond(Response::empty(401));
+                    continue;
+                }
+            }
+
+            let mut body = String::new();
+            req.as_reader()
+                .read_to_string(&mut body)
+                .expect("proxy request body should read into string");
+            let proxy_req: ProxyRequest =
+                serde_json::from_str(&body).expect("proxy request JSON should deserialize");
+            let response = handle.block_on(async { server.lock().await.handle(proxy_req).await });
+            let body =
+                serde_json::to_string(&response).expect("proxy response should serialize to JSON");
+            let resp = Response::from_string(body).with_header(
+                Header::from_bytes("Content-Type", "application/json")
+                    .expect("content-type header should be valid"),
+            );
+            let _ = req.respond(resp);
+        }
+    });
+
+    (http, thread)
+}
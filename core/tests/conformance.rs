@@ -0,0 +1,75 @@
+mod common;
+
+use {
+    common::{TempDir, git_init, serve},
+    glues_core::{
+        backend::{
+            local::Db,
+            proxy::{ProxyClient, ProxyServer},
+        },
+        conformance,
+    },
+    std::{net::TcpListener, sync::Arc},
+    tokio::sync::Mutex,
+};
+
+/// Serves a fresh in-memory notebook through `ProxyServer` and connects to it.
+async fn proxy_client() -> ProxyClient {
+    let db = Db::memory().await.expect("memory backend should open");
+    let server = Arc::new(Mutex::new(ProxyServer::new(Box::new(db))));
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("proxy server should bind");
+    let addr = listener
+        .local_addr()
+        .expect("proxy server should expose its address");
+    serve(listener, server);
+
+    ProxyClient::connect(format!("http://{addr}"), None)
+        .await
+        .expect("proxy client should connect")
+}
+
+#[tokio::test]
+async fn memory_conformance() {
+    conformance::run(|| async { Db::memory().await.expect("memory backend should open") }).await;
+}
+
+#[tokio::test]
+async fn file_conformance() {
+    let dir = TempDir::new("conformance-file");
+    conformance::run(|| {
+        let path = dir.fresh();
+        async move { Db::file(&path).await.expect("file backend should open") }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn redb_conformance() {
+    let dir = TempDir::new("conformance-redb");
+    conformance::run(|| {
+        let path = format!("{}.redb", dir.fresh());
+        async move { Db::redb(&path).await.expect("redb backend should open") }
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn git_conformance() {
+    let dir = TempDir::new("conformance-git");
+    conformance::run(|| {
+        let path = dir.fresh();
+        git_init(&path);
+        async move {
+            Db::git(&path, "origin".to_owned(), "main".to_owned())
+                .await
+                .expect("git backend should open")
+        }
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn proxy_conformance() {
+    conformance::run(proxy_client).await;
+}
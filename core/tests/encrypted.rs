@@ -0,0 +1,351 @@
+use glues_core::{
+    EntryEvent, Error, Glues,
+    backend::{
+        CoreBackend,
+        encrypted::{ENCRYPTION_META_KEY, Encrypted, is_encrypted},
+        layer::{FaultLayer, Faulty},
+        local::Db,
+    },
+};
+
+const ITERATIONS: u32 = 10;
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("glues-encrypted-{name}-{}", uuid::Uuid::now_v7()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[tokio::test]
+async fn inner_backend_only_sees_ciphertext() {
+    let db = Db::memory().await.unwrap();
+    let mut db = Encrypted::encrypt_with_iterations(db, "secret", ITERATIONS)
+        .await
+        .unwrap();
+
+    let root_id = db.root_id();
+    assert_eq!(
+        db.fetch_directory(root_id.clone()).await.unwrap().name,
+        "Notes"
+    );
+
+    let dir = db
+        .add_directory(root_id.clone(), "Diary".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(dir.name, "Diary");
+    let note = db
+        .add_note(dir.id.clone(), "Monday".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(note.name, "Monday");
+    assert_eq!(db.fetch_note_content(note.id.clone()).await.unwrap(), "");
+
+    db.update_note_content(note.id.clone(), "dear diary".to_owned())
+        .await
+        .unwrap();
+    db.rename_note(note.id.clone(), "Tuesday".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(
+        db.fetch_notes(dir.id.clone()).await.unwrap()[0].name,
+        "Tuesday"
+    );
+    assert_eq!(
+        db.fetch_note_content(note.id.clone()).await.unwrap(),
+        "dear diary"
+    );
+    assert!(matches!(
+        db.update_meta(ENCRYPTION_META_KEY.to_owned(), String::new())
+            .await,
+        Err(Error::Encryption(_))
+    ));
+
+    let mut inner = db.into_inner();
+    let stored_dir = &inner.fetch_directories(root_id.clone()).await.unwrap()[0];
+    let stored_note = &inner.fetch_notes(dir.id.clone()).await.unwrap()[0];
+    let stored_content = inner.fetch_note_content(note.id.clone()).await.unwrap();
+    for stored in [&stored_dir.name, &stored_note.name, &stored_content] {
+        assert!(stored.starts_with("enc1:"), "{stored}");
+    }
+    assert!(!stored_content.contains("diary"));
+    assert!(
+        inner
+            .fetch_directory(root_id)
+            .await
+            .unwrap()
+            .name
+            .starts_with("enc1:")
+    );
+}
+
+#[tokio::test]
+async fn ciphertexts_do_not_open_in_other_rows() {
+    let db = Db::memory().await.unwrap();
+    let mut db = Encrypted::encrypt_with_iterations(db, "secret", ITERATIONS)
+        .await
+        .unwrap();
+
+    let root_id = db.root_id();
+    let first = db
+        .add_note(root_id.clone(), "First".to_owned())
+        .await
+        .unwrap();
+    let second = db
+        .add_note(root_id.clone(), "Second".to_owned())
+        .await
+        .unwrap();
+    db.update_note_content(first.id.clone(), "first body".to_owned())
+        .await
+        .unwrap();
+
+    let mut inner = db.into_inner();
+    let sealed = inner.fetch_note_content(first.id.clone()).await.unwrap();
+    inner
+        .update_note_content(second.id.clone(), sealed)
+        .await
+        .unwrap();
+    let sealed_name = inner.fetch_notes(root_id.clone()).await.unwrap()[0]
+        .name
+        .clone();
+    inner
+        .update_note_content(first.id.clone(), sealed_name)
+        .await
+        .unwrap();
+
+    let mut db = Encrypted::unlock(inner, "secret").await.unwrap();
+    for note_id in [first.id, second.id] {
+        assert!(matches!(
+            db.fetch_note_content(note_id).await,
+            Err(Error::Encryption(_))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn plain_notebooks_are_only_encrypted_on_request() {
+    let path = temp_path("convert");
+
+    let mut db = Db::file(&path).await.unwrap();
+    let root_id = db.root_id();
+    let dir = db
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .unwrap();
+    let note = db
+        .add_note(dir.id.clone(), "Plan".to_owned())
+        .await
+        .unwrap();
+    db.update_note_content(note.id.clone(), "ship it".to_owned())
+        .await
+        .unwrap();
+    let attachment = db
+        .add_attachment(note.id.clone(), "plan.txt".to_owned(), b"steps".to_vec())
+        .await
+        .unwrap();
+    drop(db);
+
+    let db = Db::file(&path).await.unwrap();
+    assert!(matches!(
+        Encrypted::unlock(db, "secret").await,
+        Err(Error::NotEncrypted)
+    ));
+    let mut db = Db::file(&path).await.unwrap();
+    assert_eq!(
+        db.fetch_note_content(note.id.clone()).await.unwrap(),
+        "ship it"
+    );
+
+    Encrypted::encrypt_with_iterations(db, "secret", ITERATIONS)
+        .await
+        .unwrap();
+
+    let mut plain = Db::file(&path).await.unwrap();
+    let stored = plain.fetch_note_content(note.id.clone()).await.unwrap();
+    assert!(!stored.contains("ship it"));
+    assert!(
+        plain
+            .fetch_meta(ENCRYPTION_META_KEY.to_owned())
+            .await
+            .unwrap()
+            .unwrap()
+            .starts_with("pbkdf2-sha256$10$")
+    );
+
+    assert!(matches!(
+        Encrypted::unlock(plain, "wrong").await,
+        Err(Error::Encryption(message)) if message == "wrong passphrase"
+    ));
+    let db = Db::file(&path).await.unwrap();
+    assert!(matches!(
+        Encrypted::encrypt_with_iterations(db, "other", ITERATIONS).await,
+        Err(Error::Encryption(_))
+    ));
+
+    let db = Db::file(&path).await.unwrap();
+    let mut db = Encrypted::unlock(db, "secret").await.unwrap();
+    assert_eq!(db.fetch_directories(root_id).await.unwrap()[0].name, "Work");
+    assert_eq!(db.fetch_notes(dir.id).await.unwrap()[0].name, "Plan");
+    assert_eq!(
+        db.fetch_note_content(note.id.clone()).await.unwrap(),
+        "ship it"
+    );
+    let attachments = db.fetch_attachments(note.id).await.unwrap();
+    assert_eq!(attachments[0].id, attachment.id);
+    assert_eq!(attachments[0].name, "plan.txt");
+    assert_eq!(
+        db.fetch_attachment_data(attachment.id).await.unwrap(),
+        b"steps"
+    );
+
+    std::fs::remove_dir_all(&path).unwrap();
+}
+
+async fn add_notes<B: CoreBackend>(db: &mut B, names: &[&str]) -> Vec<String> {
+    let root_id = db.root_id();
+    let mut ids = Vec::new();
+    for name in names {
+        let note = db
+            .add_note(root_id.clone(), name.to_string())
+            .await
+            .unwrap();
+        db.update_note_content(note.id.clone(), format!("{name} body"))
+            .await
+            .unwrap();
+        ids.push(note.id);
+    }
+
+    ids
+}
+
+#[tokio::test]
+async fn failed_encryption_rolls_back() {
+    let path = temp_path("rollback");
+
+    let mut db = Db::redb(&path).await.unwrap();
+    let ids = add_notes(&mut db, &["One", "Two"]).await;
+
+    let faulty = Faulty::new(db, FaultLayer::every(2).only(["rename_note"]));
+    assert!(matches!(
+        Encrypted::encrypt_with_iterations(faulty, "secret", ITERATIONS).await,
+        Err(Error::BackendError(_))
+    ));
+
+    let mut db = Db::redb(&path).await.unwrap();
+    assert!(!is_encrypted(&mut db).await.unwrap());
+    for (id, name) in ids.into_iter().zip(["One", "Two"]) {
+        assert_eq!(
+            db.fetch_note_content(id).await.unwrap(),
+            format!("{name} body")
+        );
+    }
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn interrupted_encryption_is_finished_by_unlock() {
+    let path = temp_path("resume");
+
+    let mut db = Db::file(&path).await.unwrap();
+    let ids = add_notes(&mut db, &["One", "Two", "Three"]).await;
+
+    let faulty = Faulty::new(db, FaultLayer::every(2).only(["rename_note"]));
+    assert!(matches!(
+        Encrypted::encrypt_with_iterations(faulty, "secret", ITERATIONS).await,
+        Err(Error::BackendError(_))
+    ));
+
+    let mut db = Db::file(&path).await.unwrap();
+    assert!(is_encrypted(&mut db).await.unwrap());
+    assert!(matches!(
+        Encrypted::unlock(db, "wrong").await,
+        Err(Error::Encryption(_))
+    ));
+
+    let db = Db::file(&path).await.unwrap();
+    let mut db = Encrypted::unlock(db, "secret").await.unwrap();
+    let root_id = db.root_id();
+    let mut names = db
+        .fetch_notes(root_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|note| note.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, ["One", "Three", "Two"]);
+    for (id, name) in ids.into_iter().zip(["One", "Two", "Three"]) {
+        assert_eq!(
+            db.fetch_note_content(id).await.unwrap(),
+            format!("{name} body")
+        );
+    }
+
+    let mut plain = db.into_inner();
+    assert!(
+        plain
+            .fetch_meta(ENCRYPTION_META_KEY.to_owned())
+            .await
+            .unwrap()
+            .unwrap()
+            .starts_with("pbkdf2-sha256$")
+    );
+
+    std::fs::remove_dir_all(&path).unwrap();
+}
+
+#[tokio::test]
+async fn entry_requires_the_passphrase_of_encrypted_notebooks() {
+    let path = temp_path("entry");
+    let open = || EntryEvent::OpenFile(path.clone());
+    let open_encrypted = |passphrase: &str| EntryEvent::OpenEncrypted {
+        open: Box::new(open()),
+        passphrase: passphrase.to_owned(),
+    };
+
+    let mut glues = Glues::new();
+    assert!(matches!(
+        glues.dispatch(open_encrypted("secret").into()).await,
+        Err(Error::NotEncrypted)
+    ));
+    glues
+        .dispatch(
+            EntryEvent::EncryptNotebook {
+                open: Box::new(open()),
+                passphrase: "secret".to_owned(),
+            }
+            .into(),
+        )
+        .await
+        .unwrap();
+    let db = glues.db.as_mut().unwrap();
+    db.add_note(db.root_id(), "Secret".to_owned())
+        .await
+        .unwrap();
+
+    let mut glues = Glues::new();
+    assert!(matches!(
+        glues.dispatch(open().into()).await,
+        Err(Error::Encryption(_))
+    ));
+    assert!(matches!(
+        glues.dispatch(open_encrypted("wrong").into()).await,
+        Err(Error::Encryption(_))
+    ));
+    assert!(matches!(
+        glues.dispatch(open_encrypted("").into()).await,
+        Err(Error::Encryption(_))
+    ));
+
+    glues
+        .dispatch(open_encrypted("secret").into())
+        .await
+        .unwrap();
+    let db = glues.db.as_mut().unwrap();
+    let notes = db.fetch_notes(db.root_id()).await.unwrap();
+    assert_eq!(notes[0].name, "Secret");
+
+    std::fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use {
+    common::TempDir,
+    glues_core::{
+        Error,
+        backend::{BackendBox, CoreBackend, Resolution, SyncJob, SyncMode, local::Db},
+    },
+    std::process::Command,
+};
+
+fn git(path: &str, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .current_dir(path)
+        .args(args)
+        .output()
+        .expect("git should run");
+    assert!(output.status.success(), "git {args:?} failed");
+
+    String::from_utf8_lossy(&output.stdout).trim().to_owned()
+}
+
+/// A bare `origin` with two clones on branch `main`, both holding the same notebook.
+async fn setup(dir: &TempDir) -> (Db, Db) {
+    let origin = dir.join("origin");
+    std::fs::create_dir_all(&origin).unwrap();
+    git(
+        &origin,
+        &["init", "--quiet", "--bare", "--initial-branch=main"],
+    );
+
+    let clone = |name: &str| {
+        let path = dir.join(name);
+        git(&dir.join(""), &["clone", "--quiet", &origin, &path]);
+        git(&path, &["checkout", "--quiet", "-B", "main"]);
+        git(&path, &["config", "user.name", "glues"]);
+        git(&path, &["config", "user.email", "glues@example.com"]);
+        path
+    };
+
+    let (a, b) = (clone("a"), clone("b"));
+    let mut first = Db::git(&a, "origin".to_owned(), "main".to_owned())
+        .await
+        .unwrap();
+    run(&mut first).unwrap();
+
+    git(&b, &["pull", "--quiet", "origin", "main"]);
+    restore_table_dirs(&b);
+    let second = Db::git(&b, "origin".to_owned(), "main".to_owned())
+        .await
+        .unwrap();
+
+    (first, second)
+}
+
+/// Git does not track the directories of tables without rows.
+fn restore_table_dirs(path: &str) {
+    for entry in std::fs::read_dir(path).unwrap() {
+        let schema = entry.unwrap().path();
+        if schema.extension().is_some_and(|ext| ext == "sql") {
+            std::fs::create_dir_all(schema.with_extension("")).unwrap();
+        }
+    }
+}
+
+fn run(db: &mut impl CoreBackend) -> Result<(), Error> {
+    db.sync_job().expect("git notebook should sync").run()
+}
+
+fn path(db: &mut Db) -> String {
+    match db.sync_job() {
+        Some(SyncJob::Git(sync)) => sync.path.to_string_lossy().into_owned(),
+        _ => unreachable!("git notebook should sync through git"),
+    }
+}
+
+#[tokio::test]
+async fn sync_commit_describes_changes() {
+    let dir = TempDir::new("git-sync-message");
+    let (a, _) = setup(&dir).await;
+    // the app goes through `CoreBackend`, which records the changes
+    let mut a: BackendBox = Box::new(a);
+    let root_id = a.root_id();
+
+    let note = a
+        .add_note(root_id.clone(), "Todo".to_owned())
+        .await
+        .unwrap();
+    a.update_note_content(note.id.clone(), "- [ ] sync".to_owned())
+        .await
+        .unwrap();
+    a.update_note_content(note.id, "- [x] sync".to_owned())
+        .await
+        .unwrap();
+    a.add_directory(root_id, "Work".to_owned()).await.unwrap();
+    run(&mut a).unwrap();
+
+    let message = git(&dir.join("origin"), &["log", "-1", "--format=%B", "main"]);
+    assert_eq!(
+        message,
+        "Update notes (3 changes)\n\n\
+         - Add note 'Todo'\n\
+         - Edit note 'Todo'\n\
+         - Add directory 'Work'"
+    );
+}
+
+#[tokio::test]
+async fn pull_only_mode_does_not_push() {
+    let dir = TempDir::new("git-sync-pull-only");
+    let (mut a, _) = setup(&dir).await;
+    let head = git(&dir.join("origin"), &["rev-parse", "main"]);
+
+    a.sync_options.mode = SyncMode::PullOnly;
+    let root_id = a.root_id();
+    a.add_note(root_id, "Local".to_owned()).await.unwrap();
+    run(&mut a).unwrap();
+
+    assert_eq!(git(&dir.join("origin"), &["rev-parse", "main"]), head);
+}
+
+#[tokio::test]
+async fn unreachable_remote_fails_the_sync() {
+    let dir = TempDir::new("git-sync-unreachable");
+    let (mut a, _) = setup(&dir).await;
+    let head = git(&dir.join("origin"), &["rev-parse", "main"]);
+
+    let a_path = path(&mut a);
+    git(
+        &a_path,
+        &["remote", "set-url", "origin", &dir.join("missing")],
+    );
+    let root_id = a.root_id();
+    a.add_note(root_id, "Local".to_owned()).await.unwrap();
+
+    match run(&mut a) {
+        Err(Error::Sync(message)) => assert!(message.starts_with("git ls-remote failed")),
+        result => panic!("sync should fail without its remote: {result:?}"),
+    }
+    assert_eq!(git(&dir.join("origin"), &["rev-parse", "main"]), head);
+}
+
+#[tokio::test]
+async fn conflict_lists_notes_and_resolves() {
+    let dir = TempDir::new("git-sync-conflict");
+    let (mut a, mut b) = setup(&dir).await;
+    let root_id = a.root_id();
+
+    let note = a.add_note(root_id, "Shared".to_owned()).await.unwrap();
+    run(&mut a).unwrap();
+    git(&path(&mut b), &["pull", "--quiet", "origin", "main"]);
+
+    a.update_note_content(note.id.clone(), "from a".to_owned())
+        .await
+        .unwrap();
+    run(&mut a).unwrap();
+    b.update_note_content(note.id.clone(), "from b".to_owned())
+        .await
+        .unwrap();
+
+    let Some(SyncJob::Git(sync)) = b.sync_job() else {
+        unreachable!("git notebook should sync through git");
+    };
+    let conflict = match sync.run() {
+        Err(Error::SyncConflict(conflict)) => conflict,
+        result => panic!("expected a sync conflict, got {result:?}"),
+    };
+    assert_eq!(conflict.notes, vec![note.id.clone()]);
+    assert!(conflict.directories.is_empty());
+
+    sync.resolve(Resolution::TakeRemote).unwrap();
+    assert_eq!(b.fetch_note_content(note.id).await.unwrap(), "from a");
+    assert_eq!(
+        git(&dir.join("origin"), &["rev-parse", "main"]),
+        git(&sync.path.to_string_lossy(), &["rev-parse", "HEAD"])
+    );
+}
+
+#[tokio::test]
+async fn conflict_with_a_deleted_note_resolves() {
+    let dir = TempDir::new("git-sync-deleted");
+    let (mut a, mut b) = setup(&dir).await;
+    let root_id = a.root_id();
+
+    let note = a
+        .add_note(root_id.clone(), "Shared".to_owned())
+        .await
+        .unwrap();
+    run(&mut a).unwrap();
+    git(&path(&mut b), &["pull", "--quiet", "origin", "main"]);
+
+    a.remove_note(note.id.clone()).await.unwrap();
+    run(&mut a).unwrap();
+    b.update_note_content(note.id.clone(), "from b".to_owned())
+        .await
+        .unwrap();
+
+    let Some(SyncJob::Git(sync)) = b.sync_job() else {
+        unreachable!("git notebook should sync through git");
+    };
+    let conflict = match sync.run() {
+        Err(Error::SyncConflict(conflict)) => conflict,
+        result => panic!("expected a sync conflict, got {result:?}"),
+    };
+    assert_eq!(conflict.notes, vec![note.id.clone()]);
+
+    sync.resolve(Resolution::TakeRemote).unwrap();
+    assert!(b.fetch_notes(root_id).await.unwrap().is_empty());
+    assert_eq!(
+        git(&dir.join("origin"), &["rev-parse", "main"]),
+        git(&sync.path.to_string_lossy(), &["rev-parse", "HEAD"])
+    );
+}
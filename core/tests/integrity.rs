@@ -0,0 +1,227 @@
+use {
+    glues_core::{
+        CoreBackend, Error, IntegrityError,
+        backend::local::{Db, Execute, FsckIssue},
+    },
+    gluesql::core::ast_builder::{bytea, null, num, table, text, uuid},
+};
+
+const MISSING: &str = "00000000-0000-7000-8000-000000000000";
+
+fn integrity_error<T>(result: Result<T, Error>) -> IntegrityError {
+    match result {
+        Err(Error::Integrity(err)) => err,
+        Err(err) => panic!("expected an integrity error, got {err}"),
+        Ok(_) => panic!("expected an integrity error"),
+    }
+}
+
+#[tokio::test]
+async fn writes_keep_the_tree_intact() {
+    let mut db = Db::memory().await.expect("memory backend should open");
+    let root_id = db.root_id();
+    let work = db
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .expect("directory should be added");
+    let project = db
+        .add_directory(work.id.clone(), "Project".to_owned())
+        .await
+        .expect("nested directory should be added");
+    let note = db
+        .add_note(work.id.clone(), "Plan".to_owned())
+        .await
+        .expect("note should be added");
+
+    assert_eq!(
+        integrity_error(db.move_directory(work.id.clone(), work.id.clone()).await),
+        IntegrityError::Cycle {
+            directory_id: work.id.clone(),
+            parent_id: work.id.clone(),
+        }
+    );
+    assert_eq!(
+        integrity_error(db.move_directory(work.id.clone(), project.id.clone()).await),
+        IntegrityError::Cycle {
+            directory_id: work.id.clone(),
+            parent_id: project.id.clone(),
+        }
+    );
+    assert_eq!(
+        integrity_error(db.move_directory(work.id.clone(), MISSING.to_owned()).await),
+        IntegrityError::MissingDirectory(MISSING.to_owned())
+    );
+    assert_eq!(
+        integrity_error(db.move_directory(root_id.clone(), work.id.clone()).await),
+        IntegrityError::RootDirectory
+    );
+    assert_eq!(
+        integrity_error(db.remove_directory(root_id.clone()).await),
+        IntegrityError::RootDirectory
+    );
+    assert_eq!(
+        integrity_error(
+            db.add_directory(MISSING.to_owned(), "Lost".to_owned())
+                .await
+        ),
+        IntegrityError::MissingDirectory(MISSING.to_owned())
+    );
+    assert_eq!(
+        integrity_error(db.add_note(MISSING.to_owned(), "Lost".to_owned()).await),
+        IntegrityError::MissingDirectory(MISSING.to_owned())
+    );
+    assert_eq!(
+        integrity_error(db.move_note(note.id.clone(), MISSING.to_owned()).await),
+        IntegrityError::MissingDirectory(MISSING.to_owned())
+    );
+    assert_eq!(
+        integrity_error(db.move_note(MISSING.to_owned(), root_id.clone()).await),
+        IntegrityError::MissingNote(MISSING.to_owned())
+    );
+    assert_eq!(
+        integrity_error(
+            db.add_attachment(MISSING.to_owned(), "a.txt".to_owned(), b"a".to_vec())
+                .await
+        ),
+        IntegrityError::MissingNote(MISSING.to_owned())
+    );
+
+    db.move_directory(project.id.clone(), root_id.clone())
+        .await
+        .expect("moving out of a sibling should succeed");
+    db.move_directory(work.id.clone(), project.id.clone())
+        .await
+        .expect("moving into a former child should succeed once it moved out");
+    let moved = db
+        .fetch_directory(work.id)
+        .await
+        .expect("moved directory should be fetched");
+    assert_eq!(moved.parent_id, project.id);
+}
+
+#[tokio::test]
+async fn fsck_finds_and_repairs_broken_rows() {
+    let mut db = Db::memory().await.expect("memory backend should open");
+    let root_id = db.root_id();
+
+    let stray_root = "00000000-0000-7000-8000-000000000001";
+    let orphan = "00000000-0000-7000-8000-000000000002";
+    let below_orphan = "00000000-0000-7000-8000-000000000003";
+    let cycle_a = "00000000-0000-7000-8000-000000000004";
+    let cycle_b = "00000000-0000-7000-8000-000000000005";
+    let orphan_note = "00000000-0000-7000-8000-000000000006";
+    let orphan_attachment = "00000000-0000-7000-8000-000000000007";
+
+    table("Directory")
+        .insert()
+        .columns(vec!["id", "parent_id", "name", "created_at"])
+        .values(vec![
+            vec![
+                uuid(stray_root),
+                null(),
+                text("Stray root"),
+                text("2999-01-01 00:00:00"),
+            ],
+            vec![
+                uuid(orphan),
+                uuid(MISSING),
+                text("Orphan"),
+                text("2024-01-01 00:00:00"),
+            ],
+            vec![
+                uuid(below_orphan),
+                uuid(orphan),
+                text("Below orphan"),
+                text("2024-01-01 00:00:00"),
+            ],
+            vec![
+                uuid(cycle_a),
+                uuid(cycle_b),
+                text("Cycle A"),
+                text("2024-01-01 00:00:00"),
+            ],
+            vec![
+                uuid(cycle_b),
+                uuid(cycle_a),
+                text("Cycle B"),
+                text("2024-01-01 00:00:00"),
+            ],
+        ])
+        .execute(&mut db.storage)
+        .await
+        .expect("broken directories should be inserted");
+    table("Note")
+        .insert()
+        .columns(vec!["id", "directory_id", "name"])
+        .values(vec![vec![
+            uuid(orphan_note),
+            uuid(MISSING),
+            text("Orphan note"),
+        ]])
+        .execute(&mut db.storage)
+        .await
+        .expect("broken note should be inserted");
+    table("Attachment")
+        .insert()
+        .columns(vec!["id", "note_id", "name", "size", "data"])
+        .values(vec![vec![
+            uuid(orphan_attachment),
+            uuid(MISSING),
+            text("orphan.txt"),
+            num(1),
+            bytea(b"a"),
+        ]])
+        .execute(&mut db.storage)
+        .await
+        .expect("broken attachment should be inserted");
+
+    let report = db.fsck(false).await.expect("fsck should run");
+    assert!(!report.repaired);
+    assert_eq!(
+        report.issues,
+        vec![
+            FsckIssue::DuplicateRoot {
+                directory_id: stray_root.to_owned(),
+            },
+            FsckIssue::OrphanDirectory {
+                directory_id: orphan.to_owned(),
+                parent_id: MISSING.to_owned(),
+            },
+            FsckIssue::Cycle {
+                directory_ids: vec![cycle_a.to_owned(), cycle_b.to_owned()],
+            },
+            FsckIssue::OrphanNote {
+                note_id: orphan_note.to_owned(),
+                directory_id: MISSING.to_owned(),
+            },
+            FsckIssue::OrphanAttachment {
+                attachment_id: orphan_attachment.to_owned(),
+                note_id: MISSING.to_owned(),
+            },
+        ]
+    );
+
+    let report = db.fsck(true).await.expect("fsck should repair");
+    assert!(report.repaired);
+    assert_eq!(report.issues.len(), 5);
+
+    let report = db.fsck(false).await.expect("fsck should run again");
+    assert_eq!(report.issues, Vec::new());
+
+    let mut directories: Vec<String> = db
+        .fetch_directories(root_id.clone())
+        .await
+        .expect("root directories should be fetched")
+        .into_iter()
+        .map(|directory| directory.name)
+        .collect();
+    directories.sort();
+    assert_eq!(directories, vec!["Cycle A", "Orphan", "Stray root"]);
+
+    let notes = db
+        .fetch_notes(root_id)
+        .await
+        .expect("root notes should be fetched");
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].name, "Orphan note");
+}
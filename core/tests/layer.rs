@@ -0,0 +1,286 @@
+use {
+    glues_core::{
+        CoreBackend, Error,
+        backend::{
+            BackendBox,
+            layer::{
+                CacheLayer, Cached, FaultLayer, Faulty, LayerStack, ReadOnly, TIMING_LOG_CATEGORY,
+                Timed,
+            },
+            local::{Db, Execute},
+        },
+        conformance,
+    },
+    gluesql::{
+        core::ast_builder::{col, table, text},
+        prelude::{Payload, Value},
+    },
+    std::time::Duration,
+};
+
+fn is_injected<T>(result: Result<T, Error>) -> bool {
+    matches!(result, Err(Error::BackendError(message)) if message.starts_with("injected fault"))
+}
+
+#[tokio::test]
+async fn cache_answers_repeated_reads_until_a_write() {
+    let db = Db::memory().await.expect("memory backend should open");
+    // The inner backend fails its second fetch, so only cached reads succeed.
+    let faulty = Faulty::new(db, FaultLayer::every(2).only(["fetch_directory"]));
+    let mut cached = Cached::new(faulty);
+    let root_id = cached.root_id();
+
+    let root = cached
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("first fetch should reach the backend");
+    let again = cached
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("second fetch should be cached");
+    assert_eq!(root.id, again.id);
+
+    cached
+        .add_note(root_id.clone(), "Invalidate".to_owned())
+        .await
+        .expect("note should be added");
+    assert!(is_injected(cached.fetch_directory(root_id).await));
+}
+
+#[tokio::test]
+async fn cache_is_cleared_once_a_sync_finished() {
+    let db = Db::memory().await.expect("memory backend should open");
+    let faulty = Faulty::new(db, FaultLayer::every(2).only(["fetch_directory"]));
+    let mut cached = Cached::new(faulty);
+    let root_id = cached.root_id();
+
+    cached
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("first fetch should reach the backend");
+    // reads made while the job runs still come from the cache
+    let _ = cached.sync_job();
+    cached
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("fetch during the sync should be cached");
+
+    cached.sync_finished();
+    assert!(is_injected(cached.fetch_directory(root_id).await));
+}
+
+#[tokio::test]
+async fn cache_answers_meta_reads_until_meta_changes() {
+    let db = Db::memory().await.expect("memory backend should open");
+    let faulty = Faulty::new(db, FaultLayer::every(2).only(["fetch_all_meta"]));
+    let mut cached = Cached::new(faulty);
+
+    let entries = cached
+        .fetch_all_meta()
+        .await
+        .expect("first fetch should reach the backend");
+    assert_eq!(
+        cached
+            .fetch_all_meta()
+            .await
+            .expect("second fetch should be cached"),
+        entries
+    );
+
+    cached
+        .update_meta("key".to_owned(), "value".to_owned())
+        .await
+        .expect("meta should be updated");
+    assert!(is_injected(cached.fetch_all_meta().await));
+}
+
+#[tokio::test]
+async fn cache_keeps_edited_content_fresh() {
+    let mut cached = Cached::new(Db::memory().await.expect("memory backend should open"));
+    let root_id = cached.root_id();
+    let note = cached
+        .add_note(root_id.clone(), "Draft".to_owned())
+        .await
+        .expect("note should be added");
+    let before = cached
+        .fetch_notes(root_id.clone())
+        .await
+        .expect("notes should be fetched");
+
+    cached
+        .update_note_content(note.id.clone(), "edited".to_owned())
+        .await
+        .expect("content should be updated");
+    assert_eq!(
+        cached
+            .fetch_note_content(note.id.clone())
+            .await
+            .expect("content should be fetched"),
+        "edited"
+    );
+
+    let after = cached
+        .fetch_notes(root_id)
+        .await
+        .expect("notes should be fetched");
+    assert!(after[0].updated_at >= before[0].updated_at);
+}
+
+#[tokio::test]
+async fn cached_backends_pass_conformance() {
+    conformance::run(|| async {
+        Cached::new(Db::memory().await.expect("memory backend should open"))
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn read_only_refuses_writes() {
+    let mut db = Db::memory().await.expect("memory backend should open");
+    let root_id = db.root_id();
+    let note = db
+        .add_note(root_id.clone(), "Kept".to_owned())
+        .await
+        .expect("note should be added");
+    let mut read_only = ReadOnly::new(db);
+
+    match read_only
+        .add_directory(root_id.clone(), "New".to_owned())
+        .await
+    {
+        Err(Error::ReadOnly(operation)) => assert_eq!(operation, "add_directory"),
+        other => panic!("expected a read-only error, got {other:?}"),
+    }
+    assert!(matches!(
+        read_only
+            .update_note_content(note.id.clone(), "changed".to_owned())
+            .await,
+        Err(Error::ReadOnly(_))
+    ));
+    assert!(matches!(
+        read_only.remove_note(note.id.clone()).await,
+        Err(Error::ReadOnly(_))
+    ));
+    assert!(matches!(
+        read_only
+            .update_meta("key".to_owned(), "value".to_owned())
+            .await,
+        Err(Error::ReadOnly(_))
+    ));
+
+    let notes = read_only
+        .fetch_notes(root_id)
+        .await
+        .expect("reads should pass through");
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, note.id);
+}
+
+#[tokio::test]
+async fn timing_logs_through_the_backend() {
+    let db = Db::memory().await.expect("memory backend should open");
+    let mut timed = Timed::new(db, Duration::ZERO);
+    let root_id = timed.root_id();
+    timed
+        .fetch_directory(root_id.clone())
+        .await
+        .expect("root should be fetched");
+    timed
+        .fetch_note_content("00000000-0000-7000-8000-000000000000".to_owned())
+        .await
+        .expect_err("missing note should fail");
+
+    let mut db = timed.into_inner();
+    let payload = table("Log")
+        .select()
+        .filter(col("category").eq(text(TIMING_LOG_CATEGORY)))
+        .project("message")
+        .execute(&mut db.storage)
+        .await
+        .expect("log should be readable");
+    let Payload::Select { rows, .. } = payload else {
+        panic!("expected a result set");
+    };
+    let messages: Vec<String> = rows
+        .into_iter()
+        .map(|row| match &row[0] {
+            Value::Str(message) => message.clone(),
+            value => panic!("unexpected message {value:?}"),
+        })
+        .collect();
+
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].starts_with("fetch_directory took "));
+    assert!(!messages[0].ends_with("and failed"));
+    assert!(messages[1].starts_with("fetch_note_content took "));
+    assert!(messages[1].ends_with("and failed"));
+}
+
+#[tokio::test]
+async fn fault_fails_every_nth_call() {
+    let db = Db::memory().await.expect("memory backend should open");
+    let mut faulty = Faulty::new(db, FaultLayer::every(3));
+    let root_id = faulty.root_id();
+
+    for round in 1..=6 {
+        let result = faulty.fetch_directories(root_id.clone()).await;
+        assert_eq!(is_injected(result), round % 3 == 0, "call {round}");
+    }
+
+    faulty
+        .begin()
+        .await
+        .expect("transactions are never injected");
+    faulty
+        .commit()
+        .await
+        .expect("transactions are never injected");
+}
+
+#[tokio::test]
+async fn layer_stack_parses_and_applies_outermost_first() {
+    assert!(
+        "".parse::<LayerStack>()
+            .expect("empty spec should parse")
+            .is_empty()
+    );
+    assert_eq!(
+        " cache , timing=5,fault=2 "
+            .parse::<LayerStack>()
+            .expect("spec should parse")
+            .len(),
+        3
+    );
+    for spec in ["cache,zip", "timing=soon", "fault", "fault=0", "cache=1"] {
+        assert!(
+            matches!(spec.parse::<LayerStack>(), Err(Error::Layer(_))),
+            "{spec} should be rejected"
+        );
+    }
+
+    // Read-only outermost refuses before the fault layer sees the call.
+    let backend: BackendBox = Box::new(Db::memory().await.expect("memory backend should open"));
+    let mut backend = "read-only,fault=1"
+        .parse::<LayerStack>()
+        .expect("spec should parse")
+        .apply(backend);
+    let root_id = backend.root_id();
+    assert!(matches!(
+        backend.add_note(root_id.clone(), "Note".to_owned()).await,
+        Err(Error::ReadOnly(_))
+    ));
+    assert!(is_injected(backend.fetch_notes(root_id.clone()).await));
+
+    let backend: BackendBox = Box::new(Db::memory().await.expect("memory backend should open"));
+    let mut backend = LayerStack::new()
+        .layer(CacheLayer)
+        .layer(FaultLayer::every(2).only(["fetch_notes"]))
+        .apply(backend);
+    let root_id = backend.root_id();
+    for _ in 0..3 {
+        backend
+            .fetch_notes(root_id.clone())
+            .await
+            .expect("the cache should answer before the fault layer");
+    }
+}
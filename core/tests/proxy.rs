@@ -1,13 +1,20 @@
+mod common;
+
+use common::{serve, serve_with_token};
 use glues_core::{
     Error,
     backend::{
-        CoreBackend,
+        ConnectionStatus, CoreBackend,
         local::Db,
-        proxy::{ProxyClient, ProxyServer, request::ProxyRequest},
+        proxy::{
+            ATTACHMENT_CHUNK_SIZE, ProxyClient, ProxyServer, TlsOptions, TransferLimits,
+            request::ProxyRequest,
+            response::{ProxyResponse, ResultPayload},
+        },
     },
+    backup,
 };
-use std::{io::ErrorKind, net::TcpListener, sync::Arc};
-use tiny_http::{Response, Server};
+use std::{net::TcpListener, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
 #[tokio::test(flavor = "current_thread")]
@@ -18,44 +25,13 @@ async fn proxy_backend_operations() {
     let server = ProxyServer::new(Box::new(db));
     let server = Arc::new(Mutex::new(server));
 
-    let listener = match TcpListener::bind("127.0.0.1:0") {
-        Ok(listener) => listener,
-        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
-            eprintln!("skipping proxy_backend_operations: {err}");
-            return;
-        }
-        Err(err) => panic!("proxy server should bind to ephemeral port: {err}"),
+    let Some(listener) = common::bind("proxy_backend_operations") else {
+        return;
     };
     let addr = listener
         .local_addr()
         .expect("proxy server should expose a local address");
-    let http = Arc::new(
-        Server::from_listener(listener, None).expect("proxy server should accept HTTP connections"),
-    );
-    let handle = tokio::runtime::Handle::current();
-    let srv = server.clone();
-    let http_clone = http.clone();
-    let server_thread = std::thread::spawn(move || {
-        for mut req in http_clone.incoming_requests() {
-            let mut body = String::new();
-            req.as_reader()
-                .read_to_string(&mut body)
-                .expect("proxy request body should read into string");
-            let proxy_req: ProxyRequest =
-                serde_json::from_str(&body).expect("proxy request JSON should deserialize");
-            let response = handle.block_on(async {
-                let mut s = srv.lock().await;
-                s.handle(proxy_req).await
-            });
-            let body =
-                serde_json::to_string(&response).expect("proxy response should serialize to JSON");
-            let resp = Response::from_string(body).with_header(
-                tiny_http::Header::from_bytes("Content-Type", "application/json")
-                    .expect("content-type header should be valid"),
-            );
-            let _ = req.respond(resp);
-        }
-    });
+    let (http, server_thread) = serve(listener, server.clone());
 
     let mut client = ProxyClient::connect(format!("http://{addr}"), None)
         .await
@@ -124,6 +100,35 @@ async fn proxy_backend_operations() {
         .expect("proxy client should list notes in root directory");
     assert_eq!(notes_root.len(), 1);
 
+    let data = (0..ATTACHMENT_CHUNK_SIZE * 2 + 10)
+        .map(|i| (i % 251) as u8)
+        .collect::<Vec<_>>();
+    let attachment = client
+        .add_attachment(note.id.clone(), "large.bin".to_owned(), data.clone())
+        .await
+        .expect("proxy client should upload attachment in chunks");
+    assert_eq!(attachment.size, data.len() as u64);
+    let attachments = client
+        .fetch_attachments(note.id.clone())
+        .await
+        .expect("proxy client should list attachments");
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].name, "large.bin");
+    let downloaded = client
+        .fetch_attachment_data(attachment.id.clone())
+        .await
+        .expect("proxy client should download attachment in chunks");
+    assert_eq!(downloaded, data);
+    client
+        .remove_attachment(attachment.id.clone())
+        .await
+        .expect("proxy client should remove attachment");
+    let attachments = client
+        .fetch_attachments(note.id.clone())
+        .await
+        .expect("proxy client should list attachments after removal");
+    assert!(attachments.is_empty());
+
     client
         .remove_note(note.id.clone())
         .await
@@ -149,6 +154,51 @@ async fn proxy_backend_operations() {
         .await
         .expect("proxy client should append log entry");
 
+    client
+        .update_meta("global_marks".to_owned(), "A".to_owned())
+        .await
+        .expect("proxy client should update meta value");
+    let value = client
+        .fetch_meta("global_marks".to_owned())
+        .await
+        .expect("proxy client should fetch meta value");
+    assert_eq!(value.as_deref(), Some("A"));
+
+    let mut source = Db::memory()
+        .await
+        .expect("in-memory source database should initialize");
+    let source_note = source
+        .add_note(source.root_id(), "Restored".to_owned())
+        .await
+        .expect("source database should add note");
+    source
+        .add_attachment(source_note.id.clone(), "a.bin".to_owned(), vec![7; 10])
+        .await
+        .expect("source database should add attachment");
+    let archive = backup::dump(&mut source)
+        .await
+        .expect("source database should dump");
+    backup::restore(&mut client, archive)
+        .await
+        .expect("proxy client should restore archive");
+    assert_eq!(client.root_id(), source.root_id());
+    let restored = client
+        .fetch_notes(client.root_id())
+        .await
+        .expect("proxy client should list restored notes");
+    assert_eq!(restored[0].id, source_note.id);
+    let attachments = client
+        .fetch_attachments(source_note.id.clone())
+        .await
+        .expect("proxy client should list restored attachments");
+    assert_eq!(
+        client
+            .fetch_attachment_data(attachments[0].id.clone())
+            .await
+            .expect("proxy client should fetch restored attachment"),
+        vec![7; 10]
+    );
+
     http.unblock();
     server_thread
         .join()
@@ -163,57 +213,14 @@ async fn proxy_backend_requires_token() {
     let server = ProxyServer::new(Box::new(db));
     let server = Arc::new(Mutex::new(server));
 
-    let listener = match TcpListener::bind("127.0.0.1:0") {
-        Ok(listener) => listener,
-        Err(err) if err.kind() == ErrorKind::PermissionDenied => {
-            eprintln!("skipping proxy_backend_requires_token: {err}");
-            return;
-        }
-        Err(err) => panic!("proxy server should bind to ephemeral port: {err}"),
+    let Some(listener) = common::bind("proxy_backend_requires_token") else {
+        return;
     };
     let addr = listener
         .local_addr()
         .expect("proxy server should expose a local address");
-    let http = Arc::new(
-        Server::from_listener(listener, None).expect("proxy server should accept HTTP connections"),
-    );
-    let handle = tokio::runtime::Handle::current();
-    let srv = server.clone();
-    let http_clone = http.clone();
     let token = "secret-token";
-    let expected_header = format!("Bearer {token}");
-    let server_thread = std::thread::spawn(move || {
-        for mut req in http_clone.incoming_requests() {
-            let header_ok = req
-                .headers()
-                .iter()
-                .find(|h| h.field.equiv("Authorization"))
-                .map(|h| h.value.as_str().trim() == expected_header)
-                .unwrap_or(false);
-            if !header_ok {
-                let _ = req.respond(Response::empty(401));
-                continue;
-            }
-
-            let mut body = String::new();
-            req.as_reader()
-                .read_to_string(&mut body)
-                .expect("proxy request body should read into string");
-            let proxy_req: ProxyRequest =
-                serde_json::from_str(&body).expect("proxy request JSON should deserialize");
-            let response = handle.block_on(async {
-                let mut s = srv.lock().await;
-                s.handle(proxy_req).await
-            });
-            let body =
-                serde_json::to_string(&response).expect("proxy response should serialize to JSON");
-            let resp = Response::from_string(body).with_header(
-                tiny_http::Header::from_bytes("Content-Type", "application/json")
-                    .expect("content-type header should be valid"),
-            );
-            let _ = req.respond(resp);
-        }
-    });
+    let (http, server_thread) = serve_with_token(listener, server.clone(), Some(token));
 
     let err = match ProxyClient::connect(format!("http://{addr}"), None).await {
         Ok(_) => panic!("proxy client should reject missing authentication token"),
@@ -245,3 +252,540 @@ async fn proxy_backend_requires_token() {
         .join()
         .expect("proxy server thread should finish cleanly");
 }
+
+/// The listener of a dropped server closes once its accept thread wakes up.
+fn wait_until_refused(addr: std::net::SocketAddr) {
+    for _ in 0..100 {
+        if std::net::TcpStream::connect(addr).is_err() {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+
+    panic!("proxy server should stop listening");
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_queues_writes_while_offline() {
+    let db = Db::memory()
+        .await
+        .expect("in-memory proxy database should initialize");
+    let server = Arc::new(Mutex::new(ProxyServer::new(Box::new(db))));
+
+    let Some(listener) = common::bind("proxy_queues_writes_while_offline") else {
+        return;
+    };
+    let addr = listener
+        .local_addr()
+        .expect("proxy server should expose a local address");
+    let (http, server_thread) = serve(listener, server.clone());
+
+    let dir = std::env::temp_dir().join(format!("glues-proxy-queue-{}", uuid::Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    let queue_path = dir.join("queue.redb").to_string_lossy().into_owned();
+    let url = format!("http://{addr}");
+
+    let mut client =
+        ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+            .await
+            .expect("proxy client should connect with a queue");
+    let root_id = client.root_id();
+    let work = client
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .unwrap();
+    client.fetch_directories(root_id.clone()).await.unwrap();
+    client.fetch_notes(root_id.clone()).await.unwrap();
+    assert_eq!(
+        client.connection_status(),
+        Some(ConnectionStatus {
+            online: true,
+            pending: 0,
+            rejected: 0,
+        })
+    );
+
+    http.unblock();
+    server_thread
+        .join()
+        .expect("proxy server thread should finish cleanly");
+    drop(http);
+    wait_until_refused(addr);
+
+    // reopening drops the connections kept alive to the stopped server
+    drop(client);
+    let mut client =
+        ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+            .await
+            .expect("proxy client should open offline from its cache");
+    assert_eq!(
+        client.connection_status(),
+        Some(ConnectionStatus {
+            online: false,
+            pending: 0,
+            rejected: 0,
+        })
+    );
+
+    let note = client
+        .add_note(root_id.clone(), "Offline".to_owned())
+        .await
+        .expect("offline write should be queued");
+    client
+        .update_note_content(note.id.clone(), "queued".to_owned())
+        .await
+        .unwrap();
+    client
+        .rename_directory(work.id.clone(), "Home".to_owned())
+        .await
+        .unwrap();
+    assert_eq!(
+        client.connection_status(),
+        Some(ConnectionStatus {
+            online: false,
+            pending: 3,
+            rejected: 0,
+        })
+    );
+
+    // the queue outlives the client, and cached reads see the queued writes
+    drop(client);
+    let mut client =
+        ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+            .await
+            .expect("proxy client should reopen offline");
+    assert_eq!(
+        client.connection_status().map(|status| status.pending),
+        Some(3)
+    );
+    let notes = client.fetch_notes(root_id.clone()).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].id, note.id);
+    let dirs = client.fetch_directories(root_id.clone()).await.unwrap();
+    assert_eq!(dirs[0].name, "Home");
+    assert_eq!(
+        client.fetch_note_content(note.id.clone()).await.unwrap(),
+        "queued"
+    );
+    assert!(
+        client
+            .fetch_attachments(note.id.clone())
+            .await
+            .unwrap()
+            .is_empty(),
+        "notes created offline start without attachments"
+    );
+    assert!(
+        client
+            .fetch_directories(work.id.clone())
+            .await
+            .unwrap()
+            .is_empty(),
+        "directories created online start empty"
+    );
+    assert!(
+        client
+            .fetch_note_content(uuid::Uuid::now_v7().to_string())
+            .await
+            .is_err(),
+        "reads never made before are not available offline"
+    );
+
+    let listener = TcpListener::bind(addr).expect("proxy server should rebind its address");
+    let (http, server_thread) = serve(listener, server.clone());
+
+    let notes = client.fetch_notes(root_id.clone()).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].name, "Offline");
+    assert_eq!(
+        client.connection_status(),
+        Some(ConnectionStatus {
+            online: true,
+            pending: 0,
+            rejected: 0,
+        })
+    );
+
+    let mut server = server.lock().await;
+    let response = server
+        .handle(ProxyRequest::FetchNoteContent {
+            note_id: note.id.clone(),
+        })
+        .await;
+    assert_eq!(
+        serde_json::to_value(response).unwrap(),
+        serde_json::json!({ "status": "Ok", "data": { "type": "Text", "value": "queued" } })
+    );
+    drop(server);
+
+    http.unblock();
+    server_thread
+        .join()
+        .expect("proxy server thread should finish cleanly");
+    drop(client);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_sets_rejected_writes_aside() {
+    let db = Db::memory()
+        .await
+        .expect("in-memory proxy database should initialize");
+    let server = Arc::new(Mutex::new(ProxyServer::new(Box::new(db))));
+
+    let Some(listener) = common::bind("proxy_sets_rejected_writes_aside") else {
+        return;
+    };
+    let addr = listener
+        .local_addr()
+        .expect("proxy server should expose a local address");
+    let (http, server_thread) = serve(listener, server.clone());
+
+    let dir = std::env::temp_dir().join(format!("glues-proxy-rejected-{}", uuid::Uuid::now_v7()));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+    let queue_path = dir.join("queue.redb").to_string_lossy().into_owned();
+    let url = format!("http://{addr}");
+
+    let mut client =
+        ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+            .await
+            .expect("proxy client should connect with a queue");
+    let root_id = client.root_id();
+    let gone = client
+        .add_directory(root_id.clone(), "Gone".to_owned())
+        .await
+        .unwrap();
+    let kept = client
+        .add_note(root_id.clone(), "Kept".to_owned())
+        .await
+        .unwrap();
+
+    http.unblock();
+    server_thread
+        .join()
+        .expect("proxy server thread should finish cleanly");
+    drop(http);
+    wait_until_refused(addr);
+    drop(client);
+    let mut client =
+        ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+            .await
+            .expect("proxy client should open offline from its cache");
+
+    // removed elsewhere while this client adds to it offline
+    server
+        .lock()
+        .await
+        .handle(ProxyRequest::RemoveDirectory {
+            directory_id: gone.id.clone(),
+        })
+        .await;
+    client
+        .add_note(gone.id.clone(), "Offline".to_owned())
+        .await
+        .unwrap();
+    client
+        .update_note_content(kept.id.clone(), "replayed".to_owned())
+        .await
+        .unwrap();
+
+    let listener = TcpListener::bind(addr).expect("proxy server should rebind its address");
+    let (http, server_thread) = serve(listener, server.clone());
+
+    client
+        .fetch_notes(root_id.clone())
+        .await
+        .expect("a rejected write should not fail later requests");
+    assert_eq!(
+        client.connection_status(),
+        Some(ConnectionStatus {
+            online: true,
+            pending: 0,
+            rejected: 1,
+        })
+    );
+    let messages = client.take_rejected_writes();
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].contains("was rejected by the server"));
+    assert!(client.take_rejected_writes().is_empty());
+
+    let rejected = client.rejected_writes().await.unwrap();
+    assert_eq!(rejected.len(), 1);
+    assert!(matches!(
+        &rejected[0].0,
+        ProxyRequest::RestoreNote { note, .. }
+            if note.directory_id == gone.id && note.name == "Offline"
+    ));
+    assert_eq!(
+        client.fetch_note_content(kept.id.clone()).await.unwrap(),
+        "replayed",
+        "writes after a rejected one are still replayed"
+    );
+
+    // set aside for good, also for the next client
+    drop(client);
+    let client = ProxyClient::connect_with_queue(&url, None, TlsOptions::default(), &queue_path)
+        .await
+        .unwrap();
+    assert_eq!(
+        client.connection_status().map(|status| status.rejected),
+        Some(1)
+    );
+
+    http.unblock();
+    server_thread
+        .join()
+        .expect("proxy server thread should finish cleanly");
+    drop(client);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_batches_requests() {
+    let dir = std::env::temp_dir().join(format!("glues-proxy-batch-{}", uuid::Uuid::now_v7()));
+    let path = dir.join("notes.redb").to_string_lossy().into_owned();
+    let db = Db::redb(&path)
+        .await
+        .expect("redb proxy database should initialize");
+    let server = Arc::new(Mutex::new(ProxyServer::new(Box::new(db))));
+
+    let Some(listener) = common::bind("proxy_batches_requests") else {
+        return;
+    };
+    let addr = listener
+        .local_addr()
+        .expect("proxy server should expose a local address");
+    let (http, server_thread) = serve(listener, server.clone());
+
+    let mut client = ProxyClient::connect(format!("http://{addr}"), None)
+        .await
+        .expect("proxy client should connect to server");
+    let root_id = client.root_id();
+
+    // a failing request leaves the rest of a plain batch alone
+    let responses = client
+        .batch(
+            vec![
+                ProxyRequest::AddDirectory {
+                    parent_id: root_id.clone(),
+                    name: "Work".to_owned(),
+                },
+                ProxyRequest::FetchDirectory {
+                    directory_id: "missing".to_owned(),
+                },
+                ProxyRequest::FetchDirectories {
+                    parent_id: root_id.clone(),
+                },
+            ],
+            false,
+        )
+        .await
+        .expect("plain batch should be answered");
+    assert_eq!(responses.len(), 3);
+    let work = match &responses[0] {
+        ProxyResponse::Ok(ResultPayload::Directory(directory)) => directory.clone(),
+        response => panic!("unexpected response: {response:?}"),
+    };
+    assert!(matches!(responses[1], ProxyResponse::Err(_)));
+    assert!(matches!(
+        &responses[2],
+        ProxyResponse::Ok(ResultPayload::Directories(directories)) if directories.len() == 1
+    ));
+
+    // an atomic batch rolls back the writes made before its failure
+    let err = client
+        .batch(
+            vec![
+                ProxyRequest::AddNote {
+                    directory_id: work.id.clone(),
+                    name: "Draft".to_owned(),
+                },
+                ProxyRequest::FetchDirectory {
+                    directory_id: "missing".to_owned(),
+                },
+            ],
+            true,
+        )
+        .await
+        .expect_err("atomic batch should fail as a whole");
+    assert!(matches!(err, Error::Proxy(message) if message.starts_with("batch request 1 failed")));
+    assert!(
+        client
+            .fetch_notes(work.id.clone())
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let responses = client
+        .batch(
+            vec![
+                ProxyRequest::AddNote {
+                    directory_id: work.id.clone(),
+                    name: "Draft".to_owned(),
+                },
+                ProxyRequest::AddDirectory {
+                    parent_id: work.id.clone(),
+                    name: "Archive".to_owned(),
+                },
+            ],
+            true,
+        )
+        .await
+        .expect("atomic batch should commit");
+    assert_eq!(responses.len(), 2);
+
+    let children = client
+        .fetch_children(vec![root_id.clone(), work.id.clone()])
+        .await
+        .expect("children should be fetched in one batch");
+    assert_eq!(children.len(), 2);
+    assert!(children[0].0.is_empty());
+    assert_eq!(children[0].1[0].name, "Work");
+    assert_eq!(children[1].0[0].name, "Draft");
+    assert_eq!(children[1].1[0].name, "Archive");
+
+    drop(client);
+    http.unblock();
+    server_thread
+        .join()
+        .expect("proxy server thread should finish cleanly");
+    drop(server);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_bounds_attachment_uploads() {
+    let db = Db::memory()
+        .await
+        .expect("in-memory proxy database should initialize");
+    let root_id = db.root_id();
+    let mut server = ProxyServer::new(Box::new(db)).with_transfer_limits(TransferLimits {
+        max_upload_size: 4096,
+        max_pending_size: 6144,
+        idle_timeout: Duration::from_secs(3600),
+    });
+    let ProxyResponse::Ok(ResultPayload::Note(note)) = server
+        .handle(ProxyRequest::AddNote {
+            directory_id: root_id,
+            name: "Uploads".to_owned(),
+        })
+        .await
+    else {
+        panic!("note should be added");
+    };
+
+    async fn start(server: &mut ProxyServer, note_id: &str) -> String {
+        let request = ProxyRequest::StartAttachmentUpload {
+            note_id: note_id.to_owned(),
+            name: "data.bin".to_owned(),
+        };
+        match server.handle(request).await {
+            ProxyResponse::Ok(ResultPayload::Id(upload_id)) => upload_id,
+            response => panic!("upload should start: {response:?}"),
+        }
+    }
+    // 3072 zero bytes
+    let chunk = |upload_id: &str| ProxyRequest::UploadAttachmentChunk {
+        upload_id: upload_id.to_owned(),
+        data: "A".repeat(4096),
+    };
+
+    let too_large = start(&mut server, &note.id).await;
+    assert!(matches!(
+        server.handle(chunk(&too_large)).await,
+        ProxyResponse::Ok(ResultPayload::Unit)
+    ));
+    let ProxyResponse::Err(message) = server.handle(chunk(&too_large)).await else {
+        panic!("upload over the size limit should be rejected");
+    };
+    assert_eq!(message, "attachment exceeds the upload limit of 4096 bytes");
+    let finish = ProxyRequest::FinishAttachmentUpload {
+        upload_id: too_large.clone(),
+    };
+    assert!(matches!(
+        server.handle(finish).await,
+        ProxyResponse::Err(message) if message.starts_with("unknown or expired upload")
+    ));
+
+    let first = start(&mut server, &note.id).await;
+    let second = start(&mut server, &note.id).await;
+    let third = start(&mut server, &note.id).await;
+    for upload_id in [&first, &second] {
+        assert!(matches!(
+            server.handle(chunk(upload_id)).await,
+            ProxyResponse::Ok(ResultPayload::Unit)
+        ));
+    }
+    let ProxyResponse::Err(message) = server.handle(chunk(&third)).await else {
+        panic!("uploads over the pending limit should be rejected");
+    };
+    assert_eq!(message, "too many attachments in transfer, try again later");
+
+    let finish = ProxyRequest::FinishAttachmentUpload { upload_id: first };
+    let ProxyResponse::Ok(ResultPayload::Attachment(attachment)) = server.handle(finish).await
+    else {
+        panic!("upload within the limits should finish");
+    };
+    assert_eq!(attachment.size, 3072);
+
+    let mut server = ProxyServer::new(Box::new(
+        Db::memory()
+            .await
+            .expect("in-memory proxy database should initialize"),
+    ))
+    .with_transfer_limits(TransferLimits {
+        idle_timeout: Duration::ZERO,
+        ..TransferLimits::default()
+    });
+    let idle = start(&mut server, &note.id).await;
+    assert!(matches!(
+        server.handle(chunk(&idle)).await,
+        ProxyResponse::Err(message) if message.starts_with("unknown or expired upload")
+    ));
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn proxy_reads_attachment_once_per_download() {
+    let mut db = Db::memory()
+        .await
+        .expect("in-memory proxy database should initialize");
+    let root_id = db.root_id();
+    let note = db.add_note(root_id, "Downloads".to_owned()).await.unwrap();
+    let data = (0..ATTACHMENT_CHUNK_SIZE * 2 + 10)
+        .map(|i| (i % 251) as u8)
+        .collect::<Vec<_>>();
+    let attachment = db
+        .add_attachment(note.id, "large.bin".to_owned(), data)
+        .await
+        .unwrap();
+    let mut server = ProxyServer::new(Box::new(db));
+    let chunk = |offset: usize| ProxyRequest::FetchAttachmentChunk {
+        attachment_id: attachment.id.clone(),
+        offset: offset as u64,
+    };
+
+    assert!(matches!(
+        server.handle(chunk(0)).await,
+        ProxyResponse::Ok(ResultPayload::Chunk { .. })
+    ));
+    // Removed behind the server's back, the rest still comes from the blob
+    // read for the first chunk.
+    server
+        .db
+        .remove_attachment(attachment.id.clone())
+        .await
+        .unwrap();
+    for offset in [ATTACHMENT_CHUNK_SIZE, ATTACHMENT_CHUNK_SIZE * 2] {
+        assert!(matches!(
+            server.handle(chunk(offset)).await,
+            ProxyResponse::Ok(ResultPayload::Chunk { .. })
+        ));
+    }
+
+    // The last chunk ends the transfer, so a new one reads storage again.
+    assert!(matches!(
+        server.handle(chunk(ATTACHMENT_CHUNK_SIZE)).await,
+        ProxyResponse::Err(_)
+    ));
+}
@@ -0,0 +1,131 @@
+use {
+    glues_core::{
+        EntryEvent, Event, Glues, KeyEvent, NotebookEvent, NumKey, Transition,
+        rpc::{self, RpcCall, RpcOutput, RpcReply, RpcRequest, RpcResponse},
+        transition::{NormalModeTransition, NotebookTransition, VimMotion},
+        types::Cursor,
+    },
+    serde_json::{Value, json},
+};
+
+async fn serve(glues: &mut Glues, lines: &[Value]) -> Vec<Value> {
+    let input: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    let mut output = Vec::new();
+    rpc::serve(glues, input.as_bytes(), &mut output)
+        .await
+        .expect("serving in-memory lines should not fail");
+
+    String::from_utf8(output)
+        .expect("responses should be UTF-8")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each response should be a JSON line"))
+        .collect()
+}
+
+#[test]
+fn events_round_trip_through_json() {
+    let events: Vec<Event> = vec![
+        KeyEvent::Num(NumKey::Three).into(),
+        KeyEvent::Char('é').into(),
+        EntryEvent::OpenEncrypted {
+            open: Box::new(EntryEvent::OpenRedb("notes.redb".to_owned())),
+            passphrase: "secret".to_owned(),
+        }
+        .into(),
+        NotebookEvent::JumpToMark {
+            mark: 'a',
+            linewise: true,
+            cursor: Cursor { row: 2, col: 4 },
+        }
+        .into(),
+        Event::Cancel,
+    ];
+
+    for event in events {
+        let json = serde_json::to_string(&event).expect("event should serialize");
+        let back: Event = serde_json::from_str(&json).expect("event should deserialize");
+        assert_eq!(back.to_string(), event.to_string(), "{json}");
+    }
+
+    let transition: Transition = NotebookTransition::EditingNormalMode(
+        NormalModeTransition::MoveCursorMotion(VimMotion::MatchPair, 2),
+    )
+    .into();
+    let json = serde_json::to_value(&transition).expect("transition should serialize");
+    assert_eq!(
+        json,
+        json!({"Notebook": {"EditingNormalMode": {"MoveCursorMotion": ["MatchPair", 2]}}})
+    );
+    serde_json::from_value::<Transition>(json).expect("transition should deserialize");
+}
+
+#[tokio::test]
+async fn handle_describes_the_state_after_each_call() {
+    let mut glues = Glues::new();
+    let response = rpc::handle(
+        &mut glues,
+        RpcRequest {
+            id: Some(7),
+            call: RpcCall::Dispatch(EntryEvent::OpenMemory.into()),
+        },
+    )
+    .await;
+
+    let RpcResponse {
+        id,
+        reply: RpcReply::Ok(output),
+    } = response
+    else {
+        panic!("opening a memory notebook should succeed");
+    };
+    let RpcOutput { transition, state } = *output;
+    assert_eq!(id, Some(7));
+    assert_eq!(
+        transition.map(|transition| transition.to_string()),
+        Some("Entry::OpenNotebook".to_owned())
+    );
+    assert_eq!(state.description, "Directory 'Notes' selected");
+    assert!(!state.keymap.is_empty());
+}
+
+#[tokio::test]
+async fn serve_answers_every_line() {
+    let mut glues = Glues::new();
+    let responses = serve(
+        &mut glues,
+        &[
+            json!({"id": 1, "method": "State"}),
+            json!({"id": 2, "method": "Dispatch", "data": {"Entry": "OpenMemory"}}),
+            json!({"method": "Dispatch", "data": {"Key": "QuestionMark"}}),
+            json!({"id": 3, "method": "Unknown"}),
+            json!({"id": 4, "method": "Dispatch", "data": {"Entry": "OpenMemory"}}),
+        ],
+    )
+    .await;
+
+    assert_eq!(responses.len(), 5);
+
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[0]["status"], "Ok");
+    assert_eq!(responses[0]["data"]["transition"], Value::Null);
+
+    assert_eq!(responses[1]["id"], 2);
+    assert_eq!(
+        responses[1]["data"]["transition"],
+        json!({"Entry": "OpenNotebook"})
+    );
+
+    assert_eq!(responses[2]["id"], Value::Null);
+    assert_eq!(
+        responses[2]["data"]["transition"],
+        json!({"Keymap": "Show"})
+    );
+    assert_eq!(responses[2]["data"]["state"]["keymap_visible"], true);
+
+    assert_eq!(responses[3]["id"], Value::Null);
+    assert_eq!(responses[3]["status"], "Err");
+
+    // entry events are not accepted once a notebook is open
+    assert_eq!(responses[4]["id"], 4);
+    assert_eq!(responses[4]["status"], "Err");
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use {
+    common::{TempDir, git_init},
+    glues_core::{
+        Error,
+        backend::{
+            CoreBackend,
+            local::{Db, Execute, Storage},
+        },
+        schema::{self, MIGRATIONS, SCHEMA_VERSION},
+    },
+    gluesql::core::{
+        ast_builder::{col, table, text, timestamp},
+        data::Value,
+    },
+};
+
+type OpenStorage = Box<dyn Fn() -> Storage>;
+
+/// Openers for every storage kind that runs without network access.
+/// Reopening the same kind returns the same notebook, except for memory.
+fn offline_storages(dir: &TempDir) -> Vec<(&'static str, OpenStorage)> {
+    let file = dir.join("file");
+    let redb = dir.join("notes.redb");
+    let git = dir.join("git");
+    git_init(&git);
+
+    vec![
+        ("memory", Box::new(Storage::memory)),
+        ("file", Box::new(move || Storage::file(&file).unwrap())),
+        ("redb", Box::new(move || Storage::redb(&redb).unwrap())),
+        (
+            "git",
+            Box::new(move || Storage::git(&git, "origin".to_owned(), "main".to_owned()).unwrap()),
+        ),
+    ]
+}
+
+fn all_steps() -> Vec<(u32, &'static str)> {
+    MIGRATIONS
+        .iter()
+        .map(|migration| (migration.version, migration.description))
+        .collect()
+}
+
+#[test]
+fn migrations_are_ordered() {
+    for (i, migration) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.version, i as u32 + 1);
+    }
+    assert_eq!(SCHEMA_VERSION, MIGRATIONS.len() as u32);
+}
+
+#[tokio::test]
+async fn fresh_storage_is_migrated_once() {
+    let dir = TempDir::new("schema-fresh");
+
+    for (name, open) in offline_storages(&dir) {
+        let mut storage = open();
+        let report = schema::report(&mut storage).await.unwrap();
+        assert_eq!(report.current, 0, "{name}");
+        assert_eq!(report.target, SCHEMA_VERSION, "{name}");
+        assert_eq!(report.pending, all_steps(), "{name}");
+
+        let mut db = Db::open(storage).await.unwrap();
+        db.add_note(db.root_id(), "Kept".to_owned()).await.unwrap();
+        assert_eq!(
+            db.fetch_meta("schema_version".to_owned()).await.unwrap(),
+            Some(SCHEMA_VERSION.to_string()),
+            "{name}"
+        );
+
+        let report = schema::migrate(&mut db.storage).await.unwrap();
+        assert_eq!(report.current, SCHEMA_VERSION, "{name}");
+        assert!(report.is_up_to_date(), "{name}");
+
+        if name == "memory" {
+            continue;
+        }
+
+        drop(db);
+        let mut db = Db::open(open()).await.unwrap();
+        let notes = db.fetch_notes(db.root_id()).await.unwrap();
+        assert_eq!(notes.len(), 1, "{name}");
+        assert_eq!(notes[0].name, "Kept", "{name}");
+    }
+}
+
+#[tokio::test]
+async fn log_is_kept_across_opens_within_retention() {
+    let dir = TempDir::new("schema-log");
+    let path = dir.join("file");
+
+    let mut db = Db::file(&path).await.unwrap();
+    db.log("test".to_owned(), "recent".to_owned())
+        .await
+        .unwrap();
+    table("Log")
+        .insert()
+        .columns(vec!["category", "message", "created_at"])
+        .values(vec![vec![
+            text("test"),
+            text("stale"),
+            timestamp("2000-01-01 00:00:00"),
+        ]])
+        .execute(&mut db.storage)
+        .await
+        .unwrap();
+    drop(db);
+
+    let mut db = Db::file(&path).await.unwrap();
+    let messages = table("Log")
+        .select()
+        .project("message")
+        .execute(&mut db.storage)
+        .await
+        .unwrap()
+        .select()
+        .expect("Log query must yield a result set")
+        .map(|row| row.get("message").map(|value| Value::clone(value)))
+        .collect::<Vec<_>>();
+    assert_eq!(messages, vec![Some(Value::Str("recent".to_owned()))]);
+
+    db.update_meta(schema::LOG_RETENTION_KEY.to_owned(), "0".to_owned())
+        .await
+        .unwrap();
+    drop(db);
+
+    let mut db = Db::file(&path).await.unwrap();
+    let count = table("Log")
+        .select()
+        .execute(&mut db.storage)
+        .await
+        .unwrap()
+        .select()
+        .expect("Log query must yield a result set")
+        .count();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn report_does_not_write() {
+    let dir = TempDir::new("schema-dry-run");
+
+    for (name, open) in offline_storages(&dir) {
+        let mut storage = open();
+        schema::report(&mut storage).await.unwrap();
+        let report = schema::report(&mut storage).await.unwrap();
+
+        assert_eq!(report.current, 0, "{name}");
+        assert_eq!(report.pending, all_steps(), "{name}");
+    }
+}
+
+#[tokio::test]
+async fn newer_notebooks_are_refused() {
+    let dir = TempDir::new("schema-newer");
+
+    for (name, open) in offline_storages(&dir) {
+        let mut db = Db::open(open()).await.unwrap();
+        let newer = (SCHEMA_VERSION + 1).to_string();
+        db.update_meta("schema_version".to_owned(), newer)
+            .await
+            .unwrap();
+
+        let result = schema::report(&mut db.storage).await;
+        assert!(matches!(result, Err(Error::Schema(_))), "{name}");
+
+        if name == "memory" {
+            let result = schema::migrate(&mut db.storage).await;
+            assert!(matches!(result, Err(Error::Schema(_))), "{name}");
+            continue;
+        }
+
+        drop(db);
+        assert!(
+            matches!(Db::open(open()).await, Err(Error::Schema(_))),
+            "{name}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn unversioned_notebooks_are_upgraded() {
+    let dir = TempDir::new("schema-unversioned");
+
+    for (name, open) in offline_storages(&dir) {
+        // notebooks written before versioning have every table but no version key
+        let mut db = Db::open(open()).await.unwrap();
+        db.add_note(db.root_id(), "Old".to_owned()).await.unwrap();
+        table("Meta")
+            .delete()
+            .filter(col("key").eq(text("schema_version")))
+            .execute(&mut db.storage)
+            .await
+            .unwrap();
+
+        let report = schema::migrate(&mut db.storage).await.unwrap();
+        assert_eq!(report.current, 0, "{name}");
+        assert_eq!(report.pending, all_steps(), "{name}");
+
+        let report = schema::report(&mut db.storage).await.unwrap();
+        assert!(report.is_up_to_date(), "{name}");
+        let notes = db.fetch_notes(db.root_id()).await.unwrap();
+        assert_eq!(notes[0].name, "Old", "{name}");
+    }
+}
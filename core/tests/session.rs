@@ -0,0 +1,143 @@
+use glues_core::{
+    EntryEvent, Glues, NotebookEvent,
+    backend::{CoreBackend, local::Db},
+    state::{
+        GetInner, NotebookState,
+        notebook::{NotebookSession, SessionTab},
+    },
+    transition::{NotebookTransition, Transition},
+};
+
+fn temp_path() -> String {
+    std::env::temp_dir()
+        .join(format!("glues-session-{}.redb", uuid::Uuid::now_v7()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+async fn open(path: &str) -> Glues {
+    let mut glues = Glues::new();
+    glues
+        .dispatch(EntryEvent::OpenRedb(path.to_owned()).into())
+        .await
+        .expect("redb notebook should open");
+    glues
+}
+
+fn notebook(glues: &Glues) -> &NotebookState {
+    glues.state.get_inner().expect("a notebook should be open")
+}
+
+#[tokio::test]
+async fn session_reopens_directories_and_tabs() {
+    let path = temp_path();
+    let (directory, first, second) = {
+        let mut db = Db::redb(&path).await.expect("redb should open");
+        let root_id = db.root_id();
+        let directory = db
+            .add_directory(root_id, "Diary".to_owned())
+            .await
+            .expect("directory should be added");
+        let first = db
+            .add_note(directory.id.clone(), "Monday".to_owned())
+            .await
+            .expect("note should be added");
+        let second = db
+            .add_note(directory.id.clone(), "Tuesday".to_owned())
+            .await
+            .expect("note should be added");
+        db.update_note_content(second.id.clone(), "rainy".to_owned())
+            .await
+            .expect("content should be updated");
+        (directory, first, second)
+    };
+
+    let session = {
+        let mut glues = open(&path).await;
+        for event in [
+            NotebookEvent::OpenDirectory(directory.id.clone()),
+            NotebookEvent::SelectNote(first.clone()),
+            NotebookEvent::OpenNote,
+            NotebookEvent::SelectNote(second.clone()),
+            NotebookEvent::OpenNote,
+        ] {
+            glues
+                .dispatch(event.into())
+                .await
+                .expect("event should be handled");
+        }
+
+        notebook(&glues).session()
+    };
+    assert_eq!(session.directories, vec![directory.id.clone()]);
+    assert_eq!(session.tabs.len(), 2);
+    assert_eq!(session.tab_index, Some(1));
+
+    let mut glues = open(&path).await;
+    let Transition::Notebook(NotebookTransition::RestoreSession(contents)) = glues
+        .dispatch(NotebookEvent::RestoreSession(session).into())
+        .await
+        .expect("session should be restored")
+    else {
+        panic!("expected a restored session");
+    };
+
+    assert_eq!(
+        contents,
+        vec![
+            (first.id.clone(), String::new()),
+            (second.id.clone(), "rainy".to_owned()),
+        ]
+    );
+    let state = notebook(&glues);
+    assert_eq!(state.tab_index, Some(1));
+    assert_eq!(state.get_selected_id().ok(), Some(&second.id));
+    assert_eq!(state.session().directories, vec![directory.id]);
+}
+
+#[tokio::test]
+async fn session_skips_what_no_longer_exists() {
+    let path = temp_path();
+    let (kept, removed) = {
+        let mut db = Db::redb(&path).await.expect("redb should open");
+        let root_id = db.root_id();
+        let kept = db
+            .add_note(root_id.clone(), "Kept".to_owned())
+            .await
+            .expect("note should be added");
+        let removed = db
+            .add_note(root_id.clone(), "Removed".to_owned())
+            .await
+            .expect("note should be added");
+        db.remove_note(removed.id.clone())
+            .await
+            .expect("note should be removed");
+        (kept, removed)
+    };
+
+    let mut glues = open(&path).await;
+    let root_id = notebook(&glues).root.directory.id.clone();
+    let session = NotebookSession {
+        directories: vec!["00000000-0000-7000-8000-000000000000".to_owned()],
+        tabs: vec![
+            SessionTab {
+                note_id: removed.id.clone(),
+                directory_id: root_id.clone(),
+            },
+            SessionTab {
+                note_id: kept.id.clone(),
+                directory_id: root_id,
+            },
+        ],
+        tab_index: Some(0),
+    };
+    glues
+        .dispatch(NotebookEvent::RestoreSession(session).into())
+        .await
+        .expect("a stale session should still be restored");
+
+    let state = notebook(&glues);
+    assert_eq!(state.tabs.len(), 1);
+    assert_eq!(state.tabs[0].note.id, kept.id);
+    assert_eq!(state.tab_index, Some(0));
+}
@@ -0,0 +1,68 @@
+use glues_core::{
+    EntryEvent, Glues, NotebookEvent,
+    state::{GetInner, NotebookState},
+    transition::{NormalModeTransition, NotebookTransition, Transition},
+};
+
+fn notebook(glues: &Glues) -> &NotebookState {
+    glues.state.get_inner().expect("a notebook should be open")
+}
+
+#[tokio::test]
+async fn select_tab_switches_to_the_tab_at_the_index() {
+    let mut glues = Glues::new();
+    glues
+        .dispatch(EntryEvent::OpenMemory.into())
+        .await
+        .expect("instant notebook should open");
+
+    let root_id = notebook(&glues).root.directory.id.clone();
+    let db = glues.db.as_mut().expect("backend should be open");
+    let first = db
+        .add_note(root_id.clone(), "First".to_owned())
+        .await
+        .expect("note should be added");
+    let second = db
+        .add_note(root_id.clone(), "Second".to_owned())
+        .await
+        .expect("note should be added");
+
+    for event in [
+        // reload the root to list the notes added directly to the backend
+        NotebookEvent::CloseDirectory(root_id.clone()),
+        NotebookEvent::OpenDirectory(root_id),
+        NotebookEvent::SelectNote(first.clone()),
+        NotebookEvent::OpenNote,
+        NotebookEvent::SelectNote(second.clone()),
+        NotebookEvent::OpenNote,
+    ] {
+        glues
+            .dispatch(event.into())
+            .await
+            .expect("event should be handled");
+    }
+    assert_eq!(notebook(&glues).tab_index, Some(1));
+
+    let transition = glues
+        .dispatch(NotebookEvent::SelectTab(0).into())
+        .await
+        .expect("tab should be selected");
+    assert!(matches!(
+        transition,
+        Transition::Notebook(NotebookTransition::EditingNormalMode(
+            NormalModeTransition::SelectTab(ref note_id),
+        )) if note_id == &first.id
+    ));
+
+    let state = notebook(&glues);
+    assert_eq!(state.tab_index, Some(0));
+    assert_eq!(state.get_selected_id().ok(), Some(&first.id));
+
+    assert!(
+        glues
+            .dispatch(NotebookEvent::SelectTab(2).into())
+            .await
+            .is_err()
+    );
+    assert_eq!(notebook(&glues).tab_index, Some(0));
+}
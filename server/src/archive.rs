@@ -0,0 +1,111 @@
+use {
+    crate::{StorageCommand, build_backend},
+    clap::Args,
+    color_eyre::{Result, eyre::eyre},
+    glues_core::{
+        backend::{
+            BackendBox,
+            encrypted::{self, Encrypted},
+        },
+        backup::{self, Archive},
+    },
+    std::path::PathBuf,
+};
+
+#[derive(Clone, Args)]
+pub struct BackupArgs {
+    /// Archive file to write
+    #[arg(long, short)]
+    pub output: PathBuf,
+
+    /// Passphrase of an encrypted notebook
+    #[arg(long, env = "GLUES_PASSPHRASE")]
+    pub passphrase: Option<String>,
+
+    #[command(subcommand)]
+    pub storage: StorageCommand,
+}
+
+#[derive(Clone, Args)]
+pub struct RestoreArgs {
+    /// Archive file to read
+    #[arg(long, short)]
+    pub input: PathBuf,
+
+    /// Passphrase of the encrypted notebook to restore into
+    #[arg(long, env = "GLUES_PASSPHRASE")]
+    pub passphrase: Option<String>,
+
+    #[command(subcommand)]
+    pub storage: StorageCommand,
+}
+
+/// Writes the notebook in `args.storage` to an archive file.
+pub async fn backup(args: BackupArgs) -> Result<()> {
+    color_eyre::install()?;
+
+    let BackupArgs {
+        output,
+        passphrase,
+        storage,
+    } = args;
+
+    let mut backend = open_backend(storage, passphrase).await?;
+    let archive = backup::dump(&mut backend).await?;
+    std::fs::write(&output, archive.to_json()?)?;
+
+    println!(
+        "Backed up {} directories and {} notes to {}",
+        archive.directories.len(),
+        archive.notes.len(),
+        output.display()
+    );
+
+    Ok(())
+}
+
+/// Rebuilds an archive file into the empty notebook in `args.storage`.
+pub async fn restore(args: RestoreArgs) -> Result<()> {
+    color_eyre::install()?;
+
+    let RestoreArgs {
+        input,
+        passphrase,
+        storage,
+    } = args;
+
+    let archive = Archive::from_json(&std::fs::read_to_string(&input)?)?;
+    let (directories, notes) = (archive.directories.len(), archive.notes.len());
+
+    let mut backend = open_backend(storage, passphrase).await?;
+    backup::restore(&mut backend, archive).await?;
+    if let Some(job) = backend.sync_job() {
+        tokio::task::spawn_blocking(move || job.run()).await??;
+    }
+
+    println!(
+        "Restored {directories} directories and {notes} notes from {}",
+        input.display()
+    );
+
+    Ok(())
+}
+
+/// Only ever unlocks notebooks which are already encrypted. A passphrase given
+/// for a plain notebook is refused rather than used to encrypt it.
+async fn open_backend(storage: StorageCommand, passphrase: Option<String>) -> Result<BackendBox> {
+    let mut backend = build_backend(storage).await?;
+    let encrypted = encrypted::is_encrypted(&mut backend).await?;
+
+    match passphrase.filter(|passphrase| !passphrase.is_empty()) {
+        Some(_) if !encrypted => Err(eyre!(
+            "the notebook is not encrypted, drop --passphrase or encrypt it first with \
+             `glues --open <notebook> --passphrase <passphrase> --encrypt`"
+        )),
+        Some(passphrase) => Ok(Box::new(Encrypted::unlock(backend, &passphrase).await?)),
+        None if encrypted => Err(eyre!(
+            "the notebook is encrypted, pass its passphrase with --passphrase"
+        )),
+        None => Ok(backend),
+    }
+}
@@ -0,0 +1,174 @@
+use {
+    color_eyre::Result,
+    gluesql::{
+        FromGlueRow,
+        core::{
+            ast_builder::{Execute, ExprNode, col, null, num, table, text, timestamp},
+            chrono::{Duration as Days, Utc},
+            row_conversion::SelectExt,
+        },
+        gluesql_redb_storage::RedbStorage,
+        prelude::Glue,
+    },
+    serde::{Deserialize, Serialize},
+    std::{
+        path::Path,
+        time::{Duration, Instant},
+    },
+};
+
+/// Most entries `/audit` answers with at once.
+const MAX_LIMIT: i64 = 1000;
+const DEFAULT_LIMIT: i64 = 100;
+
+/// How often entries past the retention period are removed.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Identity of requests made without a token.
+pub const ANONYMOUS: &str = "anonymous";
+
+/// Requests served by the proxy, stored in a redb file of its own.
+pub struct AuditLog {
+    glue: Glue<RedbStorage>,
+    retention_days: Option<u32>,
+    pruned_at: Instant,
+}
+
+/// One served request.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, FromGlueRow)]
+pub struct AuditEntry {
+    pub at: String,
+    pub identity: String,
+    pub method: String,
+    /// Comma separated ids of the touched directories, notes and attachments.
+    pub targets: String,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+}
+
+/// Filters of `GET /audit`, newest entries first.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub limit: Option<i64>,
+    pub identity: Option<String>,
+    pub method: Option<String>,
+    pub target: Option<String>,
+    /// Only entries at or after this `YYYY-MM-DD HH:MM:SS` timestamp, in UTC.
+    pub since: Option<String>,
+}
+
+impl AuditLog {
+    /// Entries older than `retention_days` are dropped, `None` keeps them all.
+    pub async fn open(path: &Path, retention_days: Option<u32>) -> Result<Self> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut glue = Glue::new(RedbStorage::new(path)?);
+        table("Audit")
+            .create_table_if_not_exists()
+            .add_column("at TIMESTAMP NOT NULL DEFAULT NOW()")
+            .add_column("identity TEXT NOT NULL")
+            .add_column("method TEXT NOT NULL")
+            .add_column("targets TEXT NOT NULL")
+            .add_column("error TEXT NULL")
+            .add_column("latency_ms INTEGER NOT NULL")
+            .execute(&mut glue)
+            .await?;
+
+        let mut audit = Self {
+            glue,
+            retention_days,
+            pruned_at: Instant::now(),
+        };
+        audit.prune().await?;
+
+        Ok(audit)
+    }
+
+    pub async fn record(
+        &mut self,
+        identity: &str,
+        method: &str,
+        targets: &[String],
+        error: Option<&str>,
+        latency: Duration,
+    ) -> Result<()> {
+        if self.pruned_at.elapsed() >= PRUNE_INTERVAL {
+            self.prune().await?;
+        }
+
+        let error = match error {
+            Some(error) => text(error.to_owned()),
+            None => null(),
+        };
+        table("Audit")
+            .insert()
+            .columns(vec!["identity", "method", "targets", "error", "latency_ms"])
+            .values(vec![vec![
+                text(identity.to_owned()),
+                text(method.to_owned()),
+                text(targets.join(",")),
+                error,
+                num(latency.as_millis() as i64),
+            ]])
+            .execute(&mut self.glue)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn query(&mut self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let mut filter = ExprNode::from(true);
+        if let Some(identity) = &query.identity {
+            filter = filter.and(col("identity").eq(text(identity.clone())));
+        }
+        if let Some(method) = &query.method {
+            filter = filter.and(col("method").eq(text(method.clone())));
+        }
+        if let Some(target) = &query.target {
+            filter = filter.and(col("targets").like(text(format!("%{target}%"))));
+        }
+        if let Some(since) = &query.since {
+            filter = filter.and(col("at").gte(timestamp(since.clone())));
+        }
+
+        let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let entries = table("Audit")
+            .select()
+            .filter(filter)
+            .project(vec![
+                "at",
+                "identity",
+                "method",
+                "targets",
+                "error",
+                "latency_ms",
+            ])
+            .order_by("at DESC")
+            .limit(num(limit))
+            .execute(&mut self.glue)
+            .await?
+            .rows_as::<AuditEntry>()?;
+
+        Ok(entries)
+    }
+
+    async fn prune(&mut self) -> Result<()> {
+        self.pruned_at = Instant::now();
+        let Some(days) = self.retention_days else {
+            return Ok(());
+        };
+
+        let cutoff = (Utc::now() - Days::days(days.into())).naive_utc();
+        table("Audit")
+            .delete()
+            .filter(col("at").lt(timestamp(cutoff.to_string())))
+            .execute(&mut self.glue)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use {
+    crate::{StorageCommand, open_db},
+    clap::Args,
+    color_eyre::{Result, eyre::eyre},
+};
+
+#[derive(Clone, Args)]
+pub struct FsckArgs {
+    /// Move stray directories and notes into the root and drop orphaned attachments
+    #[arg(long)]
+    pub repair: bool,
+
+    #[command(subcommand)]
+    pub storage: StorageCommand,
+}
+
+/// Checks the notebook in `args.storage` for rows the root cannot reach.
+/// Fails when problems are found and left unrepaired.
+pub async fn fsck(args: FsckArgs) -> Result<()> {
+    color_eyre::install()?;
+
+    let FsckArgs { repair, storage } = args;
+
+    let mut db = open_db(storage).await?;
+    let report = db.fsck(repair).await?;
+    for issue in &report.issues {
+        println!("{issue}");
+    }
+
+    if report.repaired
+        && let Some(job) = db.sync_job()
+    {
+        tokio::task::spawn_blocking(move || job.run()).await??;
+    }
+
+    match report.issues.len() {
+        0 => println!("No problems found"),
+        count if report.repaired => println!("Repaired {count} problems"),
+        count => {
+            return Err(eyre!(
+                "found {count} problems, run again with --repair to fix them"
+            ));
+        }
+    }
+
+    Ok(())
+}
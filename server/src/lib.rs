@@ -1,24 +1,49 @@
+pub mod archive;
+pub mod audit;
+pub mod fsck;
+pub mod metrics;
+mod tls;
+
 use {
+    audit::{ANONYMOUS, AuditEntry, AuditLog, AuditQuery},
     axum::{
-        Json, Router,
+        Extension, Json, Router,
         body::Body,
-        extract::State,
-        http::{Method, Request, StatusCode, header::AUTHORIZATION},
+        extract::{Query, State},
+        http::{
+            HeaderName, Method, Request, StatusCode,
+            header::{AUTHORIZATION, CONTENT_TYPE},
+        },
         middleware::{Next, from_fn},
         response::Response,
         routing::{get, post},
     },
+    axum_server::tls_rustls::RustlsConfig,
     clap::{Args, Parser, Subcommand},
-    color_eyre::Result,
+    color_eyre::{Result, eyre::eyre},
     glues_core::backend::{
         CoreBackend,
+        layer::LayerStack,
         local::Db,
-        proxy::{ProxyServer, request::ProxyRequest, response::ProxyResponse},
+        proxy::{
+            ProxyServer,
+            request::ProxyRequest,
+            response::{ProxyResponse, ResultPayload},
+        },
+    },
+    metrics::Metrics,
+    std::{
+        collections::HashMap,
+        future::Future,
+        net::SocketAddr,
+        path::PathBuf,
+        str::FromStr,
+        sync::{Arc, Mutex, PoisonError},
+        time::Instant,
     },
-    std::{net::SocketAddr, sync::Arc},
     tokio::{net::TcpListener, signal, sync::Mutex as AsyncMutex},
     tower_http::cors::{Any, CorsLayer},
-    tracing::{error, info, warn},
+    tracing::{debug, error, info, warn},
     tracing_subscriber::EnvFilter,
 };
 
@@ -30,10 +55,72 @@ pub struct ServerArgs {
     #[arg(long, env = "GLUES_SERVER_TOKEN")]
     pub auth_token: Option<String>,
 
+    /// Extra token given as NAME=TOKEN, audited under NAME; repeatable
+    #[arg(long = "user-token", value_name = "NAME=TOKEN")]
+    pub user_tokens: Vec<UserToken>,
+
+    /// redb file recording every request, served read-only at /audit
+    #[arg(long, env = "GLUES_SERVER_AUDIT_LOG")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Days audit entries are kept for; kept forever when unset
+    #[arg(long, requires = "audit_log")]
+    pub audit_retention_days: Option<u32>,
+
+    /// Backend layers around the storage, outermost first, e.g. cache,timing=50
+    #[arg(long, env = "GLUES_SERVER_LAYERS", value_name = "LAYERS")]
+    pub layers: Option<LayerStack>,
+
+    /// PEM certificate chain to serve HTTPS with
+    #[arg(long, env = "GLUES_SERVER_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// PEM private key of the TLS certificate
+    #[arg(long, env = "GLUES_SERVER_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// PEM CA bundle which client certificates must be signed by
+    #[arg(long, env = "GLUES_SERVER_CLIENT_CA", requires = "tls_cert")]
+    pub client_ca: Option<PathBuf>,
+
     #[command(subcommand)]
     pub storage: StorageCommand,
 }
 
+/// Identity requests made with `--auth-token` are audited under.
+const DEFAULT_IDENTITY: &str = "default";
+
+#[derive(Clone, Debug)]
+pub struct UserToken {
+    pub name: String,
+    pub token: String,
+}
+
+impl FromStr for UserToken {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some((name, token)) if !name.is_empty() && !token.is_empty() => Ok(Self {
+                name: name.to_owned(),
+                token: token.to_owned(),
+            }),
+            _ => Err("expected NAME=TOKEN".to_owned()),
+        }
+    }
+}
+
+/// Name of the token a request was authenticated with.
+#[derive(Clone)]
+struct Identity(String);
+
+#[derive(Clone)]
+struct AppState {
+    server: Arc<AsyncMutex<ProxyServer>>,
+    audit: Option<Arc<AsyncMutex<AuditLog>>>,
+    metrics: Arc<Mutex<Metrics>>,
+}
+
 #[derive(Parser)]
 #[command(author, version, about = "Glues proxy server")]
 struct Cli {
@@ -70,13 +157,54 @@ pub async fn run(args: ServerArgs) -> Result<()> {
         .with_target(false)
         .init();
 
+    let listener = std::net::TcpListener::bind(args.listen)?;
+    serve(args, listener, shutdown_signal()).await
+}
+
+/// Serves the proxy on `listener`, which takes precedence over `args.listen`,
+/// until `shutdown` completes.
+pub async fn serve(
+    args: ServerArgs,
+    listener: std::net::TcpListener,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
     let ServerArgs {
-        listen,
         auth_token,
+        user_tokens,
+        audit_log,
+        audit_retention_days,
+        layers,
+        tls_cert,
+        tls_key,
+        client_ca,
         storage,
+        ..
     } = args;
 
-    let backend = build_backend(storage).await?;
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => Some(tls::server_config(&cert, &key, client_ca.as_deref())?),
+        (None, None) if client_ca.is_none() => None,
+        _ => {
+            return Err(eyre!(
+                "--tls-cert and --tls-key are required to serve HTTPS"
+            ));
+        }
+    };
+
+    let audit = match audit_log {
+        Some(path) => {
+            info!("recording requests in {}", path.display());
+            let audit = AuditLog::open(&path, audit_retention_days).await?;
+            Some(Arc::new(AsyncMutex::new(audit)))
+        }
+        None => None,
+    };
+
+    let mut backend = build_backend(storage).await?;
+    if let Some(layers) = layers.filter(|layers| !layers.is_empty()) {
+        info!("wrapping the backend in {} layers", layers.len());
+        backend = layers.apply(backend);
+    }
     let server = Arc::new(AsyncMutex::new(ProxyServer::new(backend)));
 
     let cors = CorsLayer::new()
@@ -87,28 +215,75 @@ pub async fn run(args: ServerArgs) -> Result<()> {
     let mut app = Router::new()
         .route("/", post(handle_proxy))
         .route("/health", get(health))
-        .with_state(server.clone())
+        .route("/metrics", get(render_metrics))
+        .route("/audit", get(audit_entries))
+        .with_state(AppState {
+            server,
+            audit,
+            metrics: Arc::default(),
+        })
         .layer(cors);
 
-    if let Some(token) = auth_token.as_ref() {
+    let tokens: HashMap<String, String> = auth_token
+        .map(|token| (token, DEFAULT_IDENTITY.to_owned()))
+        .into_iter()
+        .chain(
+            user_tokens
+                .into_iter()
+                .map(|UserToken { name, token }| (token, name)),
+        )
+        .collect();
+
+    let listen = listener.local_addr()?;
+    if !tokens.is_empty() {
         info!("authentication token required for proxy requests");
-        let token = Arc::new(token.clone());
+        let tokens = Arc::new(tokens);
         let auth_layer = from_fn(move |req, next| {
-            let token = Arc::clone(&token);
-            async move { enforce_bearer(token, req, next).await }
+            let tokens = Arc::clone(&tokens);
+            async move { enforce_bearer(tokens, req, next).await }
         });
         app = app.layer(auth_layer);
-    } else if !listen.ip().is_loopback() {
+    } else if client_ca.is_none() && !listen.ip().is_loopback() {
         warn!(
             "proxy server is listening on {listen} without authentication; set GLUES_SERVER_TOKEN or --auth-token to protect access"
         );
     }
 
-    let listener = TcpListener::bind(listen).await?;
-    info!("listening on {}", listen);
+    let Some(tls) = tls else {
+        if !listen.ip().is_loopback() {
+            warn!(
+                "proxy server is listening on {listen} without TLS; pass --tls-cert and --tls-key to keep requests and tokens off the wire in cleartext"
+            );
+        }
+
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        info!("listening on http://{}", listen);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown)
+            .await?;
+
+        return Ok(());
+    };
+
+    if client_ca.is_some() {
+        info!("client certificates required for proxy requests");
+    }
+    info!("listening on https://{}", listen);
+
+    let handle = axum_server::Handle::new();
+    tokio::spawn({
+        let handle = handle.clone();
+        async move {
+            shutdown.await;
+            handle.graceful_shutdown(None);
+        }
+    });
+
+    axum_server::from_tcp_rustls(listener, RustlsConfig::from_config(Arc::new(tls)))
+        .handle(handle)
+        .serve(app.into_make_service())
         .await?;
 
     Ok(())
@@ -118,35 +293,145 @@ pub async fn run_cli() -> Result<()> {
     run(parse_args()).await
 }
 
-async fn build_backend(storage: StorageCommand) -> Result<Box<dyn CoreBackend + Send>> {
-    let backend: Box<dyn CoreBackend + Send> = match storage {
-        StorageCommand::Memory => Box::new(Db::memory().await?),
-        StorageCommand::File { path } => Box::new(Db::file(&path).await?),
-        StorageCommand::Redb { path } => Box::new(Db::redb(&path).await?),
+pub(crate) async fn build_backend(storage: StorageCommand) -> Result<Box<dyn CoreBackend + Send>> {
+    Ok(Box::new(open_db(storage).await?))
+}
+
+pub(crate) async fn open_db(storage: StorageCommand) -> Result<Db> {
+    let db = match storage {
+        StorageCommand::Memory => Db::memory().await?,
+        StorageCommand::File { path } => Db::file(&path).await?,
+        StorageCommand::Redb { path } => Db::redb(&path).await?,
         StorageCommand::Git {
             path,
             remote,
             branch,
-        } => Box::new(Db::git(&path, remote, branch).await?),
-        StorageCommand::Mongo { conn_str, db_name } => {
-            Box::new(Db::mongo(&conn_str, &db_name).await?)
-        }
+        } => Db::git(&path, remote, branch).await?,
+        StorageCommand::Mongo { conn_str, db_name } => Db::mongo(&conn_str, &db_name).await?,
     };
 
-    Ok(backend)
+    Ok(db)
 }
 
 async fn handle_proxy(
-    State(server): State<Arc<AsyncMutex<ProxyServer>>>,
+    State(state): State<AppState>,
+    identity: Option<Extension<Identity>>,
     Json(request): Json<ProxyRequest>,
 ) -> (StatusCode, Json<ProxyResponse>) {
-    let mut server = server.lock_owned().await;
-    let response = server.handle(request).await;
+    let started = Instant::now();
+    let method = request.to_string();
+    let read = request.is_read();
+    let mut targets = request.targets();
+
+    let (response, sync_runs) = {
+        let mut server = state.server.lock().await;
+        let response = server.handle(request).await;
+        (response, server.take_sync_runs())
+    };
+    let latency = started.elapsed();
+
+    let identity = identity.map_or_else(|| ANONYMOUS.to_owned(), |Extension(Identity(name))| name);
+    let error = match &response {
+        ProxyResponse::Ok(ResultPayload::Directory(directory)) if !read => {
+            targets.push(directory.id.clone());
+            None
+        }
+        ProxyResponse::Ok(ResultPayload::Note(note)) if !read => {
+            targets.push(note.id.clone());
+            None
+        }
+        ProxyResponse::Ok(ResultPayload::Attachment(attachment)) if !read => {
+            targets.push(attachment.id.clone());
+            None
+        }
+        ProxyResponse::Ok(_) => None,
+        ProxyResponse::Err(error) => Some(error.as_str()),
+    };
+
+    let latency_ms = latency.as_millis() as u64;
+    let target = targets.join(",");
+    match error {
+        Some(error) => warn!(%identity, %method, %target, latency_ms, %error, "request failed"),
+        None if read => debug!(%identity, %method, %target, latency_ms, "request served"),
+        None => info!(%identity, %method, %target, latency_ms, "request served"),
+    }
+
+    {
+        let mut metrics = state.metrics.lock().unwrap_or_else(PoisonError::into_inner);
+        metrics.observe_request(&method, error.is_some(), latency);
+        for run in &sync_runs {
+            let duration_ms = run.duration.as_millis() as u64;
+            match &run.error {
+                Some(error) => warn!(duration_ms, %error, "sync failed"),
+                None => info!(duration_ms, "sync finished"),
+            }
+            metrics.observe_sync(run);
+        }
+    }
+
+    if let Some(audit) = state.audit.as_ref()
+        && let Err(err) = audit
+            .lock()
+            .await
+            .record(&identity, &method, &targets, error, latency)
+            .await
+    {
+        error!("failed to record audit entry: {err}");
+    }
+
     (StatusCode::OK, Json(response))
 }
 
-async fn health() -> StatusCode {
-    StatusCode::OK
+async fn audit_entries(
+    State(state): State<AppState>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, String)> {
+    let Some(audit) = state.audit else {
+        return Err((StatusCode::NOT_FOUND, "audit log is disabled".to_owned()));
+    };
+
+    audit
+        .lock()
+        .await
+        .query(&query)
+        .await
+        .map(Json)
+        .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))
+}
+
+/// Ready once the backend answers a query for the root directory.
+async fn health(State(state): State<AppState>) -> (StatusCode, String) {
+    let mut server = state.server.lock().await;
+    let root_id = server.db.root_id();
+    match server.db.fetch_directory(root_id).await {
+        Ok(_) => (StatusCode::OK, "ok".to_owned()),
+        Err(err) => {
+            warn!("backend is not ready: {err}");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                format!("backend unavailable: {err}"),
+            )
+        }
+    }
+}
+
+async fn render_metrics(
+    State(state): State<AppState>,
+) -> ([(HeaderName, &'static str); 1], String) {
+    let notebook = match metrics::notebook_size(&mut state.server.lock().await.db).await {
+        Ok(size) => Some(size),
+        Err(err) => {
+            warn!("failed to measure the notebook: {err}");
+            None
+        }
+    };
+    let body = state
+        .metrics
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .render(notebook);
+
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4")], body)
 }
 
 async fn shutdown_signal() {
@@ -159,8 +444,8 @@ async fn shutdown_signal() {
 }
 
 async fn enforce_bearer(
-    token: Arc<String>,
-    req: Request<Body>,
+    tokens: Arc<HashMap<String, String>>,
+    mut req: Request<Body>,
     next: Next,
 ) -> Result<Response, StatusCode> {
     if req.method() == Method::OPTIONS {
@@ -176,9 +461,10 @@ async fn enforce_bearer(
     let Some(provided) = value.strip_prefix("Bearer ").map(str::trim) else {
         return Err(StatusCode::UNAUTHORIZED);
     };
-    if provided != token.as_str() {
+    let Some(name) = tokens.get(provided).cloned() else {
         return Err(StatusCode::UNAUTHORIZED);
-    }
+    };
+    req.extensions_mut().insert(Identity(name));
 
     Ok(next.run(req).await)
 }
@@ -200,7 +486,10 @@ mod tests {
             .allow_methods(Any)
             .allow_headers(Any);
 
-        let token = Arc::new("secret".to_owned());
+        let token = Arc::new(HashMap::from([(
+            "secret".to_owned(),
+            DEFAULT_IDENTITY.to_owned(),
+        )]));
         let app = Router::new()
             .route("/", get(ok))
             .layer(cors)
@@ -230,7 +519,10 @@ mod tests {
             .allow_methods(Any)
             .allow_headers(Any);
 
-        let token = Arc::new("secret".to_owned());
+        let token = Arc::new(HashMap::from([(
+            "secret".to_owned(),
+            DEFAULT_IDENTITY.to_owned(),
+        )]));
         let app = Router::new()
             .route("/", get(ok))
             .layer(cors)
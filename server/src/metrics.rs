@@ -0,0 +1,216 @@
+use {
+    color_eyre::Result,
+    glues_core::backend::{CoreBackend, proxy::SyncRun},
+    std::{collections::BTreeMap, fmt::Write, time::Duration},
+};
+
+/// Upper bounds of the latency histograms, in seconds.
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Counters served at `/metrics` in the Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    methods: BTreeMap<String, MethodStats>,
+    sync_succeeded: u64,
+    sync_failed: u64,
+    sync_duration: Histogram,
+}
+
+#[derive(Default)]
+struct MethodStats {
+    errors: u64,
+    latency: Histogram,
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, le) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+        for (bucket, le) in self.buckets.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"{le}\"}} {bucket}");
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {}",
+            self.count
+        );
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{labels}}}")
+        };
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+    }
+}
+
+/// Directories, including the root, and notes of the served notebook.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotebookSize {
+    pub directories: usize,
+    pub notes: usize,
+}
+
+impl Metrics {
+    pub fn observe_request(&mut self, method: &str, failed: bool, latency: Duration) {
+        let stats = self.methods.entry(method.to_owned()).or_default();
+        stats.latency.observe(latency);
+        if failed {
+            stats.errors += 1;
+        }
+    }
+
+    pub fn observe_sync(&mut self, run: &SyncRun) {
+        self.sync_duration.observe(run.duration);
+        match run.error {
+            Some(_) => self.sync_failed += 1,
+            None => self.sync_succeeded += 1,
+        }
+    }
+
+    /// `notebook` is `None` when the backend could not be queried.
+    pub fn render(&self, notebook: Option<NotebookSize>) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "glues_requests_total",
+            "counter",
+            "Proxy requests served, by method.",
+        );
+        for (method, stats) in &self.methods {
+            let _ = writeln!(
+                out,
+                "glues_requests_total{{method=\"{method}\"}} {}",
+                stats.latency.count
+            );
+        }
+
+        header(
+            &mut out,
+            "glues_request_errors_total",
+            "counter",
+            "Proxy requests answered with an error, by method.",
+        );
+        for (method, stats) in &self.methods {
+            let _ = writeln!(
+                out,
+                "glues_request_errors_total{{method=\"{method}\"}} {}",
+                stats.errors
+            );
+        }
+
+        header(
+            &mut out,
+            "glues_request_duration_seconds",
+            "histogram",
+            "Time spent serving proxy requests, by method.",
+        );
+        for (method, stats) in &self.methods {
+            stats.latency.render(
+                &mut out,
+                "glues_request_duration_seconds",
+                &format!("method=\"{method}\""),
+            );
+        }
+
+        header(
+            &mut out,
+            "glues_sync_runs_total",
+            "counter",
+            "Sync jobs run, by outcome.",
+        );
+        let _ = writeln!(
+            out,
+            "glues_sync_runs_total{{outcome=\"success\"}} {}",
+            self.sync_succeeded
+        );
+        let _ = writeln!(
+            out,
+            "glues_sync_runs_total{{outcome=\"failure\"}} {}",
+            self.sync_failed
+        );
+
+        header(
+            &mut out,
+            "glues_sync_duration_seconds",
+            "histogram",
+            "Time spent running sync jobs.",
+        );
+        self.sync_duration
+            .render(&mut out, "glues_sync_duration_seconds", "");
+
+        header(
+            &mut out,
+            "glues_backend_up",
+            "gauge",
+            "Whether the storage backend answered the last query.",
+        );
+        let _ = writeln!(out, "glues_backend_up {}", u8::from(notebook.is_some()));
+
+        if let Some(NotebookSize { directories, notes }) = notebook {
+            header(
+                &mut out,
+                "glues_notebook_directories",
+                "gauge",
+                "Directories in the notebook, including the root.",
+            );
+            let _ = writeln!(out, "glues_notebook_directories {directories}");
+
+            header(
+                &mut out,
+                "glues_notebook_notes",
+                "gauge",
+                "Notes in the notebook.",
+            );
+            let _ = writeln!(out, "glues_notebook_notes {notes}");
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+/// Walks the notebook one directory level per `fetch_children` call.
+pub async fn notebook_size<B: CoreBackend + ?Sized>(backend: &mut B) -> Result<NotebookSize> {
+    let mut size = NotebookSize {
+        directories: 1,
+        notes: 0,
+    };
+
+    let mut level = vec![backend.root_id()];
+    while !level.is_empty() {
+        let children = backend.fetch_children(level).await?;
+        level = Vec::new();
+        for (notes, directories) in children {
+            size.notes += notes.len();
+            size.directories += directories.len();
+            level.extend(directories.into_iter().map(|directory| directory.id));
+        }
+    }
+
+    Ok(size)
+}
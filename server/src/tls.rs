@@ -0,0 +1,65 @@
+use {
+    color_eyre::{
+        Result,
+        eyre::{WrapErr, eyre},
+    },
+    rustls::{
+        RootCertStore, ServerConfig,
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer},
+        server::WebPkiClientVerifier,
+    },
+    std::{fs::File, io::BufReader, path::Path, sync::Arc},
+};
+
+/// Serves `cert` with `key`. With `client_ca`, only clients presenting a
+/// certificate signed by one of its CAs complete the handshake.
+pub fn server_config(cert: &Path, key: &Path, client_ca: Option<&Path>) -> Result<ServerConfig> {
+    let provider = Arc::new(ring::default_provider());
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()?;
+
+    let builder = match client_ca {
+        Some(path) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(path)? {
+                roots
+                    .add(cert)
+                    .wrap_err_with(|| format!("invalid client CA in {}", path.display()))?;
+            }
+            let verifier =
+                WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider).build()?;
+
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let mut config = builder.with_single_cert(load_certs(cert)?, load_key(key)?)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+
+    Ok(config)
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut open(path)?)
+        .collect::<Result<Vec<_>, _>>()
+        .wrap_err_with(|| format!("failed to read certificates from {}", path.display()))?;
+    if certs.is_empty() {
+        return Err(eyre!("no certificates found in {}", path.display()));
+    }
+
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    rustls_pemfile::private_key(&mut open(path)?)
+        .wrap_err_with(|| format!("failed to read private key from {}", path.display()))?
+        .ok_or_else(|| eyre!("no private key found in {}", path.display()))
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    File::open(path)
+        .map(BufReader::new)
+        .wrap_err_with(|| format!("failed to open {}", path.display()))
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use {
+    common::unique_suffix,
+    glues_core::backend::{CoreBackend, encrypted::is_encrypted, local::Db},
+    glues_server::{
+        StorageCommand,
+        archive::{self, BackupArgs},
+    },
+};
+
+#[tokio::test]
+async fn backup_refuses_a_passphrase_for_plain_notebooks() {
+    let path = std::env::temp_dir()
+        .join(format!("glues-archive-plain-{}", unique_suffix()))
+        .to_string_lossy()
+        .into_owned();
+
+    let mut db = Db::file(&path).await.unwrap();
+    let note = db.add_note(db.root_id(), "Plan".to_owned()).await.unwrap();
+    db.update_note_content(note.id.clone(), "ship it".to_owned())
+        .await
+        .unwrap();
+    drop(db);
+
+    let output = std::path::PathBuf::from(format!("{path}.json"));
+    let args = BackupArgs {
+        output: output.clone(),
+        passphrase: Some("secret".to_owned()),
+        storage: StorageCommand::File { path: path.clone() },
+    };
+    let err = archive::backup(args).await.unwrap_err();
+    assert!(err.to_string().contains("not encrypted"), "{err}");
+    assert!(!output.exists());
+
+    let mut db = Db::file(&path).await.unwrap();
+    assert!(!is_encrypted(&mut db).await.unwrap());
+    assert_eq!(db.fetch_note_content(note.id).await.unwrap(), "ship it");
+
+    std::fs::remove_dir_all(&path).unwrap();
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use {
+    common::unique_suffix,
+    glues_core::backend::{CoreBackend, proxy::ProxyClient},
+    glues_server::audit::{ANONYMOUS, AuditEntry},
+};
+
+async fn audit(url: &str, token: &str, query: &[(&str, &str)]) -> Vec<AuditEntry> {
+    reqwest::Client::new()
+        .get(format!("{url}/audit"))
+        .bearer_auth(token)
+        .query(query)
+        .send()
+        .await
+        .expect("audit request should be sent")
+        .error_for_status()
+        .expect("audit entries should be served")
+        .json()
+        .await
+        .expect("audit entries should decode")
+}
+
+#[tokio::test]
+async fn proxy_requests_are_audited() {
+    let dir = std::env::temp_dir().join(format!("glues-audit-{}", unique_suffix()));
+    let tokens = vec![
+        "alice=alice-token"
+            .parse()
+            .expect("user token should parse"),
+        "bob=bob-token".parse().expect("user token should parse"),
+    ];
+    let (url, stop) = common::start(|args| {
+        args.user_tokens = tokens;
+        args.audit_log = Some(dir.join("audit.redb"));
+    })
+    .await;
+
+    ProxyClient::connect(&url, None)
+        .await
+        .err()
+        .expect("requests without a token should be rejected");
+
+    let mut alice = ProxyClient::connect(&url, Some("alice-token".to_owned()))
+        .await
+        .expect("alice should connect");
+    let root_id = alice.root_id();
+    let note = alice
+        .add_note(root_id.clone(), "Audited".to_owned())
+        .await
+        .expect("note should be added");
+
+    let mut bob = ProxyClient::connect(&url, Some("bob-token".to_owned()))
+        .await
+        .expect("bob should connect");
+    bob.fetch_note_content(note.id.clone())
+        .await
+        .expect("note content should be fetched");
+    bob.fetch_note_content("missing".to_owned())
+        .await
+        .expect_err("missing note should fail");
+
+    let entries = audit(&url, "alice-token", &[]).await;
+    assert!(entries.iter().all(|entry| entry.identity != ANONYMOUS));
+    assert_eq!(
+        entries.first().map(|entry| entry.identity.as_str()),
+        Some("bob")
+    );
+
+    let added = audit(&url, "bob-token", &[("method", "AddNote")]).await;
+    assert_eq!(added.len(), 1);
+    assert_eq!(added[0].identity, "alice");
+    assert_eq!(added[0].targets, format!("{root_id},{}", note.id));
+    assert_eq!(added[0].error, None);
+
+    let touched = audit(&url, "alice-token", &[("target", note.id.as_str())]).await;
+    assert_eq!(touched.len(), 2);
+
+    let failed = audit(
+        &url,
+        "alice-token",
+        &[("identity", "bob"), ("target", "missing")],
+    )
+    .await;
+    assert_eq!(failed.len(), 1);
+    assert!(failed[0].error.is_some());
+
+    let limited = audit(&url, "alice-token", &[("limit", "1")]).await;
+    assert_eq!(limited.len(), 1);
+
+    let _ = stop.send(());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,53 @@
+use {
+    glues_server::{ServerArgs, StorageCommand, serve},
+    std::net::TcpListener,
+    tokio::sync::oneshot,
+};
+
+#[allow(dead_code)]
+pub fn unique_suffix() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after the epoch")
+        .as_nanos()
+}
+
+/// Starts an in-memory server on a free port with the arguments `configure`
+/// leaves, and returns its url with the sender stopping it.
+#[allow(dead_code)]
+pub async fn start(configure: impl FnOnce(&mut ServerArgs)) -> (String, oneshot::Sender<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("server should bind");
+    let addr = listener
+        .local_addr()
+        .expect("server should expose its address");
+    let mut args = ServerArgs {
+        listen: addr,
+        auth_token: None,
+        user_tokens: Vec::new(),
+        audit_log: None,
+        audit_retention_days: None,
+        layers: None,
+        tls_cert: None,
+        tls_key: None,
+        client_ca: None,
+        storage: StorageCommand::Memory,
+    };
+    configure(&mut args);
+    let scheme = if args.tls_cert.is_some() {
+        "https"
+    } else {
+        "http"
+    };
+
+    let (stop, stopped) = oneshot::channel();
+    tokio::spawn(async move {
+        let shutdown = async {
+            let _ = stopped.await;
+        };
+        serve(args, listener, shutdown)
+            .await
+            .expect("server should run");
+    });
+
+    (format!("{scheme}://{addr}"), stop)
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use {
+    glues_core::backend::{
+        CoreBackend,
+        proxy::{ProxyClient, SyncRun},
+    },
+    glues_server::metrics::{Metrics, NotebookSize},
+    std::time::Duration,
+};
+
+async fn get(url: &str) -> (u16, String) {
+    let response = reqwest::get(url).await.expect("request should be sent");
+    let status = response.status().as_u16();
+    let body = response.text().await.expect("body should be read");
+
+    (status, body)
+}
+
+#[tokio::test]
+async fn metrics_count_requests_and_notebook() {
+    let (url, stop) = common::start(|_| {}).await;
+
+    let (status, body) = get(&format!("{url}/health")).await;
+    assert_eq!((status, body.as_str()), (200, "ok"));
+
+    let mut client = ProxyClient::connect(&url, None)
+        .await
+        .expect("client should connect");
+    let root_id = client.root_id();
+    let directory = client
+        .add_directory(root_id.clone(), "Work".to_owned())
+        .await
+        .expect("directory should be added");
+    client
+        .add_note(root_id, "Inbox".to_owned())
+        .await
+        .expect("note should be added");
+    client
+        .add_note(directory.id, "Plan".to_owned())
+        .await
+        .expect("note should be added");
+    client
+        .fetch_note_content("missing".to_owned())
+        .await
+        .expect_err("missing note should fail");
+
+    let (status, body) = get(&format!("{url}/metrics")).await;
+    assert_eq!(status, 200);
+    let lines: Vec<&str> = body.lines().collect();
+    for expected in [
+        "glues_requests_total{method=\"AddNote\"} 2",
+        "glues_requests_total{method=\"AddDirectory\"} 1",
+        "glues_request_errors_total{method=\"AddNote\"} 0",
+        "glues_request_errors_total{method=\"FetchNoteContent\"} 1",
+        "glues_request_duration_seconds_count{method=\"AddNote\"} 2",
+        "glues_request_duration_seconds_bucket{method=\"AddNote\",le=\"+Inf\"} 2",
+        "glues_sync_runs_total{outcome=\"success\"} 0",
+        "glues_backend_up 1",
+        "glues_notebook_directories 2",
+        "glues_notebook_notes 2",
+    ] {
+        assert!(lines.contains(&expected), "missing {expected} in:\n{body}");
+    }
+
+    let _ = stop.send(());
+}
+
+#[test]
+fn metrics_render_sync_runs() {
+    let mut metrics = Metrics::default();
+    metrics.observe_sync(&SyncRun {
+        duration: Duration::from_millis(20),
+        error: None,
+    });
+    metrics.observe_sync(&SyncRun {
+        duration: Duration::from_secs(3),
+        error: Some("remote rejected the push".to_owned()),
+    });
+
+    let body = metrics.render(Some(NotebookSize {
+        directories: 1,
+        notes: 0,
+    }));
+    let lines: Vec<&str> = body.lines().collect();
+    for expected in [
+        "glues_sync_runs_total{outcome=\"success\"} 1",
+        "glues_sync_runs_total{outcome=\"failure\"} 1",
+        "glues_sync_duration_seconds_bucket{le=\"0.025\"} 1",
+        "glues_sync_duration_seconds_bucket{le=\"5\"} 2",
+        "glues_sync_duration_seconds_count 2",
+    ] {
+        assert!(lines.contains(&expected), "missing {expected} in:\n{body}");
+    }
+
+    let body = metrics.render(None);
+    assert!(body.lines().any(|line| line == "glues_backend_up 0"));
+    assert!(!body.contains("glues_notebook_notes"));
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use {
+    common::unique_suffix,
+    glues_core::backend::{
+        CoreBackend,
+        proxy::{ProxyClient, TlsOptions},
+    },
+    rcgen::{
+        BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+        KeyUsagePurpose, SanType,
+    },
+    std::{
+        net::{IpAddr, Ipv4Addr},
+        path::PathBuf,
+    },
+    tokio::sync::oneshot,
+};
+
+struct Certs {
+    dir: PathBuf,
+    ca: String,
+    server: String,
+    server_key: String,
+    client: String,
+}
+
+/// Writes a CA with a server certificate for 127.0.0.1 and a client
+/// identity, both signed by it.
+fn generate_certs() -> Certs {
+    let dir = std::env::temp_dir().join(format!("glues-tls-{}", unique_suffix()));
+    std::fs::create_dir_all(&dir).expect("temp dir should be created");
+
+    let ca_key = KeyPair::generate().expect("CA key should generate");
+    let mut ca_params = CertificateParams::new(Vec::new()).expect("CA params should build");
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "Glues test CA");
+    ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+    let ca = ca_params.self_signed(&ca_key).expect("CA should self-sign");
+
+    let server_key = KeyPair::generate().expect("server key should generate");
+    let mut server_params = CertificateParams::new(Vec::new()).expect("server params should build");
+    server_params
+        .distinguished_name
+        .push(DnType::CommonName, "glues-server");
+    server_params.subject_alt_names = vec![SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST))];
+    server_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    let server = server_params
+        .signed_by(&server_key, &ca, &ca_key)
+        .expect("server certificate should be signed");
+
+    let client_key = KeyPair::generate().expect("client key should generate");
+    let mut client_params = CertificateParams::new(Vec::new()).expect("client params should build");
+    client_params
+        .distinguished_name
+        .push(DnType::CommonName, "glues-client");
+    client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+    let client = client_params
+        .signed_by(&client_key, &ca, &ca_key)
+        .expect("client certificate should be signed");
+
+    let write = |name: &str, pem: String| {
+        let path = dir.join(name);
+        std::fs::write(&path, pem).expect("PEM file should be written");
+        path.to_string_lossy().into_owned()
+    };
+
+    Certs {
+        ca: write("ca.pem", ca.pem()),
+        server: write("server.pem", server.pem()),
+        server_key: write("server-key.pem", server_key.serialize_pem()),
+        client: write(
+            "client.pem",
+            format!("{}{}", client.pem(), client_key.serialize_pem()),
+        ),
+        dir,
+    }
+}
+
+/// Starts an in-memory HTTPS server and returns its url with the sender
+/// stopping it.
+async fn start(certs: &Certs, client_ca: Option<&str>) -> (String, oneshot::Sender<()>) {
+    common::start(|args| {
+        args.tls_cert = Some(PathBuf::from(&certs.server));
+        args.tls_key = Some(PathBuf::from(&certs.server_key));
+        args.client_ca = client_ca.map(PathBuf::from);
+    })
+    .await
+}
+
+#[tokio::test]
+async fn proxy_over_https() {
+    let certs = generate_certs();
+    let (url, stop) = start(&certs, None).await;
+
+    ProxyClient::connect(&url, None)
+        .await
+        .err()
+        .expect("self-signed server should be rejected without its CA");
+
+    let tls = TlsOptions {
+        ca_cert: Some(certs.ca.clone()),
+        client_identity: None,
+    };
+    let mut client = ProxyClient::connect_with_tls(&url, None, tls)
+        .await
+        .expect("client should trust the server through its CA");
+    let root = client
+        .fetch_directory(client.root_id())
+        .await
+        .expect("root directory should be fetched over HTTPS");
+    assert_eq!(root.name, "Notes");
+
+    let _ = stop.send(());
+    let _ = std::fs::remove_dir_all(&certs.dir);
+}
+
+#[tokio::test]
+async fn proxy_requires_client_certificate() {
+    let certs = generate_certs();
+    let (url, stop) = start(&certs, Some(&certs.ca)).await;
+
+    let tls = TlsOptions {
+        ca_cert: Some(certs.ca.clone()),
+        client_identity: None,
+    };
+    ProxyClient::connect_with_tls(&url, None, tls.clone())
+        .await
+        .err()
+        .expect("client without a certificate should be rejected");
+
+    let tls = TlsOptions {
+        client_identity: Some(certs.client.clone()),
+        ..tls
+    };
+    let mut client = ProxyClient::connect_with_tls(&url, None, tls)
+        .await
+        .expect("client with a signed certificate should connect");
+    let notes = client
+        .fetch_notes(client.root_id())
+        .await
+        .expect("notes should be fetched with a client certificate");
+    assert!(notes.is_empty());
+
+    let _ = stop.send(());
+    let _ = std::fs::remove_dir_all(&certs.dir);
+}
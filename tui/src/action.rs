@@ -3,17 +3,33 @@ use {
         App,
         config::{
             self, LAST_FILE_PATH, LAST_GIT_BRANCH, LAST_GIT_PATH, LAST_GIT_REMOTE,
-            LAST_MONGO_CONN_STR, LAST_MONGO_DB_NAME, LAST_PROXY_URL, LAST_REDB_PATH,
+            LAST_MONGO_CONN_STR, LAST_MONGO_DB_NAME, LAST_PROXY_CA_CERT,
+            LAST_PROXY_CLIENT_IDENTITY, LAST_PROXY_URL, LAST_REDB_PATH,
         },
         context::{ContextPrompt, InfoDialog, QuitMenu},
+        external_editor::ExternalEdit,
         logger::*,
+        notifications::NotificationCenter,
         theme::THEME,
     },
-    crate::input::{Input, KeyCode},
-    glues_core::{EntryEvent, Event, KeyEvent, NotebookEvent, NumKey, state::EntryState},
+    crate::input::{Input, KeyCode, MouseEvent},
+    glues_core::{
+        EntryEvent, Event, KeyEvent, NotebookEvent, NumKey,
+        backend::{CoreBackend, Resolution, proxy::TlsOptions},
+        backup,
+        state::EntryState,
+        types::AttachmentId,
+    },
     ratatui::{style::Stylize, text::Line},
+    std::path::{Path, PathBuf},
 };
 
+/// Suggested file name when an Instant notebook is backed up on quit.
+const BACKUP_FILE: &str = "glues-backup.json";
+
+/// Offline write queue of proxy notebooks, kept in the config directory.
+pub(crate) const PROXY_QUEUE_FILE: &str = "proxy_queue.redb";
+
 #[derive(Clone)]
 pub enum Action {
     Tui(TuiAction),
@@ -41,8 +57,18 @@ pub enum TuiAction {
     },
     Help,
     OpenThemeMenu,
+    ShowNotifications,
     ShowEditorKeymap,
     SaveAndPassThrough,
+    /// Click, drag or wheel over the note tree, the tabs or the editor.
+    Mouse(MouseEvent),
+    /// Edits made in the external editor, confirmed over a backend change.
+    ApplyExternalEdit {
+        edit: ExternalEdit,
+        content: String,
+    },
+    RecoverSwap(usize),
+    DiscardSwap(usize),
     OpenNotebookQuitMenu {
         save_before_open: bool,
     },
@@ -54,6 +80,16 @@ pub enum TuiAction {
     OpenGit(OpenGitStep),
     OpenMongo(OpenMongoStep),
     OpenProxy(OpenProxyStep),
+    OpenEncrypted(Box<EntryEvent>),
+    /// Passphrase entered again to encrypt a plain notebook.
+    EncryptNotebook {
+        open: Box<EntryEvent>,
+        passphrase: String,
+    },
+    /// Index into the recent notebooks of the entry menu.
+    OpenRecent(usize),
+    TogglePinRecent(usize),
+    ForgetRecent(usize),
 
     RenameNote,
     RemoveNote,
@@ -61,6 +97,11 @@ pub enum TuiAction {
     AddDirectory,
     RenameDirectory,
     RemoveDirectory,
+    BackupNotebook,
+    Sync,
+    ResolveSyncConflict(Resolution),
+    AddAttachment,
+    SaveAttachment(AttachmentId),
     ShowInfo {
         title: String,
         lines: Vec<Line<'static>>,
@@ -76,7 +117,18 @@ pub enum OpenMongoStep {
 #[derive(Clone)]
 pub enum OpenProxyStep {
     Url,
-    Token { url: String },
+    /// Only asked for `https://` urls, like `ClientIdentity`.
+    CaCert {
+        url: String,
+    },
+    ClientIdentity {
+        url: String,
+        ca_cert: Option<String>,
+    },
+    Token {
+        url: String,
+        tls: TlsOptions,
+    },
 }
 
 #[derive(Clone)]
@@ -102,6 +154,7 @@ impl App {
     pub async fn handle_action(&mut self, action: Action, input: Input) -> bool {
         match action {
             Action::Tui(TuiAction::Quit) => {
+                self.save_session().await;
                 return true;
             }
             Action::Tui(TuiAction::Help) => {
@@ -111,6 +164,10 @@ impl App {
                 self.context.theme_selector =
                     Some(crate::context::theme_selector::ThemeSelector::new());
             }
+            Action::Tui(TuiAction::ShowNotifications) => {
+                self.context.notification_center = Some(NotificationCenter::new());
+                self.context.notifications.mark_read();
+            }
             Action::Tui(TuiAction::ShowEditorKeymap) => {
                 self.context.editor_keymap = true;
             }
@@ -118,7 +175,7 @@ impl App {
                 self.context.info = Some(InfoDialog::new(title, lines));
             }
             Action::Tui(TuiAction::Alert(message)) => {
-                self.context.alert = Some(message);
+                self.context.show_alert(message);
             }
             Action::Tui(TuiAction::Confirm { message, action }) => {
                 self.context.confirm = Some((message, *action));
@@ -134,17 +191,32 @@ impl App {
 
                 let quit_action = Action::Tui(TuiAction::Quit);
                 let menu_action = Action::Tui(TuiAction::ReturnToEntry);
-                self.context.quit_menu = Some(QuitMenu::new(
-                    "Leave the notebook?",
-                    quit_action,
-                    menu_action,
-                ));
+                let mut menu = QuitMenu::new("Leave the notebook?", quit_action, menu_action);
+                if self.context.notebook.instant {
+                    menu.message =
+                        "Leave the notebook? Instant notes are gone once you leave.".to_owned();
+                    menu = menu.with_backup(
+                        TuiAction::Prompt {
+                            message: vec![Line::raw("Enter the path of the backup file:")],
+                            action: Box::new(TuiAction::BackupNotebook.into()),
+                            default: Some(BACKUP_FILE.to_owned()),
+                        }
+                        .into(),
+                    );
+                }
+                self.context.quit_menu = Some(menu);
             }
             Action::Tui(TuiAction::ReturnToEntry) => {
+                self.save_session().await;
+                self.notebook = None;
+
+                let keybindings = std::mem::take(&mut self.context.keybindings);
                 self.context = crate::context::Context::default();
+                self.context.keybindings = keybindings;
 
                 self.glues.db = None;
                 self.glues.state = EntryState.into();
+                self.load_recent().await;
             }
             Action::Tui(TuiAction::Prompt {
                 message,
@@ -191,19 +263,11 @@ impl App {
                     .context
                     .take_prompt_input()
                     .log_expect("branch must not be none");
-                let transition = self
-                    .glues
-                    .dispatch(
-                        EntryEvent::OpenGit {
-                            path,
-                            remote,
-                            branch,
-                        }
-                        .into(),
-                    )
-                    .await
-                    .log_unwrap();
-                self.handle_transition(transition).await;
+                self.prompt_passphrase(EntryEvent::OpenGit {
+                    path,
+                    remote,
+                    branch,
+                });
             }
             Action::Tui(TuiAction::OpenMongo(OpenMongoStep::ConnStr)) => {
                 let conn_str = self
@@ -230,12 +294,7 @@ impl App {
 
                 config::update(LAST_MONGO_DB_NAME, &db_name).await;
 
-                let transition = self
-                    .glues
-                    .dispatch(EntryEvent::OpenMongo { conn_str, db_name }.into())
-                    .await
-                    .log_unwrap();
-                self.handle_transition(transition).await;
+                self.prompt_passphrase(EntryEvent::OpenMongo { conn_str, db_name });
             }
             Action::Tui(TuiAction::OpenProxy(OpenProxyStep::Url)) => {
                 let url = self
@@ -245,17 +304,58 @@ impl App {
 
                 config::update(LAST_PROXY_URL, &url).await;
 
+                if !url.starts_with("https://") {
+                    self.prompt_proxy_token(url, TlsOptions::default());
+                    return false;
+                }
+
                 let message = vec![
-                    Line::raw("Enter the authentication token (optional):"),
-                    Line::from("Leave empty to connect without a token.".fg(THEME.hint)),
+                    Line::raw("Enter the CA bundle path (optional):"),
+                    Line::from("Leave empty to trust only the built-in roots.".fg(THEME.hint)),
+                    Line::from("Needed for servers with self-signed certificates.".fg(THEME.hint)),
+                ];
+                let action = TuiAction::OpenProxy(OpenProxyStep::CaCert { url }).into();
+                let default = config::get(LAST_PROXY_CA_CERT).await;
+                self.context.prompt = Some(ContextPrompt::new(message, action, default));
+            }
+            Action::Tui(TuiAction::OpenProxy(OpenProxyStep::CaCert { url })) => {
+                let ca_cert = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("proxy CA bundle path must not be none");
+
+                config::update(LAST_PROXY_CA_CERT, &ca_cert).await;
+
+                let message = vec![
+                    Line::raw("Enter the client identity path (optional):"),
                     Line::from(
-                        "Servers without authentication accept empty tokens.".fg(THEME.hint),
+                        "A PEM file with the client certificate and its key.".fg(THEME.hint),
                     ),
+                    Line::from("Leave empty unless the server asks for one.".fg(THEME.hint)),
                 ];
-                let action = TuiAction::OpenProxy(OpenProxyStep::Token { url }).into();
-                self.context.prompt = Some(ContextPrompt::new_masked(message, action, None, '*'));
+                let action = TuiAction::OpenProxy(OpenProxyStep::ClientIdentity {
+                    url,
+                    ca_cert: non_empty(ca_cert),
+                })
+                .into();
+                let default = config::get(LAST_PROXY_CLIENT_IDENTITY).await;
+                self.context.prompt = Some(ContextPrompt::new(message, action, default));
             }
-            Action::Tui(TuiAction::OpenProxy(OpenProxyStep::Token { url })) => {
+            Action::Tui(TuiAction::OpenProxy(OpenProxyStep::ClientIdentity { url, ca_cert })) => {
+                let client_identity = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("proxy client identity path must not be none");
+
+                config::update(LAST_PROXY_CLIENT_IDENTITY, &client_identity).await;
+
+                let tls = TlsOptions {
+                    ca_cert,
+                    client_identity: non_empty(client_identity),
+                };
+                self.prompt_proxy_token(url, tls);
+            }
+            Action::Tui(TuiAction::OpenProxy(OpenProxyStep::Token { url, tls })) => {
                 let token_input = self
                     .context
                     .take_prompt_input()
@@ -264,18 +364,66 @@ impl App {
                 let token = token_input.trim().to_owned();
                 let auth_token = if token.is_empty() { None } else { Some(token) };
 
-                match self
-                    .glues
-                    .dispatch(EntryEvent::OpenProxy { url, auth_token }.into())
-                    .await
-                {
-                    Ok(transition) => {
-                        self.handle_transition(transition).await;
-                    }
-                    Err(err) => {
-                        self.context.alert = Some(err.to_string());
+                let queue_path = config::config_dir().join(PROXY_QUEUE_FILE);
+
+                self.prompt_passphrase(EntryEvent::OpenProxy {
+                    url,
+                    auth_token,
+                    tls,
+                    queue_path: Some(queue_path.to_string_lossy().into_owned()),
+                });
+            }
+            Action::Tui(TuiAction::OpenEncrypted(event)) => {
+                let passphrase = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("passphrase must not be none");
+                let event = if passphrase.is_empty() {
+                    *event
+                } else {
+                    EntryEvent::OpenEncrypted {
+                        open: event,
+                        passphrase,
                     }
+                };
+
+                self.open_notebook(event).await;
+            }
+            Action::Tui(TuiAction::EncryptNotebook { open, passphrase }) => {
+                let confirmation = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("passphrase must not be none");
+                if confirmation != passphrase {
+                    self.context
+                        .show_alert("Passphrases do not match, the notebook was left unencrypted");
+                    return false;
                 }
+
+                self.open_notebook(EntryEvent::EncryptNotebook { open, passphrase })
+                    .await;
+            }
+            Action::Tui(TuiAction::Mouse(mouse)) => {
+                self.handle_mouse(mouse).await;
+            }
+            Action::Tui(TuiAction::ApplyExternalEdit { edit, content }) => {
+                self.apply_external_edit(edit, content).await;
+            }
+            Action::Tui(TuiAction::RecoverSwap(i)) => {
+                self.recover_swap(i).await;
+            }
+            Action::Tui(TuiAction::DiscardSwap(i)) => {
+                self.discard_swap(i).await;
+            }
+            Action::Tui(TuiAction::OpenRecent(i)) => {
+                let notebook = self.context.entry.recent[i].notebook.clone();
+                self.reopen(notebook).await;
+            }
+            Action::Tui(TuiAction::TogglePinRecent(i)) => {
+                self.toggle_pin_recent(i).await;
+            }
+            Action::Tui(TuiAction::ForgetRecent(i)) => {
+                self.forget_recent(i).await;
             }
             Action::Tui(TuiAction::OpenRedb) => {
                 let path = self
@@ -283,18 +431,13 @@ impl App {
                     .take_prompt_input()
                     .log_expect("redb path prompt must not be none");
                 if path.is_empty() {
-                    self.context.alert = Some("The redb path cannot be empty".to_string());
+                    self.context.show_alert("The redb path cannot be empty");
                     return false;
                 }
 
                 config::update(LAST_REDB_PATH, &path).await;
 
-                let transition = self
-                    .glues
-                    .dispatch(EntryEvent::OpenRedb(path).into())
-                    .await
-                    .log_unwrap();
-                self.handle_transition(transition).await;
+                self.prompt_passphrase(EntryEvent::OpenRedb(path));
             }
             Action::Tui(TuiAction::OpenFile) => {
                 let path = self
@@ -302,18 +445,13 @@ impl App {
                     .take_prompt_input()
                     .log_expect("prompt must not be none");
                 if path.is_empty() {
-                    self.context.alert = Some("Path cannot be empty".to_string());
+                    self.context.show_alert("Path cannot be empty");
                     return false;
                 }
 
                 config::update(LAST_FILE_PATH, &path).await;
 
-                let transition = self
-                    .glues
-                    .dispatch(EntryEvent::OpenFile(path).into())
-                    .await
-                    .log_unwrap();
-                self.handle_transition(transition).await;
+                self.prompt_passphrase(EntryEvent::OpenFile(path));
             }
             Action::Tui(TuiAction::RenameNote) => {
                 let new_name = self
@@ -321,7 +459,7 @@ impl App {
                     .take_prompt_input()
                     .log_expect("prompt must not be none");
                 if new_name.is_empty() {
-                    self.context.alert = Some("Note name cannot be empty".to_string());
+                    self.context.show_alert("Note name cannot be empty");
                     return false;
                 }
 
@@ -340,13 +478,123 @@ impl App {
                     .log_unwrap();
                 self.handle_transition(transition).await;
             }
+            Action::Tui(TuiAction::BackupNotebook) => {
+                let path = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("prompt must not be none");
+                if path.is_empty() {
+                    self.context.show_alert("Path cannot be empty");
+                    return false;
+                }
+
+                let db = self.glues.db.as_mut().log_expect("notebook must be open");
+                let result = match backup::dump(db).await.and_then(|archive| archive.to_json()) {
+                    Ok(json) => std::fs::write(&path, json).map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+
+                match result {
+                    Ok(()) => self
+                        .context
+                        .show_info(format!("Backed up the notebook to {path}")),
+                    Err(err) => self.context.show_alert(format!("Backup failed: {err}")),
+                }
+            }
+            Action::Tui(TuiAction::Sync) => {
+                if !self.schedule_sync() {
+                    self.context
+                        .show_alert("This notebook has no remote to sync with");
+                }
+            }
+            Action::Tui(TuiAction::ResolveSyncConflict(resolution)) => {
+                self.resolve_sync_conflict(resolution);
+            }
+            Action::Tui(TuiAction::AddAttachment) => {
+                let path = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("prompt must not be none");
+                if path.is_empty() {
+                    self.context.show_alert("Path cannot be empty");
+                    return false;
+                }
+
+                let path = Path::new(&path);
+                let Some(name) = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                else {
+                    self.context
+                        .show_alert(format!("Not a file: {}", path.display()));
+                    return false;
+                };
+                let data = match std::fs::read(path) {
+                    Ok(data) => data,
+                    Err(err) => {
+                        self.context
+                            .show_alert(format!("Cannot read {}: {err}", path.display()));
+                        return false;
+                    }
+                };
+
+                let transition = self
+                    .glues
+                    .dispatch(NotebookEvent::AddAttachment { name, data }.into())
+                    .await
+                    .log_unwrap();
+                self.handle_transition(transition).await;
+            }
+            Action::Tui(TuiAction::SaveAttachment(attachment_id)) => {
+                let path = self
+                    .context
+                    .take_prompt_input()
+                    .log_expect("prompt must not be none");
+                if path.is_empty() {
+                    self.context.show_alert("Path cannot be empty");
+                    return false;
+                }
+
+                let Some(name) = self
+                    .context
+                    .notebook
+                    .attachments
+                    .iter()
+                    .find(|attachment| attachment.id == attachment_id)
+                    .map(|attachment| attachment.name.clone())
+                else {
+                    self.context.show_alert("The attachment no longer exists");
+                    return false;
+                };
+
+                // a directory path keeps the attachment name
+                let mut path = PathBuf::from(path);
+                if path.is_dir() {
+                    path.push(&name);
+                }
+
+                let db = self.glues.db.as_mut().log_expect("notebook must be open");
+                let result = match db.fetch_attachment_data(attachment_id).await {
+                    Ok(data) => std::fs::write(&path, data).map_err(|err| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+
+                match result {
+                    Ok(()) => self
+                        .context
+                        .show_info(format!("Saved {name} to {}", path.display())),
+                    Err(err) => self
+                        .context
+                        .show_alert(format!("Cannot write {}: {err}", path.display())),
+                }
+            }
             Action::Tui(TuiAction::AddNote) => {
                 let note_name = self
                     .context
                     .take_prompt_input()
                     .log_expect("prompt must not be none");
                 if note_name.is_empty() {
-                    self.context.alert = Some("Note name cannot be empty".to_string());
+                    self.context.show_alert("Note name cannot be empty");
                     return false;
                 }
 
@@ -363,7 +611,7 @@ impl App {
                     .take_prompt_input()
                     .log_expect("prompt must not be none");
                 if directory_name.is_empty() {
-                    self.context.alert = Some("Directory name cannot be empty".to_string());
+                    self.context.show_alert("Directory name cannot be empty");
                     return false;
                 }
 
@@ -380,7 +628,7 @@ impl App {
                     .take_prompt_input()
                     .log_expect("prompt must not be none");
                 if new_name.is_empty() {
-                    self.context.alert = Some("Directory name cannot be empty".to_string());
+                    self.context.show_alert("Directory name cannot be empty");
                     return false;
                 }
 
@@ -400,8 +648,10 @@ impl App {
                 self.handle_transition(transition).await;
             }
             Action::Dispatch(event) => {
+                let instant = matches!(event, Event::Entry(EntryEvent::OpenMemory));
                 let transition = self.glues.dispatch(event).await.log_unwrap();
                 self.handle_transition(transition).await;
+                self.context.notebook.instant |= instant;
             }
 
             Action::Tui(TuiAction::SaveAndPassThrough) => {
@@ -433,6 +683,47 @@ impl App {
 
         false
     }
+
+    /// Last step of every persistent storage flow, before the notebook is opened.
+    pub(crate) fn prompt_proxy_token(&mut self, url: String, tls: TlsOptions) {
+        let message = vec![
+            Line::raw("Enter the authentication token (optional):"),
+            Line::from("Leave empty to connect without a token.".fg(THEME.hint)),
+            Line::from("Servers without authentication accept empty tokens.".fg(THEME.hint)),
+        ];
+        let action = TuiAction::OpenProxy(OpenProxyStep::Token { url, tls }).into();
+        self.context.prompt = Some(ContextPrompt::new_masked(message, action, None, '*'));
+    }
+
+    pub(crate) fn prompt_passphrase(&mut self, event: EntryEvent) {
+        let message = vec![
+            Line::raw("Enter the notebook passphrase (optional):"),
+            Line::from("Leave empty to open an unencrypted notebook.".fg(THEME.hint)),
+        ];
+        let action = TuiAction::OpenEncrypted(Box::new(event)).into();
+        self.context.prompt = Some(ContextPrompt::new_masked(message, action, None, '*'));
+    }
+
+    /// Asked when a passphrase was given for a plain notebook. Encrypting
+    /// rewrites every note, so it only happens once the passphrase is repeated.
+    pub(crate) fn prompt_encryption(&mut self, open: EntryEvent, passphrase: String) {
+        let message = vec![
+            Line::raw("This notebook is not encrypted."),
+            Line::raw("Enter the passphrase again to encrypt it:"),
+            Line::from("It cannot be opened without the passphrase afterwards.".fg(THEME.hint)),
+        ];
+        let action = TuiAction::EncryptNotebook {
+            open: Box::new(open),
+            passphrase,
+        }
+        .into();
+        self.context.prompt = Some(ContextPrompt::new_masked(message, action, None, '*'));
+    }
+}
+
+fn non_empty(path: String) -> Option<String> {
+    let path = path.trim();
+    (!path.is_empty()).then(|| path.to_owned())
 }
 
 fn to_event(input: Input) -> Option<KeyEvent> {
@@ -445,12 +736,16 @@ fn to_event(input: Input) -> Option<KeyEvent> {
 
     let event = match code {
         KeyCode::Char('h') if ctrl => KeyEvent::CtrlH,
+        KeyCode::Char('i') if ctrl => KeyEvent::CtrlI,
+        KeyCode::Char('o') if ctrl => KeyEvent::CtrlO,
         KeyCode::Char('r') if ctrl => KeyEvent::CtrlR,
+        KeyCode::Char('v') if ctrl => KeyEvent::CtrlV,
         KeyCode::Char('a') => KeyEvent::A,
         KeyCode::Char('b') => KeyEvent::B,
         KeyCode::Char('c') => KeyEvent::C,
         KeyCode::Char('d') => KeyEvent::D,
         KeyCode::Char('e') => KeyEvent::E,
+        KeyCode::Char('f') => KeyEvent::F,
         KeyCode::Char('g') => KeyEvent::G,
         KeyCode::Char('h') => KeyEvent::H,
         KeyCode::Char('i') => KeyEvent::I,
@@ -470,6 +765,7 @@ fn to_event(input: Input) -> Option<KeyEvent> {
         KeyCode::Char('y') => KeyEvent::Y,
         KeyCode::Char('z') => KeyEvent::Z,
         KeyCode::Char('A') => KeyEvent::CapA,
+        KeyCode::Char('F') => KeyEvent::CapF,
         KeyCode::Char('G') => KeyEvent::CapG,
         KeyCode::Char('H') => KeyEvent::CapH,
         KeyCode::Char('I') => KeyEvent::CapI,
@@ -478,7 +774,9 @@ fn to_event(input: Input) -> Option<KeyEvent> {
         KeyCode::Char('L') => KeyEvent::CapL,
         KeyCode::Char('O') => KeyEvent::CapO,
         KeyCode::Char('S') => KeyEvent::CapS,
+        KeyCode::Char('T') => KeyEvent::CapT,
         KeyCode::Char('U') => KeyEvent::CapU,
+        KeyCode::Char('V') => KeyEvent::CapV,
         KeyCode::Char('X') => KeyEvent::CapX,
         KeyCode::Char('1') => NumKey::One.into(),
         KeyCode::Char('2') => NumKey::Two.into(),
@@ -496,9 +794,22 @@ fn to_event(input: Input) -> Option<KeyEvent> {
         KeyCode::Char('?') => KeyEvent::QuestionMark,
         KeyCode::Char('<') => KeyEvent::AngleBracketOpen,
         KeyCode::Char('>') => KeyEvent::AngleBracketClose,
+        KeyCode::Char('(') => KeyEvent::ParenOpen,
+        KeyCode::Char(')') => KeyEvent::ParenClose,
+        KeyCode::Char('[') => KeyEvent::SquareBracketOpen,
+        KeyCode::Char(']') => KeyEvent::SquareBracketClose,
+        KeyCode::Char('{') => KeyEvent::CurlyBracketOpen,
+        KeyCode::Char('}') => KeyEvent::CurlyBracketClose,
+        KeyCode::Char('\'') => KeyEvent::SingleQuote,
+        KeyCode::Char('"') => KeyEvent::DoubleQuote,
+        KeyCode::Char('`') => KeyEvent::Backtick,
+        KeyCode::Char(';') => KeyEvent::Semicolon,
+        KeyCode::Char(',') => KeyEvent::Comma,
+        KeyCode::Char('%') => KeyEvent::Percent,
         KeyCode::Char('.') => KeyEvent::Dot,
         KeyCode::Char('-') => KeyEvent::Dash,
         KeyCode::Char(' ') => KeyEvent::Space,
+        KeyCode::Char(ch) if !ctrl => KeyEvent::Char(ch),
         KeyCode::Left => KeyEvent::Left,
         KeyCode::Right => KeyEvent::Right,
         KeyCode::Up => KeyEvent::Up,
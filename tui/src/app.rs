@@ -1,5 +1,5 @@
 use {
-    crate::{context::Context, views},
+    crate::{context::Context, session::NotebookRef, views},
     glues_core::Glues,
     ratatui::Frame,
 };
@@ -8,8 +8,14 @@ use {
     crate::{
         input::{Input, KeyCode, KeyEvent, KeyEventKind},
         logger::*,
+        notifications::Severity,
+    },
+    glues_core::{
+        Error,
+        backend::{CoreBackend, GitSync, Resolution, SyncJob, SyncTrigger, layer::LayerStack},
+        transition::Transition,
+        types::NoteId,
     },
-    glues_core::transition::Transition,
     ratatui::DefaultTerminal,
     std::{
         collections::VecDeque,
@@ -17,7 +23,7 @@ use {
             Arc, Mutex,
             atomic::{AtomicBool, Ordering},
         },
-        time::Duration,
+        time::{Duration, Instant},
     },
     tokio::{self, task},
 };
@@ -28,6 +34,18 @@ pub struct App {
     bg_transitions: Arc<Mutex<VecDeque<Transition>>>,
     sync_in_progress: Arc<AtomicBool>,
     sync_pending: Arc<AtomicBool>,
+    /// Set by a sync or merge once it finished, so the backend can drop what
+    /// it changed underneath.
+    sync_finished: Arc<AtomicBool>,
+    /// Git sync stopped at a merge conflict, kept until the user resolves it.
+    sync_conflict: Arc<Mutex<Option<GitSync>>>,
+    last_sync: Instant,
+    /// The open notebook, unless it is an Instant one.
+    pub(crate) notebook: Option<NotebookRef>,
+    pub(crate) restore_session: bool,
+    pub(crate) mouse_capture: bool,
+    /// Note to hand to the external editor once the current input is handled.
+    pub(crate) external_edit: Option<NoteId>,
 }
 
 impl Default for App {
@@ -43,6 +61,8 @@ impl App {
         let bg_transitions = Arc::new(Mutex::new(VecDeque::new()));
         let sync_in_progress = Arc::new(AtomicBool::new(false));
         let sync_pending = Arc::new(AtomicBool::new(false));
+        let sync_finished = Arc::new(AtomicBool::new(false));
+        let sync_conflict = Arc::new(Mutex::new(None));
 
         Self {
             glues,
@@ -50,9 +70,21 @@ impl App {
             bg_transitions,
             sync_in_progress,
             sync_pending,
+            sync_finished,
+            sync_conflict,
+            last_sync: Instant::now(),
+            notebook: None,
+            restore_session: false,
+            mouse_capture: false,
+            external_edit: None,
         }
     }
 
+    /// Wraps every notebook opened from the entry screen in `layers`.
+    pub fn set_backend_layers(&mut self, layers: LayerStack) {
+        self.glues.layers = layers;
+    }
+
     #[doc(hidden)]
     pub fn glues_mut(&mut self) -> &mut Glues {
         // Test-only escape hatch. Use this to simulate external backend/state
@@ -62,7 +94,19 @@ impl App {
         &mut self.glues
     }
 
+    /// Captures the mouse while the app runs, see [`crate::config::MOUSE`].
+    pub fn set_mouse_capture(&mut self, capture: bool) {
+        self.mouse_capture = capture;
+    }
+
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
+        self.capture_mouse(true)?;
+        let result = self.event_loop(&mut terminal).await;
+        self.capture_mouse(false)?;
+        result
+    }
+
+    async fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> color_eyre::Result<()> {
         use ratatui::crossterm as ct;
 
         loop {
@@ -92,7 +136,7 @@ impl App {
                 Input::Key(KeyEvent {
                     kind: KeyEventKind::Press,
                     ..
-                })
+                }) | Input::Mouse(_)
             ) {
                 continue;
             }
@@ -104,17 +148,24 @@ impl App {
                     ..
                 }) if modifiers.ctrl => {
                     self.save().await;
+                    self.save_session().await;
                     return Ok(());
                 }
                 _ => {
+                    let input = self.context.remap(input);
                     let action = self.context.consume(&input).await;
                     let quit = self.handle_action(action, input).await;
                     if quit {
                         return Ok(());
                     }
+                    self.update_swap().await;
                 }
             }
 
+            if let Some(note_id) = self.take_external_edit() {
+                self.edit_externally(terminal, note_id).await?;
+            }
+
             self.process_background().await;
         }
     }
@@ -126,11 +177,23 @@ impl App {
         };
 
         let state = &self.glues.state;
+        let connection = self
+            .glues
+            .db
+            .as_ref()
+            .and_then(|backend| backend.connection_status());
         let context = &mut self.context;
         let vertical = Layout::vertical([Length(1), Percentage(100)]);
         let [statusbar, body] = vertical.areas(frame.area());
 
-        views::statusbar::draw(frame, statusbar, state, &context.notebook);
+        views::statusbar::draw(
+            frame,
+            statusbar,
+            state,
+            connection,
+            context.notifications.unread_errors(),
+            &context.notebook,
+        );
         views::body::draw(frame, body, context);
         views::dialog::draw(frame, state, context);
     }
@@ -140,6 +203,18 @@ impl App {
     }
 
     async fn process_background(&mut self) {
+        if self.sync_finished.swap(false, Ordering::AcqRel)
+            && let Some(db) = self.glues.db.as_mut()
+        {
+            db.sync_finished();
+        }
+
+        if let Some(db) = self.glues.db.as_mut() {
+            for message in db.take_rejected_writes() {
+                self.context.notify(Severity::Error, message);
+            }
+        }
+
         let mut transitions = Vec::new();
 
         {
@@ -154,31 +229,79 @@ impl App {
         }
 
         self.flush_pending_sync();
+        self.maybe_schedule_interval_sync();
     }
 
+    /// Syncs after a change, unless the notebook syncs on an interval or manually.
     pub(crate) fn maybe_schedule_sync(&mut self) {
-        if self.sync_in_progress.swap(true, Ordering::AcqRel) {
-            self.sync_pending.store(true, Ordering::Release);
-            return;
+        let on_change = self
+            .glues
+            .db
+            .as_ref()
+            .is_some_and(|backend| backend.sync_trigger() == SyncTrigger::OnChange);
+
+        if on_change {
+            self.schedule_sync();
         }
+    }
 
-        let Some(backend) = self.glues.db.as_mut() else {
+    fn maybe_schedule_interval_sync(&mut self) {
+        let Some(SyncTrigger::Interval(interval)) =
+            self.glues.db.as_ref().map(|backend| backend.sync_trigger())
+        else {
             return;
         };
-        let Some(job) = backend.sync_job() else {
-            return;
+
+        if self.last_sync.elapsed() >= interval {
+            self.schedule_sync();
+        }
+    }
+
+    /// Starts a sync now, or once the running one finishes. Returns `false`
+    /// when the notebook has nothing to sync with.
+    pub(crate) fn schedule_sync(&mut self) -> bool {
+        if self.sync_conflict.lock().log_unwrap().is_some() {
+            // the merge in progress must be resolved first
+            return true;
+        }
+
+        if self.sync_in_progress.swap(true, Ordering::AcqRel) {
+            self.sync_pending.store(true, Ordering::Release);
+            return true;
+        }
+
+        let Some(job) = self
+            .glues
+            .db
+            .as_mut()
+            .and_then(|backend| backend.sync_job())
+        else {
+            self.sync_in_progress.store(false, Ordering::Release);
+            return false;
         };
 
+        self.last_sync = Instant::now();
         self.sync_pending.store(false, Ordering::Release);
         let queue = Arc::clone(&self.bg_transitions);
         let flag = Arc::clone(&self.sync_in_progress);
+        let finished = Arc::clone(&self.sync_finished);
+        let conflicted = Arc::clone(&self.sync_conflict);
 
         tokio::spawn(async move {
+            let git = match &job {
+                SyncJob::Git(sync) => Some(sync.clone()),
+                SyncJob::Proxy { .. } => None,
+            };
             let result = task::spawn_blocking(move || job.run()).await;
+            finished.store(true, Ordering::Release);
             let transition = match result {
                 Ok(Ok(())) => {
                     Transition::Log("Sync complete. Your notes are up to date.".to_owned())
                 }
+                Ok(Err(Error::SyncConflict(conflict))) => {
+                    *conflicted.lock().log_unwrap() = git;
+                    Transition::SyncConflict(conflict)
+                }
                 Ok(Err(err)) => Transition::Error(err.to_string()),
                 Err(join_err) => Transition::Error(format!("Sync task panicked: {join_err}")),
             };
@@ -190,6 +313,36 @@ impl App {
 
             flag.store(false, Ordering::Release);
         });
+
+        true
+    }
+
+    /// Finishes the conflicted merge in the background.
+    pub(crate) fn resolve_sync_conflict(&mut self, resolution: Resolution) {
+        let Some(sync) = self.sync_conflict.lock().log_unwrap().take() else {
+            return;
+        };
+
+        self.sync_in_progress.store(true, Ordering::Release);
+        let queue = Arc::clone(&self.bg_transitions);
+        let flag = Arc::clone(&self.sync_in_progress);
+        let finished = Arc::clone(&self.sync_finished);
+
+        tokio::spawn(async move {
+            let result = task::spawn_blocking(move || sync.resolve(resolution)).await;
+            finished.store(true, Ordering::Release);
+            let transition = match result {
+                Ok(Ok(())) if resolution == Resolution::Abort => {
+                    Transition::Log("Merge aborted. Local notes are unchanged.".to_owned())
+                }
+                Ok(Ok(())) => Transition::Log("Sync conflict resolved.".to_owned()),
+                Ok(Err(err)) => Transition::Error(err.to_string()),
+                Err(join_err) => Transition::Error(format!("Sync task panicked: {join_err}")),
+            };
+
+            queue.lock().log_unwrap().push_back(transition);
+            flag.store(false, Ordering::Release);
+        });
     }
 
     fn flush_pending_sync(&mut self) {
@@ -201,6 +354,6 @@ impl App {
             return;
         }
 
-        self.maybe_schedule_sync();
+        self.schedule_sync();
     }
 }
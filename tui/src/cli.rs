@@ -1,9 +1,153 @@
 use {
-    crate::{App, config, logger, theme},
-    color_eyre::Result,
+    crate::{
+        App,
+        action::PROXY_QUEUE_FILE,
+        config::{
+            self, LAST_GIT_BRANCH, LAST_GIT_REMOTE, LAST_MONGO_DB_NAME, LAST_PROXY_CA_CERT,
+            LAST_PROXY_CLIENT_IDENTITY, MOUSE, RESTORE_SESSION,
+        },
+        keybindings::Keybindings,
+        logger, session, theme,
+    },
+    clap::Parser,
+    color_eyre::{Result, eyre::eyre},
+    glues_core::{EntryEvent, backend::proxy::TlsOptions},
+    std::str::FromStr,
 };
 
-pub async fn run() -> Result<()> {
+#[derive(Clone, Debug, Default, Parser)]
+#[command(author, version, about = "TUI frontend for Glues", long_about = None)]
+pub struct TuiArgs {
+    /// Notebook to open instead of the entry menu: instant, file:PATH,
+    /// redb:PATH, git:PATH, mongo:CONN_STR or proxy:URL
+    #[arg(long, value_name = "NOTEBOOK")]
+    pub open: Option<OpenSpec>,
+
+    /// Passphrase of an encrypted notebook given with --open
+    #[arg(long, env = "GLUES_PASSPHRASE", hide_env_values = true)]
+    pub passphrase: Option<String>,
+
+    /// Encrypt the plain notebook given with --open using --passphrase
+    #[arg(long, requires = "passphrase")]
+    pub encrypt: bool,
+
+    /// Token of a proxy given with --open
+    #[arg(long, env = "GLUES_AUTH_TOKEN", hide_env_values = true)]
+    pub auth_token: Option<String>,
+
+    /// Reopen the last notebook with its directories, tabs and cursors, like
+    /// the restore_session config
+    #[arg(long)]
+    pub restore_session: bool,
+
+    /// Capture the mouse, like the mouse config
+    #[arg(long)]
+    pub mouse: bool,
+}
+
+/// Storage named by `--open`. Git, MongoDB and proxy notebooks take the rest
+/// of their settings from the ones last entered in the entry menu.
+#[derive(Clone, Debug)]
+pub enum OpenSpec {
+    Instant,
+    File(String),
+    Redb(String),
+    Git(String),
+    Mongo(String),
+    Proxy(String),
+}
+
+impl FromStr for OpenSpec {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "instant" {
+            return Ok(Self::Instant);
+        }
+
+        let (kind, target) = value
+            .split_once(':')
+            .ok_or_else(|| format!("expected KIND:TARGET or instant, got '{value}'"))?;
+        if target.is_empty() {
+            return Err(format!("{kind} needs a target, e.g. {kind}:notes"));
+        }
+
+        let target = target.to_owned();
+        match kind {
+            "file" => Ok(Self::File(target)),
+            "redb" => Ok(Self::Redb(target)),
+            "git" => Ok(Self::Git(target)),
+            "mongo" => Ok(Self::Mongo(target)),
+            "proxy" => Ok(Self::Proxy(target)),
+            _ => Err(format!(
+                "unknown notebook kind '{kind}', expected file, redb, git, mongo or proxy"
+            )),
+        }
+    }
+}
+
+impl TuiArgs {
+    async fn entry_event(self) -> Result<Option<EntryEvent>> {
+        let Some(open) = self.open else {
+            return Ok(None);
+        };
+        let last = |key| async move { config::get(key).await.unwrap_or_default() };
+
+        let event = match open {
+            OpenSpec::Instant => return Ok(Some(EntryEvent::OpenMemory)),
+            OpenSpec::File(path) => EntryEvent::OpenFile(path),
+            OpenSpec::Redb(path) => EntryEvent::OpenRedb(path),
+            OpenSpec::Git(path) => EntryEvent::OpenGit {
+                path,
+                remote: last(LAST_GIT_REMOTE).await,
+                branch: last(LAST_GIT_BRANCH).await,
+            },
+            OpenSpec::Mongo(conn_str) => {
+                let db_name = last(LAST_MONGO_DB_NAME).await;
+                if db_name.is_empty() {
+                    return Err(eyre!(
+                        "no MongoDB database name yet, open the notebook once from the menu"
+                    ));
+                }
+
+                EntryEvent::OpenMongo { conn_str, db_name }
+            }
+            OpenSpec::Proxy(url) => {
+                let non_empty = |value: String| (!value.is_empty()).then_some(value);
+                let queue_path = config::config_dir().join(PROXY_QUEUE_FILE);
+
+                EntryEvent::OpenProxy {
+                    url,
+                    auth_token: self.auth_token,
+                    tls: TlsOptions {
+                        ca_cert: non_empty(last(LAST_PROXY_CA_CERT).await),
+                        client_identity: non_empty(last(LAST_PROXY_CLIENT_IDENTITY).await),
+                    },
+                    queue_path: Some(queue_path.to_string_lossy().into_owned()),
+                }
+            }
+        };
+
+        let event = match self.passphrase {
+            Some(passphrase) if !passphrase.is_empty() && self.encrypt => {
+                EntryEvent::EncryptNotebook {
+                    open: Box::new(event),
+                    passphrase,
+                }
+            }
+            Some(passphrase) if !passphrase.is_empty() => EntryEvent::OpenEncrypted {
+                open: Box::new(event),
+                passphrase,
+            },
+            _ if self.encrypt => return Err(eyre!("--encrypt needs a non-empty --passphrase")),
+            _ => event,
+        };
+
+        Ok(Some(event))
+    }
+}
+
+pub async fn run(args: TuiArgs) -> Result<()> {
     config::init().await;
 
     let theme_id = config::get(config::LAST_THEME)
@@ -17,12 +161,42 @@ pub async fn run() -> Result<()> {
     logger::init().await;
     color_eyre::install()?;
 
+    let layers = match std::env::var("GLUES_BACKEND_LAYERS") {
+        Ok(layers) => layers,
+        Err(_) => config::get(config::BACKEND_LAYERS)
+            .await
+            .unwrap_or_default(),
+    };
+
+    let restore = args.restore_session
+        || config::get(RESTORE_SESSION)
+            .await
+            .is_some_and(|value| value == "true");
+
+    let mouse = args.mouse
+        || config::get(MOUSE)
+            .await
+            .is_some_and(|value| value == "true");
+
+    let mut app = App::new();
+    app.context_mut().set_keybindings(Keybindings::load());
+    app.set_backend_layers(layers.parse()?);
+    app.set_restore_session(restore);
+    app.set_mouse_capture(mouse);
+    app.load_recent().await;
+
+    if let Some(event) = args.entry_event().await? {
+        app.open_notebook(event).await;
+    } else if restore && let Some(last) = session::last_notebook().await {
+        app.reopen(last).await;
+    }
+
     let terminal = ratatui::init();
-    let app_result = App::new().run(terminal).await;
+    let app_result = app.run(terminal).await;
     ratatui::restore();
     app_result
 }
 
 pub async fn run_cli() -> Result<()> {
-    run().await
+    run(TuiArgs::parse()).await
 }
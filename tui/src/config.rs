@@ -8,7 +8,20 @@ pub const LAST_GIT_BRANCH: &str = "last_git_branch";
 pub const LAST_MONGO_CONN_STR: &str = "last_mongo_conn_str";
 pub const LAST_MONGO_DB_NAME: &str = "last_mongo_db_name";
 pub const LAST_PROXY_URL: &str = "last_proxy_url";
+pub const LAST_PROXY_CA_CERT: &str = "last_proxy_ca_cert";
+pub const LAST_PROXY_CLIENT_IDENTITY: &str = "last_proxy_client_identity";
 pub const LAST_THEME: &str = "last_theme";
+/// Backend layers wrapped around opened notebooks, e.g. `cache,timing=50`.
+/// `GLUES_BACKEND_LAYERS` takes precedence when set.
+pub const BACKEND_LAYERS: &str = "backend_layers";
+/// Reopens the directories, tabs and cursors of a notebook when it is opened
+/// again. `true` or `false`.
+pub const RESTORE_SESSION: &str = "restore_session";
+/// The notebook opened last, reopened on startup when sessions are restored.
+pub const LAST_NOTEBOOK: &str = "last_notebook";
+/// Captures the mouse to click, drag and scroll in the tree, tabs and editor.
+/// `true` or `false`; while captured, the terminal cannot select text itself.
+pub const MOUSE: &str = "mouse";
 
 const DEFAULTS: &[(&str, &str)] = &[
     (LAST_CSV_PATH, ""),
@@ -21,7 +34,13 @@ const DEFAULTS: &[(&str, &str)] = &[
     (LAST_MONGO_CONN_STR, ""),
     (LAST_MONGO_DB_NAME, ""),
     (LAST_PROXY_URL, ""),
+    (LAST_PROXY_CA_CERT, ""),
+    (LAST_PROXY_CLIENT_IDENTITY, ""),
     (LAST_THEME, "dark"),
+    (BACKEND_LAYERS, ""),
+    (RESTORE_SESSION, "false"),
+    (LAST_NOTEBOOK, ""),
+    (MOUSE, "false"),
 ];
 
 pub(crate) mod platform {
@@ -33,24 +52,28 @@ pub(crate) mod platform {
             prelude::{CsvStorage, Glue},
         },
         home::home_dir,
-        std::ops::Deref,
+        std::{ops::Deref, path::PathBuf},
     };
 
     const PATH: &str = ".glues/";
 
-    /// Returns a Glue instance with CsvStorage for configuration.
+    /// Returns the directory holding the configuration files.
     ///
     /// The config directory can be overridden via the `GLUES_CONFIG_DIR` environment variable.
     /// This is primarily used for test isolation. If not set, defaults to `~/.glues/`.
-    pub(crate) fn get_glue() -> Glue<CsvStorage> {
-        let path = if let Ok(config_dir) = std::env::var("GLUES_CONFIG_DIR") {
-            std::path::PathBuf::from(config_dir)
+    pub fn config_dir() -> PathBuf {
+        if let Ok(config_dir) = std::env::var("GLUES_CONFIG_DIR") {
+            PathBuf::from(config_dir)
         } else {
             home_dir()
                 .unwrap_or(std::env::current_dir().expect("failed to get current directory"))
                 .join(PATH)
-        };
-        let storage = CsvStorage::new(path).expect("failed to open CSV config storage");
+        }
+    }
+
+    /// Returns a Glue instance with CsvStorage for configuration.
+    pub(crate) fn get_glue() -> Glue<CsvStorage> {
+        let storage = CsvStorage::new(config_dir()).expect("failed to open CSV config storage");
 
         Glue::new(storage)
     }
@@ -110,4 +133,4 @@ pub(crate) mod platform {
     }
 }
 
-pub use platform::{get, init, update};
+pub use platform::{config_dir, get, init, update};
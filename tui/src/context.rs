@@ -6,14 +6,22 @@ use theme_selector::ThemeSelector;
 use {
     crate::{
         Action,
+        action::TuiAction,
         config::{self, LAST_THEME},
         input::{Input, KeyCode, KeyEvent},
+        keybindings::{Keybindings, Mode},
         log,
         logger::*,
-        theme,
+        notifications::{NotificationCenter, Notifications, Severity},
+        swap::{self, DiffLine, SwapFile},
+        theme::{self, THEME},
     },
-    glues_core::transition::VimKeymapKind,
-    ratatui::text::Line,
+    glues_core::{
+        backend::{Resolution, SyncConflict},
+        state::notebook::DirectoryItem,
+        transition::VimKeymapKind,
+    },
+    ratatui::{style::Stylize, text::Line, widgets::ListState},
     std::time::SystemTime,
     tui_input::InputRequest,
 };
@@ -65,6 +73,7 @@ pub struct QuitMenu {
     pub message: String,
     pub quit_action: Action,
     pub menu_action: Action,
+    pub backup_action: Option<Action>,
 }
 
 impl QuitMenu {
@@ -73,8 +82,15 @@ impl QuitMenu {
             message: message.into(),
             quit_action,
             menu_action,
+            backup_action: None,
         }
     }
+
+    /// Adds a `[b]` option, offered for notebooks which are lost on quit.
+    pub fn with_backup(mut self, backup_action: Action) -> Self {
+        self.backup_action = Some(backup_action);
+        self
+    }
 }
 
 pub struct InfoDialog {
@@ -91,6 +107,109 @@ impl InfoDialog {
     }
 }
 
+/// Modal dialog shown while a Git sync waits for a merge conflict to be resolved.
+pub struct SyncConflictDialog {
+    pub items: Vec<String>,
+}
+
+impl SyncConflictDialog {
+    /// Lists the conflicted rows by the names they have in the loaded tree.
+    pub fn new(root: &DirectoryItem, conflict: &SyncConflict) -> Self {
+        let name = |id: &String| {
+            root.find_name(id)
+                .map(str::to_owned)
+                .unwrap_or_else(|| id.chars().take(8).collect())
+        };
+
+        let items = conflict
+            .notes
+            .iter()
+            .map(name)
+            .chain(
+                conflict
+                    .directories
+                    .iter()
+                    .map(|id| format!("{}/", name(id))),
+            )
+            .chain(conflict.files.iter().cloned())
+            .collect();
+
+        Self { items }
+    }
+}
+
+/// Note left with a swap file, and the content it has in the notebook unless
+/// it was deleted since.
+pub struct Recovery {
+    pub swap: SwapFile,
+    pub saved: Option<String>,
+}
+
+/// Modal dialog listing the swaps left when a notebook was last closed.
+pub struct RecoveryDialog {
+    pub items: Vec<Recovery>,
+    pub list_state: ListState,
+}
+
+impl RecoveryDialog {
+    pub fn new(items: Vec<Recovery>) -> Self {
+        Self {
+            items,
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.list_state
+            .selected()
+            .unwrap_or_default()
+            .min(self.items.len().saturating_sub(1))
+    }
+
+    /// Removes the item, keeping the selection on the one after it.
+    pub fn take(&mut self, i: usize) -> Recovery {
+        let item = self.items.remove(i);
+        let last = self.items.len().saturating_sub(1);
+        self.list_state.select(Some(i.min(last)));
+        item
+    }
+
+    /// Changed lines between the saved note and the swap.
+    fn diff(&self, i: usize) -> InfoDialog {
+        let Recovery { swap, saved } = &self.items[i];
+        let title = format!("Swap of {}", swap.note_name);
+        let Some(saved) = saved else {
+            return InfoDialog::new(
+                title,
+                vec![Line::raw("The note no longer exists in the notebook.")],
+            );
+        };
+
+        let mut changed = swap::diff(saved, &swap.content)
+            .into_iter()
+            .filter_map(|line| match line {
+                DiffLine::Same(_) => None,
+                DiffLine::Removed(line) => Some(Line::from(format!("- {line}").fg(THEME.error))),
+                DiffLine::Added(line) => Some(Line::from(format!("+ {line}").fg(THEME.success))),
+            })
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            changed.push(Line::raw("Only the trailing whitespace differs."));
+        } else if changed.len() > MAX_DIFF_LINES {
+            let more = changed.len() - MAX_DIFF_LINES;
+            changed.truncate(MAX_DIFF_LINES);
+            changed.push(Line::from(
+                format!("and {more} more lines").fg(THEME.text_secondary),
+            ));
+        }
+
+        InfoDialog::new(title, changed)
+    }
+}
+
+/// Lines of a swap diff shown at once.
+const MAX_DIFF_LINES: usize = 20;
+
 pub struct Context {
     pub entry: EntryContext,
     pub notebook: NotebookContext,
@@ -101,15 +220,20 @@ pub struct Context {
     pub confirm: Option<(String, Action)>,
     pub alert: Option<String>,
     pub info: Option<InfoDialog>,
+    pub sync_conflict: Option<SyncConflictDialog>,
+    pub recovery: Option<RecoveryDialog>,
     pub prompt: Option<ContextPrompt>,
     pub theme_selector: Option<ThemeSelector>,
+    pub notification_center: Option<NotificationCenter>,
     pub last_log: Option<(String, SystemTime)>,
+    pub notifications: Notifications,
 
     pub help: bool,
     pub editor_keymap: bool,
     pub vim_keymap: Option<VimKeymapKind>,
 
     pub keymap: bool,
+    pub keybindings: Keybindings,
 }
 
 impl Default for Context {
@@ -123,25 +247,125 @@ impl Default for Context {
             confirm: None,
             alert: None,
             info: None,
+            sync_conflict: None,
+            recovery: None,
             prompt: None,
             theme_selector: None,
+            notification_center: None,
             last_log: None,
+            notifications: Notifications::default(),
 
             help: false,
             editor_keymap: false,
             vim_keymap: None,
 
             keymap: false,
+            keybindings: Keybindings::default(),
         }
     }
 }
 
 impl Context {
+    /// Shows `message` in an alert and keeps it in the notifications.
+    pub fn show_alert(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.notifications.push(Severity::Error, message.clone());
+        self.alert = Some(message);
+    }
+
+    /// Shows `message` in an alert, kept in the notifications as information
+    /// rather than a failure.
+    pub fn show_info(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.notifications.push(Severity::Info, message.clone());
+        self.alert = Some(message);
+    }
+
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        self.notifications.push(severity, message);
+    }
+
     pub fn take_prompt_input(&mut self) -> Option<String> {
         Some(self.prompt.take()?.input.value().to_owned())
     }
 
+    /// Installs the user key bindings and reports any conflicts found in the keymap file.
+    pub fn set_keybindings(&mut self, keybindings: Keybindings) {
+        if !keybindings.conflicts().is_empty() {
+            let lines = keybindings
+                .conflicts()
+                .iter()
+                .map(|conflict| Line::raw(conflict.clone()))
+                .collect();
+
+            self.info = Some(InfoDialog::new("Keymap conflicts", lines));
+        }
+
+        self.keybindings = keybindings;
+    }
+
+    /// Mode whose user key bindings apply to the next key press.
+    pub fn mode(&self) -> Mode {
+        use notebook::ContextState as NotebookState;
+
+        match self.state {
+            ContextState::Entry => Mode::Entry,
+            ContextState::Notebook => match self.notebook.state {
+                NotebookState::NoteTreeBrowsing
+                | NotebookState::NoteTreeNumbering
+                | NotebookState::NoteTreeGateway => Mode::Tree,
+                NotebookState::NoteActionsDialog | NotebookState::DirectoryActionsDialog => {
+                    Mode::Actions
+                }
+                NotebookState::MoveMode => Mode::Move,
+                NotebookState::EditorNormalMode { .. } => Mode::Normal,
+                NotebookState::EditorVisualMode => Mode::Visual,
+                NotebookState::EditorInsertMode => Mode::Insert,
+            },
+        }
+    }
+
+    /// Applies the user key bindings. Dialogs, prompts and the character
+    /// argument of `f`, `t`, `m` or `'` always see the raw key.
+    pub fn remap(&self, input: Input) -> Input {
+        if self.is_overlaid() || self.awaits_char() {
+            return input;
+        }
+
+        self.keybindings.remap(self.mode(), input)
+    }
+
+    fn awaits_char(&self) -> bool {
+        matches!(self.state, ContextState::Notebook) && self.notebook.awaiting_char
+    }
+
+    /// Whether a dialog, prompt or keymap is drawn over the current screen.
+    fn is_overlaid(&self) -> bool {
+        self.vim_keymap.is_some()
+            || self.editor_keymap
+            || self.help
+            || self.alert.is_some()
+            || self.info.is_some()
+            || self.sync_conflict.is_some()
+            || self.recovery.is_some()
+            || self.quit_menu.is_some()
+            || self.confirm.is_some()
+            || self.notification_center.is_some()
+            || self.theme_selector.is_some()
+            || self.prompt.is_some()
+    }
+
     pub async fn consume(&mut self, input: &Input) -> Action {
+        if let Input::Mouse(mouse) = input {
+            // dialogs and the entry menu are driven by keys only
+            return match self.state {
+                ContextState::Notebook if !self.is_overlaid() => {
+                    self.notebook.consume_mouse(*mouse)
+                }
+                _ => Action::None,
+            };
+        }
+
         if self.vim_keymap.is_some() {
             self.vim_keymap = None;
             return Action::None;
@@ -168,6 +392,50 @@ impl Context {
             }
 
             return Action::None;
+        } else if self.sync_conflict.is_some() {
+            let code = match input {
+                Input::Key(key) => key.code,
+                _ => return Action::None,
+            };
+
+            let resolution = match code {
+                KeyCode::Char('o') => Resolution::KeepLocal,
+                KeyCode::Char('t') => Resolution::TakeRemote,
+                KeyCode::Char('a') | KeyCode::Esc => Resolution::Abort,
+                _ => return Action::None,
+            };
+
+            self.sync_conflict = None;
+            return TuiAction::ResolveSyncConflict(resolution).into();
+        } else if let Some(dialog) = self.recovery.as_mut() {
+            let code = match input {
+                Input::Key(key) => key.code,
+                _ => return Action::None,
+            };
+
+            let i = dialog.selected();
+            match code {
+                KeyCode::Char('j') | KeyCode::Down => {
+                    dialog.list_state.select_next();
+                    return Action::None;
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    dialog.list_state.select_previous();
+                    return Action::None;
+                }
+                KeyCode::Char('r') => return TuiAction::RecoverSwap(i).into(),
+                KeyCode::Char('d') => {
+                    self.info = Some(dialog.diff(i));
+                    return Action::None;
+                }
+                KeyCode::Char('x') => return TuiAction::DiscardSwap(i).into(),
+                KeyCode::Esc => {
+                    // the swaps stay and are offered again next time
+                    self.recovery = None;
+                    return Action::None;
+                }
+                _ => return Action::None,
+            }
         } else if self.quit_menu.is_some() {
             let code = match input {
                 Input::Key(key) => key.code,
@@ -183,6 +451,13 @@ impl Context {
                     let menu = self.quit_menu.take().log_expect("quit menu must be some");
                     return menu.menu_action;
                 }
+                KeyCode::Char('b') => {
+                    let Some(menu) = self.quit_menu.take_if(|menu| menu.backup_action.is_some())
+                    else {
+                        return Action::None;
+                    };
+                    return menu.backup_action.log_expect("backup action must be some");
+                }
                 KeyCode::Esc => {
                     self.quit_menu = None;
                     return Action::None;
@@ -207,6 +482,24 @@ impl Context {
                 }
                 _ => return Action::None,
             }
+        } else if let Some(center) = self.notification_center.as_mut() {
+            let code = match input {
+                Input::Key(key) => key.code,
+                _ => return Action::None,
+            };
+
+            match code {
+                KeyCode::Char('j') | KeyCode::Down => center.list_state.select_next(),
+                KeyCode::Char('k') | KeyCode::Up => center.list_state.select_previous(),
+                KeyCode::Char('G') | KeyCode::End => center.list_state.select_last(),
+                KeyCode::Char('g') | KeyCode::Home => center.list_state.select_first(),
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                    self.notification_center = None;
+                }
+                _ => {}
+            }
+
+            return Action::None;
         } else if let Some(selector) = self.theme_selector.as_mut() {
             let key = match input {
                 Input::Key(key) => key,
@@ -7,6 +7,7 @@ use {
         },
         input::KeyCode,
         logger::*,
+        recent::RecentNotebook,
         theme::THEME,
     },
     glues_core::EntryEvent,
@@ -27,19 +28,30 @@ pub const MENU_ITEMS: [&str; 9] = [
     INSTANT, FILE, REDB, GIT, MONGO, PROXY, HELP, THEME_MENU, QUIT,
 ];
 
+/// Selection covers the menu items followed by the recent notebooks.
 pub struct EntryContext {
     pub list_state: ListState,
+    pub recent: Vec<RecentNotebook>,
 }
 
 impl Default for EntryContext {
     fn default() -> Self {
         Self {
             list_state: ListState::default().with_selected(Some(0)),
+            recent: Vec::new(),
         }
     }
 }
 
 impl EntryContext {
+    /// Index into `recent` of the selected item, if it is a recent notebook.
+    pub fn selected_recent(&self) -> Option<usize> {
+        self.list_state
+            .selected()?
+            .checked_sub(MENU_ITEMS.len())
+            .filter(|i| *i < self.recent.len())
+    }
+
     pub async fn consume(&mut self, code: KeyCode) -> Action {
         let open = |key, action: TuiAction| async move {
             TuiAction::Prompt {
@@ -107,7 +119,9 @@ impl EntryContext {
         match code {
             KeyCode::Char('q') => TuiAction::Quit.into(),
             KeyCode::Char('j') | KeyCode::Down => {
-                self.list_state.select_next();
+                let last = MENU_ITEMS.len() + self.recent.len() - 1;
+                let i = self.list_state.selected().unwrap_or_default();
+                self.list_state.select(Some((i + 1).min(last)));
                 Action::None
             }
             KeyCode::Char('k') | KeyCode::Up => {
@@ -122,8 +136,29 @@ impl EntryContext {
             KeyCode::Char('p') => open_proxy().await,
             KeyCode::Char('h') => TuiAction::Help.into(),
             KeyCode::Char('t') => TuiAction::OpenThemeMenu.into(),
+            KeyCode::Char('N') => TuiAction::ShowNotifications.into(),
+            KeyCode::Char(c @ '1'..='9') => {
+                let i = c as usize - '1' as usize;
+                if i < self.recent.len() {
+                    TuiAction::OpenRecent(i).into()
+                } else {
+                    Action::None
+                }
+            }
+            KeyCode::Char('P') => match self.selected_recent() {
+                Some(i) => TuiAction::TogglePinRecent(i).into(),
+                None => Action::None,
+            },
+            KeyCode::Char('x') => match self.selected_recent() {
+                Some(i) => TuiAction::ForgetRecent(i).into(),
+                None => Action::None,
+            },
 
             KeyCode::Enter => {
+                if let Some(i) = self.selected_recent() {
+                    return TuiAction::OpenRecent(i).into();
+                }
+
                 let i = self
                     .list_state
                     .selected()
@@ -1,35 +1,48 @@
 mod clipboard;
+mod completion;
 mod tree_item;
 
 use {
     crate::{
         action::{Action, TuiAction},
-        input::{Input, KeyCode, KeyEvent},
+        input::{Input, KeyCode, KeyEvent, MouseEvent},
         logger::*,
         theme::THEME,
     },
-    edtui::{EditorState as EdtuiState, Lines},
+    edtui::{EditorState as EdtuiState, Index2, Lines, RowIndex, events::MouseEventHandler},
     glues_core::{
         NotebookEvent,
-        data::Note,
+        data::{Attachment, Note},
         state::notebook::{DirectoryItem, Tab},
+        transition::VisualKind,
         types::{Id, NoteId},
     },
     ratatui::{
-        style::Style,
+        crossterm::event::{
+            KeyModifiers, MouseButton, MouseEvent as CrosstermMouseEvent, MouseEventKind,
+        },
+        layout::{Position, Rect},
+        style::{Style, Stylize},
         text::{Line, Span},
         widgets::ListState,
     },
-    std::collections::HashMap,
+    std::{
+        collections::HashMap,
+        time::{Duration, Instant},
+    },
 };
 
 use arboard::Clipboard;
 
-pub use tree_item::{TreeItem, TreeItemKind};
+pub use {
+    completion::{Completion, CompletionKind},
+    tree_item::{TreeItem, TreeItemKind},
+};
 
 pub const REMOVE_NOTE: &str = "Remove note";
 pub const RENAME_NOTE: &str = "Rename note";
 pub const SHOW_NOTE_INFO: &str = "Show note info";
+pub const ADD_ATTACHMENT: &str = "Add attachment";
 
 pub const ADD_NOTE: &str = "Add note";
 pub const ADD_DIRECTORY: &str = "Add directory";
@@ -39,7 +52,23 @@ pub const SHOW_DIRECTORY_INFO: &str = "Show directory info";
 
 pub const CLOSE: &str = "Close";
 
-pub const NOTE_ACTIONS: [&str; 4] = [RENAME_NOTE, REMOVE_NOTE, SHOW_NOTE_INFO, CLOSE];
+/// Two clicks on the same cell within this are a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+pub const NOTE_ACTIONS: [&str; 5] = [
+    RENAME_NOTE,
+    REMOVE_NOTE,
+    SHOW_NOTE_INFO,
+    ADD_ATTACHMENT,
+    CLOSE,
+];
+
+/// Row of the note actions dialog. The attachments of the note are listed
+/// between the actions and `Close`.
+pub enum NoteActionItem<'a> {
+    Action(&'static str),
+    Attachment(&'a Attachment),
+}
 pub const DIRECTORY_ACTIONS: [&str; 6] = [
     ADD_NOTE,
     ADD_DIRECTORY,
@@ -76,6 +105,9 @@ impl ContextState {
 pub struct NotebookContext {
     pub state: ContextState,
 
+    /// Opened as an Instant notebook, which is gone on quit unless backed up.
+    pub instant: bool,
+
     // note tree
     pub tree_state: ListState,
     pub tree_items: Vec<TreeItem>,
@@ -83,6 +115,7 @@ pub struct NotebookContext {
 
     // note actions
     pub note_actions_state: ListState,
+    pub attachments: Vec<Attachment>,
 
     // directory actions
     pub directory_actions_state: ListState,
@@ -99,11 +132,36 @@ pub struct NotebookContext {
     pub line_yanked: bool,
     pub yank: Option<String>,
 
+    /// Shape of the visual selection, mirrored from core for rendering and editing.
+    pub visual_kind: VisualKind,
+
+    /// The editor waits for a character argument, as after `f`, `m` or `'`,
+    /// so the next key is taken as typed.
+    pub awaiting_char: bool,
+
+    /// Block `I` / `A` in progress; the text typed on the first row is repeated
+    /// on the other rows of the block when insert mode ends.
+    pub block_insert: Option<ColumnInsert>,
+
+    /// Insert mode completion popup, drawn at the cursor while open.
+    pub completion: Option<Completion>,
+
     /// Pending scroll request to be applied at render time.
     pub pending_scroll: Option<ScrollRequest>,
 
     /// Persistent anchor for maintaining buffer shift across frames.
     pub scroll_anchor: Option<ScrollAnchor>,
+
+    /// Viewport tops restored from a session, applied when each note is
+    /// drawn next.
+    pub restored_tops: HashMap<NoteId, usize>,
+
+    /// Where the last frame drew the tree items, the editor and each tab
+    /// title, to find what a mouse event points at.
+    pub tree_area: Rect,
+    pub editor_area: Rect,
+    pub tab_areas: Vec<Rect>,
+    pub last_click: Option<(Instant, Position)>,
 }
 
 #[derive(Clone, Copy)]
@@ -111,6 +169,8 @@ pub enum ScrollRequest {
     Center,
     Top,
     Bottom,
+    /// Puts the given row at the top, as left in a saved session.
+    Row(usize),
 }
 
 #[derive(Clone, Copy)]
@@ -121,10 +181,22 @@ pub struct ScrollAnchor {
     pub actual_viewport_y: usize,
 }
 
+#[derive(Clone, Copy)]
+pub struct ColumnInsert {
+    pub top: usize,
+    pub bottom: usize,
+    pub col: usize,
+    /// `A` pads rows shorter than `col`; `I` skips them.
+    pub pad: bool,
+}
+
 pub struct EditorItem {
     pub editor: EdtuiState,
     pub clipboard: clipboard::ClipboardHandle,
     pub dirty: bool,
+    /// Text the backend or the swap file already has, so the buffer is only
+    /// written to its swap file when it changes.
+    pub persisted: String,
 }
 
 impl Default for NotebookContext {
@@ -136,6 +208,7 @@ impl Default for NotebookContext {
             tree_width: 45,
 
             note_actions_state: ListState::default(),
+            attachments: vec![],
             directory_actions_state: ListState::default(),
 
             editor_height: 0,
@@ -145,11 +218,21 @@ impl Default for NotebookContext {
 
             show_line_number: true,
             show_browser: true,
+            instant: false,
             show_syntax_highlight: true,
             line_yanked: false,
             yank: None,
+            visual_kind: VisualKind::Char,
+            awaiting_char: false,
+            block_insert: None,
+            completion: None,
             pending_scroll: None,
             scroll_anchor: None,
+            restored_tops: HashMap::new(),
+            tree_area: Rect::default(),
+            editor_area: Rect::default(),
+            tab_areas: vec![],
+            last_click: None,
         }
     }
 }
@@ -161,6 +244,35 @@ impl NotebookContext {
             .map(|t| &t.note)
     }
 
+    /// Top row of the viewport of an open note. edtui keeps its viewport
+    /// private, so the row is read back by clicking the first text row of a
+    /// copy of the editor state.
+    pub fn viewport_top(&self, note_id: &NoteId) -> Option<usize> {
+        if let Some(top) = self.restored_tops.get(note_id) {
+            return Some(*top);
+        }
+        if let Some(anchor) = self.scroll_anchor
+            && self
+                .get_opened_note()
+                .is_some_and(|note| &note.id == note_id)
+        {
+            return Some(anchor.desired_top);
+        }
+
+        let mut editor = self.editors.get(note_id)?.editor.clone();
+        let unset = Index2::new(usize::MAX, 0);
+        editor.cursor = unset;
+        let click = CrosstermMouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: self.editor_area.right().saturating_sub(2),
+            row: self.editor_area.y + 1,
+            modifiers: KeyModifiers::NONE,
+        };
+        MouseEventHandler::on_event(click, &mut editor);
+
+        (editor.cursor != unset).then_some(editor.cursor.row)
+    }
+
     pub fn get_editor(&self) -> &EdtuiState {
         let note_id = &self
             .tab_index
@@ -206,6 +318,19 @@ impl NotebookContext {
             .clipboard
     }
 
+    pub fn note_action_items(&self) -> Vec<NoteActionItem<'_>> {
+        let (close, actions) = NOTE_ACTIONS
+            .split_last()
+            .log_expect("note actions must not be empty");
+
+        actions
+            .iter()
+            .map(|action| NoteActionItem::Action(action))
+            .chain(self.attachments.iter().map(NoteActionItem::Attachment))
+            .chain(std::iter::once(NoteActionItem::Action(close)))
+            .collect()
+    }
+
     pub fn mark_dirty(&mut self) {
         if let Some(editor_item) = self
             .tab_index
@@ -219,6 +344,7 @@ impl NotebookContext {
     pub fn mark_clean(&mut self, note_id: &NoteId) {
         if let Some(editor_item) = self.editors.get_mut(note_id) {
             editor_item.dirty = false;
+            editor_item.persisted = editor_item.editor.lines.to_string();
         }
     }
 
@@ -394,12 +520,46 @@ impl NotebookContext {
             .log_expect("[NotebookContext::selected] selected must not be empty")
     }
 
+    /// Index of the selectable tree item drawn at `position`.
+    pub fn tree_item_at(&self, position: Position) -> Option<usize> {
+        if !self.tree_area.contains(position) {
+            return None;
+        }
+
+        let i = self.tree_state.offset() + usize::from(position.y - self.tree_area.y);
+        self.tree_items
+            .get(i)
+            .is_some_and(|item| item.selectable)
+            .then_some(i)
+    }
+
+    pub fn tab_at(&self, position: Position) -> Option<usize> {
+        self.tab_areas
+            .iter()
+            .position(|area| area.contains(position))
+    }
+
+    /// Records a click and tells whether it completes a double click.
+    pub fn click(&mut self, position: Position) -> bool {
+        let double = self
+            .last_click
+            .take()
+            .is_some_and(|(at, last)| last == position && at.elapsed() < DOUBLE_CLICK);
+
+        if !double {
+            self.last_click = Some((Instant::now(), position));
+        }
+
+        double
+    }
+
     pub fn open_note(&mut self, note_id: NoteId, content: String) {
         let clipboard = clipboard::ClipboardHandle::default();
         let mut editor = EdtuiState::new(Lines::from(content.as_str()));
         editor.set_clipboard(clipboard.clone());
 
         let item = EditorItem {
+            persisted: editor.lines.to_string(),
             editor,
             clipboard,
             dirty: false,
@@ -428,6 +588,17 @@ impl NotebookContext {
         self.yank = Some(text);
     }
 
+    /// Mouse input is taken where keys would browse the tree or move the
+    /// cursor, not while a dialog, a pending operator or insert mode waits.
+    pub fn consume_mouse(&self, mouse: MouseEvent) -> Action {
+        match self.state {
+            ContextState::NoteTreeBrowsing
+            | ContextState::EditorNormalMode { idle: true }
+            | ContextState::EditorVisualMode => TuiAction::Mouse(mouse).into(),
+            _ => Action::None,
+        }
+    }
+
     pub fn consume(&mut self, input: &Input) -> Action {
         let code = match input {
             Input::Key(key) => key.code,
@@ -464,6 +635,8 @@ impl NotebookContext {
 
                 Action::PassThrough
             }
+            KeyCode::Char('S') => TuiAction::Sync.into(),
+            KeyCode::Char('N') => TuiAction::ShowNotifications.into(),
             KeyCode::Esc => TuiAction::OpenNotebookQuitMenu {
                 save_before_open: false,
             }
@@ -501,10 +674,19 @@ impl NotebookContext {
             motion::{MoveToFirstRow, MoveToLastRow},
         };
 
+        if let Some(action) = self.consume_on_completion(input) {
+            return action;
+        }
+
         match input {
             Input::Key(KeyEvent {
                 code: KeyCode::Esc, ..
             }) => Action::Dispatch(NotebookEvent::ViewNote.into()),
+            Input::Key(KeyEvent {
+                code: KeyCode::Char(c @ ('n' | 'p')),
+                modifiers,
+                ..
+            }) if modifiers.ctrl => self.open_completion(*c == 'p'),
             Input::Key(KeyEvent {
                 code: KeyCode::Char('h'),
                 modifiers,
@@ -522,12 +704,6 @@ impl NotebookContext {
                     KeyCode::Char('b') if modifiers.ctrl => {
                         editor.execute(MoveBackward(1));
                     }
-                    KeyCode::Char('p') if modifiers.ctrl => {
-                        editor.execute(MoveUp(1));
-                    }
-                    KeyCode::Char('n') if modifiers.ctrl => {
-                        editor.execute(MoveDown(1));
-                    }
                     KeyCode::Char('a') if modifiers.ctrl => {
                         editor.execute(MoveToStartOfLine());
                     }
@@ -597,13 +773,168 @@ impl NotebookContext {
                     }
                     _ => {}
                 }
+
+                self.update_completion();
                 Action::None
             }
             _ => Action::None,
         }
     }
 
+    /// Keys which drive the completion popup while it is open.
+    fn consume_on_completion(&mut self, input: &Input) -> Option<Action> {
+        let completion = self.completion.as_mut()?;
+        let Input::Key(key) = input else {
+            return None;
+        };
+
+        match key.code {
+            KeyCode::Char('n') if key.modifiers.ctrl => completion.select_next(),
+            KeyCode::Down => completion.select_next(),
+            KeyCode::Char('p') if key.modifiers.ctrl => completion.select_prev(),
+            KeyCode::Up => completion.select_prev(),
+            KeyCode::Enter | KeyCode::Tab => self.accept_completion(),
+            KeyCode::Esc => self.completion = None,
+            _ => return None,
+        }
+
+        Some(Action::None)
+    }
+
+    fn open_completion(&mut self, backward: bool) -> Action {
+        let editor = self.get_editor();
+        let cursor = editor.cursor;
+        let line = editor
+            .lines
+            .get(RowIndex::new(cursor.row))
+            .map(|line| line.iter().collect::<String>())
+            .unwrap_or_default();
+        let mut completion = Completion::at(&line, cursor.row, cursor.col, backward);
+
+        if completion.kind == CompletionKind::NoteLink {
+            self.completion = Some(completion);
+            return Action::Dispatch(NotebookEvent::CompleteNoteLink.into());
+        }
+
+        let current_id = self.get_opened_note().map(|note| note.id.clone());
+        let other_ids = self
+            .tabs
+            .iter()
+            .map(|tab| &tab.note.id)
+            .filter(|id| Some(*id) != current_id.as_ref());
+        let texts = current_id
+            .iter()
+            .chain(other_ids)
+            .filter_map(|id| self.editors.get(id))
+            .map(|item| item.editor.lines.to_string())
+            .collect::<Vec<_>>();
+
+        let words = completion::collect_words(texts.iter().map(String::as_str), completion.kind);
+        if completion.set_candidates(words) {
+            self.completion = Some(completion);
+        }
+
+        Action::None
+    }
+
+    /// Fills the pending `[[` completion with the note names from the backend.
+    pub fn complete_note_link(&mut self, titles: Vec<String>) {
+        let Some(mut completion) = self.completion.take() else {
+            return;
+        };
+
+        if completion.set_candidates(titles) {
+            self.completion = Some(completion);
+        }
+    }
+
+    fn update_completion(&mut self) {
+        let Some(mut completion) = self.completion.take() else {
+            return;
+        };
+
+        let editor = self.get_editor();
+        let cursor = editor.cursor;
+        let line = editor
+            .lines
+            .get(RowIndex::new(cursor.row))
+            .map(|line| line.iter().collect::<String>())
+            .unwrap_or_default();
+
+        if completion.update(&line, cursor.row, cursor.col) {
+            self.completion = Some(completion);
+        }
+    }
+
+    /// Replaces the typed prefix with the selected item; links are closed with `]]`.
+    fn accept_completion(&mut self) {
+        use edtui::actions::{DeleteChar, InsertChar, MoveForward};
+
+        let Some(completion) = self.completion.take() else {
+            return;
+        };
+
+        let editor = self.get_editor_mut();
+        let typed = editor.cursor.col.saturating_sub(completion.start);
+        if typed > 0 {
+            editor.execute(DeleteChar(typed));
+        }
+
+        let closed = editor
+            .lines
+            .get(RowIndex::new(editor.cursor.row))
+            .is_some_and(|line| line[editor.cursor.col.min(line.len())..].starts_with(&[']', ']']));
+        let link = completion.kind == CompletionKind::NoteLink;
+
+        let mut text = completion.selected_item().to_owned();
+        if link && !closed {
+            text.push_str("]]");
+        }
+        text.chars().for_each(|c| editor.execute(InsertChar(c)));
+
+        if link && closed {
+            editor.execute(MoveForward(2));
+        }
+    }
+
     fn consume_on_note_actions(&mut self, code: KeyCode) -> Action {
+        let selected = self
+            .note_actions_state
+            .selected()
+            .log_expect("note action must not be empty");
+        let items = self.note_action_items();
+        let item = &items[selected.min(items.len() - 1)];
+
+        let attachment = match item {
+            NoteActionItem::Action(action) => {
+                let action = *action;
+                return self.consume_on_note_action_list(code, Some(action));
+            }
+            NoteActionItem::Attachment(attachment) => (*attachment).clone(),
+        };
+
+        match code {
+            KeyCode::Enter => TuiAction::Prompt {
+                message: vec![
+                    Line::raw(format!("Save {} to:", attachment.name)),
+                    Line::from("A directory keeps the attachment name.".fg(THEME.hint)),
+                ],
+                action: Box::new(TuiAction::SaveAttachment(attachment.id).into()),
+                default: Some(attachment.name),
+            }
+            .into(),
+            KeyCode::Char('x') => TuiAction::Confirm {
+                message: format!("Remove attachment {}?", attachment.name),
+                action: Box::new(Action::Dispatch(
+                    NotebookEvent::RemoveAttachment(attachment.id).into(),
+                )),
+            }
+            .into(),
+            _ => self.consume_on_note_action_list(code, None),
+        }
+    }
+
+    fn consume_on_note_action_list(&mut self, code: KeyCode, action: Option<&str>) -> Action {
         match code {
             KeyCode::Char('j') | KeyCode::Down => {
                 self.note_actions_state.select_next();
@@ -614,66 +945,66 @@ impl NotebookContext {
                 Action::None
             }
             KeyCode::Esc => Action::Dispatch(NotebookEvent::CloseNoteActionsDialog.into()),
-            KeyCode::Enter => {
-                match NOTE_ACTIONS[self
-                    .note_actions_state
-                    .selected()
-                    .log_expect("note action must not be empty")]
-                {
-                    RENAME_NOTE => TuiAction::Prompt {
-                        message: vec![Line::raw("Enter new note name:")],
-                        action: Box::new(TuiAction::RenameNote.into()),
-                        default: Some(self.selected().name()),
-                    }
-                    .into(),
-                    REMOVE_NOTE => TuiAction::Confirm {
-                        message: "Confirm to remove note?".to_owned(),
-                        action: Box::new(TuiAction::RemoveNote.into()),
-                    }
-                    .into(),
-                    SHOW_NOTE_INFO => {
-                        let note = match &self.selected().kind {
-                            TreeItemKind::Note { note } => note,
-                            _ => return Action::None,
-                        };
-
-                        let labels = [
-                            "Name",
-                            "Note ID",
-                            "Directory ID",
-                            "Created at",
-                            "Updated at",
-                        ];
-                        let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
-                        let label_style = Style::default().fg(THEME.text_secondary);
-                        let value_style = Style::default().fg(THEME.text);
-                        let build_line = |label: &str, value: &str| {
-                            let padded = format!("{:>width$}: ", label, width = label_width);
-                            Line::from(vec![
-                                Span::styled(padded, label_style),
-                                Span::styled(value.to_owned(), value_style),
-                            ])
-                        };
-
-                        let lines = vec![
-                            build_line("Name", &note.name),
-                            build_line("Note ID", &note.id),
-                            build_line("Directory ID", &note.directory_id),
-                            Line::default(),
-                            build_line("Created at", &note.created_at),
-                            build_line("Updated at", &note.updated_at),
-                        ];
-
-                        TuiAction::ShowInfo {
-                            title: "Note info".to_owned(),
-                            lines,
-                        }
-                        .into()
+            KeyCode::Enter => match action.unwrap_or_default() {
+                RENAME_NOTE => TuiAction::Prompt {
+                    message: vec![Line::raw("Enter new note name:")],
+                    action: Box::new(TuiAction::RenameNote.into()),
+                    default: Some(self.selected().name()),
+                }
+                .into(),
+                REMOVE_NOTE => TuiAction::Confirm {
+                    message: "Confirm to remove note?".to_owned(),
+                    action: Box::new(TuiAction::RemoveNote.into()),
+                }
+                .into(),
+                ADD_ATTACHMENT => TuiAction::Prompt {
+                    message: vec![Line::raw("Enter the path of the file to attach:")],
+                    action: Box::new(TuiAction::AddAttachment.into()),
+                    default: None,
+                }
+                .into(),
+                SHOW_NOTE_INFO => {
+                    let note = match &self.selected().kind {
+                        TreeItemKind::Note { note } => note,
+                        _ => return Action::None,
+                    };
+
+                    let labels = [
+                        "Name",
+                        "Note ID",
+                        "Directory ID",
+                        "Created at",
+                        "Updated at",
+                    ];
+                    let label_width = labels.iter().map(|l| l.len()).max().unwrap_or(0);
+                    let label_style = Style::default().fg(THEME.text_secondary);
+                    let value_style = Style::default().fg(THEME.text);
+                    let build_line = |label: &str, value: &str| {
+                        let padded = format!("{:>width$}: ", label, width = label_width);
+                        Line::from(vec![
+                            Span::styled(padded, label_style),
+                            Span::styled(value.to_owned(), value_style),
+                        ])
+                    };
+
+                    let lines = vec![
+                        build_line("Name", &note.name),
+                        build_line("Note ID", &note.id),
+                        build_line("Directory ID", &note.directory_id),
+                        Line::default(),
+                        build_line("Created at", &note.created_at),
+                        build_line("Updated at", &note.updated_at),
+                    ];
+
+                    TuiAction::ShowInfo {
+                        title: "Note info".to_owned(),
+                        lines,
                     }
-                    CLOSE => Action::Dispatch(NotebookEvent::CloseNoteActionsDialog.into()),
-                    _ => Action::None,
+                    .into()
                 }
-            }
+                CLOSE => Action::Dispatch(NotebookEvent::CloseNoteActionsDialog.into()),
+                _ => Action::None,
+            },
             _ => Action::PassThrough,
         }
     }
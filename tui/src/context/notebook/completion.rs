@@ -0,0 +1,160 @@
+/// What the text before the cursor is being completed into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionKind {
+    Word,
+    Tag,
+    NoteLink,
+}
+
+/// Insert mode completion popup opened with `Ctrl-n` / `Ctrl-p`.
+///
+/// `prefix` is the text between `start` and the cursor on `row`; candidates are
+/// filtered again whenever it changes while the popup is open.
+#[derive(Clone, Debug)]
+pub struct Completion {
+    pub kind: CompletionKind,
+    pub row: usize,
+    pub start: usize,
+    pub prefix: String,
+    pub items: Vec<String>,
+    pub selected: usize,
+    candidates: Vec<String>,
+    backward: bool,
+}
+
+impl Completion {
+    /// Looks at the text left of `col` and decides what to complete. `backward`
+    /// starts the selection at the last item, as `Ctrl-p` does.
+    pub fn at(line: &str, row: usize, col: usize, backward: bool) -> Self {
+        let before = line.chars().take(col).collect::<Vec<_>>();
+        let col = before.len();
+        let word_start = col - count_back(&before, is_word_char);
+        let tag_start = col - count_back(&before, |c| is_word_char(c) || c == '-');
+        let text = before.iter().collect::<String>();
+
+        let (kind, start) = match text.rfind("[[") {
+            Some(i) if !text[i..].contains("]]") => {
+                (CompletionKind::NoteLink, text[..i + 2].chars().count())
+            }
+            _ if tag_start > 0
+                && before[tag_start - 1] == '#'
+                && (tag_start == 1 || before[tag_start - 2].is_whitespace()) =>
+            {
+                (CompletionKind::Tag, tag_start - 1)
+            }
+            _ => (CompletionKind::Word, word_start),
+        };
+
+        Self {
+            kind,
+            row,
+            start,
+            prefix: before[start..].iter().collect(),
+            items: Vec::new(),
+            selected: 0,
+            candidates: Vec::new(),
+            backward,
+        }
+    }
+
+    /// Sets the pool to pick from. Returns `false` if nothing matches the prefix.
+    pub fn set_candidates(&mut self, candidates: Vec<String>) -> bool {
+        self.candidates = candidates;
+        self.filter();
+        self.selected = if self.backward {
+            self.items.len().saturating_sub(1)
+        } else {
+            0
+        };
+
+        !self.items.is_empty()
+    }
+
+    /// Follows the text typed while the popup is open. Returns `false` once the
+    /// popup should close.
+    pub fn update(&mut self, line: &str, row: usize, col: usize) -> bool {
+        let current = Self::at(line, row, col, false);
+        if current.kind != self.kind || current.row != self.row || current.start != self.start {
+            return false;
+        }
+
+        self.prefix = current.prefix;
+        self.filter();
+        self.selected = 0;
+
+        !self.items.is_empty()
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.checked_sub(1).unwrap_or(self.items.len() - 1);
+    }
+
+    pub fn selected_item(&self) -> &str {
+        &self.items[self.selected]
+    }
+
+    fn filter(&mut self) {
+        let prefix = self.prefix.to_lowercase();
+        let matches = |candidate: &String| match self.kind {
+            CompletionKind::NoteLink => candidate.to_lowercase().starts_with(&prefix),
+            CompletionKind::Word | CompletionKind::Tag => {
+                candidate.starts_with(&self.prefix) && candidate != &self.prefix
+            }
+        };
+
+        self.items = self
+            .candidates
+            .iter()
+            .filter(|c| matches(c))
+            .cloned()
+            .collect();
+    }
+}
+
+/// Words or `#tags` of the given texts in order of appearance, without duplicates.
+pub fn collect_words<'a>(
+    texts: impl IntoIterator<Item = &'a str>,
+    kind: CompletionKind,
+) -> Vec<String> {
+    let mut words = Vec::new();
+
+    for text in texts {
+        let mut word = String::new();
+        let mut prev = ' ';
+
+        for c in text.chars().chain(std::iter::once(' ')) {
+            let continues = match kind {
+                CompletionKind::Tag => {
+                    (word.is_empty() && c == '#' && prev.is_whitespace())
+                        || (!word.is_empty() && (is_word_char(c) || c == '-'))
+                }
+                _ => is_word_char(c),
+            };
+
+            if continues {
+                word.push(c);
+            } else if !word.is_empty() {
+                let word = std::mem::take(&mut word);
+                if word.chars().count() > 1 && !words.contains(&word) {
+                    words.push(word);
+                }
+            }
+
+            prev = c;
+        }
+    }
+
+    words
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn count_back(chars: &[char], matches: impl Fn(char) -> bool) -> usize {
+    chars.iter().rev().take_while(|&&c| matches(c)).count()
+}
@@ -0,0 +1,231 @@
+//! Editing a note in `$VISUAL` or `$EDITOR`. The note is saved and written to
+//! a temporary file, the terminal is handed over until the editor exits, and
+//! the file is read back into the buffer and saved like any other edit. Notes
+//! of encrypted notebooks are never written out, as the file is plaintext.
+
+use {
+    crate::{App, action::TuiAction, logger::*},
+    glues_core::{Error, types::NoteId},
+    ratatui::{
+        DefaultTerminal,
+        crossterm::{
+            execute,
+            terminal::{EnterAlternateScreen, enable_raw_mode},
+        },
+    },
+    std::{
+        fs,
+        io::{self, Write},
+        path::{Path, PathBuf},
+        process::Command,
+    },
+};
+
+/// A note handed to an external editor, with the content it had in the
+/// backend at that point to notice changes made in the meantime.
+#[derive(Clone, Debug)]
+pub struct ExternalEdit {
+    pub note_id: NoteId,
+    pub path: PathBuf,
+    base: String,
+}
+
+impl ExternalEdit {
+    /// Removes the file along with the private directory holding it.
+    fn remove_file(&self) {
+        if let Some(dir) = self.path.parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// Writes `content` to a new file readable only by the user, in a directory
+/// of its own. Both are created exclusively, so the file cannot be swapped for
+/// a link to somewhere else or read by other users.
+fn write_temp_file(extension: &str, content: &str) -> io::Result<PathBuf> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("glues-");
+    #[cfg(unix)]
+    builder.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+    let dir = builder.tempdir()?;
+    let mut file = tempfile::Builder::new()
+        .prefix("note-")
+        .suffix(&format!(".{extension}"))
+        .tempfile_in(dir.path())?;
+    file.write_all(content.as_bytes())?;
+
+    // both stay until the edit is over, see ExternalEdit::remove_file
+    let path = file.into_temp_path().keep()?;
+    let _ = dir.keep();
+    Ok(path)
+}
+
+/// `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_owned())
+}
+
+/// Runs the editor on `path` and waits for it to exit. The command may carry
+/// its own arguments, e.g. `code --wait`.
+pub fn launch(path: &Path) -> io::Result<()> {
+    let command = editor_command();
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program).args(words).arg(path).status()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{program} exited with {status}")))
+    }
+}
+
+impl App {
+    /// Note whose external edit was asked for by the last input.
+    pub fn take_external_edit(&mut self) -> Option<NoteId> {
+        self.external_edit.take()
+    }
+
+    /// Suspends the terminal while the editor runs on the note.
+    pub(crate) async fn edit_externally(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        note_id: NoteId,
+    ) -> io::Result<()> {
+        let Some(edit) = self.start_external_edit(note_id).await else {
+            return Ok(());
+        };
+
+        self.capture_mouse(false)?;
+        ratatui::restore();
+        let launched = launch(&edit.path);
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        self.capture_mouse(true)?;
+        terminal.clear()?;
+
+        match launched {
+            Ok(()) => self.finish_external_edit(edit).await,
+            Err(err) => {
+                edit.remove_file();
+                self.context
+                    .show_alert(format!("External editor failed: {err}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Saves the open buffers and writes the note to a temporary file, named
+    /// with the extension of the note so the editor picks its syntax.
+    pub async fn start_external_edit(&mut self, note_id: NoteId) -> Option<ExternalEdit> {
+        if self
+            .notebook
+            .as_ref()
+            .is_some_and(|notebook| notebook.encrypted)
+        {
+            self.context.show_alert(
+                "Notes of encrypted notebooks cannot be edited externally, the editor would see them as plaintext files",
+            );
+            return None;
+        }
+
+        self.save().await;
+
+        let tab = self
+            .context
+            .notebook
+            .tabs
+            .iter()
+            .find(|tab| tab.note.id == note_id)?;
+        let extension = tab
+            .note
+            .name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension)
+            .unwrap_or("md")
+            .to_owned();
+        let content = self
+            .context
+            .notebook
+            .editors
+            .get(&note_id)?
+            .editor
+            .lines
+            .to_string();
+
+        let base = match self.fetch_note_content(&note_id).await {
+            Ok(base) => base,
+            Err(err) => {
+                self.context
+                    .show_alert(format!("Failed to read the note: {err}"));
+                return None;
+            }
+        };
+
+        let path = match write_temp_file(&extension, &content) {
+            Ok(path) => path,
+            Err(err) => {
+                self.context
+                    .show_alert(format!("Failed to write the note for the editor: {err}"));
+                return None;
+            }
+        };
+
+        Some(ExternalEdit {
+            note_id,
+            path,
+            base,
+        })
+    }
+
+    /// Reads the edited file back. If the note changed in the backend while
+    /// it was out, the edits only replace it once confirmed.
+    pub async fn finish_external_edit(&mut self, edit: ExternalEdit) {
+        let content = match fs::read_to_string(&edit.path) {
+            Ok(content) => content,
+            Err(err) => {
+                self.context
+                    .show_alert(format!("Failed to read {}: {err}", edit.path.display()));
+                return;
+            }
+        };
+
+        // editors end the file with a newline the note did not have
+        let content = match content.strip_suffix('\n') {
+            Some(stripped) if !edit.base.ends_with('\n') => stripped.to_owned(),
+            _ => content,
+        };
+
+        match self.fetch_note_content(&edit.note_id).await {
+            Ok(current) if current != edit.base => {
+                let message = format!(
+                    "The note changed while it was edited outside. Overwrite it with the edits? Otherwise they stay in {}",
+                    edit.path.display()
+                );
+                let action = TuiAction::ApplyExternalEdit { edit, content }.into();
+                self.context.confirm = Some((message, action));
+            }
+            Ok(_) if content == edit.base => edit.remove_file(),
+            Ok(_) => self.apply_external_edit(edit, content).await,
+            Err(err) => {
+                self.context
+                    .show_alert(format!("Failed to read the note: {err}"));
+            }
+        }
+    }
+
+    pub(crate) async fn apply_external_edit(&mut self, edit: ExternalEdit, content: String) {
+        edit.remove_file();
+        self.replace_note_content(edit.note_id, content).await;
+    }
+
+    pub(crate) async fn fetch_note_content(&mut self, note_id: &NoteId) -> Result<String, Error> {
+        let db = self.glues.db.as_mut().log_expect("notebook must be open");
+        db.fetch_note_content(note_id.clone()).await
+    }
+}
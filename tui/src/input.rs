@@ -3,6 +3,7 @@ pub enum Input {
     Key(KeyEvent),
     Paste(String),
     Resize(u16, u16),
+    Mouse(MouseEvent),
 }
 
 #[derive(Clone, Debug)]
@@ -12,7 +13,7 @@ pub struct KeyEvent {
     pub kind: KeyEventKind,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum KeyCode {
     Char(char),
     F(u8),
@@ -70,6 +71,29 @@ impl Default for KeyModifiers {
     }
 }
 
+/// Left button presses and drags, and the wheel. Other mouse events are dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    pub column: u16,
+    pub row: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Down,
+    Up,
+    Drag,
+    ScrollUp,
+    ScrollDown,
+}
+
+impl MouseEvent {
+    pub fn new(kind: MouseEventKind, column: u16, row: u16) -> Self {
+        Self { kind, column, row }
+    }
+}
+
 mod native {
     use super::*;
     use ratatui::crossterm::event as ct;
@@ -80,6 +104,10 @@ mod native {
                 ct::Event::Key(key) => Input::Key(key.into()),
                 ct::Event::Paste(paste) => Input::Paste(paste),
                 ct::Event::Resize(w, h) => Input::Resize(w, h),
+                ct::Event::Mouse(mouse) => match MouseEventKind::from_native(mouse.kind) {
+                    Some(kind) => Input::Mouse(MouseEvent::new(kind, mouse.column, mouse.row)),
+                    None => Input::Resize(0, 0),
+                },
                 _ => Input::Resize(0, 0),
             }
         }
@@ -125,4 +153,36 @@ mod native {
             }
         }
     }
+
+    impl MouseEventKind {
+        fn from_native(kind: ct::MouseEventKind) -> Option<Self> {
+            match kind {
+                ct::MouseEventKind::Down(ct::MouseButton::Left) => Some(Self::Down),
+                ct::MouseEventKind::Up(ct::MouseButton::Left) => Some(Self::Up),
+                ct::MouseEventKind::Drag(ct::MouseButton::Left) => Some(Self::Drag),
+                ct::MouseEventKind::ScrollUp => Some(Self::ScrollUp),
+                ct::MouseEventKind::ScrollDown => Some(Self::ScrollDown),
+                _ => None,
+            }
+        }
+    }
+
+    impl From<MouseEvent> for ct::MouseEvent {
+        fn from(mouse: MouseEvent) -> Self {
+            let kind = match mouse.kind {
+                MouseEventKind::Down => ct::MouseEventKind::Down(ct::MouseButton::Left),
+                MouseEventKind::Up => ct::MouseEventKind::Up(ct::MouseButton::Left),
+                MouseEventKind::Drag => ct::MouseEventKind::Drag(ct::MouseButton::Left),
+                MouseEventKind::ScrollUp => ct::MouseEventKind::ScrollUp,
+                MouseEventKind::ScrollDown => ct::MouseEventKind::ScrollDown,
+            };
+
+            ct::MouseEvent {
+                kind,
+                column: mouse.column,
+                row: mouse.row,
+                modifiers: ct::KeyModifiers::NONE,
+            }
+        }
+    }
 }
@@ -0,0 +1,366 @@
+use {
+    crate::{
+        config,
+        input::{Input, KeyCode, KeyEvent, KeyModifiers},
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt,
+    },
+};
+
+/// Name of the keymap file inside the config directory.
+pub const KEYMAP_FILE: &str = "keymap";
+
+/// Mode a binding applies to, the first column of a keymap line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Mode {
+    Entry,
+    Tree,
+    Actions,
+    Move,
+    Normal,
+    Visual,
+    Insert,
+}
+
+impl Mode {
+    const ALL: [Mode; 7] = [
+        Mode::Entry,
+        Mode::Tree,
+        Mode::Actions,
+        Mode::Move,
+        Mode::Normal,
+        Mode::Visual,
+        Mode::Insert,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Entry => "entry",
+            Mode::Tree => "tree",
+            Mode::Actions => "actions",
+            Mode::Move => "move",
+            Mode::Normal => "normal",
+            Mode::Visual => "visual",
+            Mode::Insert => "insert",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+/// Single key press as written in the keymap file, e.g. `j`, `Ctrl+n` or `Esc`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 14] = [
+    ("Esc", KeyCode::Esc),
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("Space", KeyCode::Char(' ')),
+    ("Backspace", KeyCode::Backspace),
+    ("Delete", KeyCode::Delete),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+];
+
+impl Key {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (mut ctrl, mut alt) = (false, false);
+        let mut rest = value;
+
+        loop {
+            if let Some(stripped) = strip_prefix_ignore_case(rest, "ctrl+") {
+                ctrl = true;
+                rest = stripped;
+            } else if let Some(stripped) = strip_prefix_ignore_case(rest, "alt+") {
+                alt = true;
+                rest = stripped;
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if ctrl => KeyCode::Char(c.to_ascii_lowercase()),
+            (Some(c), None) => KeyCode::Char(c),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let n = strip_prefix_ignore_case(rest, "f")?.parse().ok()?;
+                    (1..=12).contains(&n).then_some(KeyCode::F(n))
+                })?,
+        };
+
+        Some(Self { code, ctrl, alt })
+    }
+
+    fn from_input(input: &Input) -> Option<Self> {
+        let Input::Key(key) = input else {
+            return None;
+        };
+
+        Some(Self {
+            code: key.code,
+            ctrl: key.modifiers.ctrl,
+            alt: key.modifiers.alt,
+        })
+    }
+
+    /// Keys without modifiers already mean something in every mode,
+    /// either a command or the character they type.
+    fn is_plain(&self) -> bool {
+        !self.ctrl && !self.alt
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            code => {
+                let name = NAMED_KEYS
+                    .iter()
+                    .find(|(_, named)| *named == code)
+                    .map_or("Null", |(name, _)| name);
+
+                write!(f, "{name}")
+            }
+        }
+    }
+}
+
+/// Effective key bindings: user keys which run the action of a default key.
+///
+/// Each line of the keymap file reads `<mode> <key> <action key>`, where the action
+/// is named by the key that triggers it out of the box:
+///
+/// ```text
+/// # Emacs style movement in the editor
+/// normal Ctrl+n j
+/// normal Ctrl+p k
+/// ```
+///
+/// A mapped key no longer runs its own default action, and mappings are not
+/// applied recursively, so two keys can be swapped.
+#[derive(Clone, Debug, Default)]
+pub struct Keybindings {
+    bindings: BTreeMap<(Mode, Key), Key>,
+    conflicts: Vec<String>,
+}
+
+impl Keybindings {
+    /// Reads the keymap file from the config directory. A missing file means no remapping.
+    pub fn load() -> Self {
+        let path = config::config_dir().join(KEYMAP_FILE);
+
+        match std::fs::read_to_string(&path) {
+            Ok(source) => Self::parse(&source),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => Self {
+                conflicts: vec![format!("Cannot read {}: {err}", path.display())],
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn parse(source: &str) -> Self {
+        let mut keybindings = Self::default();
+        let mut defined = BTreeSet::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let line_no = i + 1;
+            if line.trim_start().starts_with('#') {
+                continue;
+            }
+
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+
+            let [mode, key, action] = fields.as_slice() else {
+                if !fields.is_empty() {
+                    keybindings.conflicts.push(format!(
+                        "line {line_no}: expected `<mode> <key> <action key>`"
+                    ));
+                }
+                continue;
+            };
+
+            let Some(mode) = Mode::parse(mode) else {
+                keybindings
+                    .conflicts
+                    .push(format!("line {line_no}: unknown mode '{mode}'"));
+                continue;
+            };
+            let (Some(key), Some(action)) = (Key::parse(key), Key::parse(action)) else {
+                let invalid = if Key::parse(key).is_none() {
+                    key
+                } else {
+                    action
+                };
+                keybindings
+                    .conflicts
+                    .push(format!("line {line_no}: unknown key '{invalid}'"));
+                continue;
+            };
+
+            if !defined.insert((mode, key)) {
+                keybindings.conflicts.push(format!(
+                    "line {line_no}: {key} is bound twice in {} mode",
+                    mode.as_str()
+                ));
+                continue;
+            }
+
+            if key != action {
+                keybindings.bindings.insert((mode, key), action);
+            }
+        }
+
+        let unreachable = keybindings
+            .bindings
+            .keys()
+            .filter(|&&(mode, key)| key.is_plain() && keybindings.keys_for(mode, key).is_empty())
+            .map(|(mode, key)| {
+                format!(
+                    "{} mode: {key} is remapped, its action has no key",
+                    mode.as_str()
+                )
+            })
+            .collect::<Vec<_>>();
+        keybindings.conflicts.extend(unreachable);
+
+        keybindings
+    }
+
+    /// Problems found while reading the keymap file, reported once at startup.
+    pub fn conflicts(&self) -> &[String] {
+        &self.conflicts
+    }
+
+    /// Translates a user key into the default key whose action it is bound to.
+    pub fn remap(&self, mode: Mode, input: Input) -> Input {
+        let Some(action) = Key::from_input(&input).and_then(|key| self.bindings.get(&(mode, key)))
+        else {
+            return input;
+        };
+
+        match input {
+            Input::Key(key) => Input::Key(KeyEvent {
+                code: action.code,
+                modifiers: KeyModifiers::new(action.ctrl, action.alt, false),
+                kind: key.kind,
+            }),
+            input => input,
+        }
+    }
+
+    /// Keys which currently run the action of the default `action` key.
+    pub fn keys_for(&self, mode: Mode, action: Key) -> Vec<Key> {
+        let default = (!self.bindings.contains_key(&(mode, action))).then_some(action);
+        let aliases = self
+            .bindings
+            .iter()
+            .filter(|&(&(m, _), target)| m == mode && *target == action)
+            .map(|(&(_, key), _)| key);
+
+        default.into_iter().chain(aliases).collect()
+    }
+
+    /// Rewrites a help key list such as `j`, `v V Ctrl+v` or `t|Enter` with the
+    /// effective keys. Entries which are not single keys, like `1-9`, are kept.
+    pub fn describe(&self, mode: Mode, keys: &str) -> String {
+        let mut described = String::new();
+        let mut token = String::new();
+
+        for c in keys.chars().chain(std::iter::once('\0')) {
+            if c == ' ' || c == '|' || c == '\0' {
+                described.push_str(&self.describe_key(mode, &token, " "));
+                token.clear();
+                if c != '\0' {
+                    described.push(c);
+                }
+            } else {
+                token.push(c);
+            }
+        }
+
+        described
+    }
+
+    /// Rewrites every `[key]` of a help line, e.g. `[f] [F] Move cursor ...`.
+    pub fn describe_brackets(&self, mode: Mode, line: &str) -> String {
+        let mut described = String::new();
+        let mut rest = line;
+
+        while let Some(start) = rest.find('[') {
+            let Some(len) = rest[start + 1..].find(']') else {
+                break;
+            };
+            let token = &rest[start + 1..start + 1 + len];
+            described.push_str(&rest[..start]);
+            described.push('[');
+            described.push_str(&self.describe_key(mode, token, "] ["));
+            described.push(']');
+            rest = &rest[start + len + 2..];
+        }
+
+        described.push_str(rest);
+        described
+    }
+
+    fn describe_key(&self, mode: Mode, token: &str, separator: &str) -> String {
+        let Some(action) = Key::parse(token) else {
+            return token.to_owned();
+        };
+
+        let keys = self.keys_for(mode, action);
+        if keys.is_empty() {
+            return "none".to_owned();
+        }
+
+        keys.into_iter()
+            .map(|key| {
+                if key == action {
+                    token.to_owned()
+                } else {
+                    key.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    let rest = &value[prefix.len()..];
+
+    (head.eq_ignore_ascii_case(prefix) && !rest.is_empty()).then_some(rest)
+}
@@ -4,9 +4,16 @@ pub mod cli;
 pub mod color;
 pub mod config;
 pub mod context;
+pub mod external_editor;
 pub mod input;
+pub mod keybindings;
 #[macro_use]
 pub mod logger;
+mod mouse;
+pub mod notifications;
+pub mod recent;
+pub mod session;
+pub mod swap;
 pub mod theme;
 pub mod transitions;
 pub mod views;
@@ -2,5 +2,5 @@ use glues_tui::cli;
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
-    cli::run().await
+    cli::run_cli().await
 }
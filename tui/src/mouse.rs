@@ -0,0 +1,147 @@
+//! Mouse input, captured when the `mouse` config is on. Clicks are turned
+//! into the events the keys would dispatch, so core keeps the notebook state.
+
+use {
+    crate::{
+        App,
+        context::notebook::{ContextState, TreeItemKind},
+        input::{MouseEvent, MouseEventKind},
+        logger::*,
+    },
+    edtui::events::MouseEventHandler,
+    glues_core::{Event, KeyEvent, NotebookEvent},
+    ratatui::{
+        crossterm::{
+            event::{DisableMouseCapture, EnableMouseCapture},
+            execute,
+        },
+        layout::Position,
+    },
+    std::io,
+};
+
+impl App {
+    /// Starts or stops capturing the mouse, if the app is set to.
+    pub(crate) fn capture_mouse(&self, capture: bool) -> io::Result<()> {
+        match (self.mouse_capture, capture) {
+            (false, _) => Ok(()),
+            (true, true) => execute!(io::stdout(), EnableMouseCapture),
+            (true, false) => execute!(io::stdout(), DisableMouseCapture),
+        }
+    }
+
+    pub(crate) async fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let position = Position::new(mouse.column, mouse.row);
+
+        match mouse.kind {
+            MouseEventKind::Down => {
+                let double = self.context.notebook.click(position);
+                let notebook = &self.context.notebook;
+
+                if let Some(i) = notebook.tab_at(position) {
+                    self.leave_visual_mode().await;
+                    self.dispatch(NotebookEvent::SelectTab(i).into()).await;
+                } else if let Some(i) = notebook.tree_item_at(position) {
+                    self.click_tree_item(i, double).await;
+                } else if notebook.editor_area.contains(position) {
+                    self.click_editor(mouse).await;
+                }
+            }
+            MouseEventKind::Drag => {
+                let notebook = &self.context.notebook;
+                if !notebook.state.is_editor() || !notebook.editor_area.contains(position) {
+                    return;
+                }
+
+                if notebook.state != ContextState::EditorVisualMode {
+                    self.dispatch(Event::Key(KeyEvent::V)).await;
+                }
+                self.pass_to_editor(mouse);
+            }
+            MouseEventKind::Up => {}
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let notebook = &self.context.notebook;
+                if notebook.tree_area.contains(position) {
+                    if notebook.state != ContextState::NoteTreeBrowsing {
+                        return;
+                    }
+
+                    let key = match mouse.kind {
+                        MouseEventKind::ScrollUp => KeyEvent::K,
+                        _ => KeyEvent::J,
+                    };
+                    self.dispatch(Event::Key(key)).await;
+                } else if notebook.editor_area.contains(position) {
+                    self.pass_to_editor(mouse);
+                }
+            }
+        }
+    }
+
+    /// A click selects the item, like moving onto it with `j` and `k`, and a
+    /// double click opens a note or opens and closes a directory like `l`.
+    async fn click_tree_item(&mut self, i: usize, double: bool) {
+        self.leave_visual_mode().await;
+
+        if self.context.notebook.state.is_editor() {
+            self.context.notebook.update_yank();
+            self.save().await;
+        }
+
+        let item = &self.context.notebook.tree_items[i];
+        let id = item.id().clone();
+        let event = match &item.kind {
+            TreeItemKind::Note { note } => NotebookEvent::SelectNote(note.clone()),
+            TreeItemKind::Directory { directory, .. } => {
+                NotebookEvent::SelectDirectory(directory.clone())
+            }
+        };
+
+        self.dispatch(event.into()).await;
+        self.context.notebook.select_item(&id);
+
+        if double {
+            self.dispatch(Event::Key(KeyEvent::Enter)).await;
+        }
+    }
+
+    /// Focuses the editor from the tree, then leaves moving the cursor to edtui.
+    async fn click_editor(&mut self, mouse: MouseEvent) {
+        if self.context.notebook.tab_index.is_none() {
+            return;
+        }
+
+        match self.context.notebook.state {
+            ContextState::NoteTreeBrowsing => {
+                self.dispatch(Event::Key(KeyEvent::Tab)).await;
+            }
+            ContextState::EditorVisualMode => self.leave_visual_mode().await,
+            _ => {}
+        }
+
+        self.pass_to_editor(mouse);
+    }
+
+    /// `Esc` backs out of a pending visual command first, then visual mode.
+    async fn leave_visual_mode(&mut self) {
+        while self.context.notebook.state == ContextState::EditorVisualMode {
+            self.dispatch(Event::Key(KeyEvent::Esc)).await;
+        }
+    }
+
+    fn pass_to_editor(&mut self, mouse: MouseEvent) {
+        if self.context.notebook.tab_index.is_none() {
+            return;
+        }
+
+        // edtui maps the event onto the viewport of the last frame
+        self.context.notebook.scroll_anchor = None;
+        let editor = self.context.notebook.get_editor_mut();
+        MouseEventHandler::on_event(ratatui::crossterm::event::MouseEvent::from(mouse), editor);
+    }
+
+    async fn dispatch(&mut self, event: Event) {
+        let transition = self.glues.dispatch(event).await.log_unwrap();
+        self.handle_transition(transition).await;
+    }
+}
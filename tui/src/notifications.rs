@@ -0,0 +1,100 @@
+//! Messages shown while the app runs, kept so the ones that flashed by, such
+//! as the result of a background sync, can be read again in the notification
+//! center. The history lives as long as the app and is never written out.
+
+use {
+    gluesql::core::chrono::{DateTime, Local},
+    ratatui::widgets::ListState,
+    std::collections::VecDeque,
+};
+
+/// Older notifications are dropped beyond this many.
+pub const MAX_NOTIFICATIONS: usize = 200;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "INFO",
+            Self::Warning => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Debug, Default)]
+pub struct Notifications {
+    items: VecDeque<Notification>,
+    unread_errors: usize,
+}
+
+impl Notifications {
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>) {
+        if self.items.len() == MAX_NOTIFICATIONS {
+            self.items.pop_back();
+        }
+
+        self.items.push_front(Notification {
+            severity,
+            message: message.into(),
+            created_at: Local::now(),
+        });
+        if severity == Severity::Error {
+            self.unread_errors += 1;
+        }
+    }
+
+    /// Newest first.
+    pub fn iter(&self) -> impl Iterator<Item = &Notification> {
+        self.items.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Errors added since the notification center was last opened.
+    pub fn unread_errors(&self) -> usize {
+        self.unread_errors
+    }
+
+    pub fn mark_read(&mut self) {
+        self.unread_errors = 0;
+    }
+}
+
+/// Overlay listing the notifications, newest first.
+pub struct NotificationCenter {
+    pub list_state: ListState,
+}
+
+impl NotificationCenter {
+    pub fn new() -> Self {
+        Self {
+            list_state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl Default for NotificationCenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,190 @@
+//! Notebooks opened recently, listed in the entry menu. Pinned ones are
+//! listed first and kept until forgotten.
+
+use {
+    crate::{
+        App, config::platform::get_glue, context::entry::MENU_ITEMS, logger::*,
+        session::NotebookRef,
+    },
+    glues_core::EntryEvent,
+    gluesql::{
+        core::ast_builder::{Execute, col, function::now, table, text},
+        prelude::{CsvStorage, Glue, Value},
+    },
+};
+
+/// One entry for each of the quick-number keys 1-9.
+pub const MAX_RECENT: usize = 9;
+
+#[derive(Clone, Debug)]
+pub struct RecentNotebook {
+    pub notebook: NotebookRef,
+    pub pinned: bool,
+}
+
+impl RecentNotebook {
+    pub fn label(&self) -> String {
+        let NotebookRef {
+            open, encrypted, ..
+        } = &self.notebook;
+        let label = match open {
+            EntryEvent::OpenFile(path) => format!("Local {path}"),
+            EntryEvent::OpenRedb(path) => format!("redb {path}"),
+            EntryEvent::OpenGit {
+                path,
+                remote,
+                branch,
+            } => format!("Git {path} {remote}/{branch}"),
+            EntryEvent::OpenMongo { db_name, .. } => format!("MongoDB {db_name}"),
+            EntryEvent::OpenProxy { url, .. } => format!("Proxy {url}"),
+            EntryEvent::OpenMemory
+            | EntryEvent::OpenEncrypted { .. }
+            | EntryEvent::EncryptNotebook { .. } => "Instant".to_owned(),
+        };
+
+        if *encrypted {
+            format!("{label} (encrypted)")
+        } else {
+            label
+        }
+    }
+}
+
+async fn glue() -> Glue<CsvStorage> {
+    let mut glue = get_glue();
+    table("recent")
+        .create_table_if_not_exists()
+        .add_column("key TEXT PRIMARY KEY")
+        .add_column("notebook TEXT NOT NULL")
+        .add_column("pinned BOOLEAN NOT NULL")
+        .add_column("opened_at TIMESTAMP NOT NULL")
+        .execute(&mut glue)
+        .await
+        .expect("recent table creation should succeed");
+
+    glue
+}
+
+/// Pinned notebooks first, then the others from the most recently opened.
+pub async fn list() -> Vec<RecentNotebook> {
+    let mut recent = list_all().await;
+    recent.truncate(MAX_RECENT);
+    recent
+}
+
+/// Moves `notebook` to the top of the list, keeping whether it was pinned,
+/// and forgets the oldest unpinned entries beyond [`MAX_RECENT`].
+pub async fn record(notebook: &NotebookRef) {
+    let pinned = list_all()
+        .await
+        .iter()
+        .any(|recent| recent.pinned && recent.notebook.key() == notebook.key());
+    write(notebook, pinned).await;
+
+    let stale = list_all().await.into_iter().skip(MAX_RECENT);
+    for recent in stale.filter(|recent| !recent.pinned) {
+        forget(&recent.notebook).await;
+    }
+}
+
+pub async fn set_pinned(notebook: &NotebookRef, pinned: bool) {
+    let mut glue = glue().await;
+    table("recent")
+        .update()
+        .filter(col("key").eq(text(notebook.key())))
+        .set("pinned", pinned)
+        .execute(&mut glue)
+        .await
+        .expect("recent update should succeed");
+}
+
+pub async fn forget(notebook: &NotebookRef) {
+    let mut glue = glue().await;
+    table("recent")
+        .delete()
+        .filter(col("key").eq(text(notebook.key())))
+        .execute(&mut glue)
+        .await
+        .expect("recent delete should succeed");
+}
+
+async fn write(notebook: &NotebookRef, pinned: bool) {
+    forget(notebook).await;
+
+    let mut glue = glue().await;
+    table("recent")
+        .insert()
+        .columns(vec!["key", "notebook", "pinned", "opened_at"])
+        .values(vec![vec![
+            text(notebook.key()),
+            text(serde_json::to_string(notebook).log_unwrap()),
+            pinned.into(),
+            now(),
+        ]])
+        .execute(&mut glue)
+        .await
+        .expect("recent insert should succeed");
+}
+
+/// Entries which no longer parse, e.g. written by another version, are skipped.
+async fn list_all() -> Vec<RecentNotebook> {
+    let mut glue = glue().await;
+    let payload = table("recent")
+        .select()
+        .project(vec!["notebook", "pinned"])
+        .order_by("pinned DESC, opened_at DESC")
+        .execute(&mut glue)
+        .await
+        .log_unwrap();
+
+    payload
+        .select()
+        .log_expect("payload is not from select query")
+        .filter_map(|row| {
+            let (Some(Value::Str(notebook)), Some(Value::Bool(pinned))) =
+                (row.get("notebook"), row.get("pinned"))
+            else {
+                return None;
+            };
+
+            Some(RecentNotebook {
+                notebook: serde_json::from_str(notebook).ok()?,
+                pinned: *pinned,
+            })
+        })
+        .collect()
+}
+
+impl App {
+    /// Reloads the recent notebooks shown in the entry menu.
+    pub async fn load_recent(&mut self) {
+        self.context.entry.recent = list().await;
+    }
+
+    pub(crate) async fn toggle_pin_recent(&mut self, i: usize) {
+        let RecentNotebook { notebook, pinned } = self.context.entry.recent[i].clone();
+        set_pinned(&notebook, !pinned).await;
+        self.load_recent().await;
+
+        // keep the notebook selected as it moves in or out of the pinned ones
+        let entry = &mut self.context.entry;
+        if let Some(i) = entry
+            .recent
+            .iter()
+            .position(|recent| recent.notebook.key() == notebook.key())
+        {
+            entry.list_state.select(Some(MENU_ITEMS.len() + i));
+        }
+    }
+
+    pub(crate) async fn forget_recent(&mut self, i: usize) {
+        let notebook = self.context.entry.recent[i].notebook.clone();
+        forget(&notebook).await;
+        self.load_recent().await;
+
+        let entry = &mut self.context.entry;
+        let last = MENU_ITEMS.len() + entry.recent.len() - 1;
+        let selected = entry.list_state.selected().unwrap_or_default();
+        entry.list_state.select(Some(selected.min(last)));
+    }
+}
@@ -0,0 +1,287 @@
+//! Sessions saved per notebook in the config directory, so a notebook can be
+//! reopened with the directories, tabs, cursors and scroll positions it was
+//! left with.
+
+use {
+    crate::{
+        App,
+        config::{self, LAST_NOTEBOOK, platform::get_glue},
+        logger::*,
+        notifications::Severity,
+        recent,
+    },
+    glues_core::{
+        EntryEvent, Error, NotebookEvent,
+        state::{GetInner, NotebookState, notebook::NotebookSession},
+        types::{Cursor, NoteId},
+    },
+    gluesql::{
+        core::ast_builder::{Execute, col, table, text},
+        prelude::{CsvStorage, Glue},
+    },
+    serde::{Deserialize, Serialize},
+    std::{collections::HashMap, ops::Deref},
+};
+
+/// How to open a notebook again. Passphrases and auth tokens are never
+/// stored, only whether the notebook asked for them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NotebookRef {
+    pub open: EntryEvent,
+    pub encrypted: bool,
+    pub authenticated: bool,
+}
+
+impl NotebookRef {
+    /// Returns `None` for Instant notebooks, which are gone once left, and
+    /// for MongoDB connection strings carrying a password.
+    pub fn new(event: &EntryEvent) -> Option<Self> {
+        let notebook = match event {
+            EntryEvent::OpenMemory => return None,
+            EntryEvent::OpenMongo { conn_str, .. } if has_password(conn_str) => return None,
+            EntryEvent::OpenEncrypted { open, .. } | EntryEvent::EncryptNotebook { open, .. } => {
+                Self {
+                    encrypted: true,
+                    ..Self::new(open)?
+                }
+            }
+            EntryEvent::OpenProxy {
+                url,
+                auth_token,
+                tls,
+                queue_path,
+            } => Self {
+                open: EntryEvent::OpenProxy {
+                    url: url.clone(),
+                    auth_token: None,
+                    tls: tls.clone(),
+                    queue_path: queue_path.clone(),
+                },
+                encrypted: false,
+                authenticated: auth_token.is_some(),
+            },
+            open => Self {
+                open: open.clone(),
+                encrypted: false,
+                authenticated: false,
+            },
+        };
+
+        Some(notebook)
+    }
+
+    pub(crate) fn key(&self) -> String {
+        serde_json::to_string(&self.open).log_unwrap()
+    }
+}
+
+/// Whether `url` has a password in its user info, e.g. `mongodb://me:pw@host`.
+fn has_password(url: &str) -> bool {
+    let authority = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = authority.split('/').next().unwrap_or_default();
+
+    authority
+        .rsplit_once('@')
+        .is_some_and(|(user_info, _)| user_info.contains(':'))
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Session {
+    pub notebook: NotebookSession,
+    /// Cursor of each open tab.
+    pub cursors: HashMap<NoteId, Cursor>,
+    /// Top row of the viewport of each open tab.
+    #[serde(default)]
+    pub tops: HashMap<NoteId, usize>,
+    pub tree_width: u16,
+    pub show_browser: bool,
+}
+
+async fn glue() -> Glue<CsvStorage> {
+    let mut glue = get_glue();
+    table("session")
+        .create_table_if_not_exists()
+        .add_column("notebook TEXT PRIMARY KEY")
+        .add_column("state TEXT NOT NULL")
+        .execute(&mut glue)
+        .await
+        .expect("session table creation should succeed");
+
+    glue
+}
+
+pub async fn load(notebook: &NotebookRef) -> Option<Session> {
+    let mut glue = glue().await;
+    let state: String = table("session")
+        .select()
+        .filter(col("notebook").eq(text(notebook.key())))
+        .project(col("state"))
+        .execute(&mut glue)
+        .await
+        .log_unwrap()
+        .select()
+        .log_expect("payload is not from select query")
+        .next()?
+        .get("state")
+        .map(Deref::deref)
+        .log_expect("state does not exist in row")
+        .into();
+
+    // sessions written by other versions are dropped rather than half applied
+    serde_json::from_str(&state).ok()
+}
+
+pub async fn save(notebook: &NotebookRef, session: &Session) {
+    let mut glue = glue().await;
+    let key = notebook.key();
+    table("session")
+        .delete()
+        .filter(col("notebook").eq(text(&key)))
+        .execute(&mut glue)
+        .await
+        .expect("session delete should succeed");
+    table("session")
+        .insert()
+        .columns(vec!["notebook", "state"])
+        .values(vec![vec![
+            text(key),
+            text(serde_json::to_string(session).log_unwrap()),
+        ]])
+        .execute(&mut glue)
+        .await
+        .expect("session insert should succeed");
+}
+
+/// The notebook opened last, if it was not an Instant one.
+pub async fn last_notebook() -> Option<NotebookRef> {
+    let value = config::get(LAST_NOTEBOOK).await?;
+    serde_json::from_str(&value).ok()
+}
+
+impl App {
+    /// Restores saved sessions whenever a notebook is opened.
+    pub fn set_restore_session(&mut self, restore: bool) {
+        self.restore_session = restore;
+    }
+
+    /// Opens the notebook of `event`, then reopens its last session when
+    /// sessions are restored.
+    pub async fn open_notebook(&mut self, event: EntryEvent) {
+        let notebook = NotebookRef::new(&event);
+        match self.glues.dispatch(event.clone().into()).await {
+            Ok(transition) => {
+                self.handle_transition(transition).await;
+            }
+            Err(Error::NotEncrypted) => {
+                if let EntryEvent::OpenEncrypted { open, passphrase } = event {
+                    self.prompt_encryption(*open, passphrase);
+                }
+                return;
+            }
+            Err(err) => {
+                self.context.show_alert(err.to_string());
+                return;
+            }
+        }
+
+        let Some(notebook) = notebook else {
+            return;
+        };
+
+        config::update(
+            LAST_NOTEBOOK,
+            &serde_json::to_string(&notebook).log_unwrap(),
+        )
+        .await;
+        recent::record(&notebook).await;
+        if self.restore_session
+            && let Some(session) = load(&notebook).await
+        {
+            self.restore(session).await;
+        }
+        self.check_swaps(&notebook).await;
+        self.notebook = Some(notebook);
+    }
+
+    /// Opens `notebook` again, asking first for the secrets it needs.
+    pub async fn reopen(&mut self, notebook: NotebookRef) {
+        match notebook.open {
+            EntryEvent::OpenProxy { url, tls, .. } if notebook.authenticated => {
+                self.prompt_proxy_token(url, tls);
+            }
+            open if notebook.encrypted => self.prompt_passphrase(open),
+            open => self.open_notebook(open).await,
+        }
+    }
+
+    async fn restore(&mut self, session: Session) {
+        let Session {
+            notebook,
+            cursors,
+            tops,
+            tree_width,
+            show_browser,
+        } = session;
+
+        let event = NotebookEvent::RestoreSession(notebook).into();
+        match self.glues.dispatch(event).await {
+            Ok(transition) => self.handle_transition(transition).await,
+            Err(err) => {
+                log!("[Session] failed to restore: {err}");
+                self.context.notify(
+                    Severity::Warning,
+                    format!("Failed to restore the session: {err}"),
+                );
+                return;
+            }
+        }
+
+        self.context.notebook.tree_width = tree_width;
+        self.context.notebook.show_browser = show_browser;
+        for (note_id, cursor) in cursors {
+            self.restore_cursor(&note_id, cursor);
+        }
+        self.context.notebook.restored_tops = tops;
+    }
+
+    /// Saves the session of the open notebook, if any.
+    pub(crate) async fn save_session(&mut self) {
+        let Some(notebook) = self.notebook.as_ref() else {
+            return;
+        };
+        let state: Result<&NotebookState, _> = self.glues.state.get_inner();
+        let Ok(state) = state else {
+            return;
+        };
+
+        let notebook_context = &self.context.notebook;
+        let cursors = notebook_context
+            .editors
+            .iter()
+            .map(|(note_id, item)| {
+                let cursor = Cursor {
+                    row: item.editor.cursor.row,
+                    col: item.editor.cursor.col,
+                };
+                (note_id.clone(), cursor)
+            })
+            .collect();
+        let tops = notebook_context
+            .editors
+            .keys()
+            .filter_map(|note_id| {
+                let top = notebook_context.viewport_top(note_id)?;
+                Some((note_id.clone(), top))
+            })
+            .collect();
+        let session = Session {
+            notebook: state.session(),
+            cursors,
+            tops,
+            tree_width: notebook_context.tree_width,
+            show_browser: notebook_context.show_browser,
+        };
+
+        save(notebook, &session).await;
+    }
+}
@@ -0,0 +1,278 @@
+//! Swap files of unsaved editor buffers, kept in the config directory so the
+//! edits survive a crash. A swap is written whenever a buffer changes and is
+//! removed once the note is saved; leftovers are offered for recovery the
+//! next time their notebook is opened. Swaps are plaintext, so they are only
+//! readable by the user and never kept for encrypted notebooks.
+
+use {
+    crate::{
+        App,
+        config::config_dir,
+        context::{Recovery, RecoveryDialog},
+        notifications::Severity,
+        session::NotebookRef,
+    },
+    glues_core::types::NoteId,
+    serde::{Deserialize, Serialize},
+    std::{
+        fs,
+        io::{self, Write},
+        path::PathBuf,
+    },
+};
+
+/// Above this many line pairs the diff gives up on aligning the changed lines.
+const MAX_DIFF_CELLS: usize = 1_000_000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SwapFile {
+    /// [`NotebookRef::key`] of the notebook the note belongs to.
+    pub notebook: String,
+    pub note_id: NoteId,
+    pub note_name: String,
+    pub content: String,
+}
+
+pub fn dir() -> PathBuf {
+    config_dir().join("swap")
+}
+
+fn path(note_id: &NoteId) -> PathBuf {
+    dir().join(format!("{note_id}.swap"))
+}
+
+/// Writes to a temporary file first, so a crash never leaves half a swap.
+/// The file and the directory are only accessible to the user.
+pub fn write(swap: &SwapFile) -> io::Result<()> {
+    fs::create_dir_all(dir())?;
+    #[cfg(unix)]
+    fs::set_permissions(dir(), std::os::unix::fs::PermissionsExt::from_mode(0o700))?;
+
+    let mut file = tempfile::NamedTempFile::new_in(dir())?;
+    file.write_all(&serde_json::to_vec(swap).map_err(io::Error::other)?)?;
+    file.persist(path(&swap.note_id))?;
+
+    Ok(())
+}
+
+pub async fn remove(note_id: &NoteId) {
+    let path = path(note_id);
+    if path.exists()
+        && let Err(err) = fs::remove_file(&path)
+    {
+        log!("[Swap] failed to remove {}: {err}", path.display());
+    }
+}
+
+/// Swaps left for `notebook`, in no particular order. Files which do not
+/// parse are skipped.
+pub fn list(notebook: &NotebookRef) -> Vec<SwapFile> {
+    let Ok(entries) = fs::read_dir(dir()) else {
+        return Vec::new();
+    };
+
+    let key = notebook.key();
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "swap")
+        })
+        .filter_map(|path| serde_json::from_slice::<SwapFile>(&fs::read(path).ok()?).ok())
+        .filter(|swap| swap.notebook == key)
+        .collect()
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Removed(String),
+    Added(String),
+}
+
+/// Line diff from the saved note to the swapped buffer.
+pub fn diff(saved: &str, swapped: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = saved.lines().collect();
+    let new: Vec<&str> = swapped.lines().collect();
+
+    let prefix = old
+        .iter()
+        .zip(&new)
+        .take_while(|(old, new)| old == new)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(old, new)| old == new)
+        .count();
+    let old_changed = &old[prefix..old.len() - suffix];
+    let new_changed = &new[prefix..new.len() - suffix];
+
+    let same = |lines: &[&str]| -> Vec<DiffLine> {
+        lines
+            .iter()
+            .map(|line| DiffLine::Same(line.to_string()))
+            .collect()
+    };
+
+    let mut lines = same(&old[..prefix]);
+    lines.extend(diff_changed(old_changed, new_changed));
+    lines.extend(same(&old[old.len() - suffix..]));
+    lines
+}
+
+/// Aligns the lines on their longest common subsequence.
+fn diff_changed(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let removed = old.iter().map(|line| DiffLine::Removed(line.to_string()));
+    let added = new.iter().map(|line| DiffLine::Added(line.to_string()));
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return removed.chain(added).collect();
+    }
+
+    // common[i][j] is the LCS length of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::with_capacity(old.len() + new.len());
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_owned()));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(DiffLine::Removed(old[i].to_owned()));
+            i += 1;
+        } else {
+            lines.push(DiffLine::Added(new[j].to_owned()));
+            j += 1;
+        }
+    }
+    lines.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    lines.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    lines
+}
+
+impl App {
+    /// Writes the active buffer to its swap file if it changed since it was
+    /// saved or last swapped. Instant notebooks have nothing to recover into,
+    /// and encrypted ones would have their notes written out in plaintext.
+    pub async fn update_swap(&mut self) {
+        let Some(notebook) = self
+            .notebook
+            .as_ref()
+            .filter(|notebook| !notebook.encrypted)
+        else {
+            return;
+        };
+        let notebook_context = &mut self.context.notebook;
+        let Some(tab) = notebook_context
+            .tab_index
+            .and_then(|i| notebook_context.tabs.get(i))
+        else {
+            return;
+        };
+        let Some(item) = notebook_context.editors.get_mut(&tab.note.id) else {
+            return;
+        };
+
+        let content = item.editor.lines.to_string();
+        if content == item.persisted {
+            return;
+        }
+
+        let swap = SwapFile {
+            notebook: notebook.key(),
+            note_id: tab.note.id.clone(),
+            note_name: tab.note.name.clone(),
+            content,
+        };
+        match write(&swap) {
+            Ok(()) => item.persisted = swap.content,
+            Err(err) => {
+                let message = format!("Failed to write the swap of {}: {err}", swap.note_name);
+                log!("[Swap] {message}");
+                self.context.notify(Severity::Warning, message);
+            }
+        }
+    }
+
+    /// Offers to recover the swaps left for `notebook`. Swaps which match the
+    /// saved note have nothing to recover and are removed.
+    pub(crate) async fn check_swaps(&mut self, notebook: &NotebookRef) {
+        let mut items = Vec::new();
+        for swap in list(notebook) {
+            let saved = self.fetch_note_content(&swap.note_id).await.ok();
+            if saved
+                .as_deref()
+                .is_some_and(|saved| saved.trim_end() == swap.content.trim_end())
+            {
+                remove(&swap.note_id).await;
+                continue;
+            }
+
+            items.push(Recovery { swap, saved });
+        }
+
+        if !items.is_empty() {
+            items.sort_by(|a, b| a.swap.note_name.cmp(&b.swap.note_name));
+            self.context.recovery = Some(RecoveryDialog::new(items));
+        }
+    }
+
+    /// Saves the swapped content over the note, in its buffer too if open.
+    pub(crate) async fn recover_swap(&mut self, i: usize) {
+        let Some(dialog) = self.context.recovery.as_mut() else {
+            return;
+        };
+        if dialog.items[i].saved.is_none() {
+            let name = &dialog.items[i].swap.note_name;
+            let message = format!(
+                "{name} no longer exists in the notebook. Discard its swap or copy the text out of {}",
+                path(&dialog.items[i].swap.note_id).display()
+            );
+            self.context.show_alert(message);
+            return;
+        }
+
+        let Recovery { swap, .. } = dialog.take(i);
+        if dialog.items.is_empty() {
+            self.context.recovery = None;
+        }
+
+        self.replace_note_content(swap.note_id.clone(), swap.content)
+            .await;
+        remove(&swap.note_id).await;
+    }
+
+    pub(crate) async fn discard_swap(&mut self, i: usize) {
+        let Some(dialog) = self.context.recovery.as_mut() else {
+            return;
+        };
+
+        let Recovery { swap, .. } = dialog.take(i);
+        if dialog.items.is_empty() {
+            self.context.recovery = None;
+        }
+
+        remove(&swap.note_id).await;
+    }
+}
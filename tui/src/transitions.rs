@@ -3,7 +3,14 @@ mod keymap;
 mod notebook;
 
 use {
-    super::App, async_recursion::async_recursion, glues_core::transition::Transition,
+    super::App,
+    crate::{context::SyncConflictDialog, notifications::Severity},
+    async_recursion::async_recursion,
+    glues_core::{
+        backend::Resolution,
+        state::{GetInner, NotebookState},
+        transition::Transition,
+    },
     std::time::SystemTime,
 };
 
@@ -26,11 +33,28 @@ impl App {
             }
             Transition::Log(message) => {
                 log!("{message}");
+                self.context.notify(Severity::Info, message.clone());
                 self.context.last_log = Some((message, SystemTime::now()));
             }
             Transition::Error(message) => {
                 log!("[Err] {message}");
-                self.context.alert = Some(message);
+                self.context.show_alert(message);
+            }
+            Transition::SyncConflict(conflict) => {
+                log!("[Err] sync: {conflict}");
+                self.context
+                    .notify(Severity::Warning, format!("Sync conflict: {conflict}"));
+                let state: Result<&NotebookState, _> = self.glues.state.get_inner();
+                match state {
+                    Ok(NotebookState { root, .. }) => {
+                        self.context.sync_conflict = Some(SyncConflictDialog::new(root, &conflict));
+                    }
+                    Err(_) => {
+                        // the notebook was closed while syncing
+                        self.resolve_sync_conflict(Resolution::Abort);
+                        self.context.show_alert(format!("Sync aborted: {conflict}"));
+                    }
+                }
             }
         }
 
@@ -1,5 +1,6 @@
 mod editing_normal_mode;
 mod editing_visual_mode;
+mod motion;
 mod note_tree;
 mod textarea;
 
@@ -8,14 +9,16 @@ use {
         App,
         context::{self},
         logger::*,
+        swap,
     },
     glues_core::{
         NotebookEvent,
         state::{
             GetInner, NotebookState,
-            notebook::{EditorState, InnerState, NoteTreeState, VimNormalState},
+            notebook::{EditorState, InnerState, NoteTreeState, VimNormalState, VimVisualState},
         },
         transition::NotebookTransition,
+        types::{Cursor, NoteId},
     },
 };
 
@@ -25,8 +28,10 @@ impl App {
 
         let NotebookState {
             root,
+            tabs,
             inner_state,
             tab_index,
+            visual_kind,
             ..
         } = self.glues.state.get_inner().log_unwrap();
         let new_state = match inner_state {
@@ -54,6 +59,16 @@ impl App {
             self.context.notebook.state = new_state;
         }
 
+        self.context.notebook.visual_kind = *visual_kind;
+        self.context.notebook.awaiting_char = matches!(
+            inner_state,
+            InnerState::Editor(EditorState::Normal(
+                VimNormalState::CharSearch(..)
+                    | VimNormalState::Mark
+                    | VimNormalState::MarkJump { .. }
+            )) | InnerState::Editor(EditorState::Visual(VimVisualState::CharSearch(..)))
+        );
+
         if &self.context.notebook.tab_index != tab_index {
             self.context.notebook.tab_index = *tab_index;
             self.context.notebook.scroll_anchor = None;
@@ -64,10 +79,19 @@ impl App {
                 self.context.vim_keymap = Some(kind);
             }
             NotebookTransition::ViewMode(_note) => {
+                self.context.notebook.completion = None;
+                if let Some(insert) = self.context.notebook.block_insert.take() {
+                    let editor = self.context.notebook.get_editor_mut();
+                    textarea::repeat_block_insert(editor, &insert);
+                }
                 self.context.notebook.mark_dirty();
             }
+            NotebookTransition::CompleteNoteLink(titles) => {
+                self.context.notebook.complete_note_link(titles);
+            }
             NotebookTransition::UpdateNoteContent(note_id) => {
                 self.context.notebook.mark_clean(&note_id);
+                swap::remove(&note_id).await;
             }
             NotebookTransition::BrowseNoteTree => {}
             NotebookTransition::FocusEditor => {
@@ -93,12 +117,57 @@ impl App {
             }
             NotebookTransition::Alert(message) => {
                 log!("[Alert] {message}");
-                self.context.alert = Some(message);
+                self.context.show_alert(message);
+            }
+            NotebookTransition::Info(message) => {
+                log!("[Info] {message}");
+                self.context.show_info(message);
+            }
+            NotebookTransition::RestoreSession(contents) => {
+                for (note_id, content) in contents {
+                    self.context.notebook.open_note(note_id, content);
+                }
+                self.context.notebook.tabs = tabs.clone();
+                self.context.notebook.update_items(root);
+                if let Some(note_id) = self
+                    .context
+                    .notebook
+                    .get_opened_note()
+                    .map(|n| n.id.clone())
+                {
+                    self.context.notebook.select_item(&note_id);
+                }
+                self.context.notebook.apply_yank();
             }
             NotebookTransition::Inedible(_) | NotebookTransition::None => {}
         }
     }
 
+    /// Puts the cursor of a reopened tab back, as far as the note still reaches.
+    pub(crate) fn restore_cursor(&mut self, note_id: &NoteId, cursor: Cursor) {
+        if let Some(item) = self.context.notebook.editors.get_mut(note_id) {
+            item.editor.cursor = motion::mark_target(&item.editor.lines, cursor, false);
+        }
+    }
+
+    /// Replaces the text of a note, in its buffer too if it is open, and
+    /// saves it.
+    pub(crate) async fn replace_note_content(&mut self, note_id: NoteId, content: String) {
+        if let Some(item) = self.context.notebook.editors.get_mut(&note_id) {
+            let cursor = Cursor {
+                row: item.editor.cursor.row,
+                col: item.editor.cursor.col,
+            };
+            item.editor.lines = content.as_str().into();
+            item.editor.selection = None;
+            self.restore_cursor(&note_id, cursor);
+        }
+
+        let event = NotebookEvent::UpdateNoteContent { note_id, content }.into();
+        let transition = self.glues.dispatch(event).await.log_unwrap();
+        self.handle_transition(transition).await;
+    }
+
     pub(crate) async fn save(&mut self) {
         let mut transitions = vec![];
 
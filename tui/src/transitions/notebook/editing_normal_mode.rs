@@ -1,7 +1,10 @@
 use {
-    super::textarea::{
-        move_word_backward, move_word_forward, move_word_forward_to_end, select_inner_word,
-        set_selection, switch_case,
+    super::{
+        motion::{TextRange, mark_target, motion_range, motion_target, text_object_range},
+        textarea::{
+            move_word_backward, move_word_forward, move_word_forward_to_end, set_selection,
+            switch_case,
+        },
     },
     crate::{App, logger::*},
     edtui::{
@@ -14,8 +17,10 @@ use {
         },
     },
     glues_core::{
+        NotebookEvent,
         state::{GetInner, NotebookState},
-        transition::NormalModeTransition,
+        transition::{NormalModeTransition, VimOperator},
+        types::Cursor,
     },
 };
 
@@ -31,8 +36,9 @@ impl App {
                 editor.execute(SwitchMode(EditorMode::Normal));
             }
             ToggleMode | ToggleTabCloseMode | NumberingMode | GatewayMode | YankMode
-            | DeleteMode | DeleteInsideMode | ChangeMode | ChangeInsideMode | ScrollMode => {}
-            NextTab(note_id) | PrevTab(note_id) => {
+            | DeleteMode | ChangeMode | TextObjectMode | CharSearchMode | MarkMode
+            | MarkJumpMode | ScrollMode => {}
+            NextTab(note_id) | PrevTab(note_id) | SelectTab(note_id) => {
                 self.context.notebook.update_items(root);
                 self.context.notebook.select_item(&note_id);
                 self.context.notebook.apply_yank();
@@ -63,6 +69,9 @@ impl App {
                 self.context.notebook.show_syntax_highlight =
                     !self.context.notebook.show_syntax_highlight;
             }
+            EditExternally(note_id) => {
+                self.external_edit = Some(note_id);
+            }
             MoveCursorDown(n) => {
                 self.context.notebook.get_editor_mut().execute(MoveDown(n));
             }
@@ -128,6 +137,12 @@ impl App {
                 editor.cursor = Index2::new(target_row, 0);
                 editor.execute(MoveToFirst());
             }
+            MoveCursorMotion(motion, n) => {
+                let editor = self.context.notebook.get_editor_mut();
+                if let Some(target) = motion_target(&editor.lines, editor.cursor, motion, n) {
+                    editor.cursor = target;
+                }
+            }
             InsertNewLineBelow => {
                 let editor = self.context.notebook.get_editor_mut();
                 editor.execute(AppendNewline(1));
@@ -254,15 +269,24 @@ impl App {
                 self.context.notebook.mark_dirty();
                 self.context.notebook.update_yank();
             }
-            DeleteInsideWord(n) => {
-                let editor = self.context.notebook.get_editor_mut();
-                for _ in 0..n {
-                    select_inner_word(editor);
-                    editor.execute(DeleteSelection);
-                }
-                self.context.notebook.line_yanked = false;
-                self.context.notebook.mark_dirty();
-                self.context.notebook.update_yank();
+            Operate {
+                operator,
+                motion,
+                n,
+            } => {
+                let editor = self.context.notebook.get_editor();
+                let range = motion_range(&editor.lines, editor.cursor, motion, n);
+                self.apply_operator(operator, range);
+            }
+            OperateTextObject {
+                operator,
+                scope,
+                object,
+                n,
+            } => {
+                let editor = self.context.notebook.get_editor();
+                let range = text_object_range(&editor.lines, editor.cursor, scope, object, n);
+                self.apply_operator(operator, range);
             }
             DeleteWordEnd(n) => {
                 let editor = self.context.notebook.get_editor_mut();
@@ -340,6 +364,143 @@ impl App {
                 self.context.notebook.pending_scroll =
                     Some(crate::context::notebook::ScrollRequest::Bottom);
             }
+            SetMark(mark) => {
+                let cursor = self.editor_cursor();
+                self.dispatch_mark_event(NotebookEvent::SetMark { mark, cursor })
+                    .await;
+            }
+            JumpToMark { mark, linewise } => {
+                let cursor = self.editor_cursor();
+                self.dispatch_mark_event(NotebookEvent::JumpToMark {
+                    mark,
+                    linewise,
+                    cursor,
+                })
+                .await;
+            }
+            JumpOlder => {
+                let cursor = self.editor_cursor();
+                self.dispatch_mark_event(NotebookEvent::JumpOlder(cursor))
+                    .await;
+            }
+            JumpNewer => {
+                self.dispatch_mark_event(NotebookEvent::JumpNewer).await;
+            }
+            Jump {
+                note_id,
+                content,
+                cursor,
+                linewise,
+            } => {
+                if let Some(content) = content {
+                    self.context.notebook.open_note(note_id.clone(), content);
+                    self.context.notebook.tabs = tabs.clone();
+                }
+
+                self.context.notebook.update_items(root);
+                self.context.notebook.select_item(&note_id);
+                self.context.notebook.apply_yank();
+
+                if let Some(cursor) = cursor {
+                    let editor = self.context.notebook.get_editor_mut();
+                    editor.cursor = mark_target(&editor.lines, cursor, linewise);
+                    editor.selection = None;
+                }
+            }
+        };
+    }
+
+    fn editor_cursor(&self) -> Cursor {
+        let cursor = self.context.notebook.get_editor().cursor;
+
+        Cursor {
+            row: cursor.row,
+            col: cursor.col,
+        }
+    }
+
+    async fn dispatch_mark_event(&mut self, event: NotebookEvent) {
+        let transition = self.glues.dispatch(event.into()).await.log_unwrap();
+        self.handle_transition(transition).await;
+    }
+
+    /// Applies `d`, `c` or `y` to the range computed from a motion or a text
+    /// object. A missing range leaves the text untouched, but `c` still ends
+    /// in insert mode since core has already switched to it.
+    pub(super) fn apply_operator(&mut self, operator: VimOperator, range: Option<TextRange>) {
+        let editor = self.context.notebook.get_editor_mut();
+        let Some(range) = range.filter(|range| !range.is_empty()) else {
+            if let Some(range) = range {
+                editor.cursor = range.start;
+            }
+            if matches!(operator, VimOperator::Change) {
+                editor.execute(SwitchMode(EditorMode::Insert));
+            }
+            return;
         };
+
+        let TextRange {
+            start,
+            end,
+            linewise,
+        } = range;
+        let cursor = editor.cursor;
+
+        if linewise {
+            let end_col = editor.lines.len_col(end.row).unwrap_or(0).saturating_sub(1);
+            set_selection(
+                editor,
+                Index2::new(start.row, 0),
+                Index2::new(end.row, end_col),
+            );
+            editor.execute(CopySelection);
+            let clip = self.context.notebook.get_clipboard();
+            let text = String::from('\n') + &clip.get_text();
+
+            let editor = self.context.notebook.get_editor_mut();
+            match operator {
+                VimOperator::Yank => {
+                    editor.cursor = Index2::new(start.row, cursor.col);
+                }
+                VimOperator::Delete => {
+                    editor.cursor = Index2::new(start.row, 0);
+                    editor.execute(DeleteLine(end.row - start.row + 1));
+                    editor.execute(MoveToFirst());
+                }
+                VimOperator::Change => {
+                    set_selection(
+                        editor,
+                        Index2::new(start.row, 0),
+                        Index2::new(end.row, end_col),
+                    );
+                    editor.execute(DeleteSelection);
+                    editor.execute(SwitchMode(EditorMode::Insert));
+                }
+            }
+            self.context.notebook.get_clipboard().set_text(text);
+            self.context.notebook.line_yanked = true;
+        } else {
+            set_selection(editor, start, end);
+            match operator {
+                VimOperator::Yank => {
+                    editor.execute(CopySelection);
+                    editor.cursor = start;
+                }
+                VimOperator::Delete => {
+                    editor.execute(DeleteSelection);
+                }
+                VimOperator::Change => {
+                    editor.execute(DeleteSelection);
+                    editor.execute(SwitchMode(EditorMode::Insert));
+                    editor.cursor = start;
+                }
+            }
+            self.context.notebook.line_yanked = false;
+        }
+
+        if !matches!(operator, VimOperator::Yank) {
+            self.context.notebook.mark_dirty();
+        }
+        self.context.notebook.update_yank();
     }
 }
@@ -1,24 +1,30 @@
 use {
-    super::textarea::{
-        move_word_backward, move_word_forward, move_word_forward_to_end, switch_case_selection,
-        transform_selection,
+    super::{
+        motion::{TextRange, motion_target, text_object_range},
+        textarea::{
+            block_bounds, copy_block, delete_block, extend_selection, indent_rows, join_rows,
+            move_word_backward, move_word_forward, move_word_forward_to_end, outdent_rows, pad_row,
+            selected_rows, set_line_mode, set_selection, switch_case_selection, transform_block,
+            transform_selection,
+        },
     },
-    crate::App,
+    crate::{App, context::notebook::ColumnInsert},
     edtui::{
-        EditorMode,
+        EditorMode, Index2,
         actions::{
             ChangeSelection, CopySelection, MoveBackward, MoveDown, MoveForward, MoveToEndOfLine,
             MoveToFirst, MoveToStartOfLine, MoveUp, SwitchMode, motion::MoveToFirstRow,
             motion::MoveToLastRow,
         },
     },
-    glues_core::transition::VisualModeTransition,
+    glues_core::transition::{VimOperator, VisualKind, VisualModeTransition},
 };
 
 impl App {
     pub(super) async fn handle_visual_mode_transition(&mut self, transition: VisualModeTransition) {
         use VisualModeTransition::*;
 
+        let kind = self.context.notebook.visual_kind;
         match transition {
             IdleMode => {
                 self.context
@@ -26,7 +32,14 @@ impl App {
                     .get_editor_mut()
                     .execute(SwitchMode(EditorMode::Visual));
             }
-            NumberingMode | GatewayMode => {}
+            NumberingMode | GatewayMode | TextObjectMode | CharSearchMode => {}
+            SwitchKind(kind) => {
+                let editor = self.context.notebook.get_editor_mut();
+                if editor.mode != EditorMode::Visual {
+                    editor.execute(SwitchMode(EditorMode::Visual));
+                }
+                set_line_mode(editor, kind == VisualKind::Line);
+            }
             MoveCursorDown(n) => {
                 self.context.notebook.get_editor_mut().execute(MoveDown(n));
             }
@@ -95,12 +108,70 @@ impl App {
                 }
                 editor.execute(MoveToFirst());
             }
+            MoveCursorMotion(motion, n) => {
+                let editor = self.context.notebook.get_editor_mut();
+                if let Some(target) = motion_target(&editor.lines, editor.cursor, motion, n) {
+                    editor.cursor = target;
+                    extend_selection(editor);
+                }
+            }
+            SelectTextObject { scope, object, n } => {
+                let editor = self.context.notebook.get_editor_mut();
+                let range = text_object_range(&editor.lines, editor.cursor, scope, object, n);
+                if let Some(range) = range.filter(|range| !range.is_empty()) {
+                    let (start, end) = if range.linewise {
+                        let end_col = editor
+                            .lines
+                            .len_col(range.end.row)
+                            .unwrap_or(0)
+                            .saturating_sub(1);
+                        (
+                            Index2::new(range.start.row, 0),
+                            Index2::new(range.end.row, end_col),
+                        )
+                    } else {
+                        (range.start, range.end)
+                    };
+                    set_selection(editor, start, end);
+                    editor.cursor = end;
+                }
+            }
+            YankSelection if kind == VisualKind::Line => {
+                self.operate_on_selected_lines(VimOperator::Yank);
+            }
+            YankSelection if kind == VisualKind::Block => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some(block) = block_bounds(editor) else {
+                    return;
+                };
+                let text = copy_block(editor, block);
+                editor.cursor = Index2::new(block.top, block.left);
+                editor.execute(SwitchMode(EditorMode::Normal));
+                self.context.notebook.get_clipboard().set_text(text);
+                self.context.notebook.line_yanked = false;
+                self.context.notebook.update_yank();
+            }
             YankSelection => {
                 let editor = self.context.notebook.get_editor_mut();
                 editor.execute(CopySelection);
                 self.context.notebook.line_yanked = false;
                 self.context.notebook.update_yank();
             }
+            DeleteSelection if kind == VisualKind::Line => {
+                self.operate_on_selected_lines(VimOperator::Delete);
+            }
+            DeleteSelection if kind == VisualKind::Block => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some(block) = block_bounds(editor) else {
+                    return;
+                };
+                let text = delete_block(editor, block);
+                editor.execute(SwitchMode(EditorMode::Normal));
+                self.context.notebook.get_clipboard().set_text(text);
+                self.context.notebook.line_yanked = false;
+                self.context.notebook.mark_dirty();
+                self.context.notebook.update_yank();
+            }
             DeleteSelection => {
                 let editor = self.context.notebook.get_editor_mut();
                 editor.execute(edtui::actions::DeleteSelection);
@@ -108,6 +179,27 @@ impl App {
                 self.context.notebook.mark_dirty();
                 self.context.notebook.update_yank();
             }
+            DeleteSelectionAndInsertMode if kind == VisualKind::Line => {
+                self.operate_on_selected_lines(VimOperator::Change);
+            }
+            DeleteSelectionAndInsertMode if kind == VisualKind::Block => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some(block) = block_bounds(editor) else {
+                    return;
+                };
+                let text = delete_block(editor, block);
+                editor.execute(SwitchMode(EditorMode::Insert));
+                self.context.notebook.block_insert = Some(ColumnInsert {
+                    top: block.top,
+                    bottom: block.bottom,
+                    col: block.left,
+                    pad: false,
+                });
+                self.context.notebook.get_clipboard().set_text(text);
+                self.context.notebook.line_yanked = false;
+                self.context.notebook.mark_dirty();
+                self.context.notebook.update_yank();
+            }
             DeleteSelectionAndInsertMode => {
                 let editor = self.context.notebook.get_editor_mut();
                 editor.execute(ChangeSelection);
@@ -116,6 +208,26 @@ impl App {
                 self.context.notebook.mark_dirty();
                 self.context.notebook.update_yank();
             }
+            SwitchCase | ToLowercase | ToUppercase if kind == VisualKind::Block => {
+                let transform: fn(char) -> char = match transition {
+                    SwitchCase => |c| {
+                        if c.is_uppercase() {
+                            c.to_lowercase().next().unwrap_or(c)
+                        } else {
+                            c.to_uppercase().next().unwrap_or(c)
+                        }
+                    },
+                    ToLowercase => |c| c.to_lowercase().next().unwrap_or(c),
+                    _ => |c| c.to_uppercase().next().unwrap_or(c),
+                };
+
+                let editor = self.context.notebook.get_editor_mut();
+                if let Some(block) = block_bounds(editor) {
+                    transform_block(editor, block, transform);
+                }
+                editor.execute(SwitchMode(EditorMode::Normal));
+                self.context.notebook.mark_dirty();
+            }
             SwitchCase => {
                 let editor = self.context.notebook.get_editor_mut();
                 switch_case_selection(editor);
@@ -131,6 +243,65 @@ impl App {
                 transform_selection(editor, |c| c.to_uppercase().next().unwrap_or(c));
                 self.context.notebook.mark_dirty();
             }
+            IndentSelection(n) | OutdentSelection(n) => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some((top, bottom)) = selected_rows(editor) else {
+                    return;
+                };
+                if matches!(transition, IndentSelection(_)) {
+                    indent_rows(editor, top, bottom, n);
+                } else {
+                    outdent_rows(editor, top, bottom, n);
+                }
+                editor.execute(SwitchMode(EditorMode::Normal));
+                self.context.notebook.mark_dirty();
+            }
+            JoinSelection => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some((top, bottom)) = selected_rows(editor) else {
+                    return;
+                };
+                join_rows(editor, top, bottom);
+                editor.execute(SwitchMode(EditorMode::Normal));
+                self.context.notebook.mark_dirty();
+            }
+            BlockInsert | BlockAppend => {
+                let editor = self.context.notebook.get_editor_mut();
+                let Some(block) = block_bounds(editor) else {
+                    return;
+                };
+                let pad = matches!(transition, BlockAppend);
+                let col = if pad {
+                    pad_row(editor, block.top, block.right + 1);
+                    block.right + 1
+                } else {
+                    block.left.min(editor.lines.len_col(block.top).unwrap_or(0))
+                };
+
+                editor.selection = None;
+                editor.cursor = Index2::new(block.top, col);
+                editor.execute(SwitchMode(EditorMode::Insert));
+                self.context.notebook.block_insert = Some(ColumnInsert {
+                    top: block.top,
+                    bottom: block.bottom,
+                    col,
+                    pad,
+                });
+            }
+        }
+    }
+
+    /// `V` selections always cover whole rows, so `y`, `d` and `s` act like the
+    /// linewise operators of normal mode.
+    fn operate_on_selected_lines(&mut self, operator: VimOperator) {
+        let editor = self.context.notebook.get_editor_mut();
+        let Some((top, bottom)) = selected_rows(editor) else {
+            return;
+        };
+        if !matches!(operator, VimOperator::Change) {
+            editor.execute(SwitchMode(EditorMode::Normal));
         }
+
+        self.apply_operator(operator, Some(TextRange::linewise(top, bottom)));
     }
 }
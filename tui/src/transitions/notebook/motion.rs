@@ -0,0 +1,565 @@
+use {
+    edtui::{Index2, Lines, RowIndex},
+    glues_core::{
+        transition::{CharSearch, CharSearchKind, TextObject, TextObjectScope, VimMotion},
+        types::Cursor,
+    },
+};
+
+/// Inclusive range an operator or a visual selection applies to.
+/// A `linewise` range always covers whole rows, like `dd`.
+#[derive(Clone, Copy)]
+pub(super) struct TextRange {
+    pub start: Index2,
+    pub end: Index2,
+    pub linewise: bool,
+}
+
+impl TextRange {
+    fn charwise(start: Index2, end: Index2) -> Self {
+        Self {
+            start,
+            end,
+            linewise: false,
+        }
+    }
+
+    pub(super) fn linewise(start_row: usize, end_row: usize) -> Self {
+        Self {
+            start: Index2::new(start_row, 0),
+            end: Index2::new(end_row, 0),
+            linewise: true,
+        }
+    }
+
+    /// Empty ranges are produced by inner text objects with nothing inside,
+    /// e.g. `ci"` on `""`. `start` is still where the cursor should go.
+    pub fn is_empty(&self) -> bool {
+        !self.linewise && self.end < self.start
+    }
+}
+
+fn row(lines: &Lines, row: usize) -> &[char] {
+    lines
+        .get(RowIndex::new(row))
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+fn is_blank(lines: &Lines, r: usize) -> bool {
+    row(lines, r).iter().all(|ch| ch.is_whitespace())
+}
+
+fn first_non_blank(lines: &Lines, r: usize) -> usize {
+    row(lines, r)
+        .iter()
+        .position(|ch| !ch.is_whitespace())
+        .unwrap_or(0)
+}
+
+fn last_col(lines: &Lines, r: usize) -> usize {
+    row(lines, r).len().saturating_sub(1)
+}
+
+/// The editor may leave the cursor one past the last character (e.g. after
+/// leaving insert mode), while motions work on the character under it.
+fn clamp(lines: &Lines, cursor: Index2) -> Index2 {
+    Index2::new(cursor.row, cursor.col.min(last_col(lines, cursor.row)))
+}
+
+/// Where a mark lands once the note may have changed since it was set.
+/// `'` marks are `linewise` and go to the first non-blank character.
+pub(super) fn mark_target(lines: &Lines, cursor: Cursor, linewise: bool) -> Index2 {
+    let r = cursor.row.min(lines.len().saturating_sub(1));
+    let col = if linewise {
+        first_non_blank(lines, r)
+    } else {
+        cursor.col
+    };
+
+    clamp(lines, Index2::new(r, col))
+}
+
+/// Character class used by word text objects; mirrors `textarea::char_class`.
+fn char_class(ch: char) -> u8 {
+    if ch.is_alphanumeric() || ch == '_' {
+        0
+    } else if ch.is_whitespace() {
+        1
+    } else {
+        2
+    }
+}
+
+/// Where the cursor lands after `motion`, or `None` when it cannot move.
+pub(super) fn motion_target(
+    lines: &Lines,
+    cursor: Index2,
+    motion: VimMotion,
+    n: usize,
+) -> Option<Index2> {
+    let cursor = clamp(lines, cursor);
+
+    match motion {
+        VimMotion::CharSearch(search) => char_search(lines, cursor, search, n, false),
+        VimMotion::RepeatCharSearch(search) => char_search(lines, cursor, search, n, true),
+        VimMotion::MatchPair => match_pair(lines, cursor),
+        VimMotion::ParagraphForward => Some(paragraph_forward(lines, cursor, n)),
+        VimMotion::ParagraphBackward => Some(paragraph_backward(lines, cursor, n)),
+    }
+}
+
+/// Range covered by an operator followed by `motion`, e.g. `dt)` or `y}`.
+pub(super) fn motion_range(
+    lines: &Lines,
+    cursor: Index2,
+    motion: VimMotion,
+    n: usize,
+) -> Option<TextRange> {
+    let cursor = clamp(lines, cursor);
+    let target = motion_target(lines, cursor, motion, n)?;
+
+    match motion {
+        VimMotion::CharSearch(CharSearch { kind, .. })
+        | VimMotion::RepeatCharSearch(CharSearch { kind, .. }) => match kind {
+            CharSearchKind::Find | CharSearchKind::Till => {
+                Some(TextRange::charwise(cursor, target))
+            }
+            CharSearchKind::FindBack | CharSearchKind::TillBack => {
+                exclusive_range(lines, target, cursor)
+            }
+        },
+        VimMotion::MatchPair if target < cursor => Some(TextRange::charwise(target, cursor)),
+        VimMotion::MatchPair => Some(TextRange::charwise(cursor, target)),
+        VimMotion::ParagraphForward => {
+            if target.row == lines.len().saturating_sub(1) && !is_blank(lines, target.row) {
+                // No blank line below: the motion stops at the last character.
+                let end = Index2::new(target.row, last_col(lines, target.row));
+                Some(TextRange::charwise(cursor, end))
+            } else {
+                exclusive_range(lines, cursor, target)
+            }
+        }
+        VimMotion::ParagraphBackward => exclusive_range(lines, target, cursor),
+    }
+}
+
+/// Turns an exclusive motion into an inclusive range, applying Vim's rules:
+/// an end in the first column moves to the end of the previous line, and the
+/// range becomes linewise when it also starts at or before the first non-blank.
+fn exclusive_range(lines: &Lines, start: Index2, end: Index2) -> Option<TextRange> {
+    if end <= start {
+        return None;
+    }
+
+    if end.col == 0 && end.row > start.row {
+        let prev = end.row - 1;
+        if start.col <= first_non_blank(lines, start.row) {
+            return Some(TextRange::linewise(start.row, prev));
+        }
+
+        return Some(TextRange::charwise(
+            start,
+            Index2::new(prev, last_col(lines, prev)),
+        ));
+    }
+
+    Some(TextRange::charwise(
+        start,
+        Index2::new(end.row, end.col - 1),
+    ))
+}
+
+fn char_search(
+    lines: &Lines,
+    cursor: Index2,
+    search: CharSearch,
+    n: usize,
+    repeat: bool,
+) -> Option<Index2> {
+    let line = row(lines, cursor.row);
+    let CharSearch { kind, target } = search;
+    let n = n.max(1);
+
+    match kind {
+        CharSearchKind::Find | CharSearchKind::Till => {
+            let skip = if repeat && kind == CharSearchKind::Till {
+                2
+            } else {
+                1
+            };
+            let col = (cursor.col + skip..line.len())
+                .filter(|&col| line[col] == target)
+                .nth(n - 1)?;
+            let col = if kind == CharSearchKind::Till {
+                col - 1
+            } else {
+                col
+            };
+
+            Some(Index2::new(cursor.row, col))
+        }
+        CharSearchKind::FindBack | CharSearchKind::TillBack => {
+            let skip = if repeat && kind == CharSearchKind::TillBack {
+                2
+            } else {
+                1
+            };
+            let col = (0..cursor.col.checked_sub(skip - 1)?)
+                .rev()
+                .filter(|&col| line[col] == target)
+                .nth(n - 1)?;
+            let col = if kind == CharSearchKind::TillBack {
+                col + 1
+            } else {
+                col
+            };
+
+            Some(Index2::new(cursor.row, col))
+        }
+    }
+}
+
+const PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+/// `%`: jumps from the first bracket at or after the cursor to its match.
+fn match_pair(lines: &Lines, cursor: Index2) -> Option<Index2> {
+    let line = row(lines, cursor.row);
+    let (col, ch) = line
+        .iter()
+        .enumerate()
+        .skip(cursor.col)
+        .find(|(_, ch)| PAIRS.iter().any(|(o, c)| *ch == o || *ch == c))?;
+    let pos = Index2::new(cursor.row, col);
+
+    PAIRS.iter().find_map(|&(open, close)| {
+        if *ch == open {
+            find_close(lines, pos, open, close)
+        } else if *ch == close {
+            find_open(lines, pos, open, close)
+        } else {
+            None
+        }
+    })
+}
+
+/// Positions after `from` in document order.
+fn forward_from(lines: &Lines, from: Index2) -> impl Iterator<Item = (Index2, char)> + '_ {
+    (from.row..lines.len()).flat_map(move |r| {
+        let skip = if r == from.row { from.col + 1 } else { 0 };
+        row(lines, r)
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .map(move |(c, ch)| (Index2::new(r, c), *ch))
+    })
+}
+
+/// Positions before `from` in reverse document order.
+fn backward_from(lines: &Lines, from: Index2) -> impl Iterator<Item = (Index2, char)> + '_ {
+    (0..=from.row).rev().flat_map(move |r| {
+        let line = row(lines, r);
+        let take = if r == from.row { from.col } else { line.len() };
+        line[..take.min(line.len())]
+            .iter()
+            .enumerate()
+            .rev()
+            .map(move |(c, ch)| (Index2::new(r, c), *ch))
+    })
+}
+
+/// Finds the `close` matching the `open` at `from`.
+fn find_close(lines: &Lines, from: Index2, open: char, close: char) -> Option<Index2> {
+    let mut depth = 0usize;
+    for (idx, ch) in forward_from(lines, from) {
+        if ch == open {
+            depth += 1;
+        } else if ch == close {
+            if depth == 0 {
+                return Some(idx);
+            }
+            depth -= 1;
+        }
+    }
+
+    None
+}
+
+/// Finds the `open` matching the `close` at `from`, or, when `from` is not a
+/// bracket, the nearest unmatched `open` before it.
+fn find_open(lines: &Lines, from: Index2, open: char, close: char) -> Option<Index2> {
+    let mut depth = 0usize;
+    for (idx, ch) in backward_from(lines, from) {
+        if ch == close {
+            depth += 1;
+        } else if ch == open {
+            if depth == 0 {
+                return Some(idx);
+            }
+            depth -= 1;
+        }
+    }
+
+    None
+}
+
+/// `}`: the next blank line after the current paragraph, or the end of the note.
+fn paragraph_forward(lines: &Lines, cursor: Index2, n: usize) -> Index2 {
+    let last = lines.len().saturating_sub(1);
+    let mut r = cursor.row;
+
+    for _ in 0..n.max(1) {
+        while r < last && is_blank(lines, r) {
+            r += 1;
+        }
+        while r < last && !is_blank(lines, r) {
+            r += 1;
+        }
+    }
+
+    if is_blank(lines, r) {
+        Index2::new(r, 0)
+    } else {
+        Index2::new(r, last_col(lines, r))
+    }
+}
+
+/// `{`: the previous blank line before the current paragraph, or the top of the note.
+fn paragraph_backward(lines: &Lines, cursor: Index2, n: usize) -> Index2 {
+    let mut r = cursor.row;
+
+    for _ in 0..n.max(1) {
+        while r > 0 && is_blank(lines, r) {
+            r -= 1;
+        }
+        while r > 0 && !is_blank(lines, r) {
+            r -= 1;
+        }
+    }
+
+    Index2::new(r, 0)
+}
+
+/// Range selected by a text object such as `iw`, `ap`, `i"` or `a(`.
+pub(super) fn text_object_range(
+    lines: &Lines,
+    cursor: Index2,
+    scope: TextObjectScope,
+    object: TextObject,
+    n: usize,
+) -> Option<TextRange> {
+    let cursor = clamp(lines, cursor);
+    let n = n.max(1);
+
+    match object {
+        TextObject::Word => word_object(lines, cursor, scope, n),
+        TextObject::Paragraph => Some(paragraph_object(lines, cursor, scope, n)),
+        TextObject::Quote(quote) => quote_object(lines, cursor, scope, quote),
+        TextObject::Pair(open, close) => pair_object(lines, cursor, scope, open, close, n),
+    }
+}
+
+/// Word objects stay on the cursor line. Runs of whitespace count as words for
+/// `iw`, and `aw` takes trailing whitespace, or leading whitespace when there
+/// is none after the word.
+fn word_object(
+    lines: &Lines,
+    cursor: Index2,
+    scope: TextObjectScope,
+    n: usize,
+) -> Option<TextRange> {
+    let line = row(lines, cursor.row);
+    if cursor.col >= line.len() {
+        return None;
+    }
+
+    let run_end = |mut col: usize| {
+        let class = char_class(line[col]);
+        while col + 1 < line.len() && char_class(line[col + 1]) == class {
+            col += 1;
+        }
+        col
+    };
+
+    let mut start = cursor.col;
+    let class = char_class(line[start]);
+    while start > 0 && char_class(line[start - 1]) == class {
+        start -= 1;
+    }
+
+    let mut end = run_end(cursor.col);
+    let units = match scope {
+        TextObjectScope::Inner => n,
+        TextObjectScope::Around => n * 2,
+    };
+    for _ in 1..units {
+        if end + 1 >= line.len() {
+            break;
+        }
+        end = run_end(end + 1);
+    }
+
+    if scope == TextObjectScope::Around
+        && char_class(line[end]) != 1
+        && (end + 1 >= line.len() || char_class(line[end + 1]) != 1)
+        && class != 1
+    {
+        // No trailing whitespace to take: include the whitespace before the word.
+        while start > 0 && line[start - 1].is_whitespace() {
+            start -= 1;
+        }
+    }
+
+    Some(TextRange::charwise(
+        Index2::new(cursor.row, start),
+        Index2::new(cursor.row, end),
+    ))
+}
+
+/// Paragraph objects are always linewise. `ip` alternates between paragraph
+/// and blank blocks for each count, `ap` also takes the blank lines after the
+/// paragraph (or before it, at the end of the note).
+fn paragraph_object(lines: &Lines, cursor: Index2, scope: TextObjectScope, n: usize) -> TextRange {
+    let last = lines.len().saturating_sub(1);
+    let block_end = |mut r: usize| {
+        let blank = is_blank(lines, r);
+        while r < last && is_blank(lines, r + 1) == blank {
+            r += 1;
+        }
+        r
+    };
+
+    let mut start = cursor.row;
+    let blank = is_blank(lines, start);
+    while start > 0 && is_blank(lines, start - 1) == blank {
+        start -= 1;
+    }
+
+    let mut end = block_end(cursor.row);
+    let units = match scope {
+        TextObjectScope::Inner => n,
+        TextObjectScope::Around => n * 2,
+    };
+    for i in 1..units {
+        if end >= last {
+            if scope == TextObjectScope::Around && i == 1 && !blank {
+                while start > 0 && is_blank(lines, start - 1) {
+                    start -= 1;
+                }
+            }
+            break;
+        }
+        end = block_end(end + 1);
+    }
+
+    TextRange::linewise(start, end)
+}
+
+/// Quote objects stay on the cursor line, like Vim's.
+fn quote_object(
+    lines: &Lines,
+    cursor: Index2,
+    scope: TextObjectScope,
+    quote: char,
+) -> Option<TextRange> {
+    let line = row(lines, cursor.row);
+    let quotes: Vec<usize> = line
+        .iter()
+        .enumerate()
+        .filter(|(col, ch)| **ch == quote && (*col == 0 || line[col - 1] != '\\'))
+        .map(|(col, _)| col)
+        .collect();
+
+    let (open, close) = match quotes.iter().position(|&col| col == cursor.col) {
+        // On a quote: quotes pair up from the start of the line.
+        Some(i) if i % 2 == 0 => (quotes[i], *quotes.get(i + 1)?),
+        Some(i) => (quotes[i - 1], quotes[i]),
+        None => {
+            let before = quotes.iter().rposition(|&col| col < cursor.col);
+            match before {
+                Some(i) if i + 1 < quotes.len() => (quotes[i], quotes[i + 1]),
+                _ => {
+                    let after = quotes.iter().position(|&col| col > cursor.col)?;
+                    (quotes[after], *quotes.get(after + 1)?)
+                }
+            }
+        }
+    };
+
+    let range = match scope {
+        TextObjectScope::Inner => TextRange::charwise(
+            Index2::new(cursor.row, open + 1),
+            Index2::new(cursor.row, close - 1),
+        ),
+        TextObjectScope::Around => {
+            let mut start = open;
+            let mut end = close;
+            if end + 1 < line.len() && line[end + 1].is_whitespace() {
+                while end + 1 < line.len() && line[end + 1].is_whitespace() {
+                    end += 1;
+                }
+            } else {
+                while start > 0 && line[start - 1].is_whitespace() {
+                    start -= 1;
+                }
+            }
+
+            TextRange::charwise(Index2::new(cursor.row, start), Index2::new(cursor.row, end))
+        }
+    };
+
+    Some(range)
+}
+
+/// Bracket objects may span lines. A count selects the n-th enclosing pair.
+fn pair_object(
+    lines: &Lines,
+    cursor: Index2,
+    scope: TextObjectScope,
+    open: char,
+    close: char,
+    n: usize,
+) -> Option<TextRange> {
+    let on = lines.get(cursor).copied();
+    let mut open_pos = match on {
+        Some(ch) if ch == open => cursor,
+        Some(ch) if ch == close => find_open(lines, cursor, open, close)?,
+        _ => find_open(lines, cursor, open, close)?,
+    };
+    for _ in 1..n {
+        open_pos = find_open(lines, open_pos, open, close)?;
+    }
+    let close_pos = find_close(lines, open_pos, open, close)?;
+
+    if scope == TextObjectScope::Around {
+        return Some(TextRange::charwise(open_pos, close_pos));
+    }
+
+    let open_at_eol = open_pos.col + 1 >= row(lines, open_pos.row).len();
+    let close_at_bol = close_pos.col <= first_non_blank(lines, close_pos.row)
+        && row(lines, close_pos.row)[..close_pos.col]
+            .iter()
+            .all(|ch| ch.is_whitespace());
+
+    if open_at_eol && close_at_bol && close_pos.row > open_pos.row + 1 {
+        return Some(TextRange::linewise(open_pos.row + 1, close_pos.row - 1));
+    }
+
+    let start = if open_at_eol && close_pos.row > open_pos.row {
+        Index2::new(open_pos.row + 1, 0)
+    } else {
+        Index2::new(open_pos.row, open_pos.col + 1)
+    };
+    let end = if close_pos.col > 0 {
+        Index2::new(close_pos.row, close_pos.col - 1)
+    } else {
+        let prev = close_pos.row.saturating_sub(1);
+        Index2::new(prev, last_col(lines, prev))
+    };
+
+    Some(TextRange {
+        start,
+        end,
+        linewise: false,
+    })
+}
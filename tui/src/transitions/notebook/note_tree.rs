@@ -144,9 +144,13 @@ impl App {
                 self.context.notebook.tree_width = width;
             }
             NoteTreeTransition::GatewayMode => {}
-            NoteTreeTransition::ShowNoteActionsDialog(_) => {
+            NoteTreeTransition::ShowNoteActionsDialog { attachments, .. } => {
+                self.context.notebook.attachments = attachments;
                 self.context.notebook.note_actions_state.select(Some(0));
             }
+            NoteTreeTransition::UpdateAttachments(attachments) => {
+                self.context.notebook.attachments = attachments;
+            }
             NoteTreeTransition::ShowDirectoryActionsDialog(_) => {
                 self.context
                     .notebook
@@ -214,7 +218,7 @@ impl App {
                             if is_directory { "directory" } else { "note" }
                         );
                         log!("{message}");
-                        self.context.alert = Some(message);
+                        self.context.show_alert(message);
 
                         return;
                     }
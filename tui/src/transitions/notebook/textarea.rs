@@ -1,4 +1,7 @@
-use edtui::{EditorMode, EditorState, Index2, Lines, RowIndex, actions::SwitchMode};
+use {
+    crate::context::notebook::ColumnInsert,
+    edtui::{EditorMode, EditorState, Index2, Lines, RowIndex, actions::SwitchMode},
+};
 
 /// Creates a selection on the editor spanning from `start` to `end`.
 ///
@@ -75,6 +78,275 @@ pub(super) fn transform_selection(editor: &mut EditorState, transform: fn(char)
     editor.selection = None;
 }
 
+// ---------------------------------------------------------------------------
+// Visual line and block selections
+// ---------------------------------------------------------------------------
+
+/// Leading spaces removed by one `<` when a line is not indented with a tab.
+const SHIFT_WIDTH: usize = 4;
+
+/// Rows and columns covered by a `Ctrl-v` selection, all inclusive.
+#[derive(Clone, Copy)]
+pub(super) struct BlockBounds {
+    pub top: usize,
+    pub bottom: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+/// Takes an undo snapshot before editing `lines` directly.
+///
+/// `EditorState::capture` is crate private, but switching to insert mode
+/// captures, so the mode is switched and then restored.
+fn capture(editor: &mut EditorState) {
+    let saved_mode = editor.mode;
+    editor.execute(SwitchMode(EditorMode::Insert));
+    editor.mode = saved_mode;
+}
+
+/// Moves the end of the selection to the cursor. Line mode selections keep
+/// spanning whole rows between the anchor and the cursor.
+pub(super) fn extend_selection(editor: &mut EditorState) {
+    let cursor = editor.cursor;
+    let Some(sel) = &mut editor.selection else {
+        return;
+    };
+
+    if !sel.line_mode {
+        sel.end = cursor;
+        return;
+    }
+
+    let anchor_row = sel.anchor.map_or(sel.start.row, |anchor| anchor.row);
+    let (top, bottom) = if anchor_row <= cursor.row {
+        (anchor_row, cursor.row)
+    } else {
+        (cursor.row, anchor_row)
+    };
+    let end_col = editor.lines.len_col(bottom).unwrap_or(0).saturating_sub(1);
+
+    sel.start = Index2::new(top, 0);
+    sel.end = Index2::new(bottom, end_col);
+}
+
+/// Switches the current selection between character and line mode, keeping
+/// the position where the selection was started.
+pub(super) fn set_line_mode(editor: &mut EditorState, line_mode: bool) {
+    let Some(sel) = &mut editor.selection else {
+        return;
+    };
+
+    match (sel.line_mode, line_mode) {
+        (false, true) => {
+            sel.line_mode = true;
+            sel.anchor = Some(sel.start);
+        }
+        (true, false) => {
+            sel.line_mode = false;
+            sel.start = sel.anchor.take().unwrap_or(sel.start);
+        }
+        _ => {}
+    }
+
+    extend_selection(editor);
+}
+
+/// First and last row touched by the selection.
+pub(super) fn selected_rows(editor: &EditorState) -> Option<(usize, usize)> {
+    let sel = editor.selection.as_ref()?;
+
+    Some((sel.start().row, sel.end().row))
+}
+
+pub(super) fn block_bounds(editor: &EditorState) -> Option<BlockBounds> {
+    let sel = editor.selection.as_ref()?;
+
+    Some(BlockBounds {
+        top: sel.start.row.min(sel.end.row),
+        bottom: sel.start.row.max(sel.end.row),
+        left: sel.start.col.min(sel.end.col),
+        right: sel.start.col.max(sel.end.col),
+    })
+}
+
+/// Inserts `n` tabs in front of every non-empty row, like `>` in Vim.
+pub(super) fn indent_rows(editor: &mut EditorState, top: usize, bottom: usize, n: usize) {
+    capture(editor);
+
+    for row in top..=bottom {
+        if let Some(line) = editor.lines.get_mut(RowIndex::new(row))
+            && !line.is_empty()
+        {
+            line.splice(0..0, std::iter::repeat_n('\t', n));
+        }
+    }
+
+    move_to_first_non_blank(editor, top);
+}
+
+/// Removes `n` levels of indentation from every row, like `<` in Vim.
+/// A level is a leading tab, or up to [`SHIFT_WIDTH`] leading spaces.
+pub(super) fn outdent_rows(editor: &mut EditorState, top: usize, bottom: usize, n: usize) {
+    capture(editor);
+
+    for row in top..=bottom {
+        let Some(line) = editor.lines.get_mut(RowIndex::new(row)) else {
+            continue;
+        };
+
+        for _ in 0..n {
+            let width = match line.first() {
+                Some('\t') => 1,
+                _ => line
+                    .iter()
+                    .take(SHIFT_WIDTH)
+                    .take_while(|ch| **ch == ' ')
+                    .count(),
+            };
+            if width == 0 {
+                break;
+            }
+
+            line.drain(..width);
+        }
+    }
+
+    move_to_first_non_blank(editor, top);
+}
+
+/// Joins the rows `top..=bottom` like `J` in Vim: leading whitespace of each
+/// joined row is dropped and a single space separates the joined text. A selection within one row
+/// joins it with the row below.
+pub(super) fn join_rows(editor: &mut EditorState, top: usize, bottom: usize) {
+    let last_row = editor.lines.len().saturating_sub(1);
+    let bottom = bottom.max(top + 1).min(last_row);
+    if top >= bottom {
+        return;
+    }
+
+    capture(editor);
+
+    let mut col = 0;
+    for _ in top..bottom {
+        let mut next = editor.lines.remove(RowIndex::new(top + 1));
+        let indent = next.iter().take_while(|ch| ch.is_whitespace()).count();
+        next.drain(..indent);
+
+        let Some(line) = editor.lines.get_mut(RowIndex::new(top)) else {
+            break;
+        };
+        col = line.len();
+        if line.last().is_some_and(|ch| !ch.is_whitespace())
+            && next.first().is_some_and(|ch| *ch != ')')
+        {
+            line.push(' ');
+        }
+        line.append(&mut next);
+    }
+
+    editor.cursor = Index2::new(top, col);
+}
+
+/// Text of the block, one line per row. Rows too short to reach the block
+/// contribute an empty line.
+pub(super) fn copy_block(editor: &EditorState, block: BlockBounds) -> String {
+    (block.top..=block.bottom)
+        .map(|row| match editor.lines.get(RowIndex::new(row)) {
+            Some(line) if block.left < line.len() => line
+                [block.left..=block.right.min(line.len() - 1)]
+                .iter()
+                .collect(),
+            _ => String::new(),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Removes the block from every row and returns the removed text.
+pub(super) fn delete_block(editor: &mut EditorState, block: BlockBounds) -> String {
+    let text = copy_block(editor, block);
+    capture(editor);
+
+    for row in block.top..=block.bottom {
+        if let Some(line) = editor.lines.get_mut(RowIndex::new(row))
+            && block.left < line.len()
+        {
+            line.drain(block.left..=block.right.min(line.len() - 1));
+        }
+    }
+
+    editor.cursor = Index2::new(block.top, block.left);
+    editor.selection = None;
+    text
+}
+
+pub(super) fn transform_block(
+    editor: &mut EditorState,
+    block: BlockBounds,
+    transform: fn(char) -> char,
+) {
+    for row in block.top..=block.bottom {
+        for col in block.left..=block.right {
+            if let Some(cell) = editor.lines.get_mut(Index2::new(row, col)) {
+                *cell = transform(*cell);
+            }
+        }
+    }
+
+    editor.cursor = Index2::new(block.top, block.left);
+    editor.selection = None;
+}
+
+/// Pads `row` with spaces so that it reaches `col`.
+pub(super) fn pad_row(editor: &mut EditorState, row: usize, col: usize) {
+    if let Some(line) = editor.lines.get_mut(RowIndex::new(row))
+        && line.len() < col
+    {
+        line.resize(col, ' ');
+    }
+}
+
+/// Repeats the text typed on the first row of a block `I` / `A` on the rest
+/// of its rows. Nothing is repeated if insert mode left the first row.
+pub(super) fn repeat_block_insert(editor: &mut EditorState, insert: &ColumnInsert) {
+    let cursor = editor.cursor;
+    if cursor.row != insert.top || cursor.col <= insert.col {
+        return;
+    }
+
+    let Some(typed) = editor
+        .lines
+        .get(RowIndex::new(insert.top))
+        .and_then(|line| line.get(insert.col..cursor.col))
+        .map(<[char]>::to_vec)
+    else {
+        return;
+    };
+
+    for row in insert.top + 1..=insert.bottom {
+        if insert.pad {
+            pad_row(editor, row, insert.col);
+        }
+
+        if let Some(line) = editor.lines.get_mut(RowIndex::new(row))
+            && insert.col <= line.len()
+        {
+            line.splice(insert.col..insert.col, typed.iter().copied());
+        }
+    }
+}
+
+fn move_to_first_non_blank(editor: &mut EditorState, row: usize) {
+    let col = editor
+        .lines
+        .get(RowIndex::new(row))
+        .and_then(|line| line.iter().position(|ch| !ch.is_whitespace()))
+        .unwrap_or(0);
+
+    editor.cursor = Index2::new(row, col);
+    editor.selection = None;
+}
+
 // ---------------------------------------------------------------------------
 // Unicode-aware word motion
 // ---------------------------------------------------------------------------
@@ -143,10 +415,8 @@ fn max_col_for_mode(editor: &EditorState) -> usize {
 /// Update the visual-mode selection so that `selection.end` tracks the cursor.
 /// This mirrors what edtui's internal `set_selection_with_lines` does.
 fn update_visual_selection(editor: &mut EditorState) {
-    if editor.mode == EditorMode::Visual
-        && let Some(sel) = &mut editor.selection
-    {
-        sel.end = editor.cursor;
+    if editor.mode == EditorMode::Visual {
+        extend_selection(editor);
     }
 }
 
@@ -343,39 +613,3 @@ fn move_word_forward_to_end_once(editor: &mut EditorState) {
         }
     }
 }
-
-/// Select the inner word under the cursor (for `diw` / `ciw`).
-/// Sets `editor.selection` spanning the word, staying on the current line.
-pub(super) fn select_inner_word(editor: &mut EditorState) {
-    let row = editor.cursor.row;
-    let col = editor.cursor.col;
-
-    let Some(line) = editor.lines.get(RowIndex::new(row)) else {
-        return;
-    };
-
-    let len = line.len();
-    if len == 0 || col >= len {
-        return;
-    }
-
-    let cur_class = char_class(&line[col]);
-
-    // Find start: walk backward while same class
-    let mut start_col = col;
-    while start_col > 0 && char_class(&line[start_col - 1]) == cur_class {
-        start_col -= 1;
-    }
-
-    // Find end: walk forward while same class
-    let mut end_col = col;
-    while end_col + 1 < len && char_class(&line[end_col + 1]) == cur_class {
-        end_col += 1;
-    }
-
-    set_selection(
-        editor,
-        Index2::new(row, start_col),
-        Index2::new(row, end_col),
-    );
-}
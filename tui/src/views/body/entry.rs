@@ -8,7 +8,7 @@ use {
         layout::{Constraint::Length, Flex, Layout, Rect},
         style::{Style, Stylize},
         text::Line,
-        widgets::{Block, HighlightSpacing, List, ListDirection, Padding},
+        widgets::{Block, HighlightSpacing, List, ListDirection, ListState, Padding},
     },
     tui_big_text::BigText,
 };
@@ -20,9 +20,14 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut EntryContext) {
     let [area] = Layout::horizontal([Length(38)])
         .flex(Flex::Center)
         .areas(area);
-    let [title_area, area] = Layout::vertical([Length(9), Length(13)])
-        .flex(Flex::Center)
-        .areas(area);
+    let recent_height = match context.recent.len() {
+        0 => 0,
+        n => n as u16 + 2,
+    };
+    let [title_area, area, recent_area] =
+        Layout::vertical([Length(9), Length(13), Length(recent_height)])
+            .flex(Flex::Center)
+            .areas(area);
 
     let title = BigText::builder()
         .lines(vec!["Glues".fg(THEME.accent).into()])
@@ -40,6 +45,47 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut EntryContext) {
         .highlight_spacing(HighlightSpacing::Always)
         .direction(ListDirection::TopToBottom);
 
+    // the menu and the recent notebooks share one selection
+    let selected = context.list_state.selected().unwrap_or_default();
+    let mut menu_state =
+        ListState::default().with_selected((selected < MENU_ITEMS.len()).then_some(selected));
+    let mut recent_state = ListState::default().with_selected(context.selected_recent());
+
     frame.render_widget(title, title_area);
-    frame.render_stateful_widget(list, area, &mut context.list_state);
+    frame.render_stateful_widget(list, area, &mut menu_state);
+
+    if context.recent.is_empty() {
+        return;
+    }
+
+    let block = Block::bordered()
+        .fg(THEME.text)
+        .padding(Padding::horizontal(2))
+        .title(Line::from("Recent").centered());
+    // borders, padding, the highlight symbol and the `[n]*` prefix
+    let width = (recent_area.width as usize).saturating_sub(10);
+    let items = context.recent.iter().enumerate().map(|(i, recent)| {
+        let pin = if recent.pinned { "*" } else { " " };
+        let label = truncate_start(&recent.label(), width);
+        format!("[{}]{pin}{label}", i + 1).fg(THEME.menu)
+    });
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::new().fg(THEME.accent_text).bg(THEME.accent))
+        .highlight_symbol(" ")
+        .highlight_spacing(HighlightSpacing::Always)
+        .direction(ListDirection::TopToBottom);
+
+    frame.render_stateful_widget(list, recent_area, &mut recent_state);
+}
+
+/// Keeps the end of `label`, where paths have their file names.
+fn truncate_start(label: &str, width: usize) -> String {
+    let len = label.chars().count();
+    if len <= width {
+        return label.to_owned();
+    }
+
+    let tail: String = label.chars().skip(len - width + 1).collect();
+    format!("…{tail}")
 }
@@ -14,6 +14,7 @@ use {
 
 pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
     if !context.notebook.show_browser {
+        context.notebook.tree_area = Rect::default();
         editor::draw(frame, area, context);
 
         return;
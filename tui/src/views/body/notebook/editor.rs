@@ -2,22 +2,28 @@ use {
     crate::{
         context::{
             Context,
-            notebook::{ContextState, ScrollAnchor, ScrollRequest},
+            notebook::{Completion, ContextState, ScrollAnchor, ScrollRequest},
         },
         theme::{THEME, current_theme_id, syntect_theme_name},
     },
     edtui::{EditorState, EditorTheme, EditorView, Index2, LineNumbers, Lines, SyntaxHighlighter},
+    glues_core::transition::VisualKind,
     ratatui::{
         Frame,
         buffer::Buffer,
-        layout::Rect,
-        style::{Style, Stylize},
+        layout::{Position, Rect},
+        style::{Color, Style, Stylize},
         text::{Line, Span},
-        widgets::{Block, Padding, Widget},
+        widgets::{Block, Clear, List, ListState, Padding, Widget},
     },
 };
 
 const NOTE_SYMBOL: &str = "󱇗 ";
+/// Background only used by scratch renders to find which cells a selection covers.
+const SCRATCH_SELECTION_BG: Color = Color::Rgb(1, 2, 3);
+/// Cursor background used while the completion popup needs to find the cursor cell.
+const CURSOR_MARKER_BG: Color = Color::Rgb(3, 2, 1);
+const COMPLETION_MAX_HEIGHT: u16 = 8;
 const SAMPLE_NOTE: &str = r#"Welcome to Glues!
 
 Press `?` to see keymaps and shortcuts.
@@ -28,6 +34,8 @@ GitHub: https://github.com/gluesql/glues"#;
 
 pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
     context.notebook.editor_height = area.height.saturating_sub(2);
+    context.notebook.editor_area = area;
+    context.notebook.tab_areas = tab_areas(context, area);
 
     let block = build_block(context);
     let show_line_number = context.notebook.show_line_number;
@@ -46,11 +54,20 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
         LineNumbers::None
     };
 
+    let completion_open =
+        state == ContextState::EditorInsertMode && context.notebook.completion.is_some();
+    let marked_cursor_style = if completion_open {
+        cursor_style.bg(CURSOR_MARKER_BG)
+    } else {
+        cursor_style
+    };
+
+    let selection_style = Style::default().fg(THEME.accent_text).bg(THEME.accent);
     let theme = EditorTheme::default()
         .base(Style::default().fg(THEME.text).bg(THEME.background))
         .block(block)
-        .cursor_style(cursor_style)
-        .selection_style(Style::default().fg(THEME.accent_text).bg(THEME.accent))
+        .cursor_style(marked_cursor_style)
+        .selection_style(selection_style)
         .line_numbers_style(
             Style::default()
                 .fg(THEME.inactive_text)
@@ -78,8 +95,15 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
 
     if context.notebook.tab_index.is_some() {
         let scroll_shift = prepare_scroll_viewport(context, area);
+        let block_visual = state == ContextState::EditorVisualMode
+            && context.notebook.visual_kind == VisualKind::Block;
 
         let editor = context.notebook.get_editor_mut();
+        let block_selection = if block_visual {
+            editor.selection.take()
+        } else {
+            None
+        };
         EditorView::new(editor)
             .theme(theme)
             .syntax_highlighter(new_highlighter())
@@ -87,9 +111,27 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
             .line_numbers(line_numbers)
             .render(area, frame.buffer_mut());
 
+        if block_selection.is_some() {
+            context.notebook.get_editor_mut().selection = block_selection;
+            draw_block_selection(
+                context,
+                area,
+                frame.buffer_mut(),
+                line_numbers,
+                selection_style,
+            );
+        }
+
         if scroll_shift > 0 {
             apply_scroll_shift(frame.buffer_mut(), area, scroll_shift);
         }
+
+        if completion_open {
+            let cursor = find_marked_cursor(frame.buffer_mut(), area, cursor_style);
+            if let (Some(cursor), Some(completion)) = (cursor, &context.notebook.completion) {
+                draw_completion(frame, area, cursor, completion);
+            }
+        }
     } else {
         let mut sample_state = EditorState::new(Lines::from(SAMPLE_NOTE));
         let theme = theme.hide_cursor();
@@ -102,11 +144,36 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut Context) {
     };
 }
 
+/// Cells of each tab title on the top row, as laid out by [`build_block`].
+fn tab_areas(context: &Context, area: Rect) -> Vec<Rect> {
+    if context.notebook.tab_index.is_none() {
+        return vec![];
+    }
+
+    let mut x = area.x;
+    context
+        .notebook
+        .tabs
+        .iter()
+        .map(|tab| {
+            let width = Span::raw(tab_title(&tab.note.name)).width() as u16;
+            let width = width.min(area.right().saturating_sub(x));
+            let tab_area = Rect::new(x, area.y, width, 1);
+            x += width;
+            tab_area
+        })
+        .collect()
+}
+
+fn tab_title(name: &str) -> String {
+    format!(" {NOTE_SYMBOL}{name} ")
+}
+
 fn build_block(context: &Context) -> Block<'static> {
     let (title, mut bottom_left) = if let Some(tab_index) = context.notebook.tab_index {
         let mut title = vec![];
         for (i, tab) in context.notebook.tabs.iter().enumerate() {
-            let name = format!(" {NOTE_SYMBOL}{} ", tab.note.name);
+            let name = tab_title(&tab.note.name);
             let name = if i == tab_index {
                 if context.notebook.state.is_editor() {
                     name.fg(THEME.accent_text).bg(THEME.accent)
@@ -171,10 +238,18 @@ fn build_block(context: &Context) -> Block<'static> {
             Span::raw(" INSERT ").fg(THEME.accent_text).bg(THEME.accent),
             THEME.accent,
         ),
-        ContextState::EditorVisualMode => (
-            Span::raw(" VISUAL ").fg(THEME.error_text).bg(THEME.error),
-            THEME.error,
-        ),
+        ContextState::EditorVisualMode => {
+            let label = match context.notebook.visual_kind {
+                VisualKind::Char => " VISUAL ",
+                VisualKind::Line => " V-LINE ",
+                VisualKind::Block => " V-BLOCK ",
+            };
+
+            (
+                Span::raw(label).fg(THEME.error_text).bg(THEME.error),
+                THEME.error,
+            )
+        }
         _ => (Span::raw("        ").bg(THEME.surface), THEME.surface),
     };
 
@@ -208,7 +283,8 @@ fn build_block(context: &Context) -> Block<'static> {
     .padding(Padding::left(1))
 }
 
-/// Positions edtui's internal viewport for a pending scroll request (zt/zz/zb).
+/// Positions edtui's internal viewport for a pending scroll request (zt/zz/zb)
+/// or for the viewport top a restored session left the note with.
 ///
 /// Because edtui doesn't expose viewport controls, we use two "pre-renders"
 /// to a scratch buffer to nudge the viewport into the desired position.
@@ -219,6 +295,16 @@ fn build_block(context: &Context) -> Block<'static> {
 /// The scroll anchor persists across frames so that j/k movements after a
 /// scroll command do not cause the viewport to snap back.
 fn prepare_scroll_viewport(context: &mut Context, area: Rect) -> usize {
+    if context.notebook.pending_scroll.is_none()
+        && let Some(note_id) = context
+            .notebook
+            .get_opened_note()
+            .map(|note| note.id.clone())
+        && let Some(top) = context.notebook.restored_tops.remove(&note_id)
+    {
+        context.notebook.pending_scroll = Some(ScrollRequest::Row(top));
+    }
+
     // Case A: new scroll command
     if let Some(scroll) = context.notebook.pending_scroll.take() {
         let editor = context.notebook.get_editor_mut();
@@ -230,6 +316,10 @@ fn prepare_scroll_viewport(context: &mut Context, area: Rect) -> usize {
             ScrollRequest::Top => cursor_row,
             ScrollRequest::Center => cursor_row.saturating_sub(visible_height / 2),
             ScrollRequest::Bottom => cursor_row.saturating_sub(visible_height.saturating_sub(1)),
+            ScrollRequest::Row(top) => top.clamp(
+                cursor_row.saturating_sub(visible_height.saturating_sub(1)),
+                cursor_row,
+            ),
         };
 
         let desired_bottom = desired_top + visible_height.saturating_sub(1);
@@ -303,7 +393,114 @@ fn prepare_scroll_viewport(context: &mut Context, area: Rect) -> usize {
     0
 }
 
+/// Highlights a `Ctrl-v` block over the already rendered editor.
+///
+/// edtui only draws character and line selections, and wrapping makes screen
+/// positions hard to derive. Each row of the block is therefore rendered on
+/// its own as a character selection into a scratch buffer, and the cells that
+/// come out selected are restyled in `buf`. The cursor does not move, so the
+/// scratch renders share the viewport of the real one.
+fn draw_block_selection(
+    context: &mut Context,
+    area: Rect,
+    buf: &mut Buffer,
+    line_numbers: LineNumbers,
+    selection_style: Style,
+) {
+    let block = build_block(context);
+    let editor = context.notebook.get_editor_mut();
+    let Some(selection) = editor.selection.clone() else {
+        return;
+    };
+
+    let left = selection.start.col.min(selection.end.col);
+    let right = selection.start.col.max(selection.end.col);
+    let top = selection.start.row.min(selection.end.row);
+    let bottom = selection.start.row.max(selection.end.row);
+
+    // rows further than a screen away from the cursor cannot be visible
+    let height = area.height as usize;
+    let first = top.max(editor.cursor.row.saturating_sub(height));
+    let last = bottom.min(editor.cursor.row + height);
+
+    for row in first..=last {
+        let len = editor.lines.len_col(row).unwrap_or(0);
+        if left >= len {
+            continue;
+        }
+
+        let mut row_selection = selection.clone();
+        row_selection.start = Index2::new(row, left);
+        row_selection.end = Index2::new(row, right.min(len - 1));
+        editor.selection = Some(row_selection);
+
+        let theme = EditorTheme::default()
+            .block(block.clone())
+            .selection_style(Style::default().bg(SCRATCH_SELECTION_BG))
+            .hide_status_line();
+        let mut scratch = Buffer::empty(area);
+        EditorView::new(editor)
+            .theme(theme)
+            .wrap(true)
+            .line_numbers(line_numbers)
+            .render(area, &mut scratch);
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if scratch[(x, y)].bg == SCRATCH_SELECTION_BG {
+                    buf[(x, y)].set_style(selection_style);
+                }
+            }
+        }
+    }
+
+    editor.selection = Some(selection);
+}
+
 /// Shifts rendered content rows up to simulate scrolling past the document end.
+/// Finds the cell the editor drew the cursor in and gives it the real cursor style.
+fn find_marked_cursor(buf: &mut Buffer, area: Rect, cursor_style: Style) -> Option<Position> {
+    let position = area
+        .positions()
+        .find(|&position| buf[position].bg == CURSOR_MARKER_BG)?;
+    buf[position].set_style(cursor_style);
+
+    Some(position)
+}
+
+/// Draws the completion list below the cursor, or above it when there is no room.
+fn draw_completion(frame: &mut Frame, area: Rect, cursor: Position, completion: &Completion) {
+    let width = completion
+        .items
+        .iter()
+        .map(|item| item.chars().count() as u16)
+        .max()
+        .unwrap_or_default()
+        .saturating_add(4)
+        .min(area.width);
+    let height = (completion.items.len() as u16).min(COMPLETION_MAX_HEIGHT) + 2;
+
+    let below = area.bottom().saturating_sub(cursor.y + 1);
+    let y = if below >= height || cursor.y.saturating_sub(area.top()) < height {
+        cursor.y + 1
+    } else {
+        cursor.y - height
+    };
+    let x = cursor
+        .x
+        .saturating_sub(completion.prefix.chars().count() as u16 + 1)
+        .min(area.right().saturating_sub(width));
+    let popup = Rect::new(x, y, width, height).intersection(area);
+
+    let list = List::new(completion.items.iter().map(String::as_str))
+        .block(Block::bordered().fg(THEME.text).bg(THEME.panel))
+        .highlight_style(Style::new().fg(THEME.accent_text).bg(THEME.accent));
+    let mut list_state = ListState::default().with_selected(Some(completion.selected));
+
+    frame.render_widget(Clear, popup);
+    frame.render_stateful_widget(list, popup, &mut list_state);
+}
+
 fn apply_scroll_shift(buf: &mut Buffer, area: Rect, shift: usize) {
     let inner_y = (area.y + 1) as usize;
     let inner_height = (area.height as usize).saturating_sub(2);
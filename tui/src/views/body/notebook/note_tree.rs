@@ -39,6 +39,7 @@ pub fn draw(frame: &mut Frame, area: Rect, context: &mut NotebookContext) {
         .fg(THEME.hint)
         .title(title);
     let inner_area = block.inner(area);
+    context.tree_area = inner_area;
 
     let tree_items = context.tree_items.iter().map(
         |TreeItem {
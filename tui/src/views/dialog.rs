@@ -6,8 +6,11 @@ mod help;
 mod info;
 mod keymap;
 mod note_actions;
+mod notifications;
 mod prompt;
 mod quit_menu;
+mod recovery;
+mod sync_conflict;
 mod theme;
 mod vim_keymap;
 
@@ -22,14 +25,14 @@ use {
 
 pub fn draw(frame: &mut Frame, state: &State, context: &mut Context) {
     if context.keymap {
-        keymap::draw(frame, state.keymap().as_slice());
+        keymap::draw(frame, state.keymap().as_slice(), context);
     }
 
     if let Some(kind) = context.vim_keymap {
-        vim_keymap::draw(frame, kind);
+        vim_keymap::draw(frame, kind, &context.keybindings);
         return;
     } else if context.editor_keymap {
-        editor_keymap::draw(frame);
+        editor_keymap::draw(frame, &context.keybindings);
         return;
     } else if context.help {
         help::draw(frame);
@@ -40,12 +43,21 @@ pub fn draw(frame: &mut Frame, state: &State, context: &mut Context) {
     } else if context.info.is_some() {
         info::draw(frame, context);
         return;
+    } else if context.sync_conflict.is_some() {
+        sync_conflict::draw(frame, context);
+        return;
+    } else if context.recovery.is_some() {
+        recovery::draw(frame, context);
+        return;
     } else if context.quit_menu.is_some() {
         quit_menu::draw(frame, context);
         return;
     } else if context.confirm.is_some() {
         confirm::draw(frame, context);
         return;
+    } else if context.notification_center.is_some() {
+        notifications::draw(frame, context);
+        return;
     } else if context.theme_selector.is_some() {
         theme::draw(frame, context);
         return;
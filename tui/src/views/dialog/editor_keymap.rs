@@ -1,5 +1,8 @@
 use {
-    crate::theme::THEME,
+    crate::{
+        keybindings::{Keybindings, Mode},
+        theme::THEME,
+    },
     ratatui::{
         Frame,
         layout::{Alignment, Constraint::Length, Flex, Layout},
@@ -9,7 +12,30 @@ use {
     },
 };
 
-pub fn draw(frame: &mut Frame) {
+const EDITOR_KEYMAP: [(&[&str], &str); 17] = [
+    (&["Esc"], "Return to normal mode"),
+    (&["Backspace"], "Delete one character before cursor"),
+    (&["Ctrl+D", "Delete"], "Delete one character next to cursor"),
+    (&["Enter", "Ctrl+J"], "Insert newline"),
+    (&["Ctrl+K"], "Delete from cursor until the end of line"),
+    (&["Ctrl+U"], "Delete from cursor until the head of line"),
+    (&["Ctrl+R"], "Redo"),
+    (&["Ctrl+Y"], "Paste yanked text"),
+    (&["Ctrl+F", "→"], "Move cursor forward by one character"),
+    (&["Ctrl+B", "←"], "Move cursor backward by one character"),
+    (&["↑"], "Move cursor up by one line"),
+    (&["↓"], "Move cursor down by one line"),
+    (
+        &["Ctrl+N", "Ctrl+P"],
+        "Complete words, #tags and [[note links",
+    ),
+    (&["Ctrl+A", "Home"], "Move cursor to the head of line"),
+    (&["Ctrl+E", "End"], "Move cursor to the end of line"),
+    (&["Alt+<"], "Move cursor to top of lines"),
+    (&["Alt+>"], "Move cursor to bottom of lines"),
+];
+
+pub fn draw(frame: &mut Frame, keybindings: &Keybindings) {
     let [area] = Layout::horizontal([Length(80)])
         .flex(Flex::Center)
         .areas(frame.area());
@@ -28,27 +54,28 @@ pub fn draw(frame: &mut Frame) {
         .flex(Flex::SpaceBetween)
         .areas(inner_area);
 
-    let message = "
-| Mappings                 | Description                                |
-|--------------------------|--------------------------------------------|
-| `Backspace`              | Delete one character before cursor         |
-| `Ctrl+D`, `Delete`       | Delete one character next to cursor        |
-| `Enter`, `Ctrl+J`        | Insert newline                             |
-| `Ctrl+K`                 | Delete from cursor until the end of line   |
-| `Ctrl+U`                 | Delete from cursor until the head of line  |
-| `Ctrl+R`                 | Redo                                       |
-| `Ctrl+Y`                 | Paste yanked text                          |
-| `Ctrl+F`, `→`            | Move cursor forward by one character       |
-| `Ctrl+B`, `←`            | Move cursor backward by one character      |
-| `Ctrl+P`, `↑`            | Move cursor up by one line                 |
-| `Ctrl+N`, `↓`            | Move cursor down by one line               |
-| `Ctrl+A`, `Home`         | Move cursor to the head of line            |
-| `Ctrl+E`, `End`          | Move cursor to the end of line             |
-| `Alt+<`                  | Move cursor to top of lines                |
-| `Alt+>`                  | Move cursor to bottom of lines             |
-"
-    .lines()
-    .map(Line::from)
+    let rows = EDITOR_KEYMAP.iter().map(|(keys, desc)| {
+        let keys = keys
+            .iter()
+            .flat_map(|key| {
+                keybindings
+                    .describe(Mode::Insert, key)
+                    .split_whitespace()
+                    .map(|key| format!("`{key}`"))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Line::from(format!("| {keys:<24} | {desc:<42} |"))
+    });
+    let message = [
+        Line::raw(""),
+        Line::raw("| Mappings                 | Description                                |"),
+        Line::raw("|--------------------------|--------------------------------------------|"),
+    ]
+    .into_iter()
+    .chain(rows)
     .collect::<Vec<_>>();
 
     let paragraph = Paragraph::new(message)
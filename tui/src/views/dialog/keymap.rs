@@ -1,5 +1,5 @@
 use {
-    crate::theme::THEME,
+    crate::{context::Context, theme::THEME},
     glues_core::types::{KeymapGroup, KeymapItem},
     ratatui::{
         Frame,
@@ -18,7 +18,7 @@ use {
 const KEYMAP_WIDTH: u16 = 46;
 const KEY_WIDTH: u16 = 10;
 
-pub fn draw(frame: &mut Frame, keymap: &[KeymapGroup]) {
+pub fn draw(frame: &mut Frame, keymap: &[KeymapGroup], context: &Context) {
     let [area] = Layout::horizontal([Length(KEYMAP_WIDTH)])
         .flex(Flex::End)
         .areas(frame.area());
@@ -83,9 +83,9 @@ pub fn draw(frame: &mut Frame, keymap: &[KeymapGroup]) {
                 let [key_area, desc_area] =
                     Layout::horizontal([Length(KEY_WIDTH), Length(desc_width)]).areas(*row_area);
 
-                let key_paragraph =
-                    Paragraph::new(Line::from(vec![Span::raw(format!("[{}]", item.key))]))
-                        .alignment(Alignment::Left);
+                let key = context.keybindings.describe(context.mode(), &item.key);
+                let key_paragraph = Paragraph::new(Line::from(vec![Span::raw(format!("[{key}]"))]))
+                    .alignment(Alignment::Left);
                 let desc_lines: Vec<Line> = wrap(&item.desc, desc_width as usize)
                     .into_iter()
                     .map(|c| Line::from(c.into_owned()))
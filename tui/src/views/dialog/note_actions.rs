@@ -1,6 +1,6 @@
 use {
     crate::{
-        context::{NotebookContext, notebook::NOTE_ACTIONS},
+        context::{NotebookContext, notebook::NoteActionItem},
         theme::THEME,
     },
     ratatui::{
@@ -13,17 +13,39 @@ use {
 };
 
 pub fn draw(frame: &mut Frame, context: &mut NotebookContext) {
-    let [area] = Layout::horizontal([Length(28)])
+    let items = context
+        .note_action_items()
+        .into_iter()
+        .map(|item| match item {
+            NoteActionItem::Action(action) => action.to_owned(),
+            NoteActionItem::Attachment(attachment) => {
+                format!("{} ({})", attachment.name, format_size(attachment.size))
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let width = items
+        .iter()
+        .map(|item| item.chars().count() as u16 + 8)
+        .max()
+        .unwrap_or_default()
+        .max(28)
+        .min(frame.area().width);
+    let height = (items.len() as u16 + 4).min(frame.area().height);
+
+    let [area] = Layout::horizontal([Length(width)])
         .flex(Flex::Center)
         .areas(frame.area());
-    let [area] = Layout::vertical([Length(8)]).flex(Flex::Center).areas(area);
+    let [area] = Layout::vertical([Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
 
     let block = Block::bordered()
         .bg(THEME.surface)
         .fg(THEME.text)
         .padding(Padding::new(2, 2, 1, 1))
         .title(Line::from("Note Actions").centered());
-    let list = List::new(NOTE_ACTIONS)
+    let list = List::new(items)
         .block(block)
         .highlight_style(Style::new().fg(THEME.accent_text).bg(THEME.accent))
         .highlight_symbol(" ")
@@ -33,3 +55,23 @@ pub fn draw(frame: &mut Frame, context: &mut NotebookContext) {
     frame.render_widget(Clear, area);
     frame.render_stateful_widget(list, area, &mut context.note_actions_state);
 }
+
+fn format_size(size: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+
+    if size < 1024 {
+        return format!("{size} B");
+    }
+
+    let mut value = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
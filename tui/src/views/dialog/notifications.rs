@@ -0,0 +1,78 @@
+use {
+    crate::{context::Context, logger::*, notifications::Severity, theme::THEME},
+    ratatui::{
+        Frame,
+        layout::{Alignment, Constraint::Length, Flex, Layout},
+        style::{Style, Stylize},
+        text::{Line, Span},
+        widgets::{Block, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph},
+    },
+};
+
+const WIDTH: u16 = 90;
+const MAX_HEIGHT: u16 = 24;
+
+pub fn draw(frame: &mut Frame, context: &mut Context) {
+    let center = context
+        .notification_center
+        .as_mut()
+        .log_expect("notification center must be some");
+    let notifications = &context.notifications;
+
+    let items: Vec<ListItem> = notifications
+        .iter()
+        .map(|notification| {
+            let color = match notification.severity {
+                Severity::Info => THEME.success,
+                Severity::Warning => THEME.warning,
+                Severity::Error => THEME.error,
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::raw(notification.created_at.format("%H:%M:%S ").to_string())
+                    .fg(THEME.text_secondary),
+                Span::raw(format!("{:<6}", notification.severity.label())).fg(color),
+                Span::raw(notification.message.clone()),
+            ]))
+        })
+        .collect();
+
+    let list_height = items.len().max(1) as u16;
+    let height = (list_height + 6).min(MAX_HEIGHT).min(frame.area().height);
+
+    let [area] = Layout::horizontal([Length(WIDTH.min(frame.area().width))])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let block = Block::bordered()
+        .bg(THEME.surface)
+        .fg(THEME.text)
+        .padding(Padding::new(2, 2, 1, 1))
+        .title(Line::from(format!("Notifications ({})", notifications.len())).centered());
+    let inner_area = block.inner(area);
+    let [list_area, control_area] =
+        Layout::vertical([Length(inner_area.height.saturating_sub(2)), Length(1)])
+            .flex(Flex::SpaceBetween)
+            .areas(inner_area);
+
+    let control = Paragraph::new("[j/k] Scroll  [Esc] Close".fg(THEME.inactive_text))
+        .alignment(Alignment::Center);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    if notifications.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No notifications yet.".fg(THEME.text_secondary)),
+            list_area,
+        );
+    } else {
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(THEME.panel))
+            .highlight_spacing(HighlightSpacing::Never);
+        frame.render_stateful_widget(list, list_area, &mut center.list_state);
+    }
+    frame.render_widget(control, control_area);
+}
@@ -10,10 +10,16 @@ use {
 };
 
 pub fn draw(frame: &mut Frame, context: &mut Context) {
+    let menu = context
+        .quit_menu
+        .as_ref()
+        .log_expect("quit menu must be some");
+    let options_height = if menu.backup_action.is_some() { 5 } else { 4 };
+
     let [area] = Layout::horizontal([Length(44)])
         .flex(Flex::Center)
         .areas(frame.area());
-    let [area] = Layout::vertical([Length(11)])
+    let [area] = Layout::vertical([Length(7 + options_height)])
         .flex(Flex::Center)
         .areas(area);
 
@@ -23,15 +29,10 @@ pub fn draw(frame: &mut Frame, context: &mut Context) {
         .padding(Padding::new(2, 2, 1, 1))
         .title(Line::from("Notebook").centered());
     let inner_area = block.inner(area);
-    let [message_area, options_area] = Layout::vertical([Length(4), Length(4)])
+    let [message_area, options_area] = Layout::vertical([Length(4), Length(options_height)])
         .flex(Flex::SpaceBetween)
         .areas(inner_area);
 
-    let menu = context
-        .quit_menu
-        .as_ref()
-        .log_expect("quit menu must be some");
-
     let message = Paragraph::new(menu.message.as_str())
         .wrap(Wrap { trim: true })
         .style(Style::default())
@@ -40,11 +41,20 @@ pub fn draw(frame: &mut Frame, context: &mut Context) {
     let key_style = Style::default().fg(THEME.text);
     let hint_style = Style::default().fg(THEME.text_secondary);
 
-    let mut lines = vec![Line::from(vec![
+    let mut lines = Vec::new();
+    if menu.backup_action.is_some() {
+        lines.push(Line::from(vec![
+            Span::styled("[b]", key_style),
+            Span::raw(" "),
+            Span::styled("Back up to a file", hint_style),
+        ]));
+    }
+
+    lines.push(Line::from(vec![
         Span::styled("[m]", key_style),
         Span::raw(" "),
         Span::styled("Back to menu", hint_style),
-    ])];
+    ]));
 
     lines.push(Line::from(vec![
         Span::styled("[q]", key_style),
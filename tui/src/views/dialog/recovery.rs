@@ -0,0 +1,93 @@
+use {
+    crate::{context::Context, logger::*, theme::THEME},
+    ratatui::{
+        Frame,
+        layout::{Alignment, Constraint::Length, Flex, Layout},
+        style::{Style, Stylize},
+        text::{Line, Span},
+        widgets::{Block, Clear, HighlightSpacing, List, ListItem, Padding, Paragraph, Wrap},
+    },
+};
+
+const MAX_ITEMS: usize = 8;
+
+pub fn draw(frame: &mut Frame, context: &mut Context) {
+    let dialog = context
+        .recovery
+        .as_mut()
+        .log_expect("recovery dialog must be some");
+
+    let message = Line::raw("Unsaved changes were left by the last session:");
+    let items: Vec<ListItem> = dialog
+        .items
+        .iter()
+        .map(|item| {
+            let name = match item.saved {
+                Some(_) => item.swap.note_name.clone(),
+                None => format!("{} (deleted)", item.swap.note_name),
+            };
+            ListItem::new(name.fg(THEME.hint))
+        })
+        .collect();
+
+    let key_style = Style::default().fg(THEME.text);
+    let hint_style = Style::default().fg(THEME.text_secondary);
+    let options: Vec<Line> = [
+        ("[r]", "Restore the unsaved changes"),
+        ("[d]", "Diff against the saved note"),
+        ("[x]", "Discard the unsaved changes"),
+        ("[Esc]", "Decide later"),
+    ]
+    .into_iter()
+    .map(|(key, hint)| {
+        Line::from(vec![
+            Span::styled(key, key_style),
+            Span::raw(" "),
+            Span::styled(hint, hint_style),
+        ])
+    })
+    .collect();
+
+    let message_height = 1;
+    let list_height = items.len().min(MAX_ITEMS) as u16;
+    let options_height = options.len() as u16;
+    let height = (message_height + list_height + options_height + 6).min(frame.area().height);
+
+    let [area] = Layout::horizontal([Length(56.min(frame.area().width))])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let block = Block::bordered()
+        .bg(THEME.surface)
+        .fg(THEME.text)
+        .padding(Padding::new(2, 2, 1, 1))
+        .title(Line::from("Recover unsaved changes").centered());
+    let inner_area = block.inner(area);
+    let [message_area, list_area, options_area] = Layout::vertical([
+        Length(message_height),
+        Length(list_height),
+        Length(options_height),
+    ])
+    .flex(Flex::SpaceBetween)
+    .areas(inner_area);
+
+    let message = Paragraph::new(message)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Left);
+    let list = List::new(items)
+        .highlight_style(Style::new().fg(THEME.accent_text).bg(THEME.accent))
+        .highlight_symbol(" ")
+        .highlight_spacing(HighlightSpacing::Always);
+    let options = Paragraph::new(options)
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Left);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    frame.render_widget(message, message_area);
+    frame.render_stateful_widget(list, list_area, &mut dialog.list_state);
+    frame.render_widget(options, options_area);
+}
@@ -0,0 +1,88 @@
+use {
+    crate::{context::Context, logger::*, theme::THEME},
+    ratatui::{
+        Frame,
+        layout::{Alignment, Constraint::Length, Flex, Layout},
+        style::{Style, Stylize},
+        text::{Line, Span},
+        widgets::{Block, Clear, Padding, Paragraph, Wrap},
+    },
+};
+
+const MAX_ITEMS: usize = 8;
+
+pub fn draw(frame: &mut Frame, context: &mut Context) {
+    let dialog = context
+        .sync_conflict
+        .as_ref()
+        .log_expect("sync conflict dialog must be some");
+
+    let mut lines = vec![
+        Line::raw("Both sides changed these notes:"),
+        Line::default(),
+    ];
+    lines.extend(
+        dialog
+            .items
+            .iter()
+            .take(MAX_ITEMS)
+            .map(|item| Line::from(format!("  {item}").fg(THEME.hint))),
+    );
+    if dialog.items.len() > MAX_ITEMS {
+        let more = dialog.items.len() - MAX_ITEMS;
+        lines.push(Line::from(
+            format!("  and {more} more").fg(THEME.text_secondary),
+        ));
+    }
+
+    let key_style = Style::default().fg(THEME.text);
+    let hint_style = Style::default().fg(THEME.text_secondary);
+    let options: Vec<Line> = [
+        ("[o]", "Keep local changes"),
+        ("[t]", "Take remote changes"),
+        ("[a]", "Abort the merge"),
+    ]
+    .into_iter()
+    .map(|(key, hint)| {
+        Line::from(vec![
+            Span::styled(key, key_style),
+            Span::raw(" "),
+            Span::styled(hint, hint_style),
+        ])
+    })
+    .collect();
+
+    let message_height = lines.len() as u16;
+    let options_height = options.len() as u16;
+    let height = (message_height + options_height + 5).min(frame.area().height);
+
+    let [area] = Layout::horizontal([Length(56.min(frame.area().width))])
+        .flex(Flex::Center)
+        .areas(frame.area());
+    let [area] = Layout::vertical([Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    let block = Block::bordered()
+        .bg(THEME.surface)
+        .fg(THEME.text)
+        .padding(Padding::new(2, 2, 1, 1))
+        .title(Line::from("Sync conflict").centered());
+    let inner_area = block.inner(area);
+    let [message_area, options_area] =
+        Layout::vertical([Length(message_height), Length(options_height)])
+            .flex(Flex::SpaceBetween)
+            .areas(inner_area);
+
+    let message = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Left);
+    let options = Paragraph::new(options)
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Left);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    frame.render_widget(message, message_area);
+    frame.render_widget(options, options_area);
+}
@@ -1,5 +1,8 @@
 use {
-    crate::theme::THEME,
+    crate::{
+        keybindings::{Keybindings, Mode},
+        theme::THEME,
+    },
     glues_core::transition::VimKeymapKind,
     ratatui::{
         Frame,
@@ -10,8 +13,12 @@ use {
     },
 };
 
-pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
-    let (title, message) = match keymap_kind {
+pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind, keybindings: &Keybindings) {
+    let mode = match keymap_kind {
+        VimKeymapKind::VisualIdle | VimKeymapKind::VisualNumbering => Mode::Visual,
+        _ => Mode::Normal,
+    };
+    let (title, mut message) = match keymap_kind {
         VimKeymapKind::NormalIdle => (
             "VIM NORMAL MODE KEYMAP",
             vec![
@@ -28,10 +35,12 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::from("TO OTHER MODES".fg(THEME.accent_text).bg(THEME.accent)),
                 Line::raw("[c] Go to change mode (prepare to edit text)"),
                 Line::raw("[v] Go to visual mode (select text to edit or copy)"),
+                Line::raw("[V] [Ctrl+v] Go to visual line or visual block mode"),
                 Line::raw("[g] Go to gateway mode (access extended commands)"),
                 Line::raw("[y] Go to yank mode (prepare to copy text)"),
                 Line::raw("[d] Go to delete mode (prepare to delete text)"),
                 Line::raw("[z] Go to scroll mode (adjust viewport)"),
+                Line::raw("[Space] Go to toggle mode (tabs, browser and editor options)"),
                 Line::raw("[1-9] Go to numbering mode (repeat or extend actions with numbers)"),
                 Line::raw(""),
                 Line::from("MOVE CURSOR".fg(THEME.accent_text).bg(THEME.accent)),
@@ -46,6 +55,17 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::raw("[$] Move cursor to the end of the line"),
                 Line::raw("[^] Move cursor to the first non-blank character of the line"),
                 Line::raw("[G] Move cursor to the end of the file"),
+                Line::raw(
+                    "[f] [F] [t] [T] Move cursor to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Move cursor to the matching bracket"),
+                Line::raw("[{] [}] Move cursor to the previous or next paragraph"),
+                Line::raw(""),
+                Line::from("MARKS AND JUMPS".fg(THEME.accent_text).bg(THEME.accent)),
+                Line::raw("[m] [a-z] Set a mark in the current note"),
+                Line::raw("[m] [A-Z] Set a global mark, reachable from any note"),
+                Line::raw("['] Jump to the line of a mark, [`] to its exact position"),
+                Line::raw("[Ctrl+o] [Ctrl+i] Go to the older or newer position in the jump list"),
                 Line::raw(""),
                 Line::from("EDIT TEXT".fg(THEME.accent_text).bg(THEME.accent)),
                 Line::raw("[~] Toggle the case of the current character"),
@@ -108,11 +128,17 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::raw("[1-9] Go to delete numbering mode"),
                 Line::raw(""),
                 Line::from(
-                    "TO DELETE INSIDE MODE"
+                    "TEXT OBJECTS AND MOTIONS"
                         .fg(THEME.accent_text)
                         .bg(THEME.accent),
                 ),
-                Line::raw("[i] Go to delete inside mode"),
+                Line::raw(
+                    "[i] or [a] Delete inside or around a word, paragraph, quotes or brackets",
+                ),
+                Line::raw(
+                    "[f] [F] [t] [T] Delete to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Delete to the matching bracket, [{] [}] to the paragraph boundary"),
                 Line::raw(""),
                 Line::from("DELETE TEXT".fg(THEME.accent_text).bg(THEME.accent)),
                 Line::raw("[d] Delete the specified number of lines"),
@@ -137,11 +163,17 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::raw("[0-9] Append additional digits to extend the current command"),
                 Line::raw(""),
                 Line::from(
-                    "TO DELETE INSIDE MODE"
+                    "TEXT OBJECTS AND MOTIONS"
                         .fg(THEME.accent_text)
                         .bg(THEME.accent),
                 ),
-                Line::raw("[i] Go to delete inside mode"),
+                Line::raw(
+                    "[i] or [a] Delete inside or around a word, paragraph, quotes or brackets",
+                ),
+                Line::raw(
+                    "[f] [F] [t] [T] Delete to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Delete to the matching bracket, [{] [}] to the paragraph boundary"),
                 Line::raw(""),
                 Line::from("DELETE TEXT".fg(THEME.accent_text).bg(THEME.accent)),
                 Line::raw("[d] Delete the specified number of lines"),
@@ -158,11 +190,17 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
             "VIM NORMAL MODE KEYMAP - CHANGE",
             vec![
                 Line::from(
-                    "TO CHANGE INSIDE MODE"
+                    "TEXT OBJECTS AND MOTIONS"
                         .fg(THEME.accent_text)
                         .bg(THEME.accent),
                 ),
-                Line::raw("[i] Go to change inside mode"),
+                Line::raw(
+                    "[i] or [a] Change inside or around a word, paragraph, quotes or brackets",
+                ),
+                Line::raw(
+                    "[f] [F] [t] [T] Change to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Change to the matching bracket, [{] [}] to the paragraph boundary"),
                 Line::raw(""),
                 Line::from(
                     "CHANGE TEXT AND GO TO INSERT MODE"
@@ -193,11 +231,17 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::raw("[0-9] Append additional digits to extend the current command"),
                 Line::raw(""),
                 Line::from(
-                    "TO CHANGE INSIDE MODE"
+                    "TEXT OBJECTS AND MOTIONS"
                         .fg(THEME.accent_text)
                         .bg(THEME.accent),
                 ),
-                Line::raw("[i] Go to change inside mode"),
+                Line::raw(
+                    "[i] or [a] Change inside or around a word, paragraph, quotes or brackets",
+                ),
+                Line::raw(
+                    "[f] [F] [t] [T] Change to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Change to the matching bracket, [{] [}] to the paragraph boundary"),
                 Line::raw(""),
                 Line::from(
                     "CHANGE TEXT AND GO TO INSERT MODE"
@@ -231,6 +275,16 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 Line::raw("[$] Move cursor to the end of the line"),
                 Line::raw("[^] Move cursor to the first non-blank character of the line"),
                 Line::raw("[G] Move cursor to the end of the file"),
+                Line::raw(
+                    "[f] [F] [t] [T] Move cursor to or until a typed character, [;] [,] repeat it",
+                ),
+                Line::raw("[%] Move cursor to the matching bracket"),
+                Line::raw("[{] [}] Move cursor to the previous or next paragraph"),
+                Line::raw(""),
+                Line::from("SELECT TEXT OBJECTS".fg(THEME.accent_text).bg(THEME.accent)),
+                Line::raw(
+                    "[i] or [a] Select inside or around a word, paragraph, quotes or brackets",
+                ),
                 Line::raw(""),
                 Line::from("TO INSERT MODE".fg(THEME.accent_text).bg(THEME.accent)),
                 Line::from(vec![
@@ -238,8 +292,10 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                     "or ".fg(THEME.inactive_text),
                     "[S] Substitute selected text and go to insert mode".into(),
                 ]),
+                Line::raw("[I] [A] Insert before or append after a block, repeated on every row"),
                 Line::raw(""),
                 Line::from("TO EXTENDED MODES".fg(THEME.accent_text).bg(THEME.accent)),
+                Line::raw("[v] [V] [Ctrl+v] Switch to character, line or block selection"),
                 Line::raw("[g] Go to gateway mode for additional commands"),
                 Line::raw("[1-9] Specify repeat count for subsequent actions"),
                 Line::raw(""),
@@ -255,6 +311,7 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
                 ]),
                 Line::raw("[y] Yank (copy) selected text"),
                 Line::raw("[~] Toggle the case of the select text"),
+                Line::raw("[>] [<] Indent or outdent selected lines, [J] join them"),
             ],
         ),
         VimKeymapKind::VisualNumbering => (
@@ -285,6 +342,10 @@ pub fn draw(frame: &mut Frame, keymap_kind: VimKeymapKind) {
             ],
         ),
     };
+    for span in message.iter_mut().flat_map(|line| line.spans.iter_mut()) {
+        span.content = keybindings.describe_brackets(mode, &span.content).into();
+    }
+
     let height = message.len() as u16 + 7;
 
     let [area] = Layout::horizontal([Length(90)])
@@ -4,7 +4,7 @@ use {
         logger::*,
         theme::THEME,
     },
-    glues_core::state::State,
+    glues_core::{backend::ConnectionStatus, state::State},
     ratatui::{
         Frame,
         layout::{
@@ -16,12 +16,59 @@ use {
     },
 };
 
-pub fn draw(frame: &mut Frame, area: Rect, state: &State, context: &NotebookContext) {
+pub fn draw(
+    frame: &mut Frame,
+    area: Rect,
+    state: &State,
+    connection: Option<ConnectionStatus>,
+    unread_errors: usize,
+    context: &NotebookContext,
+) {
     let description = format!(" {}", state.describe().log_unwrap());
     let insert_mode = matches!(context.state, ContextState::EditorInsertMode);
-    let [desc_area, keymap_area] =
-        Layout::horizontal([Percentage(100), Length(if insert_mode { 23 } else { 18 })])
-            .areas(area);
+    let connection = connection.map(|status| {
+        let (mut text, color) = match status {
+            ConnectionStatus {
+                online: true,
+                pending: 0,
+                ..
+            } => (" online".to_owned(), THEME.inactive_text),
+            ConnectionStatus {
+                online: true,
+                pending,
+                ..
+            } => (format!(" online · {pending} pending"), THEME.inactive_text),
+            ConnectionStatus { pending, .. } => {
+                (format!(" offline · {pending} pending"), THEME.warning)
+            }
+        };
+        if status.rejected > 0 {
+            text.push_str(&format!(" · {} rejected", status.rejected));
+        }
+        text.push(' ');
+
+        (
+            text,
+            if status.rejected > 0 {
+                THEME.error
+            } else {
+                color
+            },
+        )
+    });
+    let connection_width = connection
+        .as_ref()
+        .map_or(0, |(text, _)| text.chars().count() as u16);
+    // errors not seen in the notification center yet
+    let badge = (unread_errors > 0).then(|| format!(" 󰂚 {unread_errors} "));
+    let badge_width = badge.as_ref().map_or(0, |text| text.chars().count() as u16);
+    let [desc_area, badge_area, connection_area, keymap_area] = Layout::horizontal([
+        Percentage(100),
+        Length(badge_width),
+        Length(connection_width),
+        Length(if insert_mode { 23 } else { 18 }),
+    ])
+    .areas(area);
 
     frame.render_widget(
         Text::raw(description)
@@ -30,6 +77,17 @@ pub fn draw(frame: &mut Frame, area: Rect, state: &State, context: &NotebookCont
         desc_area,
     );
 
+    if let Some(badge) = badge {
+        frame.render_widget(
+            Text::raw(badge).fg(THEME.error_text).bg(THEME.error),
+            badge_area,
+        );
+    }
+
+    if let Some((text, color)) = connection {
+        frame.render_widget(Text::raw(text).fg(color).bg(THEME.panel), connection_area);
+    }
+
     frame.render_widget(
         Line::from(vec![
             Span::raw("").fg(THEME.success).bg(THEME.panel),
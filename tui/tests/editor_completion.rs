@@ -0,0 +1,148 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use color_eyre::Result;
+use glues_tui::{context::notebook::CompletionKind, input::KeyCode};
+
+/// Helper: clear the opened note and type the given lines, staying in insert mode.
+async fn type_lines(t: &mut Tester, lines: &[&str]) {
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            t.key(KeyCode::Enter).await;
+        }
+        t.type_str(line).await;
+    }
+}
+
+fn completion_items(t: &mut Tester) -> Option<Vec<String>> {
+    t.app
+        .context_mut()
+        .notebook
+        .completion
+        .as_ref()
+        .map(|completion| completion.items.clone())
+}
+
+#[tokio::test]
+async fn buffer_words_complete_at_cursor() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    type_lines(&mut t, &["hello help world", "he"]).await;
+
+    t.ctrl('n').await;
+    assert_eq!(
+        completion_items(&mut t),
+        Some(vec!["hello".to_owned(), "help".to_owned()])
+    );
+    t.draw()?;
+    snap!(t, "completion_popup_words");
+
+    t.ctrl('n').await;
+    t.key(KeyCode::Enter).await;
+    assert_eq!(t.editor_text(), "hello help world\nhelp");
+    assert!(completion_items(&mut t).is_none());
+
+    // still in insert mode
+    t.type_str("er").await;
+    assert_eq!(t.editor_text(), "hello help world\nhelper");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn typing_filters_and_esc_only_closes_the_popup() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    type_lines(&mut t, &["alpha alpine beta", "a"]).await;
+
+    t.ctrl('p').await;
+    let completion = t.app.context_mut().notebook.completion.clone().unwrap();
+    assert_eq!(completion.kind, CompletionKind::Word);
+    assert_eq!(completion.selected, 1);
+
+    t.type_str("lph").await;
+    assert_eq!(completion_items(&mut t), Some(vec!["alpha".to_owned()]));
+
+    t.press('a').await;
+    assert!(completion_items(&mut t).is_none());
+
+    t.type_str(" b").await;
+    t.ctrl('n').await;
+    assert_eq!(completion_items(&mut t), Some(vec!["beta".to_owned()]));
+    t.key(KeyCode::Esc).await;
+    assert!(completion_items(&mut t).is_none());
+
+    t.press('e').await;
+    assert_eq!(t.editor_text(), "alpha alpine beta\nalpha be");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tags_and_other_tabs_are_offered() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    type_lines(&mut t, &["# Title", "#rust #rusty notes"]).await;
+    t.key(KeyCode::Esc).await;
+
+    // open a second note; its words come after the current note's
+    t.key(KeyCode::Tab).await;
+    t.type_str("ggm").await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("Second").await;
+    t.key(KeyCode::Enter).await;
+    type_lines(&mut t, &["nothing", "#ru"]).await;
+
+    t.ctrl('n').await;
+    assert_eq!(
+        completion_items(&mut t),
+        Some(vec!["#rust".to_owned(), "#rusty".to_owned()])
+    );
+    t.key(KeyCode::Esc).await;
+
+    t.type_str(" no").await;
+    t.ctrl('n').await;
+    assert_eq!(
+        completion_items(&mut t),
+        Some(vec!["nothing".to_owned(), "notes".to_owned()])
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn note_links_complete_from_the_notebook() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    type_lines(&mut t, &["see [[sa"]).await;
+
+    t.ctrl('n').await;
+    assert_eq!(
+        completion_items(&mut t),
+        Some(vec!["Sample Note".to_owned()])
+    );
+    t.draw()?;
+    snap!(t, "completion_popup_note_link");
+
+    t.key(KeyCode::Tab).await;
+    assert_eq!(t.editor_text(), "see [[Sample Note]]");
+
+    // an already closed link is not closed twice
+    t.type_str(" [[]]").await;
+    t.key(KeyCode::Left).await;
+    t.key(KeyCode::Left).await;
+    t.ctrl('n').await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("!").await;
+    assert_eq!(t.editor_text(), "see [[Sample Note]] [[Sample Note]]!");
+
+    Ok(())
+}
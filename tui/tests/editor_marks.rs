@@ -0,0 +1,142 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use color_eyre::Result;
+use glues_tui::{input::KeyCode, notifications::Severity};
+
+/// Helper: clear the opened note, type the given lines, then go to (0, 0) in normal mode.
+async fn fill_lines(t: &mut Tester, lines: &[&str]) {
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            t.key(KeyCode::Enter).await;
+        }
+        t.type_str(line).await;
+    }
+    t.key(KeyCode::Esc).await;
+
+    t.press('g').await;
+    t.press('g').await;
+    t.press('0').await;
+}
+
+/// Helper: add a note to the root directory from the editor; it opens in a new tab.
+async fn add_root_note(t: &mut Tester, name: &str) {
+    t.key(KeyCode::Tab).await;
+    t.press('g').await;
+    t.press('g').await;
+    t.press('m').await;
+    t.key(KeyCode::Enter).await;
+    t.type_str(name).await;
+    t.key(KeyCode::Enter).await;
+}
+
+fn opened_note_name(t: &mut Tester) -> String {
+    t.app
+        .context_mut()
+        .notebook
+        .get_opened_note()
+        .map(|note| note.name.clone())
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn local_mark_jumps_to_line_and_exact_position() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    fill_lines(&mut t, &["first", "  second line", "third"]).await;
+
+    t.type_str("jwwma").await;
+    assert_eq!(t.cursor(), (1, 9));
+
+    t.type_str("gg").await;
+    t.type_str("`a").await;
+    assert_eq!(t.cursor(), (1, 9));
+
+    t.type_str("gg'a").await;
+    assert_eq!(t.cursor(), (1, 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn jump_list_goes_back_and_forth() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    fill_lines(&mut t, &["one", "two", "three"]).await;
+
+    t.type_str("Gmb").await;
+    t.type_str("gg$").await;
+    t.type_str("`b").await;
+    assert_eq!(t.cursor(), (2, 0));
+
+    t.ctrl('o').await;
+    assert_eq!(t.cursor(), (0, 2));
+
+    t.ctrl('i').await;
+    assert_eq!(t.cursor(), (2, 0));
+
+    // nothing newer to go to
+    t.ctrl('i').await;
+    assert_eq!(t.cursor(), (2, 0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn missing_mark_shows_alert() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    t.type_str("'z").await;
+    assert_eq!(
+        t.app.context_mut().alert.as_deref(),
+        Some("Mark 'z' is not set")
+    );
+
+    // a missing mark is a notice, not an error
+    let notifications = &t.app.context_mut().notifications;
+    assert_eq!(notifications.unread_errors(), 0);
+    assert!(
+        notifications
+            .iter()
+            .any(|n| n.severity == Severity::Info && n.message == "Mark 'z' is not set")
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn global_mark_and_tab_switch_join_the_jump_list() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+    fill_lines(&mut t, &["sample", "marked here"]).await;
+    t.type_str("jwmA").await;
+    assert_eq!(t.cursor(), (1, 7));
+
+    add_root_note(&mut t, "Second").await;
+    assert_eq!(opened_note_name(&mut t), "Second");
+
+    // global mark opens the note it points into
+    t.type_str("`A").await;
+    assert_eq!(opened_note_name(&mut t), "Sample Note");
+    assert_eq!(t.cursor(), (1, 7));
+
+    t.ctrl('o').await;
+    assert_eq!(opened_note_name(&mut t), "Second");
+
+    // switching tabs is a jump as well
+    t.type_str(" l").await;
+    assert_eq!(opened_note_name(&mut t), "Sample Note");
+    t.ctrl('o').await;
+    assert_eq!(opened_note_name(&mut t), "Second");
+
+    Ok(())
+}
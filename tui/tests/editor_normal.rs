@@ -3,6 +3,7 @@ mod tester;
 use tester::Tester;
 
 use color_eyre::Result;
+use glues_core::backup::Archive;
 use glues_tui::input::KeyCode;
 
 #[tokio::test]
@@ -50,6 +51,37 @@ async fn quit_menu_returns_to_entry() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn quit_menu_backs_up_instant_notebook() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    t.key(KeyCode::Esc).await;
+    let path = std::env::temp_dir().join(format!("glues-backup-{}.json", std::process::id()));
+    t.press('b').await;
+    t.backspace("glues-backup.json".len()).await;
+    t.type_str(&path.to_string_lossy()).await;
+    t.key(KeyCode::Enter).await;
+    assert_eq!(
+        t.app.context_mut().alert,
+        Some(format!("Backed up the notebook to {}", path.display()))
+    );
+
+    let archive = Archive::from_json(&std::fs::read_to_string(&path)?)?;
+    std::fs::remove_file(&path)?;
+    assert_eq!(archive.notes.len(), 1);
+    assert_eq!(archive.notes[0].note.name, "Sample Note");
+    assert_eq!(archive.notes[0].content, "Hi :D");
+
+    // the notebook stays open after the backup
+    t.key(KeyCode::Enter).await;
+    let quit = t.press('q').await;
+    assert!(!quit);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn undo_then_redo_after_insert() -> Result<()> {
     let mut t = Tester::new().await?;
@@ -0,0 +1,290 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use color_eyre::Result;
+use glues_tui::input::KeyCode;
+
+/// Helper: clear the editor, type the given lines, then go to (0, 0) in normal mode.
+async fn setup_lines(t: &mut Tester, lines: &[&str]) -> Result<()> {
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            t.key(KeyCode::Enter).await;
+        }
+        t.type_str(line).await;
+    }
+    t.key(KeyCode::Esc).await;
+
+    t.press('g').await;
+    t.press('g').await;
+    t.press('0').await;
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_inside_and_around_word() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["one two three"]).await?;
+
+    t.press('w').await;
+    t.type_str("diw").await;
+    assert_eq!(t.editor_text(), "one  three");
+
+    t.press('u').await;
+    t.type_str("daw").await;
+    assert_eq!(t.editor_text(), "one three");
+
+    t.press('$').await;
+    t.type_str("daw").await;
+    assert_eq!(t.editor_text(), "one");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn change_inside_quotes() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["say \"hello\" now"]).await?;
+
+    t.type_str("ci\"").await;
+    t.type_str("bye").await;
+    t.key(KeyCode::Esc).await;
+    assert_eq!(t.editor_text(), "say \"bye\" now");
+
+    t.press('0').await;
+    t.type_str("da\"").await;
+    assert_eq!(t.editor_text(), "say now");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn change_inside_empty_quotes_inserts_between() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["x = ''"]).await?;
+
+    t.type_str("ci'").await;
+    t.type_str("ok").await;
+    t.key(KeyCode::Esc).await;
+    assert_eq!(t.editor_text(), "x = 'ok'");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_inside_and_around_brackets() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["call(a, [b, c])"]).await?;
+
+    t.type_str("fb").await;
+    t.type_str("di[").await;
+    assert_eq!(t.editor_text(), "call(a, [])");
+
+    t.type_str("di(").await;
+    assert_eq!(t.editor_text(), "call()");
+
+    t.press('u').await;
+    t.press('u').await;
+    t.type_str("0fb").await;
+    t.type_str("dab").await;
+    assert_eq!(t.editor_text(), "call");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_inside_multiline_braces_is_linewise() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["fn main() {", "    one();", "    two();", "}"]).await?;
+
+    t.press('j').await;
+    t.type_str("di{").await;
+    assert_eq!(t.editor_text(), "fn main() {\n}");
+
+    t.press('u').await;
+    t.type_str("jda}").await;
+    assert_eq!(t.editor_text(), "fn main() ");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn yank_inside_brackets_then_paste() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["[abc] "]).await?;
+
+    t.type_str("yi]").await;
+    assert_eq!(t.cursor(), (0, 1));
+    assert_eq!(t.editor_text(), "[abc] ");
+
+    t.press('p').await;
+    assert_eq!(t.editor_text(), "[aabcbc] ");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn find_and_till_motions() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["a,b,c,d"]).await?;
+
+    t.type_str("f,").await;
+    assert_eq!(t.cursor(), (0, 1));
+
+    t.press(';').await;
+    assert_eq!(t.cursor(), (0, 3));
+
+    t.press(',').await;
+    assert_eq!(t.cursor(), (0, 1));
+
+    t.type_str("Ta").await;
+    assert_eq!(t.cursor(), (0, 1));
+
+    t.type_str("2f,").await;
+    assert_eq!(t.cursor(), (0, 5));
+
+    t.type_str("Fb").await;
+    assert_eq!(t.cursor(), (0, 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn delete_with_find_and_till() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["foo(bar, baz)"]).await?;
+
+    t.type_str("dt(").await;
+    assert_eq!(t.editor_text(), "(bar, baz)");
+
+    t.type_str("df,").await;
+    assert_eq!(t.editor_text(), " baz)");
+
+    t.press('$').await;
+    t.type_str("dFb").await;
+    assert_eq!(t.editor_text(), " )");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn repeat_till_skips_adjacent_target() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["a.b.c.d"]).await?;
+
+    t.press('$').await;
+    t.type_str("T.").await;
+    assert_eq!(t.cursor(), (0, 6));
+
+    t.press(';').await;
+    assert_eq!(t.cursor(), (0, 4));
+
+    t.press(';').await;
+    assert_eq!(t.cursor(), (0, 2));
+
+    t.type_str("d,").await;
+    assert_eq!(t.editor_text(), "a..d");
+
+    t.press('0').await;
+    t.type_str("1t.").await;
+    assert_eq!(t.cursor(), (0, 0));
+
+    // bare `t` searches too
+    t.type_str("td").await;
+    assert_eq!(t.cursor(), (0, 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn match_pair_motion_and_delete() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["if (a (b)) {", "}"]).await?;
+
+    t.press('%').await;
+    assert_eq!(t.cursor(), (0, 9));
+
+    t.press('%').await;
+    assert_eq!(t.cursor(), (0, 3));
+
+    t.press('$').await;
+    t.press('%').await;
+    assert_eq!(t.cursor(), (1, 0));
+
+    t.type_str("gg0").await;
+    t.type_str("d%").await;
+    assert_eq!(t.editor_text(), " {\n}");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn paragraph_motions() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["a", "b", "", "c", "d", "", "e"]).await?;
+
+    t.press('}').await;
+    assert_eq!(t.cursor(), (2, 0));
+
+    t.press('}').await;
+    assert_eq!(t.cursor(), (5, 0));
+
+    t.press('}').await;
+    assert_eq!(t.cursor(), (6, 0));
+
+    t.press('{').await;
+    assert_eq!(t.cursor(), (5, 0));
+
+    t.type_str("2{").await;
+    assert_eq!(t.cursor(), (0, 0));
+
+    t.type_str("d}").await;
+    assert_eq!(t.editor_text(), "\nc\nd\n\ne");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn paragraph_text_objects() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["a", "b", "", "c", "", "d"]).await?;
+
+    t.type_str("dap").await;
+    assert_eq!(t.editor_text(), "c\n\nd");
+
+    t.type_str("yip").await;
+    t.press('G').await;
+    t.press('p').await;
+    assert_eq!(t.editor_text(), "c\n\nd\nc");
+
+    t.type_str("cip").await;
+    t.type_str("z").await;
+    t.key(KeyCode::Esc).await;
+    assert_eq!(t.editor_text(), "c\n\nz");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn visual_text_object_and_find() -> Result<()> {
+    let mut t = Tester::new().await?;
+    setup_lines(&mut t, &["keep (drop this) keep"]).await?;
+
+    t.type_str("fd").await;
+    t.type_str("vi(").await;
+    t.press('d').await;
+    assert_eq!(t.editor_text(), "keep () keep");
+
+    t.press('0').await;
+    t.type_str("vt(").await;
+    t.press('d').await;
+    assert_eq!(t.editor_text(), "() keep");
+
+    Ok(())
+}
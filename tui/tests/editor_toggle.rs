@@ -10,8 +10,8 @@ async fn toggle_mode_keymap() -> Result<()> {
     t.open_instant().await?;
     t.open_first_note().await?;
 
-    // enter toggle mode and show keymap with ?
-    t.press('t').await;
+    // enter toggle mode with Space and show keymap with ?
+    t.press(' ').await;
     t.press('?').await;
     t.draw()?;
     snap!(t, "toggle_mode_keymap");
@@ -26,9 +26,9 @@ async fn toggle_syntax_highlight() -> Result<()> {
     t.open_first_note().await?;
 
     // toggle syntax highlight off and back on without error
-    t.press('t').await;
+    t.press(' ').await;
     t.press('s').await;
-    t.press('t').await;
+    t.press(' ').await;
     t.press('s').await;
     t.draw()?;
     snap!(t, "after_syntax_toggle");
@@ -0,0 +1,174 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use color_eyre::Result;
+use glues_core::transition::VisualKind;
+use glues_tui::{input::KeyCode, theme::THEME};
+
+/// Helper: clear the opened note, type the given lines, then go to (0, 0) in normal mode.
+async fn fill_lines(t: &mut Tester, lines: &[&str]) -> Result<()> {
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            t.key(KeyCode::Enter).await;
+        }
+        t.type_str(line).await;
+    }
+    t.key(KeyCode::Esc).await;
+
+    t.type_str("gg0").await;
+    Ok(())
+}
+
+/// Characters drawn with the selection background on the screen row showing `text`.
+fn highlighted(t: &Tester, text: &str) -> String {
+    let buffer = t.term.backend().buffer();
+    let area = buffer.area;
+
+    (area.top()..area.bottom())
+        .map(|y| {
+            (area.left()..area.right())
+                .map(|x| &buffer[(x, y)])
+                .collect::<Vec<_>>()
+        })
+        .find(|row| {
+            row.iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+                .contains(text)
+        })
+        .map(|row| {
+            row.iter()
+                .filter(|cell| cell.bg == THEME.accent)
+                .map(|cell| cell.symbol())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn switch_between_visual_kinds() -> Result<()> {
+    let mut t = Tester::new().await?;
+    fill_lines(&mut t, &["one", "two"]).await?;
+
+    t.press('v').await;
+    assert_eq!(t.app.context_mut().notebook.visual_kind, VisualKind::Char);
+
+    t.press('V').await;
+    assert_eq!(t.app.context_mut().notebook.visual_kind, VisualKind::Line);
+
+    t.ctrl('v').await;
+    assert_eq!(t.app.context_mut().notebook.visual_kind, VisualKind::Block);
+
+    // the key of the current kind leaves visual mode
+    t.ctrl('v').await;
+    t.draw()?;
+    snap!(t, "visual_kind_toggled_off");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn visual_line_delete_and_paste() -> Result<()> {
+    let mut t = Tester::new().await?;
+    fill_lines(&mut t, &["one", "two", "three", "four"]).await?;
+
+    t.type_str("lVj").await;
+    t.draw()?;
+    snap!(t, "visual_line_selection");
+    assert_eq!(highlighted(&t, "one"), "one");
+    assert_eq!(highlighted(&t, "two"), "two");
+
+    t.press('d').await;
+    assert_eq!(t.editor_text(), "three\nfour");
+
+    t.press('p').await;
+    assert_eq!(t.editor_text(), "three\none\ntwo\nfour");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn visual_line_indent_outdent_and_join() -> Result<()> {
+    let mut t = Tester::new().await?;
+    fill_lines(&mut t, &["- one", "- two", "", "last"]).await?;
+
+    t.type_str("Vj>").await;
+    assert_eq!(t.editor_text(), "\t- one\n\t- two\n\nlast");
+    assert_eq!(t.cursor(), (0, 1));
+
+    t.type_str("Vjj2>").await;
+    assert_eq!(t.editor_text(), "\t\t\t- one\n\t\t\t- two\n\nlast");
+
+    t.type_str("Vj3<").await;
+    assert_eq!(t.editor_text(), "- one\n- two\n\nlast");
+
+    t.type_str("jVjjJ").await;
+    assert_eq!(t.editor_text(), "- one\n- two last");
+    assert_eq!(t.cursor(), (1, 5));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn visual_block_yank_and_delete() -> Result<()> {
+    let mut t = Tester::new().await?;
+    fill_lines(&mut t, &["abcd", "efgh", "ij"]).await?;
+
+    t.press('l').await;
+    t.ctrl('v').await;
+    t.type_str("jjl").await;
+    t.draw()?;
+    snap!(t, "visual_block_selection");
+    assert_eq!(highlighted(&t, "abcd"), "bc");
+    assert_eq!(highlighted(&t, "efgh"), "fg");
+
+    t.press('y').await;
+    assert_eq!(t.cursor(), (0, 1));
+    assert_eq!(
+        t.app.context_mut().notebook.yank.as_deref(),
+        Some("bc\nfg\nj")
+    );
+
+    t.ctrl('v').await;
+    t.type_str("jjld").await;
+    assert_eq!(t.editor_text(), "ad\neh\ni");
+
+    t.press('u').await;
+    assert_eq!(t.editor_text(), "abcd\nefgh\nij");
+    t.ctrl('r').await;
+    assert_eq!(t.editor_text(), "ad\neh\ni");
+
+    t.ctrl('v').await;
+    t.type_str("j~").await;
+    assert_eq!(t.editor_text(), "aD\neH\ni");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn visual_block_insert_and_append() -> Result<()> {
+    let mut t = Tester::new().await?;
+    fill_lines(&mut t, &["a b", "c", "d e"]).await?;
+
+    t.ctrl('v').await;
+    t.type_str("jjI").await;
+    t.type_str("| ").await;
+    t.key(KeyCode::Esc).await;
+    assert_eq!(t.editor_text(), "| a b\n| c\n| d e");
+
+    t.type_str("gg0ll").await;
+    t.ctrl('v').await;
+    t.type_str("jjA").await;
+    t.type_str(" |").await;
+    t.key(KeyCode::Esc).await;
+    assert_eq!(t.editor_text(), "| a | b\n| c |\n| d | e");
+
+    Ok(())
+}
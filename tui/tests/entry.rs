@@ -4,7 +4,15 @@ use tester::Tester;
 
 use {
     color_eyre::Result,
-    glues_tui::{config::LAST_PROXY_URL, input::KeyCode},
+    glues_core::{
+        EntryEvent,
+        backend::{encrypted::is_encrypted, local::Db},
+    },
+    glues_tui::{
+        config::{LAST_PROXY_CA_CERT, LAST_PROXY_CLIENT_IDENTITY, LAST_PROXY_URL},
+        input::KeyCode,
+        recent,
+    },
 };
 
 #[tokio::test]
@@ -98,10 +106,15 @@ async fn proxy_prompt_open() -> Result<()> {
     t.draw()?;
     snap!(t, "proxy_token_prompt");
 
-    // provide a token (masked) and expect a connection failure alert
+    // provide a token (masked), then the optional notebook passphrase
     t.type_str("secret").await;
     t.key(KeyCode::Enter).await;
     t.draw()?;
+    snap!(t, "proxy_passphrase_prompt");
+
+    // leave the passphrase empty and expect a connection failure alert
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
     snap!(t, "proxy_connect_error");
 
     glues_tui::config::update(LAST_PROXY_URL, "").await;
@@ -120,3 +133,78 @@ async fn theme_dialog_open() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn proxy_https_prompts() -> Result<()> {
+    let mut t = Tester::new().await?;
+    glues_tui::config::update(LAST_PROXY_URL, "").await;
+    glues_tui::config::update(LAST_PROXY_CA_CERT, "").await;
+    glues_tui::config::update(LAST_PROXY_CLIENT_IDENTITY, "").await;
+    t.draw()?;
+
+    // https urls ask for the CA bundle and client identity before the token
+    t.press('p').await;
+    t.type_str("https://127.0.0.1:9").await;
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    snap!(t, "proxy_ca_cert_prompt");
+
+    t.type_str("/tmp/glues-missing-ca.pem").await;
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    snap!(t, "proxy_client_identity_prompt");
+
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    snap!(t, "proxy_https_token_prompt");
+
+    // the missing CA bundle fails the connection
+    t.key(KeyCode::Enter).await;
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    snap!(t, "proxy_https_connect_error");
+
+    glues_tui::config::update(LAST_PROXY_URL, "").await;
+    glues_tui::config::update(LAST_PROXY_CA_CERT, "").await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn plain_notebooks_are_encrypted_once_the_passphrase_is_repeated() -> Result<()> {
+    let mut t = Tester::new().await?;
+    let path = ".glues/entry-encrypt.redb";
+    let _ = std::fs::remove_file(path);
+    let open_encrypted = || EntryEvent::OpenEncrypted {
+        open: Box::new(EntryEvent::OpenRedb(path.to_owned())),
+        passphrase: "secret".to_owned(),
+    };
+
+    t.app.open_notebook(open_encrypted()).await;
+    assert!(t.app.glues_mut().db.is_none());
+    t.draw()?;
+    snap!(t, "encrypt_prompt");
+
+    // a different passphrase leaves the notebook plain
+    t.type_str("other").await;
+    t.key(KeyCode::Enter).await;
+    assert!(t.app.glues_mut().db.is_none());
+    t.key(KeyCode::Esc).await;
+    let mut db = Db::redb(path).await?;
+    assert!(!is_encrypted(&mut db).await?);
+    drop(db);
+
+    t.app.open_notebook(open_encrypted()).await;
+    t.type_str("secret").await;
+    t.key(KeyCode::Enter).await;
+    let db = t.app.glues_mut().db.as_mut().expect("notebook should open");
+    assert!(is_encrypted(db).await?);
+    drop(t);
+
+    let _ = std::fs::remove_file(path);
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
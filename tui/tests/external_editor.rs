@@ -0,0 +1,148 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_core::{
+        EntryEvent,
+        backend::{CoreBackend, encrypted::Encrypted, local::Db},
+        types::NoteId,
+    },
+    glues_tui::recent,
+    std::fs,
+};
+
+async fn request_external_edit(t: &mut Tester) -> NoteId {
+    t.press(' ').await;
+    t.press('e').await;
+    t.app
+        .take_external_edit()
+        .expect("t e should ask for an external edit")
+}
+
+async fn backend_content(t: &mut Tester, note_id: &NoteId) -> String {
+    let db = t.app.glues_mut().db.as_mut().expect("notebook is open");
+    db.fetch_note_content(note_id.clone())
+        .await
+        .expect("note should exist")
+}
+
+#[tokio::test]
+async fn external_edits_replace_the_buffer_and_are_saved() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    // unsaved changes are saved before the note is handed over
+    t.type_str("A!").await;
+    t.key(glues_tui::input::KeyCode::Esc).await;
+    let note_id = request_external_edit(&mut t).await;
+    let edit = t
+        .app
+        .start_external_edit(note_id.clone())
+        .await
+        .expect("note should be written out");
+    assert_eq!(fs::read_to_string(&edit.path)?, "Hi :D!");
+    assert_eq!(backend_content(&mut t, &note_id).await, "Hi :D!");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &std::path::Path| {
+            fs::metadata(path).map(|meta| meta.permissions().mode() & 0o777)
+        };
+        assert_eq!(mode(&edit.path)?, 0o600);
+        assert_eq!(
+            mode(edit.path.parent().expect("file is in a directory"))?,
+            0o700
+        );
+    }
+
+    // the trailing newline editors add is dropped again
+    let path = edit.path.clone();
+    fs::write(&path, "Hello\nfrom outside\n")?;
+    t.app.finish_external_edit(edit).await;
+    t.draw()?;
+
+    assert_eq!(t.editor_text(), "Hello\nfrom outside");
+    assert_eq!(
+        backend_content(&mut t, &note_id).await,
+        "Hello\nfrom outside"
+    );
+    assert!(!path.exists());
+    assert!(!path.parent().expect("file is in a directory").exists());
+    snap!(t, "external_edit_applied");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn backend_changes_meanwhile_are_not_overwritten_silently() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    let note_id = request_external_edit(&mut t).await;
+    let edit = t
+        .app
+        .start_external_edit(note_id.clone())
+        .await
+        .expect("note should be written out");
+    let path = edit.path.clone();
+    fs::write(&path, "mine")?;
+    t.app
+        .glues_mut()
+        .db
+        .as_mut()
+        .expect("notebook is open")
+        .update_note_content(note_id.clone(), "theirs".to_owned())
+        .await?;
+
+    t.app.finish_external_edit(edit.clone()).await;
+    assert!(t.app.context_mut().confirm.is_some());
+
+    // declining keeps the backend version and the edits on disk
+    t.press('n').await;
+    assert_eq!(backend_content(&mut t, &note_id).await, "theirs");
+    assert_eq!(fs::read_to_string(&path)?, "mine");
+
+    t.app.finish_external_edit(edit).await;
+    t.press('y').await;
+    assert_eq!(backend_content(&mut t, &note_id).await, "mine");
+    assert_eq!(t.editor_text(), "mine");
+    assert!(!path.exists());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn notes_of_encrypted_notebooks_are_not_written_out() -> Result<()> {
+    const PATH: &str = ".glues/external-encrypted.redb";
+    let _ = fs::remove_file(PATH);
+    let db = Db::redb(PATH).await?;
+    let mut db = Encrypted::encrypt_with_iterations(db, "secret", 10).await?;
+    db.add_note(db.root_id(), "Secret".to_owned()).await?;
+    drop(db);
+
+    let mut t = Tester::new().await?;
+    t.app
+        .open_notebook(EntryEvent::OpenEncrypted {
+            open: Box::new(EntryEvent::OpenRedb(PATH.to_owned())),
+            passphrase: "secret".to_owned(),
+        })
+        .await;
+    t.open_first_note().await?;
+
+    let note_id = request_external_edit(&mut t).await;
+    assert!(t.app.start_external_edit(note_id).await.is_none());
+    assert!(t.app.context_mut().alert.is_some());
+    drop(t);
+
+    let _ = fs::remove_file(PATH);
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
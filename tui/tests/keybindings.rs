@@ -0,0 +1,183 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use color_eyre::Result;
+use glues_tui::{
+    config,
+    input::KeyCode,
+    keybindings::{KEYMAP_FILE, Keybindings, Mode},
+};
+
+const EMACS: &str = "
+# Emacs habits in the editor
+normal Ctrl+n j
+normal Ctrl+p k
+insert Ctrl+s Esc
+
+# swap the tree movement keys
+tree h l
+tree l h
+";
+
+#[tokio::test]
+async fn user_keys_run_the_bound_actions() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.app
+        .context_mut()
+        .set_keybindings(Keybindings::parse(EMACS));
+    assert!(t.app.context_mut().info.is_none());
+
+    t.open_instant().await?;
+
+    // `l` now closes directories and `h` opens notes
+    t.press('j').await;
+    t.press('h').await;
+    t.draw()?;
+    assert!(t.app.context_mut().notebook.get_opened_note().is_some());
+
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    t.type_str("one").await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("two").await;
+    t.ctrl('s').await;
+    assert_eq!(t.editor_text(), "one\ntwo");
+
+    t.ctrl('p').await;
+    assert_eq!(t.cursor().0, 0);
+    t.ctrl('n').await;
+    assert_eq!(t.cursor().0, 1);
+
+    // prompts keep the raw keys
+    t.key(KeyCode::Tab).await;
+    t.press('m').await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("hl").await;
+    assert_eq!(
+        t.app
+            .context_mut()
+            .prompt
+            .as_ref()
+            .map(|prompt| prompt.input.value().to_owned()),
+        Some("Sample Notehl".to_owned())
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn char_arguments_are_not_remapped() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.app
+        .context_mut()
+        .set_keybindings(Keybindings::parse("normal n j\nnormal j n\n"));
+    assert!(t.app.context_mut().info.is_none());
+
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    t.press('d').await;
+    t.press('d').await;
+    t.press('i').await;
+    t.type_str("a cat").await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("and a dog").await;
+    t.key(KeyCode::Esc).await;
+    t.press('g').await;
+    t.press('g').await;
+    t.press('0').await;
+
+    // `n` after `f` is the character to find, not `j`
+    t.press('f').await;
+    t.press('n').await;
+    assert_eq!(t.cursor(), (0, 0));
+    t.press('n').await;
+    assert_eq!(t.cursor(), (1, 0));
+    t.press('f').await;
+    t.press('n').await;
+    assert_eq!(t.cursor(), (1, 1));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keymap_dialogs_show_effective_keys() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.app
+        .context_mut()
+        .set_keybindings(Keybindings::parse(EMACS));
+    t.open_instant().await?;
+
+    t.press('?').await;
+    t.draw()?;
+    snap!(t, "keymap_swapped_tree_keys");
+    t.press('?').await;
+
+    let keybindings = &t.app.context_mut().keybindings;
+    assert_eq!(keybindings.describe(Mode::Normal, "j"), "j Ctrl+n");
+    assert_eq!(keybindings.describe(Mode::Normal, "j/k"), "j/k");
+    assert_eq!(keybindings.describe(Mode::Tree, "t|h"), "t|l");
+    assert_eq!(keybindings.describe(Mode::Tree, "1-9"), "1-9");
+    assert_eq!(
+        keybindings.describe_brackets(Mode::Normal, "[k] Move cursor up"),
+        "[k] [Ctrl+p] Move cursor up"
+    );
+
+    t.press('j').await;
+    t.press('h').await;
+    t.press('i').await;
+    t.ctrl('h').await;
+    t.draw()?;
+    snap!(t, "editor_keymap_with_user_keys");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflicts_are_reported() -> Result<()> {
+    let mut t = Tester::new().await?;
+
+    let keybindings = Keybindings::parse(
+        "normal Ctrl+n j
+normal Ctrl+N k
+normal x u
+visual q
+emacs Ctrl+a 0
+insert Ctrl+ 0
+",
+    );
+    assert_eq!(
+        keybindings.conflicts(),
+        [
+            "line 2: Ctrl+n is bound twice in normal mode",
+            "line 4: expected `<mode> <key> <action key>`",
+            "line 5: unknown mode 'emacs'",
+            "line 6: unknown key 'Ctrl+'",
+            "normal mode: x is remapped, its action has no key",
+        ]
+    );
+
+    t.app.context_mut().set_keybindings(keybindings);
+    t.draw()?;
+    snap!(t, "keymap_conflicts");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn keymap_file_is_read_from_config_dir() -> Result<()> {
+    let _t = Tester::new().await?;
+    let path = config::config_dir().join(KEYMAP_FILE);
+
+    std::fs::write(&path, "entry Ctrl+x q\n")?;
+    let keybindings = Keybindings::load();
+    std::fs::remove_file(&path)?;
+
+    assert!(keybindings.conflicts().is_empty());
+    assert_eq!(keybindings.describe(Mode::Entry, "q"), "q Ctrl+x");
+    assert!(Keybindings::load().conflicts().is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,129 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_tui::{
+        context::notebook::ContextState,
+        input::{KeyCode, MouseEventKind},
+    },
+};
+
+// Instant notebook layout on the 120x40 test terminal: tree items from row 2,
+// tab titles on row 1 from column 45, and note text from column 48 on row 2.
+const TREE_COLUMN: u16 = 4;
+const TAB_ROW: u16 = 1;
+const TEXT_COLUMN: u16 = 48;
+
+fn selected_name(t: &mut Tester) -> String {
+    t.app.context_mut().notebook.selected().name()
+}
+
+#[tokio::test]
+async fn clicks_select_and_double_clicks_open_tree_items() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+
+    t.click(TREE_COLUMN, 3).await;
+    t.draw()?;
+    assert_eq!(selected_name(&mut t), "Sample Note");
+    assert!(t.app.context_mut().notebook.tabs.is_empty());
+
+    t.click(TREE_COLUMN, 3).await;
+    t.draw()?;
+    let notebook = &t.app.context_mut().notebook;
+    assert_eq!(notebook.tabs.len(), 1);
+    assert!(notebook.state.is_editor());
+
+    // selecting from the editor moves the focus back to the tree
+    t.click(TREE_COLUMN, 2).await;
+    t.draw()?;
+    assert_eq!(selected_name(&mut t), "Notes");
+    assert!(t.app.context_mut().notebook.state == ContextState::NoteTreeBrowsing);
+
+    t.click(TREE_COLUMN, 2).await;
+    t.draw()?;
+    assert_eq!(t.app.context_mut().notebook.tree_items.len(), 1);
+
+    // rows below the last item are ignored
+    t.click(TREE_COLUMN, 10).await;
+    assert_eq!(selected_name(&mut t), "Notes");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wheel_moves_through_the_tree() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+
+    t.mouse(MouseEventKind::ScrollDown, TREE_COLUMN, 5).await;
+    assert_eq!(selected_name(&mut t), "Sample Note");
+
+    t.mouse(MouseEventKind::ScrollUp, TREE_COLUMN, 5).await;
+    assert_eq!(selected_name(&mut t), "Notes");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clicks_switch_tabs() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+
+    // add a note, which opens it, then open the sample one next to it
+    t.press('m').await;
+    t.key(KeyCode::Enter).await;
+    t.type_str("Todo").await;
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    t.click(TREE_COLUMN, 3).await;
+    t.click(TREE_COLUMN, 3).await;
+    t.draw()?;
+
+    let notebook = &t.app.context_mut().notebook;
+    assert_eq!(notebook.tabs.len(), 2);
+    assert_eq!(notebook.tab_index, Some(1));
+
+    t.click(46, TAB_ROW).await;
+    t.draw()?;
+    let notebook = &t.app.context_mut().notebook;
+    assert_eq!(notebook.tab_index, Some(0));
+    assert!(notebook.state == ContextState::EditorNormalMode { idle: true });
+    assert_eq!(selected_name(&mut t), "Todo");
+    snap!(t, "mouse_tab_selected");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clicks_and_drags_move_the_cursor_and_select() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+    t.open_first_note().await?;
+
+    // from the tree, a click in the editor focuses it
+    t.key(KeyCode::Tab).await;
+    t.draw()?;
+    t.click(TEXT_COLUMN + 2, 2).await;
+    assert!(t.app.context_mut().notebook.state.is_editor());
+    assert_eq!(t.cursor(), (0, 2));
+
+    t.mouse(MouseEventKind::Drag, TEXT_COLUMN + 4, 2).await;
+    t.mouse(MouseEventKind::Up, TEXT_COLUMN + 4, 2).await;
+    t.draw()?;
+    assert!(t.app.context_mut().notebook.state == ContextState::EditorVisualMode);
+    assert_eq!(t.cursor(), (0, 4));
+    snap!(t, "mouse_drag_selection");
+
+    // a click ends the selection where it lands
+    t.click(TEXT_COLUMN, 2).await;
+    t.draw()?;
+    let notebook = &t.app.context_mut().notebook;
+    assert!(notebook.state == ContextState::EditorNormalMode { idle: true });
+    assert!(notebook.get_editor().selection.is_none());
+    assert_eq!(t.cursor(), (0, 0));
+
+    Ok(())
+}
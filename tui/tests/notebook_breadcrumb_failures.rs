@@ -45,7 +45,7 @@ async fn closing_tab_then_reopening_still_panics() -> Result<()> {
     t.draw()?;
     snap!(t, "workspace_note_open");
 
-    t.press('t').await;
+    t.press(' ').await;
     t.press('x').await;
     t.draw()?;
 
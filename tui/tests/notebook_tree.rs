@@ -220,10 +220,7 @@ async fn remove_directory_confirm_cancel_then_accept() -> Result<()> {
     t.key(KeyCode::Enter).await;
     t.draw()?;
 
-    // select the new directory (first child under root)
-    t.press('j').await;
-    t.draw()?;
-
+    // the new directory stays selected after it is added,
     // open directory actions and choose Remove directory
     t.press('m').await;
     for _ in 0..3 {
@@ -238,11 +235,7 @@ async fn remove_directory_confirm_cancel_then_accept() -> Result<()> {
     t.draw()?;
     snap!(t, "remove_dir_cancelled");
 
-    // confirm remove
-    t.press('m').await;
-    for _ in 0..3 {
-        t.press('j').await;
-    }
+    // the dialog stays on Remove directory, confirm this time
     t.key(KeyCode::Enter).await;
     t.press('y').await;
     t.draw()?;
@@ -323,3 +316,48 @@ async fn dir_actions_dialog_toggles() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn note_attachments_add_save_and_remove() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+
+    let dir = std::env::temp_dir().join(format!("glues-attachment-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let source = dir.join("hello.txt");
+    std::fs::write(&source, "hello")?;
+    let saved = dir.join("saved");
+    std::fs::create_dir_all(&saved)?;
+
+    // select note, open note actions and choose Add attachment
+    t.press('j').await;
+    t.press('m').await;
+    for _ in 0..3 {
+        t.press('j').await;
+    }
+    t.key(KeyCode::Enter).await;
+    t.type_str(&source.to_string_lossy()).await;
+    t.key(KeyCode::Enter).await;
+    t.draw()?;
+    snap!(t, "note_attachment_added");
+
+    // save the attachment into a directory, keeping its name
+    t.press('j').await;
+    t.key(KeyCode::Enter).await;
+    t.backspace("hello.txt".len()).await;
+    t.type_str(&saved.to_string_lossy()).await;
+    t.key(KeyCode::Enter).await;
+    assert_eq!(std::fs::read_to_string(saved.join("hello.txt"))?, "hello");
+    t.app.context_mut().alert = None;
+
+    // remove it with x
+    t.press('x').await;
+    t.press('y').await;
+    t.draw()?;
+    assert!(t.app.context_mut().notebook.attachments.is_empty());
+    snap!(t, "note_attachment_removed");
+
+    std::fs::remove_dir_all(&dir)?;
+
+    Ok(())
+}
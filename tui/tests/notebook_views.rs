@@ -18,12 +18,12 @@ async fn notebook_browser_toggle() -> Result<()> {
     t.draw()?;
     snap!(t, "notebook_browser_visible");
 
-    t.press('t').await;
+    t.press(' ').await;
     t.press('b').await;
     t.draw()?;
     snap!(t, "notebook_browser_hidden");
 
-    t.press('t').await;
+    t.press(' ').await;
     t.press('b').await;
 
     Ok(())
@@ -62,11 +62,11 @@ async fn notebook_editor_states() -> Result<()> {
     snap!(t, "editor_visual_mode");
     t.key(KeyCode::Esc).await;
 
-    t.press('t').await;
+    t.press(' ').await;
     t.press('n').await;
     t.draw()?;
     snap!(t, "editor_no_line_numbers");
-    t.press('t').await;
+    t.press(' ').await;
     t.press('n').await;
 
     // Add a second note to create multiple tabs
@@ -122,7 +122,7 @@ async fn notebook_editor_states() -> Result<()> {
     t.draw()?;
     snap!(t, "editor_breadcrumb_nested");
 
-    t.press('t').await;
+    t.press(' ').await;
     t.press('x').await;
     t.draw()?;
     snap!(t, "editor_inactive");
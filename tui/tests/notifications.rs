@@ -0,0 +1,54 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_tui::{
+        input::KeyCode,
+        notifications::{MAX_NOTIFICATIONS, Notifications, Severity},
+    },
+};
+
+#[tokio::test]
+async fn errors_are_kept_and_counted_until_read() -> Result<()> {
+    let mut t = Tester::new().await?;
+    t.open_instant().await?;
+
+    // Instant notebooks have nothing to sync with, and each alert is gone
+    // with the next key
+    for _ in 0..2 {
+        t.press('S').await;
+        t.key(KeyCode::Esc).await;
+    }
+    assert_eq!(t.app.context_mut().notifications.unread_errors(), 2);
+    t.draw()?;
+    assert!(t.snapshot_text().contains(" 󰂚 2 "));
+
+    t.press('N').await;
+    assert_eq!(t.app.context_mut().notifications.unread_errors(), 0);
+    t.draw()?;
+    snap_sanitized!(t, "notification_center");
+
+    t.key(KeyCode::Esc).await;
+    assert!(t.app.context_mut().notification_center.is_none());
+    t.draw()?;
+    assert!(!t.snapshot_text().contains("󰂚"));
+
+    Ok(())
+}
+
+#[test]
+fn notifications_keep_the_newest() {
+    let mut notifications = Notifications::default();
+    notifications.push(Severity::Error, "first");
+    for i in 0..MAX_NOTIFICATIONS {
+        notifications.push(Severity::Info, format!("info {i}"));
+    }
+
+    assert_eq!(notifications.len(), MAX_NOTIFICATIONS);
+    let newest = notifications.iter().next().expect("notifications are kept");
+    assert_eq!(newest.message, format!("info {}", MAX_NOTIFICATIONS - 1));
+    assert!(notifications.iter().all(|n| n.message != "first"));
+    assert_eq!(notifications.unread_errors(), 1);
+}
@@ -0,0 +1,95 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_core::EntryEvent,
+    glues_tui::{action::TuiAction, input::Input, recent},
+};
+
+async fn return_to_entry(t: &mut Tester) {
+    t.app
+        .handle_action(TuiAction::ReturnToEntry.into(), Input::Resize(0, 0))
+        .await;
+}
+
+#[tokio::test]
+async fn recent_notebooks_can_be_reopened_pinned_and_forgotten() -> Result<()> {
+    let mut t = Tester::new().await?;
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    let paths = [".glues/recent-a.redb", ".glues/recent-b.redb"];
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+        t.app
+            .open_notebook(EntryEvent::OpenRedb(path.to_owned()))
+            .await;
+        return_to_entry(&mut t).await;
+    }
+    // Instant notebooks are not remembered
+    t.press('i').await;
+    return_to_entry(&mut t).await;
+    t.draw()?;
+    snap!(t, "recent_listed");
+
+    // the most recent one comes first, and each opens with its number
+    t.press('2').await;
+    assert!(t.app.glues_mut().db.is_some());
+    return_to_entry(&mut t).await;
+    let labels = |t: &mut Tester| {
+        t.app
+            .context_mut()
+            .entry
+            .recent
+            .iter()
+            .map(|recent| (recent.label(), recent.pinned))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        labels(&mut t),
+        vec![
+            ("redb .glues/recent-a.redb".to_owned(), false),
+            ("redb .glues/recent-b.redb".to_owned(), false),
+        ]
+    );
+
+    // select recent-b below the menu, pin it, then forget recent-a
+    t.type_str("jjjjjjjjjj").await;
+    t.press('P').await;
+    assert_eq!(
+        labels(&mut t),
+        vec![
+            ("redb .glues/recent-b.redb".to_owned(), true),
+            ("redb .glues/recent-a.redb".to_owned(), false),
+        ]
+    );
+    assert_eq!(t.app.context_mut().entry.selected_recent(), Some(0));
+
+    t.press('j').await;
+    t.press('x').await;
+    assert_eq!(
+        labels(&mut t),
+        vec![("redb .glues/recent-b.redb".to_owned(), true)]
+    );
+    assert_eq!(t.app.context_mut().entry.selected_recent(), Some(0));
+
+    // pinned notebooks stay pinned when opened again
+    t.key(glues_tui::input::KeyCode::Enter).await;
+    return_to_entry(&mut t).await;
+    assert_eq!(
+        labels(&mut t),
+        vec![("redb .glues/recent-b.redb".to_owned(), true)]
+    );
+
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+    for path in paths {
+        let _ = std::fs::remove_file(path);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_core::{
+        EntryEvent,
+        backend::{CoreBackend, local::Db},
+    },
+    glues_tui::{action::TuiAction, input::Input, recent},
+};
+
+async fn quit(t: &mut Tester) -> bool {
+    t.app
+        .handle_action(TuiAction::Quit.into(), Input::Resize(0, 0))
+        .await
+}
+
+#[tokio::test]
+async fn reopens_tabs_and_cursors_of_the_last_session() -> Result<()> {
+    let path = std::env::temp_dir()
+        .join(format!("glues-tui-session-{}.redb", std::process::id()))
+        .to_string_lossy()
+        .into_owned();
+    let _ = std::fs::remove_file(&path);
+    {
+        let mut db = Db::redb(&path).await?;
+        let directory = db.add_directory(db.root_id(), "Diary".to_owned()).await?;
+        let note = db.add_note(directory.id, "Monday".to_owned()).await?;
+        db.update_note_content(note.id, "one\ntwo\nthree".to_owned())
+            .await?;
+    }
+
+    let mut t = Tester::new().await?;
+    t.app.set_restore_session(true);
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(path.clone()))
+        .await;
+    t.press('j').await;
+    t.press('l').await;
+    t.press('j').await;
+    t.press('l').await;
+    t.type_str("jjl").await;
+    assert_eq!(t.cursor(), (2, 1));
+    assert!(quit(&mut t).await);
+    drop(t);
+
+    let mut t = Tester::new().await?;
+    t.app.set_restore_session(true);
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(path.clone()))
+        .await;
+    t.draw()?;
+
+    let notebook = &t.app.context_mut().notebook;
+    assert_eq!(notebook.tabs.len(), 1);
+    assert_eq!(notebook.tabs[0].note.name, "Monday");
+    assert!(notebook.state.is_editor());
+    assert_eq!(t.cursor(), (2, 1));
+    assert!(t.snapshot_text().contains("Monday"));
+    drop(t);
+
+    // without restoring, the notebook opens on its root as before
+    let mut t = Tester::new().await?;
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(path.clone()))
+        .await;
+    assert!(t.app.context_mut().notebook.tabs.is_empty());
+    drop(t);
+
+    let _ = std::fs::remove_file(&path);
+    // other tests snapshot the entry menu, so leave no recent notebook behind
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reopens_notes_scrolled_as_they_were_left() -> Result<()> {
+    let path = std::env::temp_dir()
+        .join(format!(
+            "glues-tui-session-scroll-{}.redb",
+            std::process::id()
+        ))
+        .to_string_lossy()
+        .into_owned();
+    let _ = std::fs::remove_file(&path);
+    let content = (1..=200)
+        .map(|i| format!("Line {i:03}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let note_id = {
+        let mut db = Db::redb(&path).await?;
+        let note = db.add_note(db.root_id(), "Long".to_owned()).await?;
+        db.update_note_content(note.id.clone(), content).await?;
+        note.id
+    };
+
+    let mut t = Tester::new().await?;
+    t.app.set_restore_session(true);
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(path.clone()))
+        .await;
+    t.press('j').await;
+    t.press('l').await;
+    t.draw()?;
+    for _ in 0..39 {
+        t.press('j').await;
+    }
+    t.press('z').await;
+    t.press('t').await;
+    t.draw()?;
+    assert_eq!(
+        t.app.context_mut().notebook.viewport_top(&note_id),
+        Some(39)
+    );
+    assert!(quit(&mut t).await);
+    drop(t);
+
+    let mut t = Tester::new().await?;
+    t.app.set_restore_session(true);
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(path.clone()))
+        .await;
+    t.draw()?;
+
+    assert_eq!(t.cursor(), (39, 0));
+    assert_eq!(
+        t.app.context_mut().notebook.viewport_top(&note_id),
+        Some(39)
+    );
+    let text = t.snapshot_text();
+    assert!(text.contains("Line 040"));
+    assert!(!text.contains("Line 039"));
+    drop(t);
+
+    let _ = std::fs::remove_file(&path);
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
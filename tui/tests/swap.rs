@@ -0,0 +1,199 @@
+#[macro_use]
+mod tester;
+use tester::Tester;
+
+use {
+    color_eyre::Result,
+    glues_core::{
+        EntryEvent,
+        backend::{CoreBackend, encrypted::Encrypted, local::Db},
+        types::NoteId,
+    },
+    glues_tui::{
+        input::KeyCode,
+        recent,
+        session::NotebookRef,
+        swap::{self, DiffLine},
+    },
+};
+
+const PATH: &str = ".glues/swap.redb";
+
+fn notebook() -> NotebookRef {
+    NotebookRef::new(&EntryEvent::OpenRedb(PATH.to_owned())).expect("redb notebooks are kept")
+}
+
+fn swapped(note_id: &NoteId) -> Option<String> {
+    swap::list(&notebook())
+        .into_iter()
+        .find(|swap| &swap.note_id == note_id)
+        .map(|swap| swap.content)
+}
+
+async fn open(t: &mut Tester) {
+    t.app
+        .open_notebook(EntryEvent::OpenRedb(PATH.to_owned()))
+        .await;
+}
+
+/// Opens Monday and appends `text` to its first line, leaving it unsaved.
+async fn edit_monday(t: &mut Tester, text: &str) {
+    t.press('j').await;
+    t.press('l').await;
+    t.press('j').await;
+    t.press('l').await;
+    t.press('A').await;
+    t.type_str(text).await;
+    t.key(KeyCode::Esc).await;
+}
+
+async fn saved(t: &mut Tester, note_id: &NoteId) -> String {
+    let db = t.app.glues_mut().db.as_mut().expect("notebook is open");
+    db.fetch_note_content(note_id.clone())
+        .await
+        .expect("note should exist")
+}
+
+#[tokio::test]
+async fn unsaved_buffers_are_swapped_and_recovered() -> Result<()> {
+    let _ = std::fs::remove_file(PATH);
+    for stale in swap::list(&notebook()) {
+        swap::remove(&stale.note_id).await;
+    }
+    let note_id = {
+        let mut db = Db::redb(PATH).await?;
+        let directory = db.add_directory(db.root_id(), "Diary".to_owned()).await?;
+        let note = db.add_note(directory.id, "Monday".to_owned()).await?;
+        db.update_note_content(note.id.clone(), "one\ntwo".to_owned())
+            .await?;
+        note.id
+    };
+
+    // edits are swapped as they are typed, and the swap goes once saved
+    let mut t = Tester::new().await?;
+    open(&mut t).await;
+    edit_monday(&mut t, " more").await;
+    assert_eq!(swapped(&note_id).as_deref(), Some("one more\ntwo"));
+    t.key(KeyCode::Tab).await;
+    assert_eq!(saved(&mut t, &note_id).await, "one more\ntwo");
+    assert_eq!(swapped(&note_id), None);
+    drop(t);
+
+    // a session which ends without saving leaves its swap behind
+    let mut t = Tester::new().await?;
+    open(&mut t).await;
+    assert!(t.app.context_mut().recovery.is_none());
+    edit_monday(&mut t, " again").await;
+    drop(t);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &std::path::Path| {
+            std::fs::metadata(path).map(|meta| meta.permissions().mode() & 0o777)
+        };
+        let file = swap::dir().join(format!("{note_id}.swap"));
+        assert_eq!(mode(&file)?, 0o600);
+        assert_eq!(mode(&swap::dir())?, 0o700);
+    }
+
+    let mut t = Tester::new().await?;
+    open(&mut t).await;
+    t.draw()?;
+    snap!(t, "swap_recovery_dialog");
+
+    t.press('d').await;
+    let info = t.app.context_mut().info.as_ref().expect("diff is shown");
+    let lines: Vec<String> = info.lines.iter().map(ToString::to_string).collect();
+    assert_eq!(lines, vec!["- one more", "+ one more again"]);
+    t.key(KeyCode::Esc).await;
+    assert!(t.app.context_mut().recovery.is_some());
+
+    t.press('r').await;
+    assert!(t.app.context_mut().recovery.is_none());
+    assert_eq!(saved(&mut t, &note_id).await, "one more again\ntwo");
+    assert_eq!(swapped(&note_id), None);
+    drop(t);
+
+    // discarding keeps the saved note
+    let mut t = Tester::new().await?;
+    open(&mut t).await;
+    edit_monday(&mut t, " lost").await;
+    drop(t);
+
+    let mut t = Tester::new().await?;
+    open(&mut t).await;
+    t.press('x').await;
+    assert!(t.app.context_mut().recovery.is_none());
+    assert_eq!(saved(&mut t, &note_id).await, "one more again\ntwo");
+    assert_eq!(swapped(&note_id), None);
+    drop(t);
+
+    let _ = std::fs::remove_file(PATH);
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn encrypted_notebooks_are_not_swapped() -> Result<()> {
+    const ENCRYPTED_PATH: &str = ".glues/swap-encrypted.redb";
+    let _ = std::fs::remove_file(ENCRYPTED_PATH);
+    let note_id = {
+        let db = Db::redb(ENCRYPTED_PATH).await?;
+        let mut db = Encrypted::encrypt_with_iterations(db, "secret", 10).await?;
+        let directory = db.add_directory(db.root_id(), "Diary".to_owned()).await?;
+        let note = db.add_note(directory.id, "Monday".to_owned()).await?;
+        db.update_note_content(note.id.clone(), "one\ntwo".to_owned())
+            .await?;
+        note.id
+    };
+    let open = EntryEvent::OpenEncrypted {
+        open: Box::new(EntryEvent::OpenRedb(ENCRYPTED_PATH.to_owned())),
+        passphrase: "secret".to_owned(),
+    };
+
+    let mut t = Tester::new().await?;
+    t.app.open_notebook(open.clone()).await;
+    edit_monday(&mut t, " secret").await;
+    assert!(
+        t.app
+            .context_mut()
+            .notebook
+            .get_editor()
+            .lines
+            .to_string()
+            .contains("secret")
+    );
+    let notebook = NotebookRef::new(&open).expect("redb notebooks are kept");
+    assert!(
+        swap::list(&notebook)
+            .iter()
+            .all(|swap| swap.note_id != note_id)
+    );
+    drop(t);
+
+    let _ = std::fs::remove_file(ENCRYPTED_PATH);
+    for stale in recent::list().await {
+        recent::forget(&stale.notebook).await;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn diff_aligns_unchanged_lines() {
+    let lines = swap::diff("a\nb\nc\nd", "a\nc\nx\nd");
+    assert_eq!(
+        lines,
+        vec![
+            DiffLine::Same("a".to_owned()),
+            DiffLine::Removed("b".to_owned()),
+            DiffLine::Same("c".to_owned()),
+            DiffLine::Added("x".to_owned()),
+            DiffLine::Same("d".to_owned()),
+        ]
+    );
+}
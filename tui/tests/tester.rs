@@ -2,7 +2,7 @@ use {
     color_eyre::Result,
     glues_tui::{
         App, config,
-        input::{Input, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+        input::{Input, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind},
         logger,
     },
     once_cell::sync::Lazy,
@@ -81,6 +81,17 @@ impl Tester {
         self.handle_input(key_press(code, KeyModifiers::NONE)).await
     }
 
+    #[allow(dead_code)]
+    pub async fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) -> bool {
+        let input = Input::Mouse(MouseEvent::new(kind, column, row));
+        self.handle_input(input).await
+    }
+
+    #[allow(dead_code)]
+    pub async fn click(&mut self, column: u16, row: u16) -> bool {
+        self.mouse(MouseEventKind::Down, column, row).await
+    }
+
     #[allow(dead_code)]
     pub async fn open_instant(&mut self) -> Result<()> {
         self.draw()?;
@@ -143,7 +154,7 @@ impl Tester {
             Input::Key(KeyEvent {
                 kind: KeyEventKind::Press,
                 ..
-            })
+            }) | Input::Mouse(_)
         ) {
             return false;
         }
@@ -155,8 +166,11 @@ impl Tester {
                 ..
             }) if modifiers.ctrl => true,
             _ => {
+                let input = self.app.context_mut().remap(input);
                 let action = self.app.context_mut().consume(&input).await;
-                self.app.handle_action(action, input).await
+                let quit = self.app.handle_action(action, input).await;
+                self.app.update_swap().await;
+                quit
             }
         }
     }
@@ -211,6 +225,12 @@ fn sanitize_snapshot(text: &str) -> String {
         .replace_all(&sanitized, "${1}1970-01-01T00:00:00.000000Z")
         .into_owned();
 
+    static NOTIFIED_AT: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\d{2}:\d{2}:\d{2} (INFO|WARN|ERROR)").unwrap());
+    sanitized = NOTIFIED_AT
+        .replace_all(&sanitized, "00:00:00 ${1}")
+        .into_owned();
+
     // remove trailing spaces so snapshots stay stable across environments
     sanitized = sanitized
         .lines()